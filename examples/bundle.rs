@@ -0,0 +1,101 @@
+//! Package a skill set as a snapshot and a Markdown bundle, then verify
+//! the snapshot against the skills on disk.
+//!
+//! ```text
+//! cargo run --example bundle -- [ROOT]
+//! ```
+//!
+//! The snapshot and bundle are written next to the skills of a temporary
+//! copy of ROOT, as `skills.snapshot.json` and `SKILLS.md`.
+
+mod common;
+
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+use skills_ref::digest::sha256_hex;
+use skills_ref::{SkillSet, to_markdown_bundle};
+
+fn run(root: &Path, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    let (set, failures) = SkillSet::discover(root)?;
+    writeln!(
+        out,
+        "packaging {} skills ({} failed to load)",
+        set.len(),
+        failures.len()
+    )?;
+
+    let snapshot = root.join("skills.snapshot.json");
+    set.save_snapshot(&snapshot, true)?;
+    let bundle = to_markdown_bundle(&set);
+    std::fs::write(root.join("SKILLS.md"), &bundle)?;
+    writeln!(
+        out,
+        "SKILLS.md: {} bytes, sha256 {}",
+        bundle.len(),
+        sha256_hex(bundle.as_bytes())
+    )?;
+
+    // With verify, skills whose SKILL.md changed since the snapshot are
+    // read again, so their digests differ from the packaged ones; skills
+    // that can no longer be read are left out.
+    let (verified, _) = SkillSet::load_snapshot(&snapshot, true)?;
+    let stale: Vec<&str> = set
+        .iter()
+        .filter(|packaged| {
+            verified
+                .get(packaged.name())
+                .is_none_or(|current| current.digest != packaged.digest)
+        })
+        .map(|packaged| packaged.name())
+        .collect();
+    if stale.is_empty() {
+        writeln!(out, "verified {} skills", verified.len())?;
+    } else {
+        writeln!(out, "changed since packaging: {}", stale.join(", "))?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let root = common::scratch_root_from_args();
+    if let Err(e) = run(root.path(), &mut std::io::stdout()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packages_and_verifies() {
+        let root = common::example_root();
+        let mut out = Vec::new();
+        run(root.path(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(
+            out.contains("packaging 3 skills (1 failed to load)"),
+            "{}",
+            out
+        );
+        assert!(out.contains("verified 3 skills"), "{}", out);
+        let bundle = std::fs::read_to_string(root.path().join("SKILLS.md")).unwrap();
+        assert!(bundle.contains("csv-summary"));
+
+        // An edit after packaging shows up on verification
+        std::fs::write(
+            root.path().join("csv-summary/skill.md"),
+            "---\nname: csv-summary\ndescription: Summarize CSV files, edited\n---\n",
+        )
+        .unwrap();
+        let snapshot = root.path().join("skills.snapshot.json");
+        let (packaged, _) = SkillSet::load_snapshot(&snapshot, false).unwrap();
+        let (verified, _) = SkillSet::load_snapshot(&snapshot, true).unwrap();
+        let csv = |set: &SkillSet| set.get("csv-summary").unwrap().digest.clone();
+        assert_ne!(csv(&packaged), csv(&verified));
+    }
+}
@@ -0,0 +1,63 @@
+//! Helpers shared by the examples.
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use skills_ref::fixtures::EXAMPLE_SKILLS;
+use tempfile::TempDir;
+
+/// A temporary directory holding the crate's example skills, one
+/// directory each.
+pub fn example_root() -> TempDir {
+    let root = TempDir::new().expect("create a temporary directory");
+    for example in EXAMPLE_SKILLS {
+        example
+            .write_to(root.path())
+            .expect("write an example skill");
+    }
+    root
+}
+
+/// The skill root named by the first argument, or else the example
+/// skills in a temporary directory, which lives as long as the returned
+/// guard.
+pub fn root_from_args() -> (PathBuf, Option<TempDir>) {
+    match std::env::args_os().nth(1) {
+        Some(root) => (PathBuf::from(root), None),
+        None => {
+            let root = example_root();
+            (root.path().to_path_buf(), Some(root))
+        }
+    }
+}
+
+/// A temporary copy of the skill root named by the first argument, or
+/// else the example skills in a temporary directory, for examples that
+/// write files. ROOT itself is never changed.
+pub fn scratch_root_from_args() -> TempDir {
+    match std::env::args_os().nth(1) {
+        Some(root) => {
+            let copy = TempDir::new().expect("create a temporary directory");
+            copy_dir(Path::new(&root), copy.path()).expect("copy the skill root");
+            copy
+        }
+        None => example_root(),
+    }
+}
+
+/// Copy the files and directories under `from` into `to`. Symlinks are
+/// copied as the files they point to.
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.path().is_dir() {
+            std::fs::create_dir_all(&target)?;
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
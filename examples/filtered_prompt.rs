@@ -0,0 +1,73 @@
+//! Load a skill set and render a prompt for a host with limited tools.
+//!
+//! ```text
+//! cargo run --example filtered_prompt -- [ROOT]
+//! ```
+//!
+//! The host only provides the `Read` tool, so skills that need anything
+//! else are left out; the render reports which and why.
+
+mod common;
+
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+use skills_ref::{
+    HostCapabilities, PathRedaction, PromptOptions, Sanitizer, SkillSet, render_prompt,
+};
+
+fn run(root: &Path, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    let (set, failures) = SkillSet::discover(root)?;
+    for failure in &failures {
+        writeln!(out, "skipped {}: {}", failure.path.display(), failure.error)?;
+    }
+
+    let options = PromptOptions::default()
+        .with_host(HostCapabilities::with_tools(["Read"]))
+        .with_description_sanitizer(Sanitizer::StripMarkup)
+        .with_redact_paths(PathRedaction::new(root));
+    let dirs: Vec<&Path> = set.iter().map(|skill| skill.dir.as_path()).collect();
+    let render = render_prompt(&dirs, &options)?;
+
+    writeln!(out, "{}", render.xml)?;
+    for skill in &render.excluded {
+        writeln!(out, "excluded {}: {}", skill.name, skill.reason)?;
+    }
+    let chars: usize = render.included.iter().map(|skill| skill.chars).sum();
+    writeln!(
+        out,
+        "{} skills in the prompt, {} chars",
+        render.included.len(),
+        chars
+    )?;
+    Ok(())
+}
+
+fn main() {
+    let (root, _guard) = common::root_from_args();
+    if let Err(e) = run(&root, &mut std::io::stdout()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_prompt_for_host() {
+        let root = common::example_root();
+        let mut out = Vec::new();
+        run(root.path(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.contains("skipped "), "{}", out);
+        assert!(out.contains("<name>\ncsv-summary\n</name>"), "{}", out);
+        assert!(out.contains("<location>\n&lt;ROOT&gt;/"), "{}", out);
+        assert!(!out.contains("<name>\npdf-forms\n</name>"), "{}", out);
+        assert!(out.contains("excluded pdf-forms: "), "{}", out);
+        assert!(out.contains("2 skills in the prompt"), "{}", out);
+    }
+}
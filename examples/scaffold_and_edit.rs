@@ -0,0 +1,84 @@
+//! Create a skill from properties, then change its frontmatter in place.
+//!
+//! ```text
+//! cargo run --example scaffold_and_edit -- [DIR]
+//! ```
+//!
+//! The skill is written to release-notes in a temporary copy of DIR, so
+//! an existing release-notes skill there is refused but never changed, or
+//! in a temporary directory.
+
+mod common;
+
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+use serde_json::json;
+use skills_ref::{
+    OverwriteMode, SkillProperties, ValidationConfig, read_properties, update_frontmatter,
+    validate_detailed, write_skill,
+};
+
+fn run(root: &Path, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    let props = SkillProperties::builder(
+        "release-notes",
+        "Draft release notes from merged changes. Use when preparing a release.",
+    )
+    .license("MIT")
+    .metadata("version", "1.0")
+    .build()?;
+    let skill_md = write_skill(
+        &root.join("release-notes"),
+        &props,
+        "# Release notes\n\nGroup changes by kind and link each one.\n",
+        OverwriteMode::Refuse,
+    )?;
+    writeln!(out, "wrote {}", skill_md.display())?;
+
+    // Edits keep every other line of the file as it is.
+    let content = std::fs::read_to_string(&skill_md)?;
+    let content = update_frontmatter(&content, "metadata.version", Some(&json!("1.1")))?;
+    let content = update_frontmatter(&content, "compatibility", Some(&json!("Needs git")))?;
+    std::fs::write(&skill_md, content)?;
+
+    let skill_dir = root.join("release-notes");
+    let diagnostics = validate_detailed(&skill_dir, &ValidationConfig::default());
+    writeln!(out, "{} diagnostics", diagnostics.len())?;
+    let props = read_properties(&skill_dir)?;
+    writeln!(
+        out,
+        "{} {} ({})",
+        props.name,
+        props.version().unwrap_or("unversioned"),
+        props.compatibility.as_deref().unwrap_or("")
+    )?;
+    Ok(())
+}
+
+fn main() {
+    let root = common::scratch_root_from_args();
+    if let Err(e) = run(root.path(), &mut std::io::stdout()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scaffolds_and_edits_skill() {
+        let root = TempDir::new().unwrap();
+        let mut out = Vec::new();
+        run(root.path(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.contains("0 diagnostics\n"), "{}", out);
+        assert!(out.ends_with("release-notes 1.1 (Needs git)\n"), "{}", out);
+        // A second run refuses to overwrite the skill
+        assert!(run(root.path(), &mut Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+//! Serve a skill set to several threads and reload it after a skill
+//! changes.
+//!
+//! ```text
+//! cargo run --example serve_reload -- [ROOT]
+//! ```
+//!
+//! Readers take the current snapshot with `load`; a reload swaps in a new
+//! one without blocking them. The example edits a skill to have something
+//! to reload, in a temporary copy of ROOT.
+
+mod common;
+
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+
+use skills_ref::SharedSkillSet;
+
+fn run(root: &Path, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    let (shared, failures) = SharedSkillSet::discover(root)?;
+    let shared = Arc::new(shared);
+    writeln!(
+        out,
+        "serving {} skills ({} failed to load)",
+        shared.load().len(),
+        failures.len()
+    )?;
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || {
+                let set = shared.load();
+                set.iter()
+                    .map(|skill| skill.properties.name.len())
+                    .sum::<usize>()
+            })
+        })
+        .collect();
+    for reader in readers {
+        reader.join().map_err(|_| "reader thread panicked")?;
+    }
+
+    let Some(skill) = shared.load().iter().next().cloned() else {
+        writeln!(out, "nothing to reload")?;
+        return Ok(());
+    };
+    let content = std::fs::read_to_string(&skill.skill_md)?;
+    std::fs::write(
+        &skill.skill_md,
+        format!("{}\nEdited while served.\n", content),
+    )?;
+
+    let report = shared.reload()?;
+    for path in &report.changed {
+        let path = path.strip_prefix(root).unwrap_or(path);
+        writeln!(out, "changed: {}", path.display())?;
+    }
+    writeln!(out, "serving {} skills", shared.load().len())?;
+    Ok(())
+}
+
+fn main() {
+    let root = common::scratch_root_from_args();
+    if let Err(e) = run(root.path(), &mut std::io::stdout()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reloads_changed_skill() {
+        let root = common::example_root();
+        let mut out = Vec::new();
+        run(root.path(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(
+            out.contains("serving 3 skills (1 failed to load)"),
+            "{}",
+            out
+        );
+        assert_eq!(out.matches("changed: ").count(), 1, "{}", out);
+    }
+}
@@ -0,0 +1,89 @@
+//! Validate every skill under a directory and print a summary.
+//!
+//! ```text
+//! cargo run --example validate_tree -- [ROOT]
+//! ```
+//!
+//! Without ROOT, the crate's example skills are validated, one of which
+//! is broken on purpose.
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+use std::process::ExitCode;
+
+use skills_ref::{ValidationConfig, validate_workspace};
+
+/// Print each skill's diagnostics and a count per rule.
+///
+/// # Returns
+///
+/// Whether every skill is valid.
+fn run(root: &Path, out: &mut dyn Write) -> Result<bool, Box<dyn Error>> {
+    let config = ValidationConfig::default().with_metadata_conventions(true);
+    let report = validate_workspace(root, &config)?;
+
+    let mut by_rule: BTreeMap<&str, usize> = BTreeMap::new();
+    for skill in &report.skills {
+        let status = if skill.is_valid() { "ok" } else { "FAIL" };
+        let path = skill.path.strip_prefix(root).unwrap_or(&skill.path);
+        writeln!(out, "{:<4} {}", status, path.display())?;
+        for diagnostic in &skill.diagnostics {
+            *by_rule.entry(&diagnostic.rule).or_default() += 1;
+            writeln!(
+                out,
+                "     {}: {} [{}]",
+                diagnostic.severity, diagnostic.message, diagnostic.rule
+            )?;
+        }
+    }
+    for finding in &report.findings {
+        *by_rule.entry(&finding.diagnostic.rule).or_default() += 1;
+        writeln!(out, "     {}", finding.diagnostic.message)?;
+    }
+
+    writeln!(
+        out,
+        "\n{} skills, {} failed",
+        report.skills.len(),
+        report.failed_count()
+    )?;
+    for (rule, count) in by_rule {
+        writeln!(out, "  {:>3} {}", count, rule)?;
+    }
+    Ok(report.is_valid())
+}
+
+fn main() -> ExitCode {
+    let (root, _guard) = common::root_from_args();
+    match run(&root, &mut std::io::stdout()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarizes_example_skills() {
+        let root = common::example_root();
+        let mut out = Vec::new();
+        let valid = run(root.path(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(!valid);
+        assert!(out.contains("ok   pdf-forms\n"), "{}", out);
+        assert!(out.contains("FAIL broken-skill\n"), "{}", out);
+        assert!(out.contains("4 skills, 1 failed"), "{}", out);
+        assert!(out.contains("  1 name-directory-match\n"), "{}", out);
+    }
+}
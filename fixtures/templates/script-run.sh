@@ -0,0 +1,10 @@
+#!/usr/bin/env bash
+# TODO: Replace with the skill's script.
+set -euo pipefail
+
+if [ "$#" -ne 1 ]; then
+    echo "usage: $0 <input>" >&2
+    exit 2
+fi
+
+echo "$1"
@@ -0,0 +1,327 @@
+//! Editing one frontmatter field across every skill under a root.
+//!
+//! Each skill is edited with [`update_frontmatter`], so comments and layout
+//! survive, and re-validated before anything is written: an edit that would
+//! add validation errors to a skill fails for that skill only.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::discovery::find_skills;
+use crate::edit::update_frontmatter;
+use crate::error::{Result, SkillError};
+use crate::parser::{find_skill_md, parse_frontmatter, read_skill_md_file};
+use crate::validator::{ALLOWED_FIELDS, ValidationConfig, is_allowed_field, validate_content};
+
+/// A field to set in every skill.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldEdit {
+    /// Field to set: a top-level field, or `parent.child` such as
+    /// `metadata.owner`.
+    pub path: String,
+
+    /// New value.
+    pub value: serde_json::Value,
+
+    /// Leave skills that already have the field alone.
+    pub only_missing: bool,
+}
+
+/// What happened to one skill in a bulk edit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum EditOutcome {
+    /// The field was changed (or would be, when planning).
+    Changed,
+
+    /// The skill was left alone.
+    Skipped {
+        /// Why the skill was skipped.
+        reason: String,
+    },
+
+    /// The skill could not be edited.
+    Failed {
+        /// Why the edit failed.
+        error: String,
+    },
+}
+
+/// One skill in a [`BulkReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkillEdit {
+    /// Path to the skill directory.
+    pub path: PathBuf,
+
+    /// What happened to the skill.
+    #[serde(flatten)]
+    pub outcome: EditOutcome,
+
+    /// Path to SKILL.md and its edited content, for changed skills.
+    #[serde(skip)]
+    pub edited: Option<(PathBuf, String)>,
+}
+
+/// Result of a bulk edit, one entry per discovered skill in discovery
+/// order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BulkReport {
+    /// Every discovered skill.
+    pub skills: Vec<SkillEdit>,
+}
+
+impl BulkReport {
+    /// Number of skills changed.
+    pub fn changed(&self) -> usize {
+        self.count(|o| matches!(o, EditOutcome::Changed))
+    }
+
+    /// Number of skills skipped.
+    pub fn skipped(&self) -> usize {
+        self.count(|o| matches!(o, EditOutcome::Skipped { .. }))
+    }
+
+    /// Number of skills that failed.
+    pub fn failed(&self) -> usize {
+        self.count(|o| matches!(o, EditOutcome::Failed { .. }))
+    }
+
+    fn count(&self, pred: impl Fn(&EditOutcome) -> bool) -> usize {
+        self.skills.iter().filter(|s| pred(&s.outcome)).count()
+    }
+}
+
+/// Work out the edit for every skill under `root` without writing.
+///
+/// Changed skills carry their edited SKILL.md content in
+/// [`SkillEdit::edited`].
+///
+/// # Errors
+///
+/// * `ValidationError` - If the top-level field of `edit.path` is not
+///   allowed in frontmatter under `config`; nothing is edited then
+/// * Any error from [`find_skills`] if `root` cannot be scanned
+pub fn plan_bulk_update(
+    root: &Path,
+    edit: &FieldEdit,
+    config: &ValidationConfig,
+) -> Result<BulkReport> {
+    let top = edit.path.split('.').next().unwrap_or_default();
+    if !is_allowed_field(top, config) {
+        return Err(SkillError::validation(format!(
+            "Cannot set '{}': field '{}' is not allowed in frontmatter. Only {:?} are allowed.",
+            edit.path, top, ALLOWED_FIELDS
+        )));
+    }
+
+    let skills = find_skills(root)?
+        .into_iter()
+        .map(|dir| {
+            let (outcome, edited) = match edit_skill(&dir, edit, config) {
+                Ok(Ok(edited)) => (EditOutcome::Changed, Some(edited)),
+                Ok(Err(reason)) => (EditOutcome::Skipped { reason }, None),
+                Err(e) => (
+                    EditOutcome::Failed {
+                        error: e.to_string(),
+                    },
+                    None,
+                ),
+            };
+            SkillEdit {
+                path: dir,
+                outcome,
+                edited,
+            }
+        })
+        .collect();
+    Ok(BulkReport { skills })
+}
+
+/// Set a field in every skill under `root` and write the changed skills.
+///
+/// See [`plan_bulk_update`]. A skill whose SKILL.md cannot be written is
+/// reported as failed.
+///
+/// # Errors
+///
+/// Same as [`plan_bulk_update`].
+pub fn bulk_update(root: &Path, edit: &FieldEdit, config: &ValidationConfig) -> Result<BulkReport> {
+    let mut report = plan_bulk_update(root, edit, config)?;
+    for skill in &mut report.skills {
+        if let Some((skill_md, content)) = &skill.edited
+            && let Err(e) = std::fs::write(skill_md, content)
+        {
+            skill.outcome = EditOutcome::Failed {
+                error: format!("Failed to write {}: {}", skill_md.display(), e),
+            };
+            skill.edited = None;
+        }
+    }
+    Ok(report)
+}
+
+/// Edit one skill: the SKILL.md path and new content, or why it is
+/// skipped.
+fn edit_skill(
+    dir: &Path,
+    edit: &FieldEdit,
+    config: &ValidationConfig,
+) -> Result<std::result::Result<(PathBuf, String), String>> {
+    let skill_md = find_skill_md(dir).ok_or_else(|| {
+        SkillError::not_found(dir, format!("SKILL.md not found in {}", dir.display()))
+    })?;
+    let content = read_skill_md_file(&skill_md)?;
+
+    let (metadata, _) = parse_frontmatter(&content)?;
+    let current = match edit.path.split_once('.') {
+        Some((parent, key)) => metadata.get(parent).and_then(|p| p.get(key)),
+        None => metadata.get(&edit.path),
+    };
+    if edit.only_missing && current.is_some() {
+        return Ok(Err(format!("'{}' is already set", edit.path)));
+    }
+    if current.and_then(|v| serde_json::to_value(v).ok()).as_ref() == Some(&edit.value) {
+        return Ok(Err(format!("'{}' already has this value", edit.path)));
+    }
+
+    let edited = update_frontmatter(&content, &edit.path, Some(&edit.value))?;
+
+    let before: Vec<String> = error_messages(dir, &content, config);
+    let introduced: Vec<String> = error_messages(dir, &edited, config)
+        .into_iter()
+        .filter(|message| !before.contains(message))
+        .collect();
+    if !introduced.is_empty() {
+        return Err(SkillError::validation_multiple(
+            format!(
+                "Setting '{}' would make the skill invalid: {}",
+                edit.path,
+                introduced.join("; ")
+            ),
+            introduced,
+        ));
+    }
+    Ok(Ok((skill_md, edited)))
+}
+
+fn error_messages(dir: &Path, content: &str, config: &ValidationConfig) -> Vec<String> {
+    validate_content(dir, content, config)
+        .into_iter()
+        .filter(|d| d.is_error())
+        .map(|d| d.message)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_skill(root: &Path, name: &str, frontmatter: &str) -> PathBuf {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {}\n{}---\n# Body\n", name, frontmatter),
+        )
+        .unwrap();
+        dir
+    }
+
+    fn owner_edit(only_missing: bool) -> FieldEdit {
+        FieldEdit {
+            path: "metadata.owner".to_string(),
+            value: "platform-team".into(),
+            only_missing,
+        }
+    }
+
+    #[test]
+    fn test_bulk_update() {
+        let dir = TempDir::new().unwrap();
+        let plain = write_skill(dir.path(), "alpha", "# keep me\ndescription: d\n");
+        let owned = write_skill(
+            dir.path(),
+            "beta",
+            "description: d\nmetadata:\n  owner: data-team\n",
+        );
+        write_skill(dir.path(), "gamma", "description: d\nmetadata: {a: b}\n");
+
+        let report =
+            bulk_update(dir.path(), &owner_edit(true), &ValidationConfig::default()).unwrap();
+        assert_eq!(
+            (report.changed(), report.skipped(), report.failed()),
+            (1, 1, 1)
+        );
+        assert_eq!(
+            std::fs::read_to_string(plain.join("SKILL.md")).unwrap(),
+            "---\nname: alpha\n# keep me\ndescription: d\nmetadata:\n  owner: platform-team\n---\n# Body\n"
+        );
+        assert_eq!(
+            report.skills[1].outcome,
+            EditOutcome::Skipped {
+                reason: "'metadata.owner' is already set".to_string()
+            }
+        );
+        assert!(matches!(
+            report.skills[2].outcome,
+            EditOutcome::Failed { .. }
+        ));
+
+        let report =
+            plan_bulk_update(dir.path(), &owner_edit(false), &ValidationConfig::default()).unwrap();
+        assert_eq!(
+            report.skills[0].outcome,
+            EditOutcome::Skipped {
+                reason: "'metadata.owner' already has this value".to_string()
+            }
+        );
+        assert_eq!(report.skills[1].outcome, EditOutcome::Changed);
+        assert!(
+            std::fs::read_to_string(owned.join("SKILL.md"))
+                .unwrap()
+                .contains("owner: data-team")
+        );
+    }
+
+    #[test]
+    fn test_bulk_update_rejects_disallowed_fields_and_invalid_results() {
+        let dir = TempDir::new().unwrap();
+        write_skill(dir.path(), "alpha", "description: d\n");
+
+        let edit = FieldEdit {
+            path: "owner".to_string(),
+            value: "platform-team".into(),
+            only_missing: false,
+        };
+        let err = plan_bulk_update(dir.path(), &edit, &ValidationConfig::default()).unwrap_err();
+        assert!(err.to_string().contains("field 'owner' is not allowed"));
+
+        let edit = FieldEdit {
+            path: "x-owner".to_string(),
+            ..edit
+        };
+        let config = ValidationConfig {
+            allow_x_prefixed_fields: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            plan_bulk_update(dir.path(), &edit, &config)
+                .unwrap()
+                .changed(),
+            1
+        );
+
+        let edit = FieldEdit {
+            path: "description".to_string(),
+            value: "d".repeat(2000).into(),
+            only_missing: false,
+        };
+        let report = plan_bulk_update(dir.path(), &edit, &config).unwrap();
+        assert!(matches!(
+            &report.skills[0].outcome,
+            EditOutcome::Failed { error } if error.contains("1024")
+        ));
+    }
+}
@@ -0,0 +1,548 @@
+//! Reuse of parsed properties and validation results across runs.
+//!
+//! [`read_properties_cached`] and [`validate_detailed_cached`] look up
+//! their result in a [`SkillCache`] before doing the work, and store it
+//! afterwards. Entries are keyed by a hash of the SKILL.md content and the
+//! crate version, so editing a skill or upgrading the crate misses the
+//! cache instead of returning a stale result. Validation also depends on
+//! the skill's location, its other files, and the configuration, so those
+//! go into its key too.
+//!
+//! A cache only ever holds what could be recomputed. An entry that cannot
+//! be decoded, or that was written for another key or crate version, is
+//! ignored, and the fresh result replaces it.
+
+use std::collections::HashMap;
+use std::path::Path;
+#[cfg(feature = "disk-cache")]
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostic::Diagnostic;
+use crate::digest::sha256_hex;
+use crate::error::Result;
+use crate::metrics::{CACHE_HITS, CACHE_MISSES, Metrics, NoopMetrics};
+use crate::models::SkillProperties;
+use crate::parser::{parse_frontmatter, properties_from_metadata, read_frontmatter_only};
+use crate::source::{FsSource, locate_skill};
+use crate::validator::{NameMatch, SizeLimits, ValidationConfig, read_skill_md, validate_content};
+
+/// Version of the crate that wrote an entry.
+const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Storage for cached results, as opaque bytes under string keys.
+///
+/// Keys are lowercase hex digests, safe to use as file names. A backend
+/// may drop entries at any time; failing to store one is not an error.
+pub trait SkillCache: Send + Sync {
+    /// The bytes stored under `key`, if any.
+    fn load(&self, key: &str) -> Option<Vec<u8>>;
+
+    /// Store `data` under `key`, replacing any previous entry.
+    fn store(&self, key: &str, data: &[u8]);
+}
+
+/// A [`SkillCache`] that lives as long as the value, for hosts that
+/// validate the same skills repeatedly in one process.
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl MemoryCache {
+    /// An empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of entries stored.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Check if no entry is stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl SkillCache for MemoryCache {
+    fn load(&self, key: &str) -> Option<Vec<u8>> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    fn store(&self, key: &str, data: &[u8]) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), data.to_vec());
+    }
+}
+
+/// A [`SkillCache`] keeping one JSON file per entry in a directory, so
+/// results survive between runs.
+///
+/// The directory is created on the first store. Entries are written to a
+/// temporary file and renamed into place, so concurrent runs never read a
+/// partly written entry.
+#[cfg(feature = "disk-cache")]
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+#[cfg(feature = "disk-cache")]
+impl DiskCache {
+    /// A cache storing its entries in `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// A cache in the platform's per-user cache directory (see
+    /// [`default_dir`](Self::default_dir)), if it can be determined.
+    pub fn in_default_dir() -> Option<Self> {
+        Self::default_dir().map(Self::new)
+    }
+
+    /// The `skills-ref` directory in the platform's per-user cache
+    /// directory: `$XDG_CACHE_HOME`, else `~/Library/Caches` on macOS,
+    /// `%LOCALAPPDATA%` on Windows, and `~/.cache` elsewhere.
+    pub fn default_dir() -> Option<PathBuf> {
+        let env_dir = |name: &str| {
+            std::env::var_os(name)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        };
+        let base = if let Some(dir) = env_dir("XDG_CACHE_HOME") {
+            dir
+        } else if cfg!(windows) {
+            env_dir("LOCALAPPDATA")?
+        } else if cfg!(target_os = "macos") {
+            env_dir("HOME")?.join("Library").join("Caches")
+        } else {
+            env_dir("HOME")?.join(".cache")
+        };
+        Some(base.join("skills-ref"))
+    }
+
+    /// Directory the entries are stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+#[cfg(feature = "disk-cache")]
+impl SkillCache for DiskCache {
+    fn load(&self, key: &str) -> Option<Vec<u8>> {
+        std::fs::read(self.entry_path(key)).ok()
+    }
+
+    fn store(&self, key: &str, data: &[u8]) {
+        if std::fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+        let tmp = self
+            .dir
+            .join(format!(".{key}.{}.tmp", uuid::Uuid::new_v4().simple()));
+        if std::fs::write(&tmp, data).is_err()
+            || std::fs::rename(&tmp, self.entry_path(key)).is_err()
+        {
+            let _ = std::fs::remove_file(&tmp);
+        }
+    }
+}
+
+/// A cached value with what it was computed for.
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    crate_version: String,
+    key: String,
+    value: T,
+}
+
+/// The value stored under `key`, unless the entry is missing, corrupted,
+/// or was written for another key or crate version.
+fn load<T: DeserializeOwned>(cache: &dyn SkillCache, key: &str) -> Option<T> {
+    let data = cache.load(key)?;
+    let entry: Entry<T> = serde_json::from_slice(&data).ok()?;
+    (entry.crate_version == CRATE_VERSION && entry.key == key).then_some(entry.value)
+}
+
+fn store<T: Serialize>(cache: &dyn SkillCache, key: &str, value: T) {
+    let entry = Entry {
+        crate_version: CRATE_VERSION.to_string(),
+        key: key.to_string(),
+        value,
+    };
+    if let Ok(data) = serde_json::to_vec(&entry) {
+        cache.store(key, &data);
+    }
+}
+
+/// Hash the parts of a key, each length-prefixed so no two lists of parts
+/// collide.
+fn key_of(parts: &[&[u8]]) -> String {
+    let mut data = Vec::new();
+    for part in [CRATE_VERSION.as_bytes()].iter().chain(parts) {
+        data.extend_from_slice(&(part.len() as u64).to_le_bytes());
+        data.extend_from_slice(part);
+    }
+    sha256_hex(&data)
+}
+
+/// [`read_properties`](crate::read_properties), reusing the result stored
+/// in `cache` for the same frontmatter.
+///
+/// Only successful reads are cached; an error is returned as is and
+/// computed again next time.
+///
+/// # Errors
+///
+/// Same as [`read_properties`](crate::read_properties).
+pub fn read_properties_cached(skill_dir: &Path, cache: &dyn SkillCache) -> Result<SkillProperties> {
+    read_properties_cached_with(skill_dir, cache, &NoopMetrics)
+}
+
+/// [`read_properties_cached`], counting each lookup toward
+/// [`CACHE_HITS`] or [`CACHE_MISSES`], labeled `properties`.
+pub fn read_properties_cached_with(
+    skill_dir: &Path,
+    cache: &dyn SkillCache,
+    metrics: &dyn Metrics,
+) -> Result<SkillProperties> {
+    let (_, skill_md) = locate_skill(&FsSource, skill_dir)?;
+    let content = read_frontmatter_only(&skill_md)?;
+    let key = key_of(&[b"properties", content.as_bytes()]);
+    if let Some(props) = load(cache, &key) {
+        metrics.incr_labeled(CACHE_HITS, "properties");
+        return Ok(props);
+    }
+    metrics.incr_labeled(CACHE_MISSES, "properties");
+
+    let (metadata, _) = parse_frontmatter(&content)?;
+    let props = properties_from_metadata(&metadata)?;
+    store(cache, &key, &props);
+    Ok(props)
+}
+
+/// [`validate_detailed`](crate::validate_detailed), reusing the result
+/// stored in `cache` for the same SKILL.md content.
+///
+/// The key also covers the path of `skill_dir`, the file name of its
+/// SKILL.md, and `config`. With [`size_limits`](ValidationConfig::size_limits)
+/// set it covers the name, kind, size, and modification time of every
+/// file in the skill too; otherwise the rest of the tree is not read.
+/// Configurations with custom rules or a message renderer cannot be
+/// fingerprinted, so they bypass the cache.
+pub fn validate_detailed_cached(
+    skill_dir: &Path,
+    config: &ValidationConfig,
+    cache: &dyn SkillCache,
+) -> Vec<Diagnostic> {
+    validate_detailed_cached_with(skill_dir, config, cache, &NoopMetrics)
+}
+
+/// [`validate_detailed_cached`], counting each lookup toward
+/// [`CACHE_HITS`] or [`CACHE_MISSES`], labeled `validation`. Skills that
+/// bypass the cache, or cannot be read, count toward neither.
+pub fn validate_detailed_cached_with(
+    skill_dir: &Path,
+    config: &ValidationConfig,
+    cache: &dyn SkillCache,
+    metrics: &dyn Metrics,
+) -> Vec<Diagnostic> {
+    let (skill_md, content) = match read_skill_md(skill_dir, config.needs_body()) {
+        Ok(read) => read,
+        Err(mut diagnostic) => {
+            config.render_messages([&mut diagnostic]);
+            return vec![diagnostic];
+        }
+    };
+    let Some(config_key) = config_key(config) else {
+        return validate_content(skill_dir, &content, config);
+    };
+    // Only size checks look past SKILL.md.
+    let inventory = match config.size_limits {
+        Some(_) => match inventory(skill_dir) {
+            Some(inventory) => inventory,
+            None => return validate_content(skill_dir, &content, config),
+        },
+        None => String::new(),
+    };
+
+    let key = key_of(&[
+        b"validation",
+        skill_dir.to_string_lossy().as_bytes(),
+        skill_md
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default()
+            .as_bytes(),
+        config_key.as_bytes(),
+        inventory.as_bytes(),
+        content.as_bytes(),
+    ]);
+    if let Some(diagnostics) = load(cache, &key) {
+        metrics.incr_labeled(CACHE_HITS, "validation");
+        return diagnostics;
+    }
+    metrics.incr_labeled(CACHE_MISSES, "validation");
+
+    let diagnostics = validate_content(skill_dir, &content, config);
+    store(cache, &key, &diagnostics);
+    diagnostics
+}
+
+/// Every option of `config`, one `name=value` line each, or `None` if it
+/// has custom rules or a message renderer, which cannot be fingerprinted.
+fn config_key(config: &ValidationConfig) -> Option<String> {
+    // Destructured so a new option cannot be left out of the key.
+    let ValidationConfig {
+        metadata_conventions,
+        allow_x_prefixed_fields,
+        list_placeholders,
+        name_match,
+        max_name_bytes,
+        size_limits,
+        custom_rules,
+        min_description_chars,
+        message_value_chars,
+        message_renderer,
+    } = config;
+    if !custom_rules.is_empty() || message_renderer.is_some() {
+        return None;
+    }
+
+    let optional = |value: Option<usize>| value.map_or("none".to_string(), |v| v.to_string());
+    let name_match = match name_match {
+        NameMatch::ImmediateDir => "immediate-dir".to_string(),
+        NameMatch::AnyAncestorWithin(n) => format!("any-ancestor-within:{n}"),
+        NameMatch::Disabled => "disabled".to_string(),
+    };
+    let size_limits = match size_limits {
+        Some(SizeLimits {
+            max_total_bytes,
+            max_file_bytes,
+            max_files,
+        }) => format!("{max_total_bytes},{max_file_bytes},{max_files}"),
+        None => "none".to_string(),
+    };
+    Some(
+        [
+            format!("metadata_conventions={metadata_conventions}"),
+            format!("allow_x_prefixed_fields={allow_x_prefixed_fields}"),
+            format!("list_placeholders={list_placeholders}"),
+            format!("name_match={name_match}"),
+            format!("max_name_bytes={}", optional(*max_name_bytes)),
+            format!("size_limits={size_limits}"),
+            format!("min_description_chars={}", optional(*min_description_chars)),
+            format!("message_value_chars={}", optional(*message_value_chars)),
+        ]
+        .join("\n"),
+    )
+}
+
+/// One line per entry under `dir`, sorted, with its kind, size, and
+/// modification time. `None` if the tree cannot be listed.
+fn inventory(dir: &Path) -> Option<String> {
+    fn walk(dir: &Path, rel: &Path, lines: &mut Vec<String>) -> Option<()> {
+        for entry in std::fs::read_dir(dir).ok()? {
+            let entry = entry.ok()?;
+            let meta = entry.metadata().ok()?;
+            let rel = rel.join(entry.file_name());
+            let mtime = meta
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |since| since.as_nanos());
+            let kind = if meta.is_symlink() {
+                'l'
+            } else if meta.is_dir() {
+                'd'
+            } else {
+                'f'
+            };
+            lines.push(format!(
+                "{kind}\t{}\t{}\t{mtime}",
+                meta.len(),
+                rel.to_string_lossy()
+            ));
+            if meta.is_dir() {
+                walk(&entry.path(), &rel, lines)?;
+            }
+        }
+        Some(())
+    }
+
+    let mut lines = Vec::new();
+    walk(dir, Path::new(""), &mut lines)?;
+    lines.sort();
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::RecordingMetrics;
+    use crate::{read_properties, validate_detailed};
+    use tempfile::TempDir;
+
+    /// A cache counting its loads that found an entry.
+    #[derive(Default)]
+    struct Counting {
+        inner: MemoryCache,
+        hits: Mutex<usize>,
+    }
+
+    impl SkillCache for Counting {
+        fn load(&self, key: &str) -> Option<Vec<u8>> {
+            let data = self.inner.load(key);
+            if data.is_some() {
+                *self.hits.lock().unwrap() += 1;
+            }
+            data
+        }
+
+        fn store(&self, key: &str, data: &[u8]) {
+            self.inner.store(key, data);
+        }
+    }
+
+    fn skill(root: &TempDir, name: &str, content: &str) -> std::path::PathBuf {
+        let dir = root.path().join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("SKILL.md"), content).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_read_properties_cached_reuses_entry() {
+        let root = TempDir::new().unwrap();
+        let dir = skill(
+            &root,
+            "my-skill",
+            "---\nname: my-skill\ndescription: Does things\n---\n",
+        );
+        let cache = Counting::default();
+        let metrics = RecordingMetrics::new();
+
+        let first = read_properties_cached_with(&dir, &cache, &metrics).unwrap();
+        let second = read_properties_cached_with(&dir, &cache, &metrics).unwrap();
+        assert_eq!(first, read_properties(&dir).unwrap());
+        assert_eq!(second, first);
+        assert_eq!(*cache.hits.lock().unwrap(), 1);
+        assert_eq!(metrics.count("cache_hits{properties}"), 1);
+        assert_eq!(metrics.count("cache_misses{properties}"), 1);
+
+        // An edit changes the key
+        std::fs::write(
+            dir.join("SKILL.md"),
+            "---\nname: my-skill\ndescription: Does other things\n---\n",
+        )
+        .unwrap();
+        let edited = read_properties_cached(&dir, &cache).unwrap();
+        assert_eq!(edited.description, "Does other things");
+        assert_eq!(cache.inner.len(), 2);
+    }
+
+    #[test]
+    fn test_validate_detailed_cached_matches_uncached() {
+        let root = TempDir::new().unwrap();
+        let dir = skill(&root, "wrong-dir", "---\nname: MySkill\n---\nBody\n");
+        let config = ValidationConfig::default();
+        let cache = Counting::default();
+        let metrics = RecordingMetrics::new();
+
+        let expected = validate_detailed(&dir, &config);
+        assert!(!expected.is_empty());
+        for _ in 0..2 {
+            let diagnostics = validate_detailed_cached_with(&dir, &config, &cache, &metrics);
+            assert_eq!(diagnostics, expected);
+        }
+        assert_eq!(*cache.hits.lock().unwrap(), 1);
+        assert_eq!(metrics.count("cache_hits{validation}"), 1);
+        assert_eq!(metrics.count("cache_misses{validation}"), 1);
+
+        // Another configuration is another entry
+        let config = config.with_metadata_conventions(true);
+        validate_detailed_cached(&dir, &config, &cache);
+        assert_eq!(cache.inner.len(), 2);
+
+        // Other files only matter when sizes are checked
+        std::fs::write(dir.join("notes.txt"), "x").unwrap();
+        validate_detailed_cached(&dir, &config, &cache);
+        assert_eq!(cache.inner.len(), 2);
+        let config = config.with_size_limits(SizeLimits::default());
+        validate_detailed_cached(&dir, &config, &cache);
+        assert_eq!(cache.inner.len(), 3);
+        std::fs::write(dir.join("notes.txt"), "xy").unwrap();
+        validate_detailed_cached(&dir, &config, &cache);
+        assert_eq!(cache.inner.len(), 4);
+    }
+
+    #[test]
+    fn test_config_key_is_stable() {
+        let config = ValidationConfig::default()
+            .with_name_match(NameMatch::AnyAncestorWithin(2))
+            .with_max_name_bytes(40);
+        assert_eq!(
+            config_key(&config).unwrap(),
+            "metadata_conventions=false\nallow_x_prefixed_fields=false\n\
+             list_placeholders=false\nname_match=any-ancestor-within:2\n\
+             max_name_bytes=40\nsize_limits=none\nmin_description_chars=none\n\
+             message_value_chars=none"
+        );
+    }
+
+    #[test]
+    fn test_corrupted_and_mismatched_entries_are_replaced() {
+        let root = TempDir::new().unwrap();
+        let dir = skill(
+            &root,
+            "my-skill",
+            "---\nname: my-skill\ndescription: Does things\n---\n",
+        );
+        let cache = MemoryCache::new();
+        let expected = read_properties(&dir).unwrap();
+        read_properties_cached(&dir, &cache).unwrap();
+        let key = cache.entries.lock().unwrap().keys().next().unwrap().clone();
+
+        let stale = serde_json::json!({
+            "crate_version": "0.0.0",
+            "key": key,
+            "value": {"name": "stale", "description": "Stale"},
+        });
+        let moved = serde_json::json!({
+            "crate_version": CRATE_VERSION,
+            "key": "another-key",
+            "value": {"name": "moved", "description": "Moved"},
+        });
+        for bad in [
+            b"not json".to_vec(),
+            serde_json::to_vec(&stale).unwrap(),
+            serde_json::to_vec(&moved).unwrap(),
+        ] {
+            cache.store(&key, &bad);
+            assert_eq!(read_properties_cached(&dir, &cache).unwrap(), expected);
+            assert_ne!(cache.load(&key).unwrap(), bad);
+        }
+    }
+
+    #[cfg(feature = "disk-cache")]
+    #[test]
+    fn test_disk_cache_round_trip() {
+        let root = TempDir::new().unwrap();
+        let cache = DiskCache::new(root.path().join("cache"));
+        assert_eq!(cache.load("abc"), None);
+        cache.store("abc", b"data");
+        assert_eq!(cache.load("abc").as_deref(), Some(&b"data"[..]));
+        assert_eq!(std::fs::read_dir(cache.dir()).unwrap().count(), 1);
+    }
+}
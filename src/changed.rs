@@ -0,0 +1,139 @@
+//! Detection of skills that differ between two directory trees.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::digest::dir_digest;
+use crate::discovery::find_skills;
+use crate::error::Result;
+
+/// Skills that differ between two trees.
+///
+/// Added and modified paths point into the new tree; removed paths point
+/// into the old tree. Each list is sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ChangeSet {
+    /// Skills present only in the new tree.
+    pub added: Vec<PathBuf>,
+
+    /// Skills present only in the old tree.
+    pub removed: Vec<PathBuf>,
+
+    /// Skills present in both trees whose contents differ.
+    pub modified: Vec<PathBuf>,
+}
+
+impl ChangeSet {
+    /// Check if no skill changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// Skills that exist in the new tree and need re-checking: added and
+    /// modified skills, sorted.
+    pub fn affected(&self) -> Vec<&Path> {
+        let mut affected: Vec<&Path> = self
+            .added
+            .iter()
+            .chain(&self.modified)
+            .map(PathBuf::as_path)
+            .collect();
+        crate::ordering::sort_paths(&mut affected);
+        affected
+    }
+}
+
+/// Compare the skills under two directory trees.
+///
+/// Skills are matched by their path relative to each root and compared by
+/// [`dir_digest`], so a change to any file in a skill directory (not just
+/// SKILL.md) marks the skill as modified.
+///
+/// # Errors
+///
+/// Returns an error if either root cannot be scanned or a skill directory
+/// cannot be read.
+pub fn changed_skills(old_root: &Path, new_root: &Path) -> Result<ChangeSet> {
+    let old = digests(old_root)?;
+    let new = digests(new_root)?;
+    let mut changes = ChangeSet::default();
+
+    for (rel, digest) in &new {
+        match old.get(rel) {
+            None => changes.added.push(new_root.join(rel)),
+            Some(old_digest) if old_digest != digest => changes.modified.push(new_root.join(rel)),
+            Some(_) => {}
+        }
+    }
+    for rel in old.keys() {
+        if !new.contains_key(rel) {
+            changes.removed.push(old_root.join(rel));
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Digest every skill under `root`, keyed by path relative to `root`.
+fn digests(root: &Path) -> Result<BTreeMap<PathBuf, String>> {
+    find_skills(root)?
+        .into_iter()
+        .map(|dir| {
+            let digest = dir_digest(&dir).map_err(|e| e.in_skill(&dir))?;
+            let rel = dir.strip_prefix(root).unwrap_or(&dir).to_path_buf();
+            Ok((rel, digest))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_skill(root: &Path, rel: &str, body: &str) {
+        let dir = root.join(rel);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("SKILL.md"), body).unwrap();
+    }
+
+    #[test]
+    fn test_changed_skills() {
+        let old = TempDir::new().unwrap();
+        let new = TempDir::new().unwrap();
+        for root in [old.path(), new.path()] {
+            create_skill(root, "same", "same");
+            create_skill(root, "group/edited", "v1");
+            create_skill(root, "script-edited", "same");
+            std::fs::write(root.join("script-edited/run.sh"), "v1").unwrap();
+        }
+        create_skill(old.path(), "gone", "x");
+        create_skill(new.path(), "fresh", "x");
+        create_skill(new.path(), "group/edited", "v2");
+        std::fs::write(new.path().join("script-edited/run.sh"), "v2").unwrap();
+
+        let changes = changed_skills(old.path(), new.path()).unwrap();
+        assert_eq!(changes.added, vec![new.path().join("fresh")]);
+        assert_eq!(changes.removed, vec![old.path().join("gone")]);
+        assert_eq!(
+            changes.modified,
+            vec![
+                new.path().join("group/edited"),
+                new.path().join("script-edited")
+            ]
+        );
+        assert_eq!(changes.affected().len(), 3);
+    }
+
+    #[test]
+    fn test_identical_trees() {
+        let old = TempDir::new().unwrap();
+        let new = TempDir::new().unwrap();
+        create_skill(old.path(), "a", "a");
+        create_skill(new.path(), "a", "a");
+
+        assert!(changed_skills(old.path(), new.path()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,206 @@
+//! Planned filesystem changes for mutating commands.
+//!
+//! Mutating commands compute a [`ChangePlan`] first and then hand it to
+//! [`ChangePlan::apply`], which honors the global `--dry-run` and `--diff`
+//! flags uniformly.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use skills_ref::atomic_write;
+
+use super::diff::unified_diff;
+
+/// A single planned filesystem operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Create a directory and any missing parents.
+    CreateDir { path: PathBuf },
+
+    /// Create or overwrite a file with new contents.
+    WriteFile { path: PathBuf, contents: String },
+
+    /// Rename a file or directory.
+    Rename { from: PathBuf, to: PathBuf },
+
+    /// Copy a file, keeping its permission bits.
+    CopyFile { from: PathBuf, to: PathBuf },
+
+    /// Make a file executable (mode 755). Does nothing outside unix.
+    MakeExecutable { path: PathBuf },
+}
+
+/// How a [`ChangePlan`] should be applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ApplyMode {
+    /// Compute and print the changes without touching the filesystem.
+    pub dry_run: bool,
+
+    /// Print the diff of each change while applying it.
+    pub show_diff: bool,
+}
+
+/// An ordered list of filesystem changes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangePlan {
+    changes: Vec<Change>,
+}
+
+impl ChangePlan {
+    /// Create an empty plan.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a change to the plan.
+    pub fn push(&mut self, change: Change) {
+        self.changes.push(change);
+    }
+
+    /// Render the plan as unified diffs and directory operation notes.
+    ///
+    /// File writes are diffed against the file's current contents, read at
+    /// render time.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for change in &self.changes {
+            match change {
+                Change::CreateDir { path } => {
+                    out.push_str(&format!("mkdir {}\n", path.display()));
+                }
+                Change::WriteFile { path, contents } => {
+                    let label = path.display().to_string();
+                    let (old, old_label) = match std::fs::read_to_string(path) {
+                        Ok(old) => (old, label.clone()),
+                        Err(_) => (String::new(), "/dev/null".to_string()),
+                    };
+                    out.push_str(&unified_diff(&old, contents, &old_label, &label));
+                }
+                Change::Rename { from, to } => {
+                    out.push_str(&format!("rename {} -> {}\n", from.display(), to.display()));
+                }
+                Change::CopyFile { from, to } => {
+                    out.push_str(&format!("copy {} -> {}\n", from.display(), to.display()));
+                }
+                Change::MakeExecutable { path } => {
+                    out.push_str(&format!("chmod 755 {}\n", path.display()));
+                }
+            }
+        }
+        out
+    }
+
+    /// Apply the plan according to `mode`.
+    ///
+    /// With `dry_run`, the rendered plan is printed and nothing is written.
+    /// With `show_diff`, the rendered plan is printed before applying it.
+    pub fn apply(&self, mode: ApplyMode) -> io::Result<()> {
+        if mode.dry_run || mode.show_diff {
+            out!("{}", self.render());
+        }
+        if mode.dry_run {
+            return Ok(());
+        }
+
+        for change in &self.changes {
+            match change {
+                Change::CreateDir { path } => std::fs::create_dir_all(path)?,
+                Change::WriteFile { path, contents } => atomic_write(path, contents)?,
+                Change::Rename { from, to } => std::fs::rename(from, to)?,
+                Change::CopyFile { from, to } => {
+                    std::fs::copy(from, to)?;
+                }
+                Change::MakeExecutable { path } => make_executable(path)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Set the mode of `path` to 755.
+#[cfg(unix)]
+fn make_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+}
+
+/// Unix permission bits have no equivalent here.
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_dry_run_writes_nothing() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("SKILL.md");
+        std::fs::write(&file, "old\n").unwrap();
+
+        let mut plan = ChangePlan::new();
+        plan.push(Change::WriteFile {
+            path: file.clone(),
+            contents: "new\n".to_string(),
+        });
+        plan.push(Change::Rename {
+            from: file.clone(),
+            to: dir.path().join("skill.md"),
+        });
+        plan.apply(ApplyMode {
+            dry_run: true,
+            show_diff: false,
+        })
+        .unwrap();
+
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "old\n");
+        assert!(!dir.path().join("skill.md").exists());
+    }
+
+    #[test]
+    fn test_apply_writes_changes() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("SKILL.md");
+        std::fs::write(&file, "old\n").unwrap();
+
+        let mut plan = ChangePlan::new();
+        plan.push(Change::WriteFile {
+            path: file.clone(),
+            contents: "new\n".to_string(),
+        });
+        plan.push(Change::Rename {
+            from: dir.path().join("SKILL.md"),
+            to: dir.path().join("skill.md"),
+        });
+        plan.apply(ApplyMode::default()).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("skill.md")).unwrap(),
+            "new\n"
+        );
+    }
+
+    #[test]
+    fn test_render_plan() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("SKILL.md");
+        std::fs::write(&file, "a\nold\n").unwrap();
+
+        let mut plan = ChangePlan::new();
+        plan.push(Change::WriteFile {
+            path: file.clone(),
+            contents: "a\nnew\n".to_string(),
+        });
+        plan.push(Change::Rename {
+            from: PathBuf::from("x"),
+            to: PathBuf::from("y"),
+        });
+
+        let rendered = plan.render();
+        assert!(rendered.contains("-old\n+new\n"));
+        assert!(rendered.ends_with("rename x -> y\n"));
+    }
+}
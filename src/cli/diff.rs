@@ -0,0 +1,203 @@
+//! Minimal line-based unified diff rendering.
+
+/// Number of unchanged lines shown around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// A single step of a line edit script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Compute an edit script turning `old` into `new` using a longest common
+/// subsequence table. Common prefix and suffix lines are stripped first so
+/// the quadratic table only covers the changed region.
+fn edit_script(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    // lcs[i][j] = length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops: Vec<Op> = (0..prefix).map(|i| Op::Equal(i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push(Op::Equal(prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(Op::Delete(prefix + i));
+            i += 1;
+        } else {
+            ops.push(Op::Insert(prefix + j));
+            j += 1;
+        }
+    }
+    for k in 0..suffix {
+        ops.push(Op::Equal(old.len() - suffix + k, new.len() - suffix + k));
+    }
+    ops
+}
+
+/// Render a unified diff between two texts.
+///
+/// Returns an empty string when the texts are identical.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = edit_script(&old_lines, &new_lines);
+
+    let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+
+    let changed: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, Op::Equal(..)))
+        .map(|(i, _)| i)
+        .collect();
+    if changed.is_empty() {
+        // Only trailing-newline differences remain.
+        out.push_str("@@ -0,0 +0,0 @@\n\\ Line endings differ\n");
+        return out;
+    }
+
+    // Group changes whose context windows overlap into hunks.
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &idx in &changed {
+        let start = idx.saturating_sub(CONTEXT_LINES);
+        let end = (idx + CONTEXT_LINES + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    for (start, end) in hunks {
+        let slice = &ops[start..end];
+        let old_start = slice.iter().find_map(|op| match op {
+            Op::Equal(i, _) | Op::Delete(i) => Some(*i),
+            Op::Insert(_) => None,
+        });
+        let new_start = slice.iter().find_map(|op| match op {
+            Op::Equal(_, j) | Op::Insert(j) => Some(*j),
+            Op::Delete(_) => None,
+        });
+        let old_count = slice
+            .iter()
+            .filter(|op| !matches!(op, Op::Insert(_)))
+            .count();
+        let new_count = slice
+            .iter()
+            .filter(|op| !matches!(op, Op::Delete(_)))
+            .count();
+
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        ));
+        for op in slice {
+            match *op {
+                Op::Equal(i, _) => out.push_str(&format!(" {}\n", old_lines[i])),
+                Op::Delete(i) => out.push_str(&format!("-{}\n", old_lines[i])),
+                Op::Insert(j) => out.push_str(&format!("+{}\n", new_lines[j])),
+            }
+        }
+    }
+
+    out
+}
+
+/// Format a hunk range as `start,count` (1-based, `0,0` when empty).
+fn hunk_range(start: Option<usize>, count: usize) -> String {
+    match start {
+        Some(start) if count > 0 => format!("{},{}", start + 1, count),
+        _ => "0,0".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_texts() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "a", "b"), "");
+    }
+
+    #[test]
+    fn test_single_line_change() {
+        let old = "---\nname: old-name\ndescription: A skill\n---\n# Body\n";
+        let new = "---\nname: new-name\ndescription: A skill\n---\n# Body\n";
+        let diff = unified_diff(old, new, "a/SKILL.md", "b/SKILL.md");
+        assert_eq!(
+            diff,
+            "--- a/SKILL.md\n\
+             +++ b/SKILL.md\n\
+             @@ -1,5 +1,5 @@\n \
+             ---\n\
+             -name: old-name\n\
+             +name: new-name\n \
+             description: A skill\n \
+             ---\n \
+             # Body\n"
+        );
+    }
+
+    #[test]
+    fn test_insertion_and_deletion() {
+        let old = "a\nb\nc\n";
+        let new = "a\nc\nd\n";
+        let diff = unified_diff(old, new, "old", "new");
+        assert_eq!(diff, "--- old\n+++ new\n@@ -1,3 +1,3 @@\n a\n-b\n c\n+d\n");
+    }
+
+    #[test]
+    fn test_separate_hunks() {
+        let old: String = (1..=20).map(|i| format!("{}\n", i)).collect();
+        let new: String = (1..=20)
+            .map(|i| match i {
+                2 => "two\n".to_string(),
+                19 => "nineteen\n".to_string(),
+                _ => format!("{}\n", i),
+            })
+            .collect();
+        let diff = unified_diff(&old, &new, "old", "new");
+        assert_eq!(diff.matches("@@ ").count(), 2);
+        assert!(diff.contains("@@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n"));
+        assert!(diff.contains("@@ -16,5 +16,5 @@\n 16\n 17\n 18\n-19\n+nineteen\n 20\n"));
+    }
+
+    #[test]
+    fn test_new_file() {
+        let diff = unified_diff("", "a\nb\n", "/dev/null", "b/SKILL.md");
+        assert_eq!(
+            diff,
+            "--- /dev/null\n+++ b/SKILL.md\n@@ -0,0 +1,2 @@\n+a\n+b\n"
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! The `doctor` subcommand: check skill roots and catalog health.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use skills_ref::workspace::{path_length_findings, validate_skill_dirs};
+use skills_ref::{ValidationConfig, WorkspaceReport, find_skills};
+
+/// Outcome of one doctor check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// Nothing to do.
+    Ok,
+    /// Worth a look, but not broken.
+    Warning,
+    /// Broken.
+    Error,
+}
+
+/// One line of a doctor report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    /// Outcome of the check.
+    pub status: CheckStatus,
+
+    /// What was found.
+    pub message: String,
+}
+
+impl Check {
+    fn new(status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Everything `doctor` found, by output section.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DoctorReport {
+    /// One check per root: whether it can be read and how many skills it
+    /// holds.
+    pub roots: Vec<Check>,
+
+    /// Validation of all discovered skills, including problems shared by
+    /// many of them.
+    pub catalog: Vec<Check>,
+
+    /// Suggested commands and fixes, most useful first.
+    pub next_steps: Vec<String>,
+}
+
+impl DoctorReport {
+    /// Check if no check failed.
+    pub fn is_healthy(&self) -> bool {
+        self.roots
+            .iter()
+            .chain(&self.catalog)
+            .all(|c| c.status != CheckStatus::Error)
+    }
+
+    /// Render the report as sectioned text.
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        for (title, checks) in [("Roots", &self.roots), ("Catalog", &self.catalog)] {
+            if checks.is_empty() {
+                continue;
+            }
+            out.push_str(title);
+            out.push('\n');
+            for check in checks {
+                let label = match check.status {
+                    CheckStatus::Ok => "ok",
+                    CheckStatus::Warning => "warning",
+                    CheckStatus::Error => "error",
+                };
+                out.push_str(&format!("  {:<8} {}\n", label, check.message));
+            }
+        }
+        if self.next_steps.is_empty() {
+            out.push_str("\nNo problems found.\n");
+        } else {
+            out.push_str("\nNext steps\n");
+            for step in &self.next_steps {
+                out.push_str(&format!("  - {}\n", step));
+            }
+        }
+        out
+    }
+}
+
+/// Check `roots`, then discover and validate every skill under them.
+pub fn doctor(roots: &[PathBuf], config: &ValidationConfig) -> DoctorReport {
+    let mut report = DoctorReport::default();
+    let mut skill_dirs = Vec::new();
+    let mut path_findings = Vec::new();
+
+    for root in roots {
+        match root_skills(root) {
+            Ok(found) if found.is_empty() => {
+                report.roots.push(Check::new(
+                    CheckStatus::Warning,
+                    format!("{}: no skills found", root.display()),
+                ));
+                report.next_steps.push(format!(
+                    "{} has no SKILL.md files; point doctor at the directory that contains your skill directories",
+                    root.display()
+                ));
+            }
+            Ok(found) => {
+                report.roots.push(Check::new(
+                    CheckStatus::Ok,
+                    format!("{}: {} skill(s)", root.display(), found.len()),
+                ));
+                path_findings.extend(path_length_findings(root, &found));
+                skill_dirs.extend(found);
+            }
+            Err(problem) => {
+                report.roots.push(Check::new(
+                    CheckStatus::Error,
+                    format!("{}: {}", root.display(), problem),
+                ));
+                report
+                    .next_steps
+                    .push(format!("Fix or remove the root {}", root.display()));
+            }
+        }
+    }
+
+    if skill_dirs.is_empty() {
+        return report;
+    }
+    let mut workspace = validate_skill_dirs(&skill_dirs, config);
+    workspace.findings.extend(path_findings);
+    catalog_checks(&workspace, &mut report);
+    report
+}
+
+/// The skills under `root`, or why it cannot be scanned.
+fn root_skills(root: &Path) -> Result<Vec<PathBuf>, String> {
+    if !root.exists() {
+        return Err("does not exist".to_string());
+    }
+    if !root.is_dir() {
+        return Err("is not a directory".to_string());
+    }
+    if let Err(e) = std::fs::read_dir(root) {
+        return Err(format!("cannot be read: {}", e));
+    }
+    find_skills(root).map_err(|e| format!("cannot be scanned: {}", e))
+}
+
+/// Summarize validation results and point out rules that many skills fail.
+fn catalog_checks(workspace: &WorkspaceReport, report: &mut DoctorReport) {
+    let total = workspace.skills.len();
+    let failed = workspace.failed_count();
+    if failed == 0 {
+        report.catalog.push(Check::new(
+            CheckStatus::Ok,
+            format!("all {} skill(s) are valid", total),
+        ));
+    } else {
+        report.catalog.push(Check::new(
+            CheckStatus::Error,
+            format!("{} of {} skill(s) are invalid", failed, total),
+        ));
+    }
+
+    // Skills failing each error rule, counting each skill once per rule.
+    let mut failing: BTreeMap<&str, usize> = BTreeMap::new();
+    for skill in &workspace.skills {
+        let mut rules: Vec<&str> = skill
+            .diagnostics
+            .iter()
+            .filter(|d| d.is_error())
+            .map(|d| d.rule.as_str())
+            .collect();
+        rules.sort_unstable();
+        rules.dedup();
+        for rule in rules {
+            *failing.entry(rule).or_default() += 1;
+        }
+    }
+    for (rule, count) in &failing {
+        if *count >= 2 && count * 2 >= total {
+            let mut message = format!("{} of {} skills fail {}", count, total, rule);
+            if let Some(hint) = systemic_hint(rule) {
+                message.push_str(" - ");
+                message.push_str(hint);
+            }
+            report
+                .catalog
+                .push(Check::new(CheckStatus::Warning, message));
+        }
+    }
+
+    for finding in &workspace.findings {
+        let status = if finding.diagnostic.is_error() {
+            CheckStatus::Error
+        } else {
+            CheckStatus::Warning
+        };
+        report.catalog.push(Check::new(
+            status,
+            format!(
+                "{} ({})",
+                finding.diagnostic.message, finding.diagnostic.rule
+            ),
+        ));
+    }
+
+    if let Some(first) = workspace.skills.iter().find(|s| !s.is_valid()) {
+        report.next_steps.push(format!(
+            "Run `skills-ref explain {}` to see how to fix a failing skill",
+            first.path.display()
+        ));
+    }
+    if let Some(rule) = failing.keys().next() {
+        report.next_steps.push(format!(
+            "Run `skills-ref rules --explain {}` to read about a failing rule",
+            rule
+        ));
+    }
+    if workspace.findings.iter().any(|f| f.diagnostic.is_error()) {
+        report.next_steps.push(
+            "Run `skills-ref validate-all` on each root to see which skills clash".to_string(),
+        );
+    }
+}
+
+/// Likely cause of a rule failing across much of a catalog.
+fn systemic_hint(rule: &str) -> Option<&'static str> {
+    match rule {
+        "name-directory-match" => Some(
+            "are you using a versioned layout (pdf/1.2.0/SKILL.md)? Consider --name-match-ancestors 2",
+        ),
+        "unexpected-fields" => Some(
+            "move client-specific fields under metadata, or pass --allow-x-fields if they are x- prefixed",
+        ),
+        "skill-md-missing" | "skill-file" => {
+            Some("check that each skill directory has a SKILL.md with frontmatter")
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_skill(dir: &Path, name: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {}\ndescription: d\n---\n", name),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_doctor_finds_systemic_problems() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("skills");
+        for name in ["pdf", "csv", "xlsx"] {
+            write_skill(&root.join(name).join("1.0.0"), name);
+        }
+        write_skill(&root.join("docx"), "docx");
+        let empty = dir.path().join("empty");
+        std::fs::create_dir_all(&empty).unwrap();
+        let missing = dir.path().join("missing");
+
+        let report = doctor(
+            &[root.clone(), empty.clone(), missing.clone()],
+            &ValidationConfig::default(),
+        );
+        assert!(!report.is_healthy());
+        assert_eq!(
+            report.roots,
+            [
+                Check::new(CheckStatus::Ok, format!("{}: 4 skill(s)", root.display())),
+                Check::new(
+                    CheckStatus::Warning,
+                    format!("{}: no skills found", empty.display())
+                ),
+                Check::new(
+                    CheckStatus::Error,
+                    format!("{}: does not exist", missing.display())
+                ),
+            ]
+        );
+        assert_eq!(report.catalog[0].message, "3 of 4 skill(s) are invalid");
+        assert_eq!(report.catalog[1].status, CheckStatus::Warning);
+        assert!(report.catalog[1].message.starts_with(
+            "3 of 4 skills fail name-directory-match - are you using a versioned layout"
+        ));
+        assert_eq!(report.next_steps.len(), 4);
+        assert!(report.render_text().contains("\nNext steps\n  - "));
+    }
+
+    #[test]
+    fn test_doctor_healthy_catalog() {
+        let dir = TempDir::new().unwrap();
+        write_skill(&dir.path().join("pdf"), "pdf");
+
+        let report = doctor(&[dir.path().to_path_buf()], &ValidationConfig::default());
+        assert!(report.is_healthy());
+        assert_eq!(
+            report.render_text(),
+            format!(
+                "Roots\n  ok       {}: 1 skill(s)\nCatalog\n  ok       all 1 skill(s) are valid\n\nNo problems found.\n",
+                dir.path().display()
+            )
+        );
+    }
+}
@@ -0,0 +1,372 @@
+//! The `explain` subcommand: validation diagnostics with guidance.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use skills_ref::models::is_extension_field;
+use skills_ref::ordering;
+use skills_ref::validator::{ALLOWED_FIELDS, rule_info};
+use skills_ref::{
+    Diagnostic, Param, ValidationConfig, find_skill_md, parse_frontmatter, slugify,
+    validate_detailed,
+};
+use unicode_normalization::UnicodeNormalization;
+
+/// Indentation for the lines under each numbered diagnostic.
+const INDENT: &str = "   ";
+
+/// Number of characters of an over-long value's excess shown.
+const TAIL_PREVIEW: usize = 40;
+
+/// Everything `explain` knows about the skill being explained.
+struct Context<'a> {
+    dir_name: &'a str,
+    metadata: HashMap<String, serde_yaml::Value>,
+    config: &'a ValidationConfig,
+}
+
+impl Context<'_> {
+    fn str_field(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).and_then(|v| v.as_str())
+    }
+
+    /// The name as the validator sees it: trimmed and NFKC-normalized.
+    fn name(&self) -> Option<String> {
+        self.str_field("name")
+            .map(|name| name.trim().nfkc().collect())
+    }
+
+    fn conventional(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .get("metadata")
+            .and_then(|m| m.get(key))
+            .and_then(|v| v.as_str())
+    }
+}
+
+/// Explain every validation problem in the skill at `skill_dir`.
+///
+/// # Returns
+///
+/// Tuple of (rendered explanation, number of problems).
+pub fn explain(skill_dir: &Path, config: &ValidationConfig) -> (String, usize) {
+    let diagnostics: Vec<Diagnostic> = validate_detailed(skill_dir, config)
+        .into_iter()
+        .filter(Diagnostic::is_error)
+        .collect();
+    let metadata = find_skill_md(skill_dir)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| parse_frontmatter(&content).ok())
+        .map(|(metadata, _)| metadata)
+        .unwrap_or_default();
+    let dir_name = skill_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let context = Context {
+        dir_name,
+        metadata,
+        config,
+    };
+
+    let mut out = String::new();
+    for (i, diagnostic) in diagnostics.iter().enumerate() {
+        out.push_str(&format!("{}. {}\n", i + 1, diagnostic.message));
+        if let Some(rule) = rule_info(&diagnostic.rule) {
+            out.push_str(&format!("{}Rule: {} - {}\n", INDENT, rule.id, rule.summary));
+            out.push_str(&format!("{}Why: {}\n", INDENT, rule.description));
+        }
+        for line in details(diagnostic, &context) {
+            out.push_str(&format!("{}{}\n", INDENT, line));
+        }
+        out.push('\n');
+    }
+    (out, diagnostics.len())
+}
+
+/// Show `value` with a caret under the character at `offset`.
+fn highlight(value: &str, offset: usize, note: &str) -> Vec<String> {
+    const LABEL: &str = "Value: ";
+    vec![
+        format!("{}{}", LABEL, value),
+        format!("{}^ {}", " ".repeat(LABEL.len() + offset), note),
+    ]
+}
+
+/// Describe the character at `offset` for a highlight note.
+fn describe_char(c: char, offset: usize) -> String {
+    format!("'{}' (U+{:04X}) at offset {}", c, c as u32, offset)
+}
+
+/// The `limit` and `actual` params of a diagnostic from a rule that
+/// bounds a number.
+fn limit_and_actual(diagnostic: &Diagnostic) -> Option<(usize, usize)> {
+    let limit = diagnostic.number_param("limit")?;
+    let actual = diagnostic.number_param("actual")?;
+    Some((limit as usize, actual as usize))
+}
+
+/// Length, limit, and a preview of the excess for an over-long value.
+fn over_length(value: &str, limit: usize, actual: usize) -> Vec<String> {
+    let cut = value
+        .char_indices()
+        .nth(limit)
+        .map_or(value.len(), |(offset, _)| offset);
+    let tail: String = value[cut..].chars().take(TAIL_PREVIEW).collect();
+    let ellipsis = if value[cut..].chars().count() > TAIL_PREVIEW {
+        "..."
+    } else {
+        ""
+    };
+    vec![
+        format!(
+            "Length: {} chars, limit {} ({} over)",
+            actual,
+            limit,
+            actual.saturating_sub(limit)
+        ),
+        format!("Excess: {:?}{}", tail, ellipsis),
+    ]
+}
+
+/// Value details and a suggested fix for one diagnostic.
+fn details(diagnostic: &Diagnostic, cx: &Context) -> Vec<String> {
+    let name = cx.name().unwrap_or_default();
+    let slug_fix = || match slugify(&name) {
+        slug if slug.is_empty() => {
+            "Fix: choose a name made of lowercase letters, digits, and hyphens".to_string()
+        }
+        slug => format!("Fix: name: {}", slug),
+    };
+
+    let mut lines = Vec::new();
+    match diagnostic.rule.as_str() {
+        "name-length" => {
+            if let Some((limit, actual)) = limit_and_actual(diagnostic) {
+                let tail: String = name.chars().skip(limit).collect();
+                lines.push(format!("Length: {} chars, limit {}", actual, limit));
+                lines.push(format!("Excess: {:?}", tail));
+            }
+            lines.push(slug_fix());
+        }
+        "name-lowercase" => {
+            if let Some((offset, c)) = name.chars().enumerate().find(|(_, c)| c.is_uppercase()) {
+                lines.extend(highlight(&name, offset, &describe_char(c, offset)));
+            }
+            lines.push(slug_fix());
+        }
+        "name-hyphen-boundary" => {
+            let offset = if name.starts_with('-') {
+                0
+            } else {
+                name.chars().count() - 1
+            };
+            lines.extend(highlight(&name, offset, "hyphen at the edge"));
+            lines.push(slug_fix());
+        }
+        "name-consecutive-hyphens" => {
+            if let Some(byte) = name.find("--") {
+                let offset = name[..byte].chars().count();
+                lines.extend(highlight(
+                    &name,
+                    offset,
+                    &format!("'--' at offset {}", offset),
+                ));
+            }
+            lines.push(slug_fix());
+        }
+        "name-characters" => {
+            if let Some((offset, c)) = name
+                .chars()
+                .enumerate()
+                .find(|(_, c)| !c.is_alphanumeric() && *c != '-')
+            {
+                lines.extend(highlight(&name, offset, &describe_char(c, offset)));
+            }
+            lines.push(slug_fix());
+        }
+        "name-normalization" => {
+            if let Some(Param::Text(normalized)) = diagnostic.params.get("normalized") {
+                lines.push(format!("Fix: name: {}", normalized));
+            }
+        }
+        "name-directory-match" => {
+            lines.push(format!("Directory: {}", cx.dir_name));
+            lines.push(format!("Name:      {}", name));
+            lines.push(format!(
+                "Fix: rename the directory to '{}', or set name: {}",
+                name,
+                slugify(cx.dir_name)
+            ));
+        }
+        "description-length" => {
+            if let (Some(description), Some((limit, actual))) =
+                (cx.str_field("description"), limit_and_actual(diagnostic))
+            {
+                lines.extend(over_length(description, limit, actual));
+                lines.push(format!(
+                    "Fix: shorten the description by at least {} chars and move detail into the SKILL.md body",
+                    actual - limit
+                ));
+            }
+        }
+        "compatibility-length" => {
+            if let (Some(compatibility), Some((limit, actual))) =
+                (cx.str_field("compatibility"), limit_and_actual(diagnostic))
+            {
+                lines.extend(over_length(compatibility.trim(), limit, actual));
+                lines.push(format!(
+                    "Fix: shorten the compatibility note by at least {} chars",
+                    actual - limit
+                ));
+            }
+        }
+        "metadata-depth" => {
+            if let Some((limit, actual)) = limit_and_actual(diagnostic) {
+                lines.push(format!("Depth: {} levels, limit {}", actual, limit));
+            }
+            lines.push(
+                "Fix: flatten the value, or move the structure into a resource file".to_string(),
+            );
+        }
+        "unexpected-fields" => {
+            let mut extra: Vec<(&String, &serde_yaml::Value)> = cx
+                .metadata
+                .iter()
+                .filter(|(key, _)| {
+                    let allowed = ALLOWED_FIELDS.contains(&key.as_str())
+                        || (cx.config.allow_x_prefixed_fields && is_extension_field(key));
+                    !allowed
+                })
+                .collect();
+            extra.sort_by(|a, b| ordering::compare(a.0, b.0));
+            lines.push(
+                "Fix: move client-specific values under metadata, or remove them:".to_string(),
+            );
+            lines.push("  metadata:".to_string());
+            for (key, value) in extra {
+                let value = serde_yaml::to_string(value).unwrap_or_default();
+                lines.push(format!("    {}: {}", key, value.trim_end()));
+            }
+        }
+        "required-field" => {
+            if diagnostic.message.ends_with("name") {
+                lines.push(format!("Fix: add name: {}", slugify(cx.dir_name)));
+            } else {
+                lines.push(
+                    "Fix: add description: <what the skill does and when to use it>".to_string(),
+                );
+            }
+        }
+        "field-type" => {
+            lines.push("Fix: write the value as non-empty text; quote it if it looks like a number or boolean".to_string());
+        }
+        "metadata-email-format" => {
+            if let Some(email) = cx.conventional("maintainer-email") {
+                lines.push(format!("Value: {}", email));
+            }
+            lines.push("Fix: use an address of the form name@example.com".to_string());
+        }
+        "metadata-url-format" => {
+            if let Some(url) = cx.conventional("homepage") {
+                lines.push(format!("Value: {}", url));
+            }
+            lines.push("Fix: use an absolute URL starting with https://".to_string());
+        }
+        "path-missing" => {
+            lines.push("Fix: check the path for typos, or drop it from the list".to_string());
+        }
+        "path-not-directory" => {
+            lines.push("Fix: pass the directory that contains SKILL.md".to_string());
+        }
+        "skill-md-missing" | "skill-file" => {
+            lines.push("Fix: create SKILL.md starting with a frontmatter block:".to_string());
+            if let Some(rule) = rule_info("skill-file") {
+                for line in rule.passing_example.lines() {
+                    lines.push(format!("  {}", line));
+                }
+            }
+        }
+        _ => {}
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_explain_snapshot() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            format!(
+                "---\nname: My_Skill\ndescription: {}\nauthor: Jane\n---\n# Body\n",
+                "d".repeat(1020) + "THE EXCESS TAIL"
+            ),
+        )
+        .unwrap();
+
+        let (text, count) = explain(&skill_dir, &ValidationConfig::default());
+        assert_eq!(count, 5);
+        assert_eq!(text, SNAPSHOT);
+    }
+
+    #[test]
+    fn test_explain_valid_skill() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: my-skill\ndescription: d\n---\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            explain(&skill_dir, &ValidationConfig::default()),
+            (String::new(), 0)
+        );
+    }
+
+    const SNAPSHOT: &str = r#"1. Unexpected fields in frontmatter: author. Only ["allowed-tools", "compatibility", "description", "license", "metadata", "name"] are allowed.
+   Rule: unexpected-fields - Frontmatter may only contain fields defined by the spec
+   Why: The Agent Skills spec defines a fixed set of top-level frontmatter fields. Anything else is rejected so that typos (e.g. `descripton`) are caught instead of silently ignored. Client-specific values belong under `metadata`.
+   Fix: move client-specific values under metadata, or remove them:
+     metadata:
+       author: Jane
+
+2. Skill name 'My_Skill' contains invalid characters. Only letters, digits, and hyphens are allowed.
+   Rule: name-characters - Skill names may only contain letters, digits, and hyphens
+   Why: Names may use Unicode letters and digits plus hyphens. Underscores, spaces, dots, and punctuation are rejected.
+   Value: My_Skill
+            ^ '_' (U+005F) at offset 2
+   Fix: name: my-skill
+
+3. Directory name 'my-skill' must match skill name 'My_Skill'
+   Rule: name-directory-match - Skill names must match their directory name
+   Why: Clients locate skills by directory, so the `name` field must equal the name of the directory containing SKILL.md (after Unicode normalization).
+   Directory: my-skill
+   Name:      My_Skill
+   Fix: rename the directory to 'My_Skill', or set name: my-skill
+
+4. Skill name 'My_Skill' must be lowercase
+   Rule: name-lowercase - Skill names must be lowercase
+   Why: Names are compared case-sensitively by clients and must match the directory name, so they are required to be lowercase to avoid ambiguity across case-insensitive file systems.
+   Value: My_Skill
+          ^ 'M' (U+004D) at offset 0
+   Fix: name: my-skill
+
+5. Description exceeds 1024 character limit (1035 chars)
+   Rule: description-length - Descriptions must be at most 1024 characters
+   Why: Descriptions are embedded in every agent prompt, so they are limited to 1024 characters. Move detail into the SKILL.md body.
+   Length: 1035 chars, limit 1024 (11 over)
+   Excess: "EXCESS TAIL"
+   Fix: shorten the description by at least 11 chars and move detail into the SKILL.md body
+
+"#;
+}
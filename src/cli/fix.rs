@@ -0,0 +1,75 @@
+//! Output of `fmt` and `lint --fix` over a tree of skills.
+
+use skills_ref::{FixReport, FixStatus};
+
+use super::changes::{ApplyMode, Change, ChangePlan};
+use super::output;
+
+/// Write the files `report` changes according to `mode`, then print the
+/// report: as JSON with `json`, else one line per file and a summary.
+///
+/// Returns whether every file ended up changed or clean.
+pub fn finish(mut report: FixReport, mode: ApplyMode, json: bool) -> bool {
+    if mode.dry_run || mode.show_diff {
+        let mut plan = ChangePlan::new();
+        for file in &report.files {
+            if let Some(contents) = &file.edited {
+                plan.push(Change::WriteFile {
+                    path: file.path.clone(),
+                    contents: contents.clone(),
+                });
+            }
+        }
+        out!("{}", plan.render());
+    }
+    if !mode.dry_run {
+        report.write();
+    }
+
+    if json {
+        output::print_json(&serde_json::json!({
+            "files": report.files,
+            "summary": report.summary(),
+        }));
+        return report.is_success();
+    }
+
+    for file in &report.files {
+        let path = file.path.display();
+        match file.status {
+            FixStatus::Changed => outln!(
+                "changed: {} ({} fixed, {} bytes)",
+                path,
+                file.fixed.len(),
+                file.bytes_changed
+            ),
+            FixStatus::Clean => outln!("clean: {}", path),
+            FixStatus::WriteFailed => errln!("Error: {}", file.error.as_deref().unwrap_or("")),
+            _ => {
+                outln!("unfixable: {} ({} fixed)", path, file.fixed.len());
+                for finding in &file.remaining {
+                    let location = match finding.line {
+                        Some(line) => format!("{}:{}", path, line),
+                        None => path.to_string(),
+                    };
+                    outln!(
+                        "  {}: {} [{}] {}",
+                        location,
+                        finding.diagnostic.severity,
+                        finding.diagnostic.rule,
+                        finding.diagnostic.message
+                    );
+                }
+            }
+        }
+    }
+    let summary = report.summary();
+    outln!(
+        "{} changed, {} clean, {} unfixable, {} write failed",
+        summary.changed,
+        summary.clean,
+        summary.unfixable,
+        summary.write_failed
+    );
+    report.is_success()
+}
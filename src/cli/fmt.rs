@@ -0,0 +1,127 @@
+//! The `fmt` subcommand: reflow the paragraphs of a skill body and
+//! convert its frontmatter syntax.
+
+use std::path::Path;
+
+use skills_ref::{
+    FrontmatterSyntax, Reflow, convert_frontmatter, find_skill_md, reflow_skill_md,
+    resolve_skill_dir,
+};
+
+use super::changes::{Change, ChangePlan};
+
+/// A `--wrap` value: a width, or `None` for `off`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WrapWidth(pub Option<usize>);
+
+/// Parse a `--wrap` value: a width, or `off` to leave paragraphs alone.
+pub fn parse_wrap(value: &str) -> Result<WrapWidth, String> {
+    if value.eq_ignore_ascii_case("off") {
+        return Ok(WrapWidth(None));
+    }
+    match value.parse::<usize>() {
+        Ok(0) | Err(_) => Err(format!(
+            "expected a width of at least 1 or 'off', got '{}'",
+            value
+        )),
+        Ok(width) => Ok(WrapWidth(Some(width))),
+    }
+}
+
+/// Build the plan that reflows the body of the skill at `skill_path` and,
+/// with `syntax` set, converts its frontmatter to that syntax.
+///
+/// Without `syntax` the frontmatter is never changed. With both `mode` and
+/// `syntax` `None` the plan is empty.
+pub fn plan_fmt(
+    skill_path: &Path,
+    mode: Option<Reflow>,
+    syntax: Option<FrontmatterSyntax>,
+) -> Result<ChangePlan, String> {
+    let skill_dir = resolve_skill_dir(skill_path).map_err(|e| e.to_string())?;
+    let skill_md = find_skill_md(&skill_dir)
+        .ok_or_else(|| format!("SKILL.md not found in {}", skill_dir.display()))?;
+    let content = std::fs::read_to_string(&skill_md).map_err(|e| e.to_string())?;
+
+    let mut new_content = match syntax {
+        Some(syntax) => convert_frontmatter(&content, syntax).map_err(|e| e.to_string())?,
+        None => content.clone(),
+    };
+    if let Some(mode) = mode {
+        new_content = reflow_skill_md(&new_content, mode).map_err(|e| e.to_string())?;
+    }
+
+    let mut plan = ChangePlan::new();
+    if new_content != content {
+        plan.push(Change::WriteFile {
+            path: skill_md,
+            contents: new_content,
+        });
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::changes::ApplyMode;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_wrap() {
+        assert_eq!(parse_wrap("80"), Ok(WrapWidth(Some(80))));
+        assert_eq!(parse_wrap("off"), Ok(WrapWidth(None)));
+        assert!(parse_wrap("0").is_err());
+        assert!(parse_wrap("wide").is_err());
+    }
+
+    #[test]
+    fn test_plan_fmt_rewrites_body_only_once() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("pdf");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        let content = "---\nname: pdf\ndescription: d\n---\nFill the form\nand save it.\n";
+        std::fs::write(skill_dir.join("SKILL.md"), content).unwrap();
+
+        plan_fmt(&skill_dir, Some(Reflow::Unwrap), None)
+            .unwrap()
+            .apply(ApplyMode::default())
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(skill_dir.join("SKILL.md")).unwrap(),
+            "---\nname: pdf\ndescription: d\n---\nFill the form and save it.\n"
+        );
+        assert_eq!(
+            plan_fmt(&skill_dir, Some(Reflow::Unwrap), None).unwrap(),
+            ChangePlan::new()
+        );
+        assert_eq!(plan_fmt(&skill_dir, None, None).unwrap(), ChangePlan::new());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_plan_fmt_converts_syntax() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("pdf");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        let content = "---\nname: pdf\ndescription: d\n---\nFill the form\nand save it.\n";
+        std::fs::write(skill_dir.join("SKILL.md"), content).unwrap();
+
+        plan_fmt(
+            &skill_dir,
+            Some(Reflow::Unwrap),
+            Some(FrontmatterSyntax::Toml),
+        )
+        .unwrap()
+        .apply(ApplyMode::default())
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(skill_dir.join("SKILL.md")).unwrap(),
+            "+++\nname = \"pdf\"\ndescription = \"d\"\n+++\nFill the form and save it.\n"
+        );
+        assert_eq!(
+            plan_fmt(&skill_dir, None, Some(FrontmatterSyntax::Toml)).unwrap(),
+            ChangePlan::new()
+        );
+    }
+}
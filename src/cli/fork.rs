@@ -0,0 +1,252 @@
+//! The `fork` subcommand: derive a new skill from a copy of an existing one.
+
+use std::path::{Path, PathBuf};
+
+use skills_ref::{
+    find_skill_md, ordering, parse_frontmatter, reformat_frontmatter, resolve_skill_dir,
+    update_frontmatter, validate_metadata,
+};
+
+use super::changes::{Change, ChangePlan};
+use super::rename::rewrite_name;
+
+/// Options for [`plan_fork`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ForkOptions<'a> {
+    /// Parent directory for the new skill; defaults to the source's parent.
+    pub dir: Option<&'a Path>,
+
+    /// Copy only SKILL.md, leaving resources behind.
+    pub no_resources: bool,
+}
+
+/// Remove `metadata.version` from the parsed frontmatter, in place where
+/// the layout allows it and by re-emitting the frontmatter otherwise (TOML,
+/// or a flow-style `metadata: {...}` mapping).
+fn clear_metadata_version(content: &str) -> Result<String, String> {
+    let (metadata, _) = parse_frontmatter(content).map_err(|e| e.to_string())?;
+    if metadata
+        .get("metadata")
+        .and_then(|m| m.get("version"))
+        .is_none()
+    {
+        return Ok(content.to_string());
+    }
+    update_frontmatter(content, "metadata.version", None)
+        .or_else(|_| reformat_frontmatter(content, "metadata.version", None))
+        .map_err(|e| e.to_string())
+}
+
+/// Build the plan that forks the skill at `source` into a new skill named
+/// `new_name`.
+///
+/// The new directory gets a copy of every resource file (unless
+/// `no_resources` is set) and a SKILL.md with the new `name` and without
+/// `metadata.version`. The result is validated before anything is planned,
+/// and an existing target directory is refused.
+pub fn plan_fork(
+    source: &Path,
+    new_name: &str,
+    options: ForkOptions,
+) -> Result<(PathBuf, ChangePlan), String> {
+    let skill_dir = resolve_skill_dir(source).map_err(|e| e.to_string())?;
+    let parent = match options.dir {
+        Some(dir) => dir.to_path_buf(),
+        None => skill_dir
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from(".")),
+    };
+    let new_dir = parent.join(new_name);
+    if new_dir.exists() {
+        return Err(format!("Target already exists: {}", new_dir.display()));
+    }
+
+    let skill_md = find_skill_md(&skill_dir)
+        .ok_or_else(|| format!("SKILL.md not found in {}", skill_dir.display()))?;
+    let content = std::fs::read_to_string(&skill_md).map_err(|e| e.to_string())?;
+    let new_content = rewrite_name(&content, new_name)
+        .ok_or_else(|| format!("No 'name' line found in {}", skill_md.display()))?;
+    let new_content = clear_metadata_version(&new_content)?;
+
+    let (metadata, _) = parse_frontmatter(&new_content).map_err(|e| e.to_string())?;
+    let errors = validate_metadata(&metadata, Some(&new_dir));
+    if !errors.is_empty() {
+        return Err(format!(
+            "Forked skill would be invalid: {}",
+            errors.join("; ")
+        ));
+    }
+
+    let mut plan = ChangePlan::new();
+    plan.push(Change::CreateDir {
+        path: new_dir.clone(),
+    });
+    if !options.no_resources {
+        plan_copies(&skill_dir, &new_dir, &skill_md, &mut plan).map_err(|e| e.to_string())?;
+    }
+    plan.push(Change::WriteFile {
+        path: new_dir.join(skill_md.file_name().unwrap_or("SKILL.md".as_ref())),
+        contents: new_content,
+    });
+
+    Ok((new_dir, plan))
+}
+
+/// Plan copies of every file under `from` into `to`, except `skill_md`.
+///
+/// Symlinks are not followed.
+pub fn plan_copies(
+    from: &Path,
+    to: &Path,
+    skill_md: &Path,
+    plan: &mut ChangePlan,
+) -> std::io::Result<()> {
+    let mut entries: Vec<_> = std::fs::read_dir(from)?.collect::<std::io::Result<_>>()?;
+    entries.sort_by(|a, b| {
+        ordering::compare(
+            &a.file_name().to_string_lossy(),
+            &b.file_name().to_string_lossy(),
+        )
+    });
+    for entry in entries {
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+
+        if file_type.is_dir() {
+            plan.push(Change::CreateDir {
+                path: target.clone(),
+            });
+            plan_copies(&path, &target, skill_md, plan)?;
+        } else if file_type.is_file() && path != skill_md {
+            plan.push(Change::CopyFile {
+                from: path,
+                to: target,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::changes::ApplyMode;
+    use tempfile::TempDir;
+
+    fn source_skill(dir: &TempDir) -> PathBuf {
+        let skill_dir = dir.path().join("pdf-tools");
+        std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: pdf-tools\ndescription: d\nmetadata:\n  author: Jane\n  version: \"1.2\"\n---\n# Body\n",
+        )
+        .unwrap();
+        std::fs::write(skill_dir.join("scripts/run.sh"), "#!/bin/sh\n").unwrap();
+        skill_dir
+    }
+
+    #[test]
+    fn test_clear_metadata_version() {
+        assert_eq!(
+            clear_metadata_version(
+                "---\nname: a\nmetadata:\n  version: |\n    1\n  b: c\n---\nversion: body\n"
+            )
+            .unwrap(),
+            "---\nname: a\nmetadata:\n  b: c\n---\nversion: body\n"
+        );
+        assert_eq!(
+            clear_metadata_version(
+                "---\nname: a\nmetadata:\n  version: \"1\"\ndescription: d\n---\n"
+            )
+            .unwrap(),
+            "---\nname: a\ndescription: d\n---\n"
+        );
+
+        // Flow-style mappings are re-emitted without the version.
+        let content = clear_metadata_version(
+            "---\nname: a\ndescription: d\nmetadata: {version: \"1.0\", author: Jane}\n---\nBody\n",
+        )
+        .unwrap();
+        let (metadata, body) = parse_frontmatter(&content).unwrap();
+        assert!(metadata["metadata"].get("version").is_none(), "{}", content);
+        assert_eq!(metadata["metadata"]["author"], "Jane");
+        assert_eq!(body, "Body");
+        let content = clear_metadata_version(
+            "---\nname: a\ndescription: d\nmetadata: {version: \"1.0\"}\n---\n",
+        )
+        .unwrap();
+        let (metadata, _) = parse_frontmatter(&content).unwrap();
+        assert!(!metadata.contains_key("metadata"), "{}", content);
+    }
+
+    #[test]
+    fn test_plan_fork_copies_resources() {
+        let dir = TempDir::new().unwrap();
+        let source = source_skill(&dir);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let script = source.join("scripts/run.sh");
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let (new_dir, plan) = plan_fork(&source, "pdf-forms", ForkOptions::default()).unwrap();
+        plan.apply(ApplyMode::default()).unwrap();
+
+        assert_eq!(new_dir, dir.path().join("pdf-forms"));
+        assert!(skills_ref::validate(&new_dir).is_empty());
+        let props = skills_ref::read_properties(&new_dir).unwrap();
+        assert_eq!(props.name, "pdf-forms");
+        assert_eq!(props.metadata_value("version"), None);
+        assert_eq!(props.author(), Some("Jane"));
+        assert!(source.join("SKILL.md").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(new_dir.join("scripts/run.sh"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+    }
+
+    #[test]
+    fn test_plan_fork_without_resources() {
+        let dir = TempDir::new().unwrap();
+        let source = source_skill(&dir);
+        let target = dir.path().join("out");
+        let options = ForkOptions {
+            dir: Some(&target),
+            no_resources: true,
+        };
+
+        let (new_dir, plan) = plan_fork(&source, "pdf-forms", options).unwrap();
+        plan.apply(ApplyMode::default()).unwrap();
+
+        assert_eq!(new_dir, target.join("pdf-forms"));
+        assert!(new_dir.join("SKILL.md").exists());
+        assert!(!new_dir.join("scripts").exists());
+    }
+
+    #[test]
+    fn test_plan_fork_refuses_existing_target() {
+        let dir = TempDir::new().unwrap();
+        let source = source_skill(&dir);
+        std::fs::create_dir(dir.path().join("pdf-forms")).unwrap();
+
+        let err = plan_fork(&source, "pdf-forms", ForkOptions::default()).unwrap_err();
+        assert!(err.contains("already exists"));
+    }
+
+    #[test]
+    fn test_plan_fork_rejects_invalid_name() {
+        let dir = TempDir::new().unwrap();
+        let source = source_skill(&dir);
+
+        let err = plan_fork(&source, "PDF_Forms", ForkOptions::default()).unwrap_err();
+        assert!(err.contains("would be invalid"));
+    }
+}
@@ -0,0 +1,391 @@
+//! The `init` subcommand: scaffold a new skill directory.
+
+use std::path::{Path, PathBuf};
+
+use skills_ref::{
+    Manifest, NameAvailability, SkillProperties, ValidationConfig, find_skill_md,
+    is_name_available, validate_content_at, validate_properties,
+};
+
+use super::changes::{Change, ChangePlan};
+use super::fork::plan_copies;
+use super::templates::{TemplateSource, fill_skill_md};
+
+/// Description written when none is given.
+const PLACEHOLDER_DESCRIPTION: &str = "TODO: Describe what this skill does and when to use it.";
+
+/// Number of alternative names suggested when a name is taken.
+const SUGGESTIONS: usize = 3;
+
+/// Check `name` against the skills in `manifest`.
+fn check_manifest(name: &str, manifest: &Manifest) -> Result<(), String> {
+    let conflict = match is_name_available(name, manifest) {
+        NameAvailability::Available => return Ok(()),
+        NameAvailability::Taken { existing } => {
+            format!("Skill name '{}' is already taken by '{}'", name, existing)
+        }
+        NameAvailability::Confusable { existing } => {
+            format!("Skill name '{}' is confusable with '{}'", name, existing)
+        }
+    };
+
+    let suggestions = manifest.suggest_names(name, SUGGESTIONS);
+    if suggestions.is_empty() {
+        Err(conflict)
+    } else {
+        Err(format!("{}; try: {}", conflict, suggestions.join(", ")))
+    }
+}
+
+/// Build the plan that creates a new skill at `skill_dir`.
+///
+/// The name defaults to the directory name. If a manifest is given, the
+/// name must not be taken by or confusable with a skill listed in it. An
+/// `id` is written as `metadata.id`. The files come from `template`, and
+/// the filled-in SKILL.md must pass validation.
+pub fn plan_init(
+    skill_dir: &Path,
+    name: Option<&str>,
+    description: Option<&str>,
+    manifest: Option<&Manifest>,
+    id: Option<&str>,
+    template: TemplateSource,
+) -> Result<(String, ChangePlan), String> {
+    let dir_name = skill_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid skill directory: {}", skill_dir.display()))?;
+    let name = name.unwrap_or(dir_name).to_string();
+    let description = description.unwrap_or(PLACEHOLDER_DESCRIPTION);
+
+    let messages: Vec<String> = validate_properties(
+        &SkillProperties::new(name.as_str(), description),
+        Some(dir_name),
+    )
+    .into_iter()
+    .filter(|d| d.is_error())
+    .map(|d| d.message)
+    .collect();
+    if !messages.is_empty() {
+        return Err(format!(
+            "Invalid skill name '{}': {}",
+            name,
+            messages.join("; ")
+        ));
+    }
+
+    if let Some(manifest) = manifest {
+        check_manifest(&name, manifest)?;
+    }
+
+    if skill_dir.is_file() {
+        return Err(format!("Target is a file: {}", skill_dir.display()));
+    }
+    if find_skill_md(skill_dir).is_some() {
+        return Err(format!("Skill already exists: {}", skill_dir.display()));
+    }
+
+    let mut plan = ChangePlan::new();
+    if !skill_dir.exists() {
+        plan.push(Change::CreateDir {
+            path: skill_dir.to_path_buf(),
+        });
+    }
+    let template_md = match template {
+        TemplateSource::Builtin(template) => {
+            for (path, contents) in template.files {
+                let path = skill_dir.join(path);
+                if let Some(parent) = path.parent().filter(|p| *p != skill_dir) {
+                    plan.push(Change::CreateDir {
+                        path: parent.to_path_buf(),
+                    });
+                }
+                plan.push(Change::WriteFile {
+                    path: path.clone(),
+                    contents: contents.to_string(),
+                });
+                if path.starts_with(skill_dir.join("scripts")) {
+                    plan.push(Change::MakeExecutable { path });
+                }
+            }
+            template.skill_md.to_string()
+        }
+        TemplateSource::Dir(dir) => {
+            let skill_md = find_skill_md(dir)
+                .ok_or_else(|| format!("Template has no SKILL.md: {}", dir.display()))?;
+            plan_copies(dir, skill_dir, &skill_md, &mut plan).map_err(|e| e.to_string())?;
+            std::fs::read_to_string(&skill_md).map_err(|e| e.to_string())?
+        }
+    };
+
+    let contents = fill_skill_md(&template_md, &name, description, id)?;
+    let errors: Vec<String> =
+        validate_content_at(&contents, skill_dir, &ValidationConfig::default())
+            .into_iter()
+            .filter(|d| d.is_error())
+            .map(|d| d.message)
+            .collect();
+    if !errors.is_empty() {
+        return Err(format!(
+            "Template gives an invalid skill: {}",
+            errors.join("; ")
+        ));
+    }
+    plan.push(Change::WriteFile {
+        path: PathBuf::from(skill_dir).join("SKILL.md"),
+        contents,
+    });
+
+    Ok((name, plan))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skills_ref::{ManifestEntry, read_properties, validate};
+    use tempfile::TempDir;
+
+    use crate::cli::changes::ApplyMode;
+    use crate::cli::templates::{TEMPLATES, find_template};
+
+    fn manifest(names: &[&str]) -> Manifest {
+        Manifest {
+            skills: names
+                .iter()
+                .map(|name| ManifestEntry {
+                    name: name.to_string(),
+                    path: None,
+                    description: None,
+                    id: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_init_creates_valid_skill() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("pdf-tools");
+
+        let (name, plan) = plan_init(
+            &skill_dir,
+            None,
+            None,
+            None,
+            None,
+            TemplateSource::default(),
+        )
+        .unwrap();
+        assert_eq!(name, "pdf-tools");
+        plan.apply(ApplyMode::default()).unwrap();
+
+        assert!(validate(&skill_dir).is_empty());
+        let props = read_properties(&skill_dir).unwrap();
+        assert_eq!(props.description, PLACEHOLDER_DESCRIPTION);
+    }
+
+    #[test]
+    fn test_init_with_id() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("pdf-tools");
+        let id = "3f2b8c1e-9d4a-4f6b-8e2a-1c5d7e9f0a3b";
+
+        let (_, plan) = plan_init(
+            &skill_dir,
+            None,
+            None,
+            None,
+            Some(id),
+            TemplateSource::default(),
+        )
+        .unwrap();
+        plan.apply(ApplyMode::default()).unwrap();
+
+        assert!(validate(&skill_dir).is_empty());
+        assert_eq!(read_properties(&skill_dir).unwrap().stable_id(), Some(id));
+    }
+
+    #[test]
+    fn test_init_refuses_existing_skill() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("pdf-tools");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skill_dir.join("SKILL.md"), "x").unwrap();
+
+        let err = plan_init(
+            &skill_dir,
+            None,
+            None,
+            None,
+            None,
+            TemplateSource::default(),
+        )
+        .unwrap_err();
+        assert!(err.contains("already exists"));
+    }
+
+    #[test]
+    fn test_init_checks_manifest() {
+        let dir = TempDir::new().unwrap();
+        let manifest = manifest(&["pdf-tools", "pdf-tools-2"]);
+
+        let err = plan_init(
+            &dir.path().join("pdf-tools"),
+            None,
+            None,
+            Some(&manifest),
+            None,
+            TemplateSource::default(),
+        )
+        .unwrap_err();
+        assert!(err.contains("already taken by 'pdf-tools'"));
+        assert!(err.contains("try: pdf-tools-3, pdf-tools-4, pdf-tools-5"));
+
+        let err = plan_init(
+            &dir.path().join("pdf-t00ls"),
+            None,
+            None,
+            Some(&manifest),
+            None,
+            TemplateSource::default(),
+        )
+        .unwrap_err();
+        assert!(err.contains("confusable with 'pdf-tools'"));
+
+        assert!(
+            plan_init(
+                &dir.path().join("docx"),
+                None,
+                None,
+                Some(&manifest),
+                None,
+                TemplateSource::default()
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_every_template_gives_valid_skill() {
+        for template in TEMPLATES {
+            let dir = TempDir::new().unwrap();
+            let skill_dir = dir.path().join("pdf-tools");
+
+            let (_, plan) = plan_init(
+                &skill_dir,
+                None,
+                Some("Fill PDF forms: text fields and checkboxes"),
+                None,
+                None,
+                TemplateSource::Builtin(template),
+            )
+            .unwrap();
+            plan.apply(ApplyMode::default()).unwrap();
+
+            assert!(validate(&skill_dir).is_empty(), "{}", template.name);
+            let props = read_properties(&skill_dir).unwrap();
+            assert_eq!(props.name, "pdf-tools", "{}", template.name);
+            for (path, _) in template.files {
+                assert!(
+                    skill_dir.join(path).is_file(),
+                    "{}: {}",
+                    template.name,
+                    path
+                );
+            }
+            let content = std::fs::read_to_string(skill_dir.join("SKILL.md")).unwrap();
+            assert!(!content.contains("{{"), "{}", template.name);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_template_scripts_are_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("pdf-tools");
+        let (_, plan) = plan_init(
+            &skill_dir,
+            None,
+            None,
+            None,
+            None,
+            TemplateSource::Builtin(find_template("script").unwrap()),
+        )
+        .unwrap();
+        plan.apply(ApplyMode::default()).unwrap();
+
+        let mode = std::fs::metadata(skill_dir.join("scripts/run.sh"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+
+    #[test]
+    fn test_init_from_template_dir() {
+        let dir = TempDir::new().unwrap();
+        let template_dir = dir.path().join("template");
+        std::fs::create_dir_all(template_dir.join("scripts")).unwrap();
+        std::fs::write(
+            template_dir.join("SKILL.md"),
+            "---\nname: \"{{name}}\"\ndescription: \"{{description}}\"\nlicense: MIT\n---\n# {{name}}\n\n{{description}}\n",
+        )
+        .unwrap();
+        std::fs::write(template_dir.join("scripts/fill.py"), "print('hi')\n").unwrap();
+        let skill_dir = dir.path().join("pdf-tools");
+
+        let (_, plan) = plan_init(
+            &skill_dir,
+            None,
+            Some("Fill PDF forms"),
+            None,
+            None,
+            TemplateSource::Dir(&template_dir),
+        )
+        .unwrap();
+        plan.apply(ApplyMode::default()).unwrap();
+
+        assert!(validate(&skill_dir).is_empty());
+        let props = read_properties(&skill_dir).unwrap();
+        assert_eq!(props.description, "Fill PDF forms");
+        assert_eq!(props.license.as_deref(), Some("MIT"));
+        let content = std::fs::read_to_string(skill_dir.join("SKILL.md")).unwrap();
+        assert!(
+            content.ends_with("# pdf-tools\n\nFill PDF forms\n"),
+            "{}",
+            content
+        );
+        assert_eq!(
+            std::fs::read_to_string(skill_dir.join("scripts/fill.py")).unwrap(),
+            "print('hi')\n"
+        );
+
+        let err = plan_init(
+            &dir.path().join("docx"),
+            None,
+            None,
+            None,
+            None,
+            TemplateSource::Dir(&skill_dir.join("scripts")),
+        )
+        .unwrap_err();
+        assert!(err.starts_with("Template has no SKILL.md"), "{}", err);
+    }
+
+    #[test]
+    fn test_init_rejects_invalid_name() {
+        let dir = TempDir::new().unwrap();
+        let err = plan_init(
+            &dir.path().join("PDF_Tools"),
+            None,
+            None,
+            None,
+            None,
+            TemplateSource::default(),
+        )
+        .unwrap_err();
+        assert!(err.contains("Invalid skill name"));
+    }
+}
@@ -0,0 +1,65 @@
+//! Text rendering for the `inspect` subcommand.
+
+use skills_ref::InspectionReport;
+use skills_ref::ordering;
+
+/// Render an inspection report for humans.
+pub fn render_text(report: &InspectionReport) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("Path: {}\n", report.path.display()));
+    if let Some(digest) = &report.digest {
+        out.push_str(&format!("Digest: {}\n", digest));
+    }
+
+    if let Some(props) = &report.properties {
+        out.push_str("\nProperties:\n");
+        let mut fields: Vec<_> = props.to_dict().into_iter().collect();
+        fields.sort_by(|a, b| ordering::compare(&a.0, &b.0));
+        for (key, value) in fields {
+            match value {
+                serde_json::Value::String(s) => out.push_str(&format!("  {}: {}\n", key, s)),
+                other => out.push_str(&format!("  {}: {}\n", key, other)),
+            }
+        }
+    }
+
+    let errors = report.diagnostics.iter().filter(|d| d.is_error()).count();
+    if report.valid {
+        out.push_str("\nValidation: valid\n");
+    } else {
+        out.push_str(&format!("\nValidation: {} error(s)\n", errors));
+    }
+    for diagnostic in &report.diagnostics {
+        out.push_str(&format!(
+            "  {} [{}] {}\n",
+            diagnostic.severity, diagnostic.rule, diagnostic.message
+        ));
+    }
+
+    let total: u64 = report.resources.iter().map(|r| r.size).sum();
+    out.push_str(&format!(
+        "\nResources: {} file(s), {} bytes\n",
+        report.resources.len(),
+        total
+    ));
+    for resource in &report.resources {
+        out.push_str(&format!("  {:>8}  {}\n", resource.size, resource.path));
+    }
+
+    if let Some(body) = &report.body {
+        out.push_str(&format!(
+            "\nBody: {} lines, {} words, ~{} tokens\n",
+            body.lines, body.words, body.estimated_tokens
+        ));
+        for heading in &body.headings {
+            out.push_str(&format!(
+                "  {}{} {}\n",
+                "  ".repeat(heading.level - 1),
+                "#".repeat(heading.level),
+                heading.text
+            ));
+        }
+    }
+
+    out
+}
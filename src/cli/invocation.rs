@@ -0,0 +1,34 @@
+//! Text rendering for the `manifest-one` subcommand.
+
+use skills_ref::InvocationManifest;
+
+/// Render an invocation manifest for humans.
+pub fn render_text(manifest: &InvocationManifest) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("Name: {}\n", manifest.name));
+    out.push_str(&format!("SKILL.md: {}\n", manifest.skill_md.display()));
+    out.push_str(&format!("Digest: {}\n", manifest.digest));
+
+    if manifest.allowed_tools.is_empty() {
+        out.push_str("Allowed tools: (none)\n");
+    } else {
+        let tools: Vec<String> = manifest
+            .allowed_tools
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        out.push_str(&format!("Allowed tools: {}\n", tools.join(" ")));
+    }
+
+    out.push_str(&format!("\nResources ({}):\n", manifest.resources.len()));
+    for resource in &manifest.resources {
+        out.push_str(&format!("  {} ({} bytes)\n", resource.path, resource.size));
+    }
+    if !manifest.excluded.is_empty() {
+        out.push_str(&format!("\nExcluded ({}):\n", manifest.excluded.len()));
+        for excluded in &manifest.excluded {
+            out.push_str(&format!("  {}: {}\n", excluded.path, excluded.reason));
+        }
+    }
+    out
+}
@@ -0,0 +1,50 @@
+//! Text rendering for the `list` subcommand.
+
+use skills_ref::SkillSet;
+
+/// Render one line per skill: its name and directory, with deprecated
+/// skills flagged.
+pub fn render_text(set: &SkillSet) -> String {
+    let mut out = String::new();
+    for skill in set {
+        out.push_str(&format!("{}  {}", skill.name(), skill.dir.display()));
+        if skill.properties.is_deprecated() {
+            out.push_str("  [deprecated]");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skills_ref::Skill;
+    use tempfile::TempDir;
+
+    fn load_skill(dir: &TempDir, name: &str, metadata: &str) -> Skill {
+        let skill_dir = dir.path().join(name);
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            format!("---\nname: {name}\ndescription: d\n{metadata}---\n"),
+        )
+        .unwrap();
+        Skill::load(&skill_dir).unwrap()
+    }
+
+    #[test]
+    fn test_render_text_flags_deprecated() {
+        let dir = TempDir::new().unwrap();
+        let set = SkillSet::new(vec![
+            load_skill(&dir, "pdf", "metadata:\n  deprecated: \"true\"\n"),
+            load_skill(&dir, "pdf-tools", ""),
+        ]);
+
+        let text = render_text(&set);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("pdf  ") && lines[0].ends_with("  [deprecated]"));
+        assert!(lines[1].starts_with("pdf-tools  ") && !lines[1].contains("deprecated"));
+    }
+}
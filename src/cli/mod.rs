@@ -0,0 +1,22 @@
+//! Shared building blocks for CLI subcommands.
+
+pub mod changes;
+pub mod diff;
+pub mod doctor;
+pub mod explain;
+pub mod fix;
+pub mod fmt;
+pub mod fork;
+pub mod init;
+pub mod inspect;
+pub mod invocation;
+pub mod list;
+pub mod output;
+pub mod preview;
+pub mod rename;
+pub mod report;
+pub mod self_test;
+pub mod set;
+pub mod set_field;
+pub mod stats;
+pub mod templates;
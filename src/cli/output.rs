@@ -0,0 +1,59 @@
+//! Output with `--redact-paths` applied.
+//!
+//! Text goes through the `outln!`, `out!`, and `errln!` macros defined in
+//! `main.rs`, which redact the formatted line. JSON is redacted value by
+//! value before serialization, so escaping is unaffected. Prompt XML is
+//! redacted by the library before escaping and printed as is.
+
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use skills_ref::PathRedaction;
+
+static REDACTION: OnceLock<PathRedaction> = OnceLock::new();
+
+/// Redact all later output with `redaction`. Only the first call has an
+/// effect.
+pub fn set_redaction(redaction: PathRedaction) {
+    let _ = REDACTION.set(redaction);
+}
+
+/// The redaction set with `--redact-paths`, if any.
+pub fn redaction() -> Option<&'static PathRedaction> {
+    REDACTION.get()
+}
+
+/// Redact `text`, or return it unchanged without `--redact-paths`.
+pub fn redact(text: &str) -> String {
+    match redaction() {
+        Some(redaction) => redaction.redact(text),
+        None => text.to_string(),
+    }
+}
+
+/// Print `value` as pretty JSON, redacting every string in it.
+///
+/// Redacted output goes through `serde_json::Value`, whose objects sort
+/// their keys; unredacted output keeps the field order of `value`.
+pub fn print_json<T: Serialize + ?Sized>(value: &T) {
+    println!("{}", to_json(value));
+}
+
+/// Write `value` to `path` as pretty JSON, redacted as by [`print_json`].
+pub fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> io::Result<()> {
+    std::fs::write(path, to_json(value) + "\n")
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> String {
+    let json = match redaction() {
+        Some(redaction) => {
+            let mut json = serde_json::to_value(value).unwrap();
+            redaction.redact_json(&mut json);
+            serde_json::to_string_pretty(&json)
+        }
+        None => serde_json::to_string_pretty(value),
+    };
+    json.unwrap()
+}
@@ -0,0 +1,215 @@
+//! Terminal layout for the `preview` subcommand.
+//!
+//! The generated prompt is shown line by line with the XML tags
+//! highlighted, each skill's size in a left margin, and a footer comparing
+//! the total size with an optional budget.
+
+use skills_ref::estimate_tokens;
+
+/// Width of the size margin, including the separator.
+const MARGIN_WIDTH: usize = 16;
+
+const TAG: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// How to lay out a preview.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreviewOptions {
+    /// Terminal width in columns.
+    pub width: usize,
+
+    /// Emit ANSI colors.
+    pub color: bool,
+
+    /// Size budget for the whole prompt, in characters.
+    pub budget: Option<usize>,
+}
+
+impl PreviewOptions {
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Split `line` into chunks of at most `width` characters. An empty line
+/// yields one empty chunk.
+pub fn wrap(line: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let chars: Vec<char> = line.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars
+        .chunks(width)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+/// Margin text for a skill of `chars` characters, e.g. `1234c ~309t`.
+fn size_label(chars: usize, tokens: usize) -> String {
+    format!("{}c ~{}t", chars, tokens)
+}
+
+/// Character count of the `<skill>` element starting at line `start` of
+/// `lines`, including its inner newlines.
+fn skill_size(lines: &[&str], start: usize) -> (usize, usize) {
+    let end = lines[start..]
+        .iter()
+        .position(|line| *line == "</skill>")
+        .map_or(lines.len(), |i| start + i + 1);
+    let text = lines[start..end].join("\n");
+    (text.chars().count(), estimate_tokens(&text))
+}
+
+/// Render `xml` for the terminal.
+///
+/// Lines wider than the space left of the margin are wrapped. When the
+/// terminal is too narrow for the margin, sizes are printed on a line of
+/// their own above each skill instead.
+pub fn render_preview(xml: &str, options: &PreviewOptions) -> String {
+    let lines: Vec<&str> = xml.lines().collect();
+    let with_margin = options.width > MARGIN_WIDTH + 8;
+    let content_width = if with_margin {
+        options.width - MARGIN_WIDTH
+    } else {
+        options.width
+    };
+
+    let mut out = String::new();
+    let mut in_name = false;
+    for (i, line) in lines.iter().enumerate() {
+        let label = (*line == "<skill>").then(|| {
+            let (chars, tokens) = skill_size(&lines, i);
+            size_label(chars, tokens)
+        });
+        if let (Some(label), false) = (&label, with_margin) {
+            out.push_str(&options.paint(BOLD, &format!("# {}", label)));
+            out.push('\n');
+        }
+
+        let is_tag = line.starts_with('<') && line.ends_with('>');
+        for (j, chunk) in wrap(line, content_width).iter().enumerate() {
+            if with_margin {
+                let margin = match (&label, j) {
+                    (Some(label), 0) => label.as_str(),
+                    _ => "",
+                };
+                let margin = format!("{:>width$} │ ", margin, width = MARGIN_WIDTH - 3);
+                out.push_str(&options.paint(BOLD, &margin));
+            }
+            let style = if is_tag {
+                Some(TAG)
+            } else if in_name {
+                Some(BOLD)
+            } else {
+                None
+            };
+            match style {
+                Some(style) => out.push_str(&options.paint(style, chunk)),
+                None => out.push_str(chunk),
+            }
+            out.push('\n');
+        }
+
+        if is_tag {
+            in_name = *line == "<name>";
+        }
+    }
+
+    out.push_str(&footer(xml, options));
+    out
+}
+
+/// Total size line, compared with the budget if there is one.
+fn footer(xml: &str, options: &PreviewOptions) -> String {
+    let total = xml.chars().count();
+    let mut out = format!("Total: {} chars, ~{} tokens", total, estimate_tokens(xml));
+    if let Some(budget) = options.budget {
+        let comparison = if total > budget {
+            options.paint(RED, &format!("over by {}", total - budget))
+        } else {
+            options.paint(GREEN, &format!("under by {}", budget - total))
+        };
+        out.push_str(&format!(" (budget {}: {})", budget, comparison));
+    }
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = "<available_skills>\n<skill>\n<name>\npdf-tools\n</name>\n<description>\nExtract text from PDF files\n</description>\n</skill>\n</available_skills>";
+
+    fn plain(width: usize, budget: Option<usize>) -> PreviewOptions {
+        PreviewOptions {
+            width,
+            color: false,
+            budget,
+        }
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("abcdef", 4), ["abcd", "ef"]);
+        assert_eq!(wrap("", 4), [""]);
+        assert_eq!(wrap("äöü", 1), ["ä", "ö", "ü"]);
+        assert_eq!(wrap("ab", 0), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_preview_with_margin() {
+        let preview = render_preview(XML, &plain(80, Some(200)));
+        let lines: Vec<&str> = preview.lines().collect();
+        assert_eq!(lines[0], "              │ <available_skills>");
+        assert_eq!(lines[1], "     98c ~25t │ <skill>");
+        assert_eq!(lines[3], "              │ pdf-tools");
+        assert_eq!(
+            lines.last().unwrap(),
+            &"Total: 137 chars, ~35 tokens (budget 200: under by 63)"
+        );
+    }
+
+    #[test]
+    fn test_preview_wraps_at_narrow_width() {
+        let preview = render_preview(XML, &plain(30, Some(100)));
+        let lines: Vec<&str> = preview.lines().collect();
+        assert!(lines.contains(&"              │ Extract text f"));
+        assert!(lines.contains(&"              │ rom PDF files"));
+        assert!(preview.ends_with("(budget 100: over by 37)\n"));
+        for line in &lines[..lines.len() - 1] {
+            assert!(line.chars().count() <= 30, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_preview_without_room_for_margin() {
+        let preview = render_preview(XML, &plain(10, None));
+        let lines: Vec<&str> = preview.lines().collect();
+        assert_eq!(lines[2], "# 98c ~25t");
+        assert_eq!(lines[3], "<skill>");
+        assert!(lines.contains(&"Extract te"));
+        assert!(lines.contains(&"xt from PD"));
+    }
+
+    #[test]
+    fn test_preview_color() {
+        let options = PreviewOptions {
+            width: 80,
+            color: true,
+            budget: Some(10),
+        };
+        let preview = render_preview(XML, &options);
+        assert!(preview.contains("\x1b[36m<name>\x1b[0m"));
+        assert!(preview.contains("\x1b[1mpdf-tools\x1b[0m"));
+        assert!(preview.contains("\x1b[31mover by 127\x1b[0m"));
+    }
+}
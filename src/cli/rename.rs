@@ -0,0 +1,188 @@
+//! The `rename` subcommand: rename a skill and its directory together.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use skills_ref::{find_skill_md, parse_frontmatter, resolve_skill_dir, validate_metadata};
+
+use super::changes::{Change, ChangePlan};
+
+/// Replace the value of the top-level `name` entry in the frontmatter:
+/// the `name:` line in YAML, or the `name = ...` line before the first
+/// table header in TOML (`+++`).
+///
+/// Returns `None` if the frontmatter has no single-line `name` entry.
+pub(super) fn rewrite_name(content: &str, new_name: &str) -> Option<String> {
+    let mut out = String::with_capacity(content.len());
+    let mut lines = content.split_inclusive('\n');
+    let first = lines.next()?;
+    out.push_str(first);
+    let toml = first.trim_end() == "+++";
+
+    let mut replaced = false;
+    let mut in_frontmatter = true;
+    for line in lines {
+        if in_frontmatter && line.trim_end() == first.trim_end() {
+            in_frontmatter = false;
+        } else if toml && line.starts_with('[') {
+            // Keys after a table header belong to that table.
+            in_frontmatter = false;
+        } else if in_frontmatter && !replaced && is_name_line(line, toml) {
+            let ending = &line[line.trim_end_matches(['\n', '\r']).len()..];
+            if toml {
+                let quoted = new_name.replace('\\', "\\\\").replace('"', "\\\"");
+                out.push_str(&format!("name = \"{}\"{}", quoted, ending));
+            } else {
+                out.push_str(&format!("name: {}{}", new_name, ending));
+            }
+            replaced = true;
+            continue;
+        }
+        out.push_str(line);
+    }
+
+    replaced.then_some(out)
+}
+
+/// Whether `line` is a top-level `name` entry.
+fn is_name_line(line: &str, toml: bool) -> bool {
+    if toml {
+        line.split_once('=')
+            .is_some_and(|(key, _)| matches!(key.trim(), "name" | "\"name\""))
+    } else {
+        line.starts_with("name:")
+    }
+}
+
+/// Build the plan that renames the skill at `skill_path` to `new_name`.
+///
+/// The plan rewrites the frontmatter `name` and renames the directory so
+/// the two keep matching.
+pub fn plan_rename(skill_path: &Path, new_name: &str) -> Result<(PathBuf, ChangePlan), String> {
+    let skill_dir = resolve_skill_dir(skill_path).map_err(|e| e.to_string())?;
+    let parent = skill_dir
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let new_dir = parent.join(new_name);
+
+    // Check the new name against the name rules in isolation.
+    let mut probe = HashMap::new();
+    probe.insert("name".to_string(), serde_yaml::Value::from(new_name));
+    probe.insert("description".to_string(), serde_yaml::Value::from("-"));
+    let errors = validate_metadata(&probe, Some(&new_dir));
+    if !errors.is_empty() {
+        return Err(format!(
+            "Invalid skill name '{}': {}",
+            new_name,
+            errors.join("; ")
+        ));
+    }
+
+    let skill_md = find_skill_md(&skill_dir)
+        .ok_or_else(|| format!("SKILL.md not found in {}", skill_dir.display()))?;
+    let content = std::fs::read_to_string(&skill_md).map_err(|e| e.to_string())?;
+    parse_frontmatter(&content).map_err(|e| e.to_string())?;
+    let new_content = rewrite_name(&content, new_name)
+        .ok_or_else(|| format!("No 'name' line found in {}", skill_md.display()))?;
+
+    let mut plan = ChangePlan::new();
+    plan.push(Change::WriteFile {
+        path: skill_md,
+        contents: new_content,
+    });
+    if skill_dir.file_name() != new_dir.file_name() {
+        if new_dir.exists() {
+            return Err(format!("Target already exists: {}", new_dir.display()));
+        }
+        plan.push(Change::Rename {
+            from: skill_dir,
+            to: new_dir.clone(),
+        });
+    }
+
+    Ok((new_dir, plan))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::changes::ApplyMode;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rewrite_name_preserves_other_lines() {
+        let content = "---\n# comment\nname: old\r\ndescription: d\n---\nname: body\n";
+        let rewritten = rewrite_name(content, "new").unwrap();
+        assert_eq!(
+            rewritten,
+            "---\n# comment\nname: new\r\ndescription: d\n---\nname: body\n"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_name_toml() {
+        let content = "+++\n# comment\nname = \"old\"\n\n[metadata]\nname = \"x\"\n+++\n";
+        assert_eq!(
+            rewrite_name(content, "new").unwrap(),
+            "+++\n# comment\nname = \"new\"\n\n[metadata]\nname = \"x\"\n+++\n"
+        );
+        assert!(rewrite_name("+++\n[metadata]\nname = \"x\"\n+++\n", "new").is_none());
+    }
+
+    #[test]
+    fn test_rewrite_name_missing() {
+        assert!(rewrite_name("---\ndescription: d\n---\n", "new").is_none());
+    }
+
+    #[test]
+    fn test_plan_rename_renames_directory() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("old-name");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: old-name\ndescription: d\n---\n",
+        )
+        .unwrap();
+
+        let (new_dir, plan) = plan_rename(&skill_dir, "new-name").unwrap();
+        plan.apply(ApplyMode::default()).unwrap();
+
+        assert!(!skill_dir.exists());
+        assert!(skills_ref::validate(&new_dir).is_empty());
+    }
+
+    #[test]
+    fn test_plan_rename_rejects_invalid_name() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("old-name");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: old-name\ndescription: d\n---\n",
+        )
+        .unwrap();
+
+        let err = plan_rename(&skill_dir, "New_Name").unwrap_err();
+        assert!(err.contains("Invalid skill name"));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_plan_rename_toml_frontmatter() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("old-name");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "+++\nname = \"old-name\"\ndescription = \"d\"\n+++\n",
+        )
+        .unwrap();
+
+        let (new_dir, plan) = plan_rename(&skill_dir, "new-name").unwrap();
+        plan.apply(ApplyMode::default()).unwrap();
+
+        assert!(skills_ref::validate(&new_dir).is_empty());
+    }
+}
@@ -0,0 +1,183 @@
+//! Porcelain output for `validate`, `validate-all`, and `list`.
+//!
+//! Porcelain output is for scripts. Its format only changes with a major
+//! version bump, whatever happens to the wording of the human-oriented
+//! output. Every line is a record of tab-separated fields, and the first
+//! field says what kind of record it is:
+//!
+//! ```text
+//! valid     <path>
+//! invalid   <path>  <rule-id>  <message>
+//! warning   <path>  <rule-id>  <message>
+//! info      <path>  <rule-id>  <message>
+//! skill     <name>  <path>     active|deprecated
+//! ```
+//!
+//! `validate` and `validate-all` print a `valid` record for each skill
+//! without errors and an `invalid` record for each error, followed by the
+//! `warning` and `info` records of that skill. Problems between skills
+//! found by `validate-all` come last, one record per skill involved. `list`
+//! prints one `skill` record per skill.
+//!
+//! In every field, a backslash is written as `\\`, a tab as `\t`, a
+//! newline as `\n`, and a carriage return as `\r`, so a record is always
+//! one line with a fixed number of fields. Records of kinds added later
+//! can be skipped by their first field.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use skills_ref::workspace::{SkillValidation, WorkspaceFinding};
+use skills_ref::{Diagnostic, Severity, SkillSet};
+
+/// Escape a field so it contains no tabs or line breaks.
+pub fn escape(field: &str) -> Cow<'_, str> {
+    if !field.contains(['\\', '\t', '\n', '\r']) {
+        return Cow::Borrowed(field);
+    }
+    let mut out = String::with_capacity(field.len() + 8);
+    for c in field.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Append one record of escaped `fields`.
+fn push_record(out: &mut String, fields: &[&str]) {
+    let fields: Vec<Cow<str>> = fields.iter().map(|f| escape(f)).collect();
+    out.push_str(&fields.join("\t"));
+    out.push('\n');
+}
+
+/// Append the record for one diagnostic about `path`.
+fn push_diagnostic(out: &mut String, path: &Path, diagnostic: &Diagnostic) {
+    let kind = match diagnostic.severity {
+        Severity::Error => "invalid",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+    };
+    push_record(
+        out,
+        &[
+            kind,
+            &path.to_string_lossy(),
+            &diagnostic.rule,
+            &diagnostic.message,
+        ],
+    );
+}
+
+/// Render validation results: each skill's records in order, then one
+/// record per skill involved in each finding.
+pub fn render_validation(skills: &[SkillValidation], findings: &[WorkspaceFinding]) -> String {
+    let mut out = String::new();
+    for skill in skills {
+        if skill.is_valid() {
+            push_record(&mut out, &["valid", &skill.path.to_string_lossy()]);
+        }
+        let (errors, notes): (Vec<_>, Vec<_>) =
+            skill.diagnostics.iter().partition(|d| d.is_error());
+        for diagnostic in errors.into_iter().chain(notes) {
+            push_diagnostic(&mut out, &skill.path, diagnostic);
+        }
+    }
+    for finding in findings {
+        for path in &finding.paths {
+            push_diagnostic(&mut out, path, &finding.diagnostic);
+        }
+    }
+    out
+}
+
+/// Render one `skill` record per skill.
+pub fn render_list(set: &SkillSet) -> String {
+    let mut out = String::new();
+    for skill in set {
+        let status = if skill.properties.is_deprecated() {
+            "deprecated"
+        } else {
+            "active"
+        };
+        push_record(
+            &mut out,
+            &["skill", skill.name(), &skill.dir.to_string_lossy(), status],
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    use skills_ref::Skill;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_escape() {
+        assert!(matches!(escape("plain text"), Cow::Borrowed(_)));
+        assert_eq!(escape("a\tb\nc\\d\re"), "a\\tb\\nc\\\\d\\re");
+    }
+
+    #[test]
+    fn test_render_validation_golden() {
+        let skills = vec![
+            SkillValidation {
+                path: PathBuf::from("skills/pdf"),
+                diagnostics: vec![Diagnostic::info("body-placeholders", "Body uses: X")],
+            },
+            SkillValidation {
+                path: PathBuf::from("skills/csv"),
+                diagnostics: vec![
+                    Diagnostic::warning("deprecated-without-message", "No message"),
+                    Diagnostic::error("name-lowercase", "Name 'CSV'\tmust be\nlowercase"),
+                ],
+            },
+        ];
+        let findings = vec![WorkspaceFinding {
+            diagnostic: Diagnostic::error("workspace-duplicate-name", "2 skills are named 'pdf'"),
+            paths: vec![PathBuf::from("a/pdf"), PathBuf::from("b/pdf")],
+        }];
+
+        assert_eq!(
+            render_validation(&skills, &findings),
+            "valid\tskills/pdf\n\
+             info\tskills/pdf\tbody-placeholders\tBody uses: X\n\
+             invalid\tskills/csv\tname-lowercase\tName 'CSV'\\tmust be\\nlowercase\n\
+             warning\tskills/csv\tdeprecated-without-message\tNo message\n\
+             invalid\ta/pdf\tworkspace-duplicate-name\t2 skills are named 'pdf'\n\
+             invalid\tb/pdf\tworkspace-duplicate-name\t2 skills are named 'pdf'\n"
+        );
+    }
+
+    #[test]
+    fn test_render_list_golden() {
+        let dir = TempDir::new().unwrap();
+        let mut skills = Vec::new();
+        for (name, metadata) in [("pdf", "metadata:\n  deprecated: \"true\"\n"), ("csv", "")] {
+            let skill_dir = dir.path().join(name);
+            std::fs::create_dir_all(&skill_dir).unwrap();
+            std::fs::write(
+                skill_dir.join("SKILL.md"),
+                format!("---\nname: {name}\ndescription: d\n{metadata}---\n"),
+            )
+            .unwrap();
+            skills.push(Skill::load(&skill_dir).unwrap());
+        }
+
+        assert_eq!(
+            render_list(&SkillSet::new(skills)),
+            format!(
+                "skill\tpdf\t{0}/pdf\tdeprecated\nskill\tcsv\t{0}/csv\tactive\n",
+                dir.path().display()
+            )
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! The `self-test` subcommand: run the pipeline against the bundled
+//! example skills.
+//!
+//! The examples are written to a scratch directory, so the checks also
+//! exercise the platform's filesystem: how it stores non-ASCII file names
+//! and whether `skill.md` is found where `SKILL.md` is expected.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use skills_ref::fixtures::{EXAMPLE_SKILLS, ExampleSkill};
+use skills_ref::{
+    OverwriteMode, Skill, ValidationConfig, parse_prompt, read_properties, to_prompt,
+    validate_detailed, write_skill,
+};
+
+/// One self-test check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SelfTestCheck {
+    /// What was checked, such as `validate pdf-forms`.
+    pub name: String,
+
+    /// Why the check failed, or `None` if it passed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Every check `self-test` ran, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SelfTestReport {
+    /// The checks.
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    /// Check if every check passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.error.is_none())
+    }
+
+    /// Render one line per check, then a summary.
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        for check in &self.checks {
+            match &check.error {
+                None => out.push_str(&format!("pass  {}\n", check.name)),
+                Some(error) => out.push_str(&format!("FAIL  {}: {}\n", check.name, error)),
+            }
+        }
+        let failed = self.checks.iter().filter(|c| c.error.is_some()).count();
+        if failed == 0 {
+            out.push_str(&format!("\nAll {} checks passed.\n", self.checks.len()));
+        } else {
+            out.push_str(&format!(
+                "\n{} of {} checks failed.\n",
+                failed,
+                self.checks.len()
+            ));
+        }
+        out
+    }
+
+    fn record(&mut self, name: String, result: Result<(), String>) {
+        self.checks.push(SelfTestCheck {
+            name,
+            error: result.err(),
+        });
+    }
+}
+
+/// Write the example skills under `scratch` and check that validating,
+/// reading, prompting, and rewriting them give the expected results.
+pub fn self_test(scratch: &Path) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+    let mut prompt_dirs: Vec<(PathBuf, &str)> = Vec::new();
+
+    for example in EXAMPLE_SKILLS {
+        let name = example.dir_name;
+        let skill_dir = match example.write_to(scratch) {
+            Ok(dir) => dir,
+            Err(e) => {
+                report.record(format!("write {}", name), Err(e.to_string()));
+                continue;
+            }
+        };
+
+        report.record(
+            format!("validate {}", name),
+            check_rules(&skill_dir, example),
+        );
+        if !example.valid {
+            continue;
+        }
+        report.record(
+            format!("read-properties {}", name),
+            read_properties(&skill_dir)
+                .map_err(|e| e.to_string())
+                .and_then(|props| {
+                    if props.name == name {
+                        Ok(())
+                    } else {
+                        Err(format!("read name '{}', expected '{}'", props.name, name))
+                    }
+                }),
+        );
+        report.record(
+            format!("round-trip {}", name),
+            round_trip(&skill_dir, &scratch.join("round-trip").join(name)),
+        );
+        prompt_dirs.push((skill_dir, name));
+    }
+
+    let dirs: Vec<&Path> = prompt_dirs.iter().map(|(dir, _)| dir.as_path()).collect();
+    report.record(
+        "to-prompt".to_string(),
+        to_prompt(&dirs)
+            .and_then(|xml| parse_prompt(&xml))
+            .map_err(|e| e.to_string())
+            .and_then(|entries| {
+                let listed: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+                let expected: Vec<&str> = prompt_dirs.iter().map(|(_, name)| *name).collect();
+                if listed == expected {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "prompt lists [{}], expected [{}]",
+                        listed.join(", "),
+                        expected.join(", ")
+                    ))
+                }
+            }),
+    );
+    report
+}
+
+/// Check that validating `skill_dir` triggers exactly the example's rules.
+fn check_rules(skill_dir: &Path, example: &ExampleSkill) -> Result<(), String> {
+    let mut rules: Vec<String> = validate_detailed(skill_dir, &ValidationConfig::default())
+        .into_iter()
+        .map(|d| d.rule)
+        .collect();
+    rules.sort_unstable();
+    rules.dedup();
+    if rules == example.rules {
+        Ok(())
+    } else {
+        Err(format!(
+            "triggered [{}], expected [{}]",
+            rules.join(", "),
+            example.rules.join(", ")
+        ))
+    }
+}
+
+/// Load the skill in `skill_dir`, write it to `copy_dir`, and check that
+/// the copy reads back with the same properties.
+fn round_trip(skill_dir: &Path, copy_dir: &Path) -> Result<(), String> {
+    let skill = Skill::load(skill_dir).map_err(|e| e.to_string())?;
+    write_skill(
+        copy_dir,
+        &skill.properties,
+        &skill.body,
+        OverwriteMode::Refuse,
+    )
+    .map_err(|e| e.to_string())?;
+    let copy = read_properties(copy_dir).map_err(|e| e.to_string())?;
+    if copy == skill.properties {
+        Ok(())
+    } else {
+        Err("properties changed when written and read back".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_self_test_passes() {
+        let dir = TempDir::new().unwrap();
+        let report = self_test(dir.path());
+        assert!(report.passed(), "{}", report.render_text());
+        // Three checks per valid example, one for the invalid one, and the
+        // prompt.
+        assert_eq!(report.checks.len(), 3 * 3 + 1 + 1);
+        assert!(report.render_text().ends_with("\nAll 11 checks passed.\n"));
+    }
+}
@@ -0,0 +1,77 @@
+//! The `set` subcommand: change one frontmatter field of a skill.
+
+use std::path::Path;
+
+use skills_ref::{find_skill_md, reformat_frontmatter, resolve_skill_dir, update_frontmatter};
+
+use super::changes::{Change, ChangePlan};
+
+/// Build the plan that sets the field at `path` to `value` in the skill at
+/// `skill_path`, or removes it if `value` is `None`.
+///
+/// Only the lines of that field change unless `reformat` is set, in which
+/// case the whole frontmatter is re-emitted in canonical form.
+pub fn plan_set(
+    skill_path: &Path,
+    path: &str,
+    value: Option<&str>,
+    reformat: bool,
+) -> Result<ChangePlan, String> {
+    let skill_dir = resolve_skill_dir(skill_path).map_err(|e| e.to_string())?;
+    let skill_md = find_skill_md(&skill_dir)
+        .ok_or_else(|| format!("SKILL.md not found in {}", skill_dir.display()))?;
+    let content = std::fs::read_to_string(&skill_md).map_err(|e| e.to_string())?;
+
+    let value = value.map(serde_json::Value::from);
+    let edit = if reformat {
+        reformat_frontmatter
+    } else {
+        update_frontmatter
+    };
+    let new_content = edit(&content, path, value.as_ref()).map_err(|e| e.to_string())?;
+
+    let mut plan = ChangePlan::new();
+    if new_content != content {
+        plan.push(Change::WriteFile {
+            path: skill_md,
+            contents: new_content,
+        });
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::changes::ApplyMode;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_plan_set_keeps_comments() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("pdf");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        let content =
+            "---\nname: pdf\n# keep short\ndescription: d\nmetadata:\n  version: \"1\"\n---\n";
+        std::fs::write(skill_dir.join("SKILL.md"), content).unwrap();
+
+        plan_set(&skill_dir, "metadata.version", Some("2"), false)
+            .unwrap()
+            .apply(ApplyMode::default())
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(skill_dir.join("SKILL.md")).unwrap(),
+            content.replace("\"1\"", "\"2\"")
+        );
+
+        plan_set(&skill_dir, "metadata.version", Some("3"), true)
+            .unwrap()
+            .apply(ApplyMode::default())
+            .unwrap();
+        assert!(
+            !std::fs::read_to_string(skill_dir.join("SKILL.md"))
+                .unwrap()
+                .contains("# keep short")
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! The `set-field` subcommand: set one frontmatter field in every skill
+//! under a root.
+
+use std::path::Path;
+
+use skills_ref::{BulkReport, EditOutcome, FieldEdit, ValidationConfig, plan_bulk_update};
+
+use super::changes::{Change, ChangePlan};
+
+/// Work out the edit for every skill under `root` and the plan that writes
+/// the changed ones.
+pub fn plan_set_field(
+    root: &Path,
+    edit: &FieldEdit,
+    config: &ValidationConfig,
+) -> Result<(BulkReport, ChangePlan), String> {
+    let report = plan_bulk_update(root, edit, config).map_err(|e| e.to_string())?;
+    let mut plan = ChangePlan::new();
+    for skill in &report.skills {
+        if let Some((path, contents)) = &skill.edited {
+            plan.push(Change::WriteFile {
+                path: path.clone(),
+                contents: contents.clone(),
+            });
+        }
+    }
+    Ok((report, plan))
+}
+
+/// Render the per-skill outcomes and totals of a bulk edit.
+pub fn render_text(report: &BulkReport) -> String {
+    let mut out = String::new();
+    for skill in &report.skills {
+        let line = match &skill.outcome {
+            EditOutcome::Changed => format!("changed  {}", skill.path.display()),
+            EditOutcome::Skipped { reason } => {
+                format!("skipped  {} ({})", skill.path.display(), reason)
+            }
+            EditOutcome::Failed { error } => {
+                format!("failed   {}: {}", skill.path.display(), error)
+            }
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out.push_str(&format!(
+        "{} changed, {} skipped, {} failed\n",
+        report.changed(),
+        report.skipped(),
+        report.failed()
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::changes::ApplyMode;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_plan_set_field() {
+        let dir = TempDir::new().unwrap();
+        for (name, extra) in [("alpha", ""), ("beta", "metadata:\n  owner: data-team\n")] {
+            let skill_dir = dir.path().join(name);
+            std::fs::create_dir_all(&skill_dir).unwrap();
+            std::fs::write(
+                skill_dir.join("SKILL.md"),
+                format!("---\nname: {}\ndescription: d\n{}---\n", name, extra),
+            )
+            .unwrap();
+        }
+        let edit = FieldEdit {
+            path: "metadata.owner".to_string(),
+            value: "platform-team".into(),
+            only_missing: true,
+        };
+
+        let (report, plan) =
+            plan_set_field(dir.path(), &edit, &ValidationConfig::default()).unwrap();
+        assert_eq!(
+            render_text(&report),
+            format!(
+                "changed  {}\nskipped  {} ('metadata.owner' is already set)\n1 changed, 1 skipped, 0 failed\n",
+                dir.path().join("alpha").display(),
+                dir.path().join("beta").display()
+            )
+        );
+
+        plan.apply(ApplyMode::default()).unwrap();
+        assert!(
+            std::fs::read_to_string(dir.path().join("alpha/SKILL.md"))
+                .unwrap()
+                .contains("metadata:\n  owner: platform-team\n")
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! Text rendering for catalog statistics.
+
+use skills_ref::CatalogStats;
+
+/// Render catalog statistics for humans.
+pub fn render_text(stats: &CatalogStats) -> String {
+    let mut out = format!(
+        "Skills: {} ({} valid, {} invalid)\n",
+        stats.skills, stats.valid, stats.invalid
+    );
+    if let Some(length) = &stats.description_length {
+        out.push_str(&format!(
+            "Description length: min {}, median {}, max {}\n",
+            length.min, length.median, length.max
+        ));
+    }
+    out.push_str(&format!("Resource bytes: {}\n", stats.resource_bytes));
+    out.push_str(&format!(
+        "With allowed-tools: {}\n",
+        stats.with_allowed_tools
+    ));
+
+    out.push_str("Licenses:\n");
+    for (license, count) in &stats.licenses {
+        out.push_str(&format!("  {:>5}  {}\n", count, license));
+    }
+
+    out.push_str("Estimated body tokens:\n");
+    for bucket in &stats.token_histogram {
+        let range = match bucket.max {
+            Some(max) => format!("{}-{}", bucket.min, max),
+            None => format!("{}+", bucket.min),
+        };
+        out.push_str(&format!("  {:>9}  {}\n", range, bucket.count));
+    }
+    out
+}
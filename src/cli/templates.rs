@@ -0,0 +1,161 @@
+//! Starting points for `init`: templates built into the binary and
+//! template directories.
+//!
+//! A template is a SKILL.md and resource files. `init` sets the `name` and
+//! `description` fields of the template's SKILL.md, then replaces the
+//! `{{name}}` and `{{description}}` placeholders in the rest of it.
+//! Resource files are copied as they are.
+
+use std::path::Path;
+
+use serde_json::json;
+use skills_ref::update_frontmatter;
+
+/// A template built into the binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Template {
+    /// Name given to `--template`.
+    pub name: &'static str,
+
+    /// One line for `--list-templates`.
+    pub summary: &'static str,
+
+    /// SKILL.md, with placeholders.
+    pub skill_md: &'static str,
+
+    /// Resource files, as paths relative to the skill directory and their
+    /// contents. Files under `scripts/` are made executable.
+    pub files: &'static [(&'static str, &'static str)],
+}
+
+/// Every built-in template, in the order `--list-templates` shows them.
+pub const TEMPLATES: &[Template] = &[
+    Template {
+        name: "minimal",
+        summary: "SKILL.md with a usage section",
+        skill_md: include_str!("../../fixtures/templates/minimal.md"),
+        files: &[],
+    },
+    Template {
+        name: "script",
+        summary: "a script in scripts/, with usage and arguments sections",
+        skill_md: include_str!("../../fixtures/templates/script.md"),
+        files: &[(
+            "scripts/run.sh",
+            include_str!("../../fixtures/templates/script-run.sh"),
+        )],
+    },
+    Template {
+        name: "reference",
+        summary: "detailed material in references/, linked from SKILL.md",
+        skill_md: include_str!("../../fixtures/templates/reference.md"),
+        files: &[(
+            "references/REFERENCE.md",
+            include_str!("../../fixtures/templates/reference-REFERENCE.md"),
+        )],
+    },
+];
+
+/// Template used when none is given.
+pub const DEFAULT_TEMPLATE: &str = "minimal";
+
+/// Where `init` takes a new skill's files from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateSource<'a> {
+    /// A built-in template.
+    Builtin(&'static Template),
+
+    /// A directory holding a SKILL.md and resource files.
+    Dir(&'a Path),
+}
+
+impl Default for TemplateSource<'_> {
+    fn default() -> Self {
+        TemplateSource::Builtin(find_template(DEFAULT_TEMPLATE).expect("default template exists"))
+    }
+}
+
+/// The built-in template called `name`.
+pub fn find_template(name: &str) -> Result<&'static Template, String> {
+    TEMPLATES
+        .iter()
+        .find(|template| template.name == name)
+        .ok_or_else(|| {
+            let names: Vec<&str> = TEMPLATES.iter().map(|t| t.name).collect();
+            format!(
+                "Unknown template '{}'; available: {}",
+                name,
+                names.join(", ")
+            )
+        })
+}
+
+/// One line per built-in template: its name and summary.
+pub fn render_list() -> String {
+    let width = TEMPLATES.iter().map(|t| t.name.len()).max().unwrap_or(0);
+    TEMPLATES
+        .iter()
+        .map(|t| {
+            let marker = if t.name == DEFAULT_TEMPLATE {
+                " (default)"
+            } else {
+                ""
+            };
+            format!("{:<width$}  {}{}\n", t.name, t.summary, marker)
+        })
+        .collect()
+}
+
+/// Fill in a template SKILL.md for the skill `name`, giving it
+/// `metadata.id` if `id` is set.
+///
+/// The fields are written with the quoting SKILL.md needs, so any name or
+/// description works; the placeholders in the rest of the file are
+/// replaced as plain text.
+pub fn fill_skill_md(
+    template: &str,
+    name: &str,
+    description: &str,
+    id: Option<&str>,
+) -> Result<String, String> {
+    let mut content = update_frontmatter(template, "name", Some(&json!(name)))
+        .and_then(|c| update_frontmatter(&c, "description", Some(&json!(description))))
+        .map_err(|e| format!("Invalid template SKILL.md: {}", e))?;
+    if let Some(id) = id {
+        content = update_frontmatter(&content, "metadata.id", Some(&json!(id)))
+            .map_err(|e| format!("Invalid template SKILL.md: {}", e))?;
+    }
+    Ok(content
+        .replace("{{name}}", name)
+        .replace("{{description}}", description))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skills_ref::parse_frontmatter;
+
+    #[test]
+    fn test_fill_skill_md() {
+        let content = fill_skill_md(
+            "---\nname: \"{{name}}\"\nlicense: MIT\n---\n# {{name}}\n\n{{description}}\n",
+            "pdf-tools",
+            "Fill forms: PDF",
+            Some("com.example.pdf-tools"),
+        )
+        .unwrap();
+        let (metadata, body) = parse_frontmatter(&content).unwrap();
+        assert_eq!(metadata["name"], "pdf-tools");
+        assert_eq!(metadata["description"], "Fill forms: PDF");
+        assert_eq!(metadata["license"], "MIT");
+        assert_eq!(metadata["metadata"]["id"], "com.example.pdf-tools");
+        assert_eq!(body, "# pdf-tools\n\nFill forms: PDF");
+
+        assert!(
+            find_template("nope")
+                .unwrap_err()
+                .contains("available: minimal, script")
+        );
+        assert!(render_list().starts_with("minimal    SKILL.md with a usage section (default)\n"));
+    }
+}
@@ -0,0 +1,93 @@
+//! Confusable-name detection.
+//!
+//! A small subset of the Unicode confusables data (UTS #39) covering the
+//! lookalikes that show up in skill names: Cyrillic and Greek letters that
+//! render like Latin ones, and digits that pass for letters.
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Lookalike characters and the Latin text they are mistaken for.
+const CONFUSABLES: &[(char, &str)] = &[
+    // Cyrillic
+    ('а', "a"),
+    ('е', "e"),
+    ('һ', "h"),
+    ('і', "i"),
+    ('ј', "j"),
+    ('к', "k"),
+    ('ӏ', "l"),
+    ('м', "rn"),
+    ('о', "o"),
+    ('р', "p"),
+    ('ԛ', "q"),
+    ('ѕ', "s"),
+    ('с', "c"),
+    ('у', "y"),
+    ('ԝ', "w"),
+    ('х', "x"),
+    ('ԁ', "d"),
+    // Greek
+    ('α', "a"),
+    ('ι', "i"),
+    ('κ', "k"),
+    ('ν', "v"),
+    ('ο', "o"),
+    ('ρ', "p"),
+    ('υ', "u"),
+    ('χ', "x"),
+    // Latin lookalikes
+    ('ı', "i"),
+    ('0', "o"),
+    ('1', "l"),
+    ('m', "rn"),
+];
+
+/// Reduce a name to its confusable skeleton.
+///
+/// The name is NFKC-normalized and lowercased, then each lookalike is
+/// replaced by the Latin text it resembles, so `pdf-tооls` (Cyrillic `о`)
+/// and `pdf-tools` share a skeleton, as do `rn` and `m`.
+pub fn skeleton(name: &str) -> String {
+    let folded = name.nfkc().collect::<String>().to_lowercase();
+    let mut out = String::with_capacity(folded.len());
+    for c in folded.chars() {
+        match CONFUSABLES.iter().find(|(from, _)| *from == c) {
+            Some((_, to)) => out.push_str(to),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// Check whether two names look alike.
+pub fn is_confusable(a: &str, b: &str) -> bool {
+    skeleton(a) == skeleton(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skeleton_cyrillic_and_greek() {
+        assert_eq!(skeleton("pdf-tооls"), "pdf-tools");
+        assert_eq!(skeleton("ρdf"), "pdf");
+        assert!(is_confusable("раураl", "paypal"));
+    }
+
+    #[test]
+    fn test_skeleton_digits_and_multichar() {
+        assert!(is_confusable("t00ls", "tools"));
+        assert!(is_confusable("he1per", "helper"));
+        assert!(is_confusable("modern", "rnodern"));
+        assert!(is_confusable("мodern", "modern"));
+        assert!(!is_confusable("pdf-tools", "pdf-tool"));
+    }
+
+    #[test]
+    fn test_skeleton_case_and_compatibility_forms() {
+        assert!(is_confusable("PDF", "pdf"));
+        // Fullwidth letters fold under NFKC.
+        assert!(is_confusable("ｐｄｆ", "pdf"));
+    }
+}
@@ -0,0 +1,255 @@
+//! Structured validation diagnostics.
+//!
+//! Besides its English message, every diagnostic carries the values the
+//! message is built from as typed [`Param`]s, so a [`MessageRenderer`] can
+//! word it in another language.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The skill is invalid.
+    Error,
+    /// The skill is valid but likely has a problem.
+    Warning,
+    /// Informational note; never affects validity.
+    Info,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+        })
+    }
+}
+
+/// A single finding produced by a validation rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// ID of the rule that produced this diagnostic (e.g. `name-lowercase`).
+    pub rule: String,
+
+    /// Severity of the finding.
+    pub severity: Severity,
+
+    /// Human-readable message.
+    pub message: String,
+
+    /// The complete value the diagnostic is about, if the message quotes
+    /// one. Messages shorten long values (see [`shorten`]); this keeps the
+    /// original.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+
+    /// Values the message is built from, by name: limits, offending
+    /// values, field names. Values are complete, never shortened.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, Param>,
+}
+
+impl Diagnostic {
+    /// Create a new error diagnostic.
+    pub fn error(rule: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            rule: rule.into(),
+            severity: Severity::Error,
+            message: message.into(),
+            value: None,
+            params: BTreeMap::new(),
+        }
+    }
+
+    /// Create a new warning diagnostic.
+    pub fn warning(rule: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            rule: rule.into(),
+            severity: Severity::Warning,
+            message: message.into(),
+            value: None,
+            params: BTreeMap::new(),
+        }
+    }
+
+    /// Create a new informational diagnostic.
+    pub fn info(rule: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            rule: rule.into(),
+            severity: Severity::Info,
+            message: message.into(),
+            value: None,
+            params: BTreeMap::new(),
+        }
+    }
+
+    /// Attach the complete value the message quotes, also as the `value`
+    /// param.
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        let value = value.into();
+        self.params
+            .insert("value".to_string(), Param::Text(value.clone()));
+        self.value = Some(value);
+        self
+    }
+
+    /// Attach a value the message is built from.
+    pub fn with_param(mut self, name: impl Into<String>, value: impl Into<Param>) -> Self {
+        self.params.insert(name.into(), value.into());
+        self
+    }
+
+    /// Attach the `limit` a rule enforces and the `actual` size, length,
+    /// or count that broke it, for rules that bound a number.
+    pub fn with_limit(self, limit: impl Into<Param>, actual: impl Into<Param>) -> Self {
+        self.with_param("limit", limit).with_param("actual", actual)
+    }
+
+    /// The param `name`, if it is a number.
+    pub fn number_param(&self, name: &str) -> Option<u64> {
+        match self.params.get(name) {
+            Some(Param::Number(n)) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// The message as worded by `renderer`, or the built-in English
+    /// message if the renderer has none for this rule.
+    pub fn render(&self, renderer: &dyn MessageRenderer) -> Cow<'_, str> {
+        match renderer.render(&self.rule, &self.params) {
+            Some(message) => Cow::Owned(message),
+            None => Cow::Borrowed(&self.message),
+        }
+    }
+
+    /// Check if this diagnostic makes the skill invalid.
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+/// A value a diagnostic message is built from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Param {
+    /// A name, value, path, or kind of problem.
+    Text(String),
+
+    /// A limit, length, or count.
+    Number(u64),
+
+    /// Several names, values, or paths.
+    List(Vec<String>),
+}
+
+impl From<&str> for Param {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<String> for Param {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<u64> for Param {
+    fn from(value: u64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<usize> for Param {
+    fn from(value: usize) -> Self {
+        Self::Number(value as u64)
+    }
+}
+
+impl From<Vec<String>> for Param {
+    fn from(value: Vec<String>) -> Self {
+        Self::List(value)
+    }
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(text) => f.write_str(text),
+            Self::Number(n) => write!(f, "{}", n),
+            Self::List(items) => f.write_str(&items.join(", ")),
+        }
+    }
+}
+
+/// Words diagnostic messages, for example in another language.
+///
+/// A renderer maps a rule ID and the diagnostic's [`params`](Diagnostic::params)
+/// to a message. Set one as
+/// [`ValidationConfig::message_renderer`](crate::ValidationConfig::message_renderer)
+/// to have validation use it, or call [`Diagnostic::render`].
+pub trait MessageRenderer: Send + Sync {
+    /// Language tag of the messages, such as `de` or `pt-BR`.
+    fn locale(&self) -> &str;
+
+    /// The message for a diagnostic of `rule` with `params`, or `None` to
+    /// keep the built-in English message (for example, for a rule the
+    /// renderer does not know).
+    fn render(&self, rule: &str, params: &BTreeMap<String, Param>) -> Option<String>;
+}
+
+impl fmt::Debug for dyn MessageRenderer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MessageRenderer({})", self.locale())
+    }
+}
+
+/// Renderers are equal if their locales are.
+impl PartialEq for dyn MessageRenderer {
+    fn eq(&self, other: &Self) -> bool {
+        self.locale() == other.locale()
+    }
+}
+
+impl Eq for dyn MessageRenderer {}
+
+/// The built-in English messages, used when no other renderer is set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EnglishRenderer;
+
+impl MessageRenderer for EnglishRenderer {
+    fn locale(&self) -> &str {
+        "en"
+    }
+
+    fn render(&self, _rule: &str, _params: &BTreeMap<String, Param>) -> Option<String> {
+        None
+    }
+}
+
+/// `value` for quoting in a message: at most `max_chars` characters, or
+/// the first `max_chars` followed by an ellipsis and the full length.
+pub fn shorten(value: &str, max_chars: usize) -> Cow<'_, str> {
+    match value.char_indices().nth(max_chars) {
+        None => Cow::Borrowed(value),
+        Some((end, _)) => Cow::Owned(format!(
+            "{}... ({} chars)",
+            &value[..end],
+            value.chars().count()
+        )),
+    }
+}
+
+/// Writes the message alone, as [`validate`](crate::validate) returns it.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
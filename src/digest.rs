@@ -0,0 +1,95 @@
+//! Content digests for change detection.
+
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::error::Result;
+
+/// Compute the hex-encoded SHA-256 digest of `data`.
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Compute the hex-encoded SHA-256 digest of a directory tree.
+///
+/// The digest covers every file's path (relative to `dir`) and contents,
+/// so renaming, adding, removing, or editing any file changes it. Symlinks
+/// are not followed; their target path is hashed instead.
+pub fn dir_digest(dir: &Path) -> Result<String> {
+    let mut entries = Vec::new();
+    collect_entries(dir, Path::new(""), &mut entries)?;
+    entries.sort();
+
+    let mut hasher = Sha256::new();
+    for (rel, data) in entries {
+        hasher.update((rel.len() as u64).to_le_bytes());
+        hasher.update(rel.as_bytes());
+        hasher.update((data.len() as u64).to_le_bytes());
+        hasher.update(&data);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Collect (relative path, tagged contents) pairs for every file under `dir`.
+fn collect_entries(dir: &Path, rel: &Path, entries: &mut Vec<(String, Vec<u8>)>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let rel = rel.join(entry.file_name());
+        let key = rel.to_string_lossy().replace('\\', "/");
+
+        if file_type.is_symlink() {
+            let target = std::fs::read_link(entry.path())?;
+            let mut data = b"link:".to_vec();
+            data.extend_from_slice(target.to_string_lossy().as_bytes());
+            entries.push((key, data));
+        } else if file_type.is_dir() {
+            collect_entries(&entry.path(), &rel, entries)?;
+        } else {
+            let mut data = b"file:".to_vec();
+            data.extend(std::fs::read(entry.path())?);
+            entries.push((key, data));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_dir_digest_tracks_paths_and_contents() {
+        let a = tempfile::TempDir::new().unwrap();
+        let b = tempfile::TempDir::new().unwrap();
+        for dir in [&a, &b] {
+            std::fs::create_dir_all(dir.path().join("scripts")).unwrap();
+            std::fs::write(dir.path().join("SKILL.md"), "skill").unwrap();
+            std::fs::write(dir.path().join("scripts/run.sh"), "echo").unwrap();
+        }
+        assert_eq!(dir_digest(a.path()).unwrap(), dir_digest(b.path()).unwrap());
+
+        std::fs::write(b.path().join("scripts/run.sh"), "echo hi").unwrap();
+        assert_ne!(dir_digest(a.path()).unwrap(), dir_digest(b.path()).unwrap());
+
+        std::fs::write(b.path().join("scripts/run.sh"), "echo").unwrap();
+        std::fs::rename(b.path().join("scripts"), b.path().join("bin")).unwrap();
+        assert_ne!(dir_digest(a.path()).unwrap(), dir_digest(b.path()).unwrap());
+    }
+}
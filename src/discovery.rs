@@ -0,0 +1,437 @@
+//! Discovery of skill directories under a root directory.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::diagnostic::Diagnostic;
+use crate::error::{Result, SkillError};
+use crate::models::SkillProperties;
+use crate::parser::{find_skill_md, read_properties};
+use crate::validator::{ValidationConfig, validate_detailed};
+use crate::version::Version;
+
+/// Find all skill directories under `root`.
+///
+/// A skill directory is any directory containing a SKILL.md (or skill.md).
+/// Discovery does not descend into skill directories, skips hidden
+/// directories (names starting with `.`), and does not follow symlinked
+/// directories. If `root` is itself a skill directory, it is the only
+/// result.
+///
+/// # Returns
+///
+/// Skill directory paths, sorted by path.
+///
+/// # Errors
+///
+/// * `NotFound` - If `root` does not exist
+/// * `IoError` - If a directory cannot be read
+pub fn find_skills(root: &Path) -> Result<Vec<PathBuf>> {
+    find_skills_with(root, &DiscoveryOptions::default())
+}
+
+/// Options for [`find_skills_with`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DiscoveryOptions {
+    /// Treat skill directories named like a version (`1.2.0`, `v2`,
+    /// `2.0.0-rc.1`) as versions of the skill named by their parent
+    /// directory, as in `skills/pdf/1.2.0/SKILL.md`, and return only the
+    /// highest version of each.
+    pub latest_version_only: bool,
+}
+
+impl DiscoveryOptions {
+    /// Set [`latest_version_only`](Self::latest_version_only).
+    pub fn with_latest_version_only(mut self, latest_version_only: bool) -> Self {
+        self.latest_version_only = latest_version_only;
+        self
+    }
+}
+
+/// Find all skill directories under `root`, as configured by `options`.
+///
+/// See [`find_skills`] for the discovery rules. Pair
+/// `latest_version_only` with
+/// [`NameMatch::AnyAncestorWithin(2)`](crate::NameMatch::AnyAncestorWithin)
+/// when validating versioned layouts.
+///
+/// # Errors
+///
+/// Same as [`find_skills`].
+pub fn find_skills_with(root: &Path, options: &DiscoveryOptions) -> Result<Vec<PathBuf>> {
+    if !root.is_dir() {
+        return Err(SkillError::not_found(
+            root,
+            format!("Skill root does not exist: {}", root.display()),
+        ));
+    }
+
+    let mut found = Vec::new();
+    walk(root, &mut found)?;
+    if options.latest_version_only {
+        found = latest_versions(found);
+    }
+    crate::ordering::sort_paths(&mut found);
+    Ok(found)
+}
+
+/// Iterate over the skill directories under `root` as they are found.
+///
+/// Yields the skills [`find_skills_with`] returns, in the same order, but
+/// reads each directory only when the walk reaches it, so dropping the
+/// iterator early leaves the rest of the tree unread. A directory that
+/// cannot be read yields an error naming it, and the walk goes on with
+/// its siblings. A missing `root` yields one `NotFound` error.
+///
+/// The iterator and its items are `Send`, so it can feed a parallel
+/// consumer such as rayon's `par_bridge`.
+pub fn iter_skills(root: &Path, options: &DiscoveryOptions) -> SkillIter {
+    let (pending, root_error) = if root.is_dir() {
+        (vec![vec![root.to_path_buf()].into_iter()], None)
+    } else {
+        let error = SkillError::not_found(
+            root,
+            format!("Skill root does not exist: {}", root.display()),
+        );
+        (Vec::new(), Some(error))
+    };
+    SkillIter {
+        latest_version_only: options.latest_version_only,
+        pending,
+        root_error,
+        dirs_read: 0,
+    }
+}
+
+/// Iterator returned by [`iter_skills`].
+#[derive(Debug)]
+pub struct SkillIter {
+    latest_version_only: bool,
+    /// Directories still to visit, one sorted list per level of the walk.
+    pending: Vec<std::vec::IntoIter<PathBuf>>,
+    root_error: Option<SkillError>,
+    /// Directories listed so far.
+    dirs_read: usize,
+}
+
+impl SkillIter {
+    /// The subdirectories of `dir` the walk visits, sorted.
+    fn children(&mut self, dir: &Path) -> Result<Vec<PathBuf>> {
+        self.dirs_read += 1;
+        let mut children = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() && !entry.file_name().to_string_lossy().starts_with('.')
+            {
+                children.push(entry.path());
+            }
+        }
+        if self.latest_version_only {
+            let (skills, mut others): (Vec<PathBuf>, Vec<PathBuf>) = children
+                .into_iter()
+                .partition(|child| find_skill_md(child).is_some());
+            others.extend(latest_versions(skills));
+            children = others;
+        }
+        crate::ordering::sort_paths(&mut children);
+        Ok(children)
+    }
+}
+
+impl Iterator for SkillIter {
+    type Item = Result<DiscoveredSkill>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.root_error.take() {
+            return Some(Err(error));
+        }
+        loop {
+            let Some(dir) = self.pending.last_mut()?.next() else {
+                self.pending.pop();
+                continue;
+            };
+            if find_skill_md(&dir).is_some() {
+                return Some(Ok(DiscoveredSkill::new(dir)));
+            }
+            match self.children(&dir) {
+                Ok(children) => self.pending.push(children.into_iter()),
+                Err(e) => return Some(Err(e.in_skill(&dir))),
+            }
+        }
+    }
+}
+
+/// A skill directory found by [`iter_skills`], read from disk only when
+/// its details are asked for.
+#[derive(Debug)]
+pub struct DiscoveredSkill {
+    path: PathBuf,
+    properties: OnceLock<SkillProperties>,
+}
+
+impl DiscoveredSkill {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            properties: OnceLock::new(),
+        }
+    }
+
+    /// Path to the skill directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The skill's properties, read from SKILL.md on the first call and
+    /// kept after that. A failed read is not kept, so the next call tries
+    /// again.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`read_properties`].
+    pub fn properties(&self) -> Result<&SkillProperties> {
+        if let Some(properties) = self.properties.get() {
+            return Ok(properties);
+        }
+        let properties = read_properties(&self.path)?;
+        Ok(self.properties.get_or_init(|| properties))
+    }
+
+    /// Validate the skill, as [`validate_detailed`] does.
+    pub fn validate(&self, config: &ValidationConfig) -> Vec<Diagnostic> {
+        validate_detailed(&self.path, config)
+    }
+
+    /// The path to the skill directory, consuming the skill.
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+}
+
+/// Keep only the highest version among skill directories that share a
+/// parent and are named like versions. Other directories are kept.
+fn latest_versions(dirs: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut latest: BTreeMap<PathBuf, PathBuf> = BTreeMap::new();
+    let mut result = Vec::new();
+    for dir in dirs {
+        let version = dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(Version::parse);
+        let (Some(version), Some(parent)) = (version, dir.parent()) else {
+            result.push(dir);
+            continue;
+        };
+        let newer = latest.get(parent).is_none_or(|best| {
+            let best = best.file_name().and_then(|n| n.to_str());
+            best.and_then(Version::parse)
+                .is_none_or(|best| version > best)
+        });
+        if newer {
+            latest.insert(parent.to_path_buf(), dir.clone());
+        }
+    }
+    result.extend(latest.into_values());
+    result
+}
+
+fn walk(dir: &Path, found: &mut Vec<PathBuf>) -> Result<()> {
+    if find_skill_md(dir).is_some() {
+        found.push(dir.to_path_buf());
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if !file_type.is_dir() {
+            continue;
+        }
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        walk(&entry.path(), found)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_skill(root: &Path, rel: &str) {
+        let dir = root.join(rel);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("SKILL.md"), "test").unwrap();
+    }
+
+    #[test]
+    fn test_find_skills_nested() {
+        let dir = TempDir::new().unwrap();
+        create_skill(dir.path(), "b-skill");
+        create_skill(dir.path(), "group/a-skill");
+        create_skill(dir.path(), "b-skill/nested");
+        create_skill(dir.path(), ".hidden/skill");
+        std::fs::create_dir_all(dir.path().join("empty")).unwrap();
+
+        let found = find_skills(dir.path()).unwrap();
+        assert_eq!(
+            found,
+            vec![dir.path().join("b-skill"), dir.path().join("group/a-skill")]
+        );
+    }
+
+    #[test]
+    fn test_find_skills_root_is_skill() {
+        let dir = TempDir::new().unwrap();
+        create_skill(dir.path(), "my-skill");
+
+        let root = dir.path().join("my-skill");
+        assert_eq!(find_skills(&root).unwrap(), vec![root]);
+    }
+
+    #[test]
+    fn test_find_skills_latest_version_only() {
+        let dir = TempDir::new().unwrap();
+        for rel in [
+            "pdf/1.2.0",
+            "pdf/1.10.0",
+            "pdf/2.0.0-rc.1",
+            "docx/v1",
+            "plain-skill",
+        ] {
+            create_skill(dir.path(), rel);
+        }
+
+        assert_eq!(find_skills(dir.path()).unwrap().len(), 5);
+        let options = DiscoveryOptions {
+            latest_version_only: true,
+        };
+        assert_eq!(
+            find_skills_with(dir.path(), &options).unwrap(),
+            vec![
+                dir.path().join("docx/v1"),
+                dir.path().join("pdf/2.0.0-rc.1"),
+                dir.path().join("plain-skill"),
+            ]
+        );
+    }
+
+    fn iter_paths(root: &Path, options: &DiscoveryOptions) -> Vec<PathBuf> {
+        iter_skills(root, options)
+            .map(|skill| skill.unwrap().into_path())
+            .collect()
+    }
+
+    #[test]
+    fn test_iter_skills_matches_find_skills() {
+        let dir = TempDir::new().unwrap();
+        for rel in [
+            "b-skill",
+            "group/a-skill",
+            "group/Z-skill",
+            "b-skill/nested",
+            ".hidden/skill",
+            "pdf/1.2.0",
+            "pdf/1.10.0",
+            "pdf/notes/1.0.0",
+        ] {
+            create_skill(dir.path(), rel);
+        }
+        std::fs::create_dir_all(dir.path().join("empty")).unwrap();
+
+        for options in [
+            DiscoveryOptions::default(),
+            DiscoveryOptions::default().with_latest_version_only(true),
+        ] {
+            assert_eq!(
+                iter_paths(dir.path(), &options),
+                find_skills_with(dir.path(), &options).unwrap()
+            );
+        }
+        let root = dir.path().join("b-skill");
+        assert_eq!(iter_paths(&root, &DiscoveryOptions::default()), vec![root]);
+    }
+
+    #[test]
+    fn test_iter_skills_stops_walking_when_dropped() {
+        let dir = TempDir::new().unwrap();
+        for group in ["a", "b", "c", "d", "e"] {
+            create_skill(dir.path(), &format!("{}/deep/skill", group));
+        }
+
+        let mut iter = iter_skills(dir.path(), &DiscoveryOptions::default());
+        let first: Vec<_> = iter.by_ref().take(1).collect();
+        assert_eq!(first.len(), 1);
+        // The root, a, and a/deep; not the other four groups.
+        assert_eq!(iter.dirs_read, 3);
+        assert_eq!(iter.count(), 4);
+    }
+
+    #[test]
+    fn test_iter_skills_continues_after_errors() {
+        let dir = TempDir::new().unwrap();
+        create_skill(dir.path(), "a/skill");
+        create_skill(dir.path(), "b/skill");
+        create_skill(dir.path(), "c/skill");
+
+        let mut iter = iter_skills(dir.path(), &DiscoveryOptions::default());
+        assert!(iter.next().unwrap().is_ok());
+        // Already listed, so reading it fails.
+        std::fs::remove_dir_all(dir.path().join("b")).unwrap();
+        let err = iter.next().unwrap().unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with(&dir.path().join("b").display().to_string())
+        );
+        let last = iter.next().unwrap().unwrap();
+        assert_eq!(last.path(), dir.path().join("c/skill"));
+        assert!(iter.next().is_none());
+
+        let mut missing = iter_skills(&dir.path().join("missing"), &DiscoveryOptions::default());
+        assert!(matches!(
+            missing.next(),
+            Some(Err(SkillError::NotFound { .. }))
+        ));
+        assert!(missing.next().is_none());
+    }
+
+    #[test]
+    fn test_discovered_skill_loads_lazily() {
+        fn assert_send<T: Send>() {}
+        assert_send::<SkillIter>();
+        assert_send::<DiscoveredSkill>();
+
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skill_dir.join("SKILL.md"), "no frontmatter").unwrap();
+
+        let skill = iter_skills(dir.path(), &DiscoveryOptions::default())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert!(skill.properties().is_err());
+        assert!(!skill.validate(&ValidationConfig::default()).is_empty());
+
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: my-skill\ndescription: A test skill\n---\n",
+        )
+        .unwrap();
+        assert_eq!(skill.properties().unwrap().name, "my-skill");
+        // Kept after the first successful read
+        std::fs::remove_file(skill_dir.join("SKILL.md")).unwrap();
+        assert_eq!(skill.properties().unwrap().name, "my-skill");
+    }
+
+    #[test]
+    fn test_find_skills_missing_root() {
+        let dir = TempDir::new().unwrap();
+        let err = find_skills(&dir.path().join("missing")).unwrap_err();
+        assert!(matches!(err, SkillError::NotFound { .. }));
+    }
+}
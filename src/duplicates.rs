@@ -0,0 +1,271 @@
+//! Detection of duplicate and near-duplicate skills in a [`SkillSet`].
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::digest::sha256_hex;
+use crate::skill::Skill;
+use crate::skill_set::SkillSet;
+
+/// How the skills in a [`DuplicateGroup`] resemble each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKind {
+    /// Skills with byte-identical bodies.
+    Exact,
+
+    /// Skills whose descriptions are at least as similar as the configured
+    /// threshold.
+    Similar,
+}
+
+/// Options for [`SkillSet::find_duplicates_with`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct DuplicateOptions {
+    /// Also group skills whose description similarity (see
+    /// [`description_similarity`]) is at least this value, between 0.0 and
+    /// 1.0. `None` reports exact duplicates only.
+    pub similarity_threshold: Option<f64>,
+}
+
+impl DuplicateOptions {
+    /// Set [`similarity_threshold`](Self::similarity_threshold).
+    pub fn with_similarity_threshold(mut self, similarity_threshold: f64) -> Self {
+        self.similarity_threshold = Some(similarity_threshold);
+        self
+    }
+}
+
+/// A group of two or more skills that look like duplicates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup<'a> {
+    /// How the skills resemble each other.
+    pub kind: DuplicateKind,
+
+    /// Skills in the group, in skill set order.
+    pub skills: Vec<&'a Skill>,
+
+    /// Lowest similarity among the pairs that put skills in this group;
+    /// 1.0 for exact duplicates.
+    pub similarity: f64,
+}
+
+/// Split a description into its set of lowercase alphanumeric tokens.
+fn tokens(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Similarity of two descriptions as the Jaccard index of their token sets.
+///
+/// Descriptions are split on every non-alphanumeric character and
+/// lowercased, so punctuation, case, word order, and repeated words do not
+/// matter. The result is the number of shared tokens divided by the number
+/// of distinct tokens across both, from 0.0 (nothing shared) to 1.0 (same
+/// tokens). Two descriptions without any tokens score 0.0.
+pub fn description_similarity(a: &str, b: &str) -> f64 {
+    let a = tokens(a);
+    let b = tokens(b);
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// Find the root of `i`'s group, compressing the path on the way.
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut i = i;
+    while parent[i] != root {
+        let next = parent[i];
+        parent[i] = root;
+        i = next;
+    }
+    root
+}
+
+impl SkillSet {
+    /// Group skills with identical bodies.
+    ///
+    /// Same as [`SkillSet::find_duplicates_with`] with default options.
+    pub fn find_duplicates(&self) -> Vec<DuplicateGroup<'_>> {
+        self.find_duplicates_with(&DuplicateOptions::default())
+    }
+
+    /// Group skills that look like duplicates.
+    ///
+    /// Skills whose bodies are identical form [`DuplicateKind::Exact`]
+    /// groups. With `options.similarity_threshold`, skills whose
+    /// descriptions score at least the threshold are linked, and linked
+    /// skills form [`DuplicateKind::Similar`] groups (so `a ~ b` and
+    /// `b ~ c` put all three together). Pairs that are already exact
+    /// duplicates are not linked again.
+    ///
+    /// Exact groups come first, then similar groups; each list is ordered
+    /// by the position of the group's first skill in the set.
+    pub fn find_duplicates_with(&self, options: &DuplicateOptions) -> Vec<DuplicateGroup<'_>> {
+        let skills = self.skills();
+        let mut groups = Vec::new();
+
+        let mut by_body: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut body_digests = Vec::with_capacity(skills.len());
+        for (i, skill) in skills.iter().enumerate() {
+            let digest = sha256_hex(skill.body.as_bytes());
+            by_body.entry(digest.clone()).or_default().push(i);
+            body_digests.push(digest);
+        }
+        let mut exact: Vec<&Vec<usize>> = by_body.values().filter(|g| g.len() > 1).collect();
+        exact.sort_by_key(|g| g[0]);
+        groups.extend(exact.into_iter().map(|g| DuplicateGroup {
+            kind: DuplicateKind::Exact,
+            skills: g.iter().map(|&i| &skills[i]).collect(),
+            similarity: 1.0,
+        }));
+
+        let Some(threshold) = options.similarity_threshold else {
+            return groups;
+        };
+
+        let mut parent: Vec<usize> = (0..skills.len()).collect();
+        let mut lowest = vec![f64::INFINITY; skills.len()];
+        for i in 0..skills.len() {
+            for j in i + 1..skills.len() {
+                if body_digests[i] == body_digests[j] {
+                    continue;
+                }
+                let score = description_similarity(
+                    &skills[i].properties.description,
+                    &skills[j].properties.description,
+                );
+                if score >= threshold {
+                    let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                    let (root, child) = (a.min(b), a.max(b));
+                    parent[child] = root;
+                    lowest[root] = lowest[root].min(lowest[child]).min(score);
+                }
+            }
+        }
+
+        let mut similar: Vec<Vec<usize>> = Vec::new();
+        let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+        for i in 0..skills.len() {
+            let root = find(&mut parent, i);
+            let index = *group_of_root.entry(root).or_insert_with(|| {
+                similar.push(Vec::new());
+                similar.len() - 1
+            });
+            similar[index].push(i);
+        }
+        groups.extend(
+            similar
+                .into_iter()
+                .filter(|g| g.len() > 1)
+                .map(|g| DuplicateGroup {
+                    kind: DuplicateKind::Similar,
+                    similarity: lowest[find(&mut parent, g[0])],
+                    skills: g.iter().map(|&i| &skills[i]).collect(),
+                }),
+        );
+
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn skill(name: &str, description: &str, body: &str) -> Skill {
+        let content = format!(
+            "---\nname: {}\ndescription: {}\n---\n{}",
+            name, description, body
+        );
+        Skill::from_content(PathBuf::from(name), PathBuf::from("SKILL.md"), &content).unwrap()
+    }
+
+    fn names<'a>(group: &DuplicateGroup<'a>) -> Vec<&'a str> {
+        group.skills.iter().map(|s| s.name()).collect()
+    }
+
+    #[test]
+    fn test_description_similarity_pinned() {
+        assert_eq!(
+            description_similarity("Read PDF files", "read pdf files."),
+            1.0
+        );
+        assert_eq!(
+            description_similarity("files PDF read", "Read PDF files"),
+            1.0
+        );
+        // {extract, text, from, pdf, files} vs {extract, text, from, word, files}
+        assert!(
+            (description_similarity(
+                "Extract text from PDF files",
+                "Extract text from Word files"
+            ) - 4.0 / 6.0)
+                .abs()
+                < 1e-9
+        );
+        assert_eq!(description_similarity("Deploy apps", "Read PDF files"), 0.0);
+        assert_eq!(description_similarity("", "..."), 0.0);
+    }
+
+    #[test]
+    fn test_exact_duplicates() {
+        let set = SkillSet::new(vec![
+            skill("a", "A", "# Same"),
+            skill("b", "B", "# Other"),
+            skill("c", "C", "# Same"),
+            skill("d", "D", "# Other"),
+            skill("e", "E", "# Unique"),
+        ]);
+
+        let groups = set.find_duplicates();
+        assert_eq!(groups.len(), 2);
+        assert!(groups.iter().all(|g| g.kind == DuplicateKind::Exact));
+        assert_eq!(names(&groups[0]), vec!["a", "c"]);
+        assert_eq!(names(&groups[1]), vec!["b", "d"]);
+    }
+
+    #[test]
+    fn test_similar_descriptions_opt_in() {
+        let set = SkillSet::new(vec![
+            skill("pdf-a", "Extract text from PDF files", "1"),
+            skill("deploy", "Deploy web apps", "2"),
+            skill("pdf-b", "Extract the text from PDF files", "3"),
+            skill("pdf-c", "extract text from pdf files and forms", "4"),
+        ]);
+
+        assert!(set.find_duplicates().is_empty());
+
+        let options = DuplicateOptions {
+            similarity_threshold: Some(0.7),
+        };
+        let groups = set.find_duplicates_with(&options);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].kind, DuplicateKind::Similar);
+        assert_eq!(names(&groups[0]), vec!["pdf-a", "pdf-b", "pdf-c"]);
+        assert!(groups[0].similarity >= 0.7 && groups[0].similarity < 1.0);
+    }
+
+    #[test]
+    fn test_exact_pairs_not_repeated_as_similar() {
+        let set = SkillSet::new(vec![
+            skill("a", "Same description", "# Same"),
+            skill("b", "Same description", "# Same"),
+        ]);
+
+        let options = DuplicateOptions {
+            similarity_threshold: Some(0.5),
+        };
+        let groups = set.find_duplicates_with(&options);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].kind, DuplicateKind::Exact);
+    }
+}
@@ -0,0 +1,448 @@
+//! Comment-preserving edits to SKILL.md frontmatter.
+//!
+//! [`update_frontmatter`] changes one field by rewriting only the lines that
+//! belong to it, so comments, blank lines, and the layout of every other
+//! field stay as the author wrote them. [`reformat_frontmatter`] makes the
+//! same change by re-emitting the whole frontmatter in canonical form, for
+//! layouts the line editor cannot handle, and [`convert_frontmatter`]
+//! re-emits it in another [`FrontmatterSyntax`].
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::error::{Result, SkillError};
+use crate::models::AllowedToolsRepr;
+use crate::parser::{
+    FrontmatterSyntax, is_blank_or_comment, is_frontmatter_delimiter, parse_frontmatter,
+    properties_from_metadata,
+};
+use crate::writer::to_skill_md_with;
+use crate::yaml_emit::{INDENT, Node, emit_field};
+
+/// A field to edit: a top-level key, or `parent.child` for a key inside a
+/// top-level mapping such as `metadata`.
+struct FieldPath<'a> {
+    parent: Option<&'a str>,
+    key: &'a str,
+}
+
+impl<'a> FieldPath<'a> {
+    fn parse(path: &'a str) -> Result<Self> {
+        let (parent, key) = match path.split_once('.') {
+            Some((parent, key)) => (Some(parent), key),
+            None => (None, path),
+        };
+        if key.is_empty() || key.contains('.') || parent.is_some_and(str::is_empty) {
+            return Err(SkillError::parse(format!(
+                "Cannot edit '{}': expected a top-level field or parent.child",
+                path
+            )));
+        }
+        Ok(Self { parent, key })
+    }
+}
+
+/// Width of a line's leading whitespace.
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '\t']).len()
+}
+
+/// The key of a `key: value` line with its indentation removed.
+fn entry_key(line: &str) -> Option<&str> {
+    let (key, rest) = line.split_once(':')?;
+    (rest.is_empty() || rest.starts_with([' ', '\t', '\r', '\n'])).then_some(key.trim_end())
+}
+
+/// Find the lines of the entry for `key` at exactly `indent` within
+/// `range`.
+///
+/// An entry is its key line plus every following line indented deeper
+/// (or, at the top level, a `-` sequence item), including blank lines
+/// between them. Blank lines and comments after its last such line belong
+/// to whatever follows.
+fn find_entry(
+    lines: &[&str],
+    range: Range<usize>,
+    indent: usize,
+    key: &str,
+) -> Option<Range<usize>> {
+    let start = range.clone().find(|&i| {
+        let line = lines[i];
+        !is_blank_or_comment(line)
+            && indent_of(line) == indent
+            && entry_key(&line[indent..]) == Some(key)
+    })?;
+
+    let mut last = start;
+    for (i, line) in lines.iter().enumerate().take(range.end).skip(start + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_indent = indent_of(line);
+        if line_indent > indent || (line_indent == indent && line[indent..].starts_with("- ")) {
+            last = i;
+        } else {
+            break;
+        }
+    }
+    Some(start..last + 1)
+}
+
+/// Set the frontmatter field at `path` to `value`, or remove it if `value`
+/// is `None`, changing only the lines of that field.
+///
+/// `path` is a top-level key such as `description`, or `parent.child` for
+/// a key of a top-level mapping, such as `metadata.version`. The new value
+/// is written with the same quoting rules as
+/// [`to_skill_md`](crate::to_skill_md). A missing field is added at the end
+/// of the frontmatter (or of its parent mapping); removing a missing field
+/// changes nothing, and removing the only key of a parent mapping removes
+/// the parent. Every other line, including comments and blank lines, is
+/// kept as is.
+///
+/// # Errors
+///
+/// Returns `ParseError` if `content` does not parse, if the frontmatter is
+/// TOML or its parent mapping is written in flow style (`{...}`), or if the
+/// edited frontmatter would not read back with the new value. Use
+/// [`reformat_frontmatter`] in those cases.
+pub fn update_frontmatter(
+    content: &str,
+    path: &str,
+    value: Option<&serde_json::Value>,
+) -> Result<String> {
+    let field = FieldPath::parse(path)?;
+    parse_frontmatter(content)?;
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    if !lines
+        .first()
+        .is_some_and(|line| is_frontmatter_delimiter(line))
+    {
+        return Err(cannot_edit(
+            path,
+            "only YAML frontmatter can be edited in place",
+        ));
+    }
+    let close = (1..lines.len())
+        .find(|&i| is_frontmatter_delimiter(lines[i]))
+        .expect("parsed frontmatter has a closing delimiter");
+    let node = value.map(Node::from);
+
+    let (replace, text) = match field.parent {
+        None => {
+            let text = node.map_or_else(String::new, |node| emit_field("", field.key, &node));
+            match find_entry(&lines, 1..close, 0, field.key) {
+                Some(span) => (span, text),
+                None => (close..close, text),
+            }
+        }
+        Some(parent) => match find_entry(&lines, 1..close, 0, parent) {
+            Some(parent_span) => {
+                let header = lines[parent_span.start]
+                    .split_once(':')
+                    .map_or("", |(_, rest)| rest);
+                if !is_blank_or_comment(header) {
+                    return Err(cannot_edit(
+                        path,
+                        &format!("'{}' is not a block mapping", parent),
+                    ));
+                }
+                let children = parent_span.start + 1..parent_span.end;
+                let indent = children
+                    .clone()
+                    .map(|i| lines[i])
+                    .find(|line| !is_blank_or_comment(line))
+                    .map_or(INDENT.len(), indent_of);
+                let text = node.map_or_else(String::new, |node| {
+                    emit_field(&" ".repeat(indent), field.key, &node)
+                });
+                match find_entry(&lines, children.clone(), indent, field.key) {
+                    // Removing the only child removes the parent too.
+                    Some(span)
+                        if value.is_none()
+                            && children
+                                .filter(|i| !span.contains(i))
+                                .all(|i| is_blank_or_comment(lines[i])) =>
+                    {
+                        (parent_span, text)
+                    }
+                    Some(span) => (span, text),
+                    None => (parent_span.end..parent_span.end, text),
+                }
+            }
+            None => {
+                let text = node.map_or_else(String::new, |node| {
+                    format!("{}:\n{}", parent, emit_field(INDENT, field.key, &node))
+                });
+                (close..close, text)
+            }
+        },
+    };
+
+    let mut out: String = lines[..replace.start].concat();
+    out.push_str(&text);
+    out.push_str(&lines[replace.end..].concat());
+
+    let edited = parse_frontmatter(&out)
+        .map_err(|e| cannot_edit(path, &format!("the result does not parse ({})", e)))?;
+    let top = edited.0.get(field.parent.unwrap_or(field.key));
+    let actual = match field.parent {
+        Some(_) => top.and_then(|parent| parent.get(field.key)),
+        None => top,
+    };
+    let actual = actual.and_then(|v| serde_json::to_value(v).ok());
+    if actual.as_ref() != value {
+        return Err(cannot_edit(
+            path,
+            "the result does not read back as the new value",
+        ));
+    }
+
+    Ok(out)
+}
+
+/// The error for an edit [`update_frontmatter`] cannot make in place.
+fn cannot_edit(path: &str, reason: &str) -> SkillError {
+    SkillError::parse(format!(
+        "Cannot edit '{}' in place: {}; reformat the frontmatter instead",
+        path, reason
+    ))
+}
+
+/// Set or remove the frontmatter field at `path` like
+/// [`update_frontmatter`], then re-emit the whole SKILL.md with
+/// [`to_skill_md_with`] in the frontmatter syntax it already uses.
+///
+/// Comments and custom layout are lost, and so are top-level fields that
+/// [`SkillProperties`](crate::SkillProperties) does not keep. An
+/// `allowed-tools` list becomes a single space-separated string, the
+/// spec's form.
+///
+/// # Errors
+///
+/// Returns `ParseError` if `content` does not parse or the parent of `path`
+/// is not a mapping, and `ValidationError` if required fields are missing
+/// afterwards or the values have no TOML form.
+pub fn reformat_frontmatter(
+    content: &str,
+    path: &str,
+    value: Option<&serde_json::Value>,
+) -> Result<String> {
+    let field = FieldPath::parse(path)?;
+    let (mut metadata, body) = parse_frontmatter(content)?;
+    let value = value
+        .map(|v| serde_yaml::to_value(v).map_err(|e| SkillError::parse(e.to_string())))
+        .transpose()?;
+
+    match field.parent {
+        None => {
+            match value {
+                Some(value) => metadata.insert(field.key.to_string(), value),
+                None => metadata.remove(field.key),
+            };
+        }
+        Some(parent) => {
+            let entry = metadata
+                .entry(parent.to_string())
+                .or_insert_with(|| serde_yaml::Mapping::new().into());
+            let mapping = entry.as_mapping_mut().ok_or_else(|| {
+                SkillError::parse(format!(
+                    "Cannot edit '{}': '{}' is not a mapping",
+                    path, parent
+                ))
+            })?;
+            match value {
+                Some(value) => mapping.insert(field.key.into(), value),
+                None => mapping.remove(field.key),
+            };
+        }
+    }
+
+    reemit(&metadata, &body, syntax_of(content))
+}
+
+/// Re-emit SKILL.md `content` with its frontmatter in `syntax`.
+///
+/// Content already in `syntax` is returned unchanged. Otherwise the
+/// frontmatter is rewritten as by [`reformat_frontmatter`], losing
+/// comments and custom layout.
+///
+/// # Errors
+///
+/// Returns `ParseError` if `content` does not parse, and `ValidationError`
+/// if required fields are missing, or if TOML is requested without the
+/// `toml` feature or a value has no TOML form.
+pub fn convert_frontmatter(content: &str, syntax: FrontmatterSyntax) -> Result<String> {
+    let (metadata, body) = parse_frontmatter(content)?;
+    if syntax_of(content) == syntax {
+        return Ok(content.to_string());
+    }
+    reemit(&metadata, &body, syntax)
+}
+
+/// The frontmatter syntax `content` opens with.
+fn syntax_of(content: &str) -> FrontmatterSyntax {
+    content
+        .lines()
+        .next()
+        .and_then(FrontmatterSyntax::of_delimiter)
+        .unwrap_or_default()
+}
+
+/// Render parsed frontmatter and body as a canonical SKILL.md in `syntax`.
+fn reemit(
+    metadata: &HashMap<String, serde_yaml::Value>,
+    body: &str,
+    syntax: FrontmatterSyntax,
+) -> Result<String> {
+    let mut props = properties_from_metadata(metadata)?;
+    props.allowed_tools = props
+        .allowed_tools
+        .map(|tools| AllowedToolsRepr::Raw(tools.raw().into_owned()));
+    to_skill_md_with(&props, body, syntax)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SKILL_MD: &str = "---
+# Shown in the skill picker
+name: pdf-tools
+# keep under 200 chars for the picker UI
+description: |
+  Extract text from PDF files.
+
+  Also fills forms.
+metadata:
+  # bump on every release
+  version: \"1.2.0\"
+
+  author: Jane
+---
+# PDF tools
+";
+
+    #[test]
+    fn test_version_bump_keeps_comments() {
+        let out = update_frontmatter(SKILL_MD, "metadata.version", Some(&"1.3.0".into())).unwrap();
+        assert_eq!(out, SKILL_MD.replace("1.2.0", "1.3.0"));
+    }
+
+    #[test]
+    fn test_replace_multi_line_field() {
+        let out = update_frontmatter(SKILL_MD, "description", Some(&"Read PDFs".into())).unwrap();
+        assert!(out.contains(
+            "# keep under 200 chars for the picker UI\ndescription: Read PDFs\nmetadata:\n"
+        ));
+        assert!(!out.contains("fills forms"));
+    }
+
+    #[test]
+    fn test_add_and_remove_fields() {
+        let out = update_frontmatter(SKILL_MD, "metadata.homepage", Some(&"https://x.io".into()))
+            .unwrap();
+        assert!(out.contains("  author: Jane\n  homepage: https://x.io\n---\n"));
+
+        let out = update_frontmatter(&out, "license", Some(&"MIT".into())).unwrap();
+        assert!(out.contains("  homepage: https://x.io\nlicense: MIT\n---\n"));
+
+        let out = update_frontmatter(&out, "metadata.version", None).unwrap();
+        assert!(out.contains("  # bump on every release\n\n  author: Jane\n"));
+
+        let out = update_frontmatter(&out, "compatibility", None).unwrap();
+        assert!(out.starts_with("---\n# Shown in the skill picker\nname: pdf-tools\n"));
+
+        let bare = "---\nname: a\ndescription: b\n---\n";
+        let out = update_frontmatter(bare, "metadata.version", Some(&"1".into())).unwrap();
+        assert_eq!(
+            out,
+            "---\nname: a\ndescription: b\nmetadata:\n  version: \"1\"\n---\n"
+        );
+        assert_eq!(
+            update_frontmatter(&out, "metadata.version", None).unwrap(),
+            bare
+        );
+    }
+
+    #[test]
+    fn test_flow_mapping_needs_reformat() {
+        let content =
+            "---\nname: a\n# note\ndescription: b\nmetadata: {version: \"1\"}\n---\nBody\n";
+        let err = update_frontmatter(content, "metadata.version", Some(&"2".into())).unwrap_err();
+        assert!(err.to_string().contains("not a block mapping"), "{}", err);
+
+        let out = reformat_frontmatter(content, "metadata.version", Some(&"2".into())).unwrap();
+        assert_eq!(
+            out,
+            "---\nname: a\ndescription: b\nmetadata:\n  version: \"2\"\n---\nBody\n"
+        );
+    }
+
+    #[test]
+    fn test_allowed_tools_list_kept_unless_reformatted() {
+        let content =
+            "---\nname: a\ndescription: b\nallowed-tools:\n  - Bash(git:*)\n  - Read\n---\n";
+        let out = update_frontmatter(content, "license", Some(&"MIT".into())).unwrap();
+        assert!(out.contains("allowed-tools:\n  - Bash(git:*)\n  - Read\n"));
+
+        let out = reformat_frontmatter(content, "license", Some(&"MIT".into())).unwrap();
+        assert!(out.contains("allowed-tools: Bash(git:*) Read\n"), "{}", out);
+    }
+
+    #[test]
+    fn test_mixed_allowed_tools_list_kept_when_reformatted() {
+        let content = "---\nname: a\ndescription: b\nallowed-tools: [Read, 42]\n---\n";
+        let out = reformat_frontmatter(content, "license", Some(&"MIT".into())).unwrap();
+        assert!(out.contains("allowed-tools: Read 42\n"), "{}", out);
+    }
+
+    #[test]
+    fn test_invalid_path() {
+        let err = update_frontmatter(SKILL_MD, "metadata.a.b", None).unwrap_err();
+        assert!(err.to_string().contains("parent.child"));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_frontmatter_keeps_its_syntax() {
+        let content = "+++\nname = \"a\"\ndescription = \"b\"\n+++\nBody\n";
+        let err = update_frontmatter(content, "license", Some(&"MIT".into())).unwrap_err();
+        assert!(err.to_string().contains("reformat"), "{}", err);
+
+        let out = reformat_frontmatter(content, "license", Some(&"MIT".into())).unwrap();
+        assert_eq!(
+            out,
+            "+++\nname = \"a\"\ndescription = \"b\"\nlicense = \"MIT\"\n+++\nBody\n"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_convert_frontmatter() {
+        let yaml = "---\nname: a\ndescription: b\n---\nBody\n";
+        let toml = convert_frontmatter(yaml, FrontmatterSyntax::Toml).unwrap();
+        assert_eq!(toml, "+++\nname = \"a\"\ndescription = \"b\"\n+++\nBody\n");
+        assert_eq!(
+            convert_frontmatter(&toml, FrontmatterSyntax::Toml).unwrap(),
+            toml
+        );
+        assert_eq!(
+            convert_frontmatter(&toml, FrontmatterSyntax::Yaml).unwrap(),
+            yaml
+        );
+    }
+
+    #[cfg(not(feature = "toml"))]
+    #[test]
+    fn test_convert_frontmatter_to_toml_needs_feature() {
+        let yaml = "---\nname: a\ndescription: b\n---\n";
+        assert_eq!(
+            convert_frontmatter(yaml, FrontmatterSyntax::Yaml).unwrap(),
+            yaml
+        );
+        let err = convert_frontmatter(yaml, FrontmatterSyntax::Toml).unwrap_err();
+        assert!(err.to_string().contains("`toml` feature"), "{}", err);
+    }
+}
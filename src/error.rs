@@ -1,7 +1,10 @@
 //! Skill-related error types.
 
+use std::path::{Path, PathBuf};
+
 /// Base error type for all skill-related errors.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum SkillError {
     /// Raised when SKILL.md parsing fails.
     #[error("{0}")]
@@ -14,9 +17,26 @@ pub enum SkillError {
         errors: Vec<String>,
     },
 
+    /// Raised when a skill path, or the SKILL.md inside it, does not exist.
+    #[error("{message}")]
+    NotFound { path: PathBuf, message: String },
+
+    /// Raised when a path exists but is not the kind of entry expected
+    /// (e.g. a plain file where a skill directory or SKILL.md was expected).
+    #[error("{message}")]
+    InvalidPath { path: PathBuf, message: String },
+
     /// Raised when an I/O operation fails.
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    /// Raised by operations over several skills, naming the skill whose
+    /// own error is `source`.
+    #[error("{}: {source}", path.display())]
+    InSkill {
+        path: PathBuf,
+        source: Box<SkillError>,
+    },
 }
 
 impl SkillError {
@@ -25,6 +45,22 @@ impl SkillError {
         Self::Parse(message.into())
     }
 
+    /// Create a new not-found error for `path`.
+    pub fn not_found(path: &Path, message: impl Into<String>) -> Self {
+        Self::NotFound {
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+
+    /// Create a new invalid-path error for `path`.
+    pub fn invalid_path(path: &Path, message: impl Into<String>) -> Self {
+        Self::InvalidPath {
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+
     /// Create a new validation error with a single message.
     pub fn validation(message: impl Into<String>) -> Self {
         let msg = message.into();
@@ -42,9 +78,31 @@ impl SkillError {
         }
     }
 
+    /// Attach the path of the skill being processed.
+    ///
+    /// Errors that already name a path (`NotFound`, `InvalidPath`, and
+    /// `InSkill` itself) are returned unchanged.
+    pub fn in_skill(self, path: &Path) -> Self {
+        match self {
+            Self::NotFound { .. } | Self::InvalidPath { .. } | Self::InSkill { .. } => self,
+            other => Self::InSkill {
+                path: path.to_path_buf(),
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// The underlying error, without any skill context.
+    pub fn root(&self) -> &SkillError {
+        match self {
+            Self::InSkill { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
     /// Get the list of validation errors, if this is a validation error.
     pub fn errors(&self) -> Option<&[String]> {
-        match self {
+        match self.root() {
             Self::Validation { errors, .. } => Some(errors),
             _ => None,
         }
@@ -0,0 +1,269 @@
+//! Export of skills to other document formats.
+
+use std::collections::HashSet;
+
+use crate::markdown;
+use crate::skill::Skill;
+use crate::skill_set::SkillSet;
+
+/// Deepest ATX heading level; demoted headings are clamped to it.
+const MAX_HEADING_LEVEL: usize = 6;
+
+/// Level of each skill's own heading in a bundle.
+const SKILL_HEADING_LEVEL: usize = 2;
+
+/// Body headings at most this many levels below the body's shallowest
+/// heading (1 being the shallowest itself) are listed in the table of
+/// contents.
+const TOC_MAX_BODY_LEVEL: usize = 2;
+
+/// Generates anchors that are unique within one document.
+#[derive(Default)]
+struct Anchors {
+    used: HashSet<String>,
+}
+
+impl Anchors {
+    /// Reserve and return a unique anchor for `text`.
+    ///
+    /// The base slug follows the GitHub style (lowercase, spaces to
+    /// hyphens, punctuation dropped); repeats get `-1`, `-2`, ... appended.
+    fn unique(&mut self, text: &str) -> String {
+        let base = anchor_slug(text);
+        let mut anchor = base.clone();
+        let mut n = 0;
+        while !self.used.insert(anchor.clone()) {
+            n += 1;
+            anchor = format!("{}-{}", base, n);
+        }
+        anchor
+    }
+}
+
+/// Turn heading text into an anchor slug.
+fn anchor_slug(text: &str) -> String {
+    let slug: String = text
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect();
+    if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug
+    }
+}
+
+/// A heading of the bundle that appears in the table of contents.
+struct TocEntry {
+    level: usize,
+    text: String,
+    anchor: String,
+}
+
+/// Rewrite `body` with its headings moved to nest under a heading of level
+/// `parent`, each preceded by an explicit anchor.
+///
+/// The body's shallowest heading becomes level `parent + 1` and the others
+/// keep their depth relative to it, so a body starting at `##` nests the
+/// same as one starting at `#`. Headings in fenced code blocks are left
+/// alone. Returns the new body and the headings to list in the table of
+/// contents.
+fn demote_headings(body: &str, parent: usize, anchors: &mut Anchors) -> (String, Vec<TocEntry>) {
+    let mut out = String::with_capacity(body.len());
+    let mut toc = Vec::new();
+    let mut last = 0;
+
+    let headings = markdown::headings(body);
+    let shallowest = headings.iter().map(|h| h.level).min().unwrap_or(1);
+    for heading in headings {
+        let depth = heading.level - shallowest + 1;
+        let level = (parent + depth).min(MAX_HEADING_LEVEL);
+        let anchor = anchors.unique(&heading.text);
+        out.push_str(&body[last..heading.line.start]);
+        out.push_str(&format!(
+            "<a id=\"{}\"></a>\n{} {}",
+            anchor,
+            "#".repeat(level),
+            heading.text
+        ));
+        last = heading.line.end;
+
+        if depth <= TOC_MAX_BODY_LEVEL {
+            toc.push(TocEntry {
+                level,
+                text: heading.text,
+                anchor,
+            });
+        }
+    }
+    out.push_str(&body[last..]);
+    (out, toc)
+}
+
+/// Escape a value for a markdown table cell.
+fn table_cell(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+/// Metadata table for one skill.
+fn metadata_table(skill: &Skill) -> String {
+    let props = &skill.properties;
+    let rows = [
+        ("Description", Some(props.description.as_str())),
+        ("License", props.license.as_deref()),
+        ("Compatibility", props.compatibility.as_deref()),
+        ("Version", props.version()),
+    ];
+
+    let mut out = String::from("| Field | Value |\n| --- | --- |\n");
+    for (field, value) in rows {
+        if let Some(value) = value {
+            out.push_str(&format!("| {} | {} |\n", field, table_cell(value)));
+        }
+    }
+    out
+}
+
+/// Render every skill in `set` as one markdown document.
+///
+/// The document starts with a table of contents, followed by a section per
+/// skill: a level-2 heading with the skill name, a metadata table, and the
+/// body with its headings demoted so they nest under the skill heading.
+/// Every heading gets an explicit `<a id>` anchor, unique across the whole
+/// document, so links work even when skills share section names.
+pub fn to_markdown_bundle(set: &SkillSet) -> String {
+    let mut anchors = Anchors::default();
+    anchors.unique("Skills");
+    anchors.unique("Contents");
+
+    let mut toc = Vec::new();
+    let mut sections = Vec::new();
+    for skill in set {
+        let anchor = anchors.unique(skill.name());
+        let (body, body_toc) = demote_headings(&skill.body, SKILL_HEADING_LEVEL, &mut anchors);
+
+        let mut section = format!(
+            "<a id=\"{}\"></a>\n{} {}\n\n{}",
+            anchor,
+            "#".repeat(SKILL_HEADING_LEVEL),
+            skill.name(),
+            metadata_table(skill)
+        );
+        if !body.trim().is_empty() {
+            section.push('\n');
+            section.push_str(body.trim());
+            section.push('\n');
+        }
+        sections.push(section);
+
+        toc.push(TocEntry {
+            level: SKILL_HEADING_LEVEL,
+            text: skill.name().to_string(),
+            anchor,
+        });
+        toc.extend(body_toc);
+    }
+
+    let mut out = String::from("# Skills\n\n## Contents\n\n");
+    for entry in &toc {
+        out.push_str(&format!(
+            "{}- [{}](#{})\n",
+            "  ".repeat(entry.level - SKILL_HEADING_LEVEL),
+            entry.text.replace(['[', ']'], ""),
+            entry.anchor
+        ));
+    }
+    for section in sections {
+        out.push('\n');
+        out.push_str(&section);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn skill(name: &str, frontmatter: &str, body: &str) -> Skill {
+        let content = format!(
+            "---\nname: {}\ndescription: About {}\n{}---\n{}",
+            name, name, frontmatter, body
+        );
+        Skill::from_content(PathBuf::from(name), PathBuf::from("SKILL.md"), &content).unwrap()
+    }
+
+    #[test]
+    fn test_anchor_slugs_unique() {
+        let mut anchors = Anchors::default();
+        assert_eq!(anchors.unique("Usage"), "usage");
+        assert_eq!(anchors.unique("usage"), "usage-1");
+        assert_eq!(anchors.unique("Usage"), "usage-2");
+        assert_eq!(anchors.unique("usage-1"), "usage-1-1");
+        assert_eq!(anchors.unique("C++ & Rust: Tips!"), "c--rust-tips");
+        assert_eq!(anchors.unique("???"), "section");
+    }
+
+    #[test]
+    fn test_demote_headings() {
+        let mut anchors = Anchors::default();
+        let body = "# Title\ntext\n```\n# not a heading\n```\n## Usage ##\n##### Deep\n";
+        let (demoted, toc) = demote_headings(body, 2, &mut anchors);
+        assert_eq!(
+            demoted,
+            "<a id=\"title\"></a>\n### Title\ntext\n```\n# not a heading\n```\n\
+             <a id=\"usage\"></a>\n#### Usage\n<a id=\"deep\"></a>\n###### Deep\n"
+        );
+        let listed: Vec<_> = toc.iter().map(|e| (e.level, e.anchor.as_str())).collect();
+        assert_eq!(listed, vec![(3, "title"), (4, "usage")]);
+
+        // A body starting at level 2 nests the same way.
+        let (demoted, toc) = demote_headings(
+            "## Usage
+### Steps
+",
+            2,
+            &mut anchors,
+        );
+        assert_eq!(
+            demoted,
+            "<a id=\"usage-1\"></a>\n### Usage\n<a id=\"steps\"></a>\n#### Steps\n"
+        );
+        assert_eq!(toc.len(), 2);
+    }
+
+    #[test]
+    fn test_markdown_bundle() {
+        let set = SkillSet::new(vec![
+            skill(
+                "pdf",
+                "license: MIT\nmetadata:\n  version: \"1.2\"\n",
+                "# PDF\n## Usage\nRun fill.py.",
+            ),
+            skill("usage", "compatibility: a | b\n", "## Usage\nOther."),
+        ]);
+
+        assert_eq!(
+            to_markdown_bundle(&set),
+            "# Skills\n\n## Contents\n\n\
+             - [pdf](#pdf)\n  - [PDF](#pdf-1)\n    - [Usage](#usage)\n\
+             - [usage](#usage-1)\n  - [Usage](#usage-2)\n\
+             \n<a id=\"pdf\"></a>\n## pdf\n\n\
+             | Field | Value |\n| --- | --- |\n| Description | About pdf |\n| License | MIT |\n| Version | 1.2 |\n\
+             \n<a id=\"pdf-1\"></a>\n### PDF\n<a id=\"usage\"></a>\n#### Usage\nRun fill.py.\n\
+             \n<a id=\"usage-1\"></a>\n## usage\n\n\
+             | Field | Value |\n| --- | --- |\n| Description | About usage |\n| Compatibility | a \\| b |\n\
+             \n<a id=\"usage-2\"></a>\n### Usage\nOther.\n"
+        );
+    }
+}
@@ -0,0 +1,507 @@
+//! Fixing every skill under a root: reflowing bodies (`fmt`) and
+//! rewriting what lint can fix on its own (`lint --fix`).
+//!
+//! Like [`bulk`](crate::bulk), each run is planned first and written
+//! second, and the [`FixReport`] says what happened to every SKILL.md:
+//! whether it changed, was already clean, still has findings no fixer
+//! handles, or could not be written.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::diagnostic::Diagnostic;
+use crate::discovery::find_skills;
+use crate::edit::{convert_frontmatter, update_frontmatter};
+use crate::error::Result;
+use crate::lint::{LintFinding, lint_content};
+use crate::markdown;
+use crate::parser::{
+    FrontmatterSyntax, find_skill_md, parse_frontmatter, read_skill_md_file, split_frontmatter,
+};
+use crate::reflow::{Reflow, reflow_skill_md};
+use crate::writer::atomic_write;
+
+/// Lint rules that [`lint_tree`] fixes.
+///
+/// * `setext-heading` - the underlined heading is rewritten with `#`
+///   markers;
+/// * `description-newline` - the lines of the description are joined with
+///   spaces.
+pub const FIXABLE_LINT_RULES: &[&str] = &["setext-heading", "description-newline"];
+
+/// What happened to one SKILL.md.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum FixStatus {
+    /// The file was rewritten (or would be, when planning) and nothing is
+    /// left to fix.
+    Changed,
+
+    /// The file needed no change.
+    Clean,
+
+    /// Findings remain that no fixer handles. Fixes for the others are
+    /// still written; [`FileFix::bytes_changed`] tells whether there were
+    /// any.
+    Unfixable,
+
+    /// The fixed content could not be written.
+    WriteFailed,
+}
+
+/// One SKILL.md in a [`FixReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileFix {
+    /// Path to the SKILL.md.
+    pub path: PathBuf,
+
+    /// What happened to the file.
+    pub status: FixStatus,
+
+    /// Findings the fix removed.
+    pub fixed: Vec<LintFinding>,
+
+    /// Findings left after the fix, with their lines in the fixed file. A
+    /// file that cannot be read or parsed has one `skill-file` error here.
+    pub remaining: Vec<LintFinding>,
+
+    /// Size of the changed region: the longer of the old and new text
+    /// between the first and last differing byte. 0 if nothing changed.
+    pub bytes_changed: usize,
+
+    /// Why writing failed, for [`FixStatus::WriteFailed`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// The fixed content, for files that change.
+    #[serde(skip)]
+    pub edited: Option<String>,
+
+    /// The content the fix was planned from, for files that change.
+    #[serde(skip)]
+    pub original: Option<String>,
+}
+
+/// Counts of files per [`FixStatus`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct FixSummary {
+    /// Files rewritten.
+    pub changed: usize,
+
+    /// Files that needed no change.
+    pub clean: usize,
+
+    /// Files with findings left.
+    pub unfixable: usize,
+
+    /// Files that could not be written.
+    pub write_failed: usize,
+}
+
+/// Result of fixing the skills under a root, one entry per discovered
+/// skill in discovery order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FixReport {
+    /// Every discovered skill's SKILL.md.
+    pub files: Vec<FileFix>,
+}
+
+impl FixReport {
+    /// Number of files per status.
+    pub fn summary(&self) -> FixSummary {
+        let mut summary = FixSummary::default();
+        for file in &self.files {
+            *match file.status {
+                FixStatus::Changed => &mut summary.changed,
+                FixStatus::Clean => &mut summary.clean,
+                FixStatus::Unfixable => &mut summary.unfixable,
+                FixStatus::WriteFailed => &mut summary.write_failed,
+            } += 1;
+        }
+        summary
+    }
+
+    /// Check if every file ended up changed or clean.
+    pub fn is_success(&self) -> bool {
+        self.files
+            .iter()
+            .all(|f| matches!(f.status, FixStatus::Changed | FixStatus::Clean))
+    }
+
+    /// Write the fixed content of every file that changes, atomically.
+    ///
+    /// A file whose content changed since it was planned is left alone.
+    /// It becomes [`FixStatus::WriteFailed`], as does a file that cannot be
+    /// written.
+    pub fn write(&mut self) {
+        for file in &mut self.files {
+            let Some(content) = &file.edited else {
+                continue;
+            };
+            let current = read_skill_md_file(&file.path);
+            let error = if current.ok() != file.original {
+                Some(format!(
+                    "{} changed since the fix was planned; not written",
+                    file.path.display()
+                ))
+            } else {
+                atomic_write(&file.path, content)
+                    .err()
+                    .map(|e| format!("Failed to write {}: {}", file.path.display(), e))
+            };
+            if let Some(error) = error {
+                file.status = FixStatus::WriteFailed;
+                file.error = Some(error);
+                file.edited = None;
+            }
+        }
+    }
+}
+
+/// Work out the reflow of every skill body under `root` without writing.
+///
+/// With `syntax` set, each frontmatter is first converted to it with
+/// [`convert_frontmatter`]; otherwise the frontmatter is never changed.
+/// With both `mode` and `syntax` `None` every file is clean.
+///
+/// # Errors
+///
+/// Any error from [`find_skills`] if `root` cannot be scanned.
+pub fn plan_fmt_tree(
+    root: &Path,
+    mode: Option<Reflow>,
+    syntax: Option<FrontmatterSyntax>,
+) -> Result<FixReport> {
+    plan_tree(root, |content| {
+        let converted = match syntax {
+            Some(syntax) => convert_frontmatter(content, syntax)?,
+            None => content.to_string(),
+        };
+        let edited = match mode {
+            Some(mode) => reflow_skill_md(&converted, mode)?,
+            None => converted,
+        };
+        Ok((edited, Vec::new(), Vec::new()))
+    })
+}
+
+/// Reflow every skill body under `root` and write the changed files.
+///
+/// See [`plan_fmt_tree`] and [`FixReport::write`].
+///
+/// # Errors
+///
+/// Same as [`plan_fmt_tree`].
+pub fn fmt_tree(
+    root: &Path,
+    mode: Option<Reflow>,
+    syntax: Option<FrontmatterSyntax>,
+) -> Result<FixReport> {
+    let mut report = plan_fmt_tree(root, mode, syntax)?;
+    report.write();
+    Ok(report)
+}
+
+/// Work out the lint fixes (see [`FIXABLE_LINT_RULES`]) for every skill
+/// under `root` without writing.
+///
+/// Suppressed findings are left alone.
+///
+/// # Errors
+///
+/// Any error from [`find_skills`] if `root` cannot be scanned.
+pub fn plan_lint_tree(root: &Path) -> Result<FixReport> {
+    plan_tree(root, |content| {
+        let before = lint_content(content)?.findings;
+        let edited = fix_lint_findings(content)?;
+        let after = lint_content(&edited)?.findings;
+        let fixed = before
+            .into_iter()
+            .filter(|f| !after.iter().any(|a| a.diagnostic == f.diagnostic))
+            .collect();
+        Ok((edited, fixed, after))
+    })
+}
+
+/// Fix lint findings in every skill under `root` and write the changed
+/// files.
+///
+/// See [`plan_lint_tree`] and [`FixReport::write`].
+///
+/// # Errors
+///
+/// Same as [`plan_lint_tree`].
+pub fn lint_tree(root: &Path) -> Result<FixReport> {
+    let mut report = plan_lint_tree(root)?;
+    report.write();
+    Ok(report)
+}
+
+/// The fixed content, fixed findings, and remaining findings for one
+/// SKILL.md.
+type Fixed = (String, Vec<LintFinding>, Vec<LintFinding>);
+
+/// Apply `fix` to the SKILL.md of every skill under `root`.
+fn plan_tree(root: &Path, fix: impl Fn(&str) -> Result<Fixed>) -> Result<FixReport> {
+    let files = find_skills(root)?
+        .into_iter()
+        .map(|dir| {
+            let path = find_skill_md(&dir).unwrap_or_else(|| dir.join("SKILL.md"));
+            let fixed = read_skill_md_file(&path)
+                .and_then(|content| fix(&content).map(|fixed| (content, fixed)));
+            match fixed {
+                Ok((content, (edited, fixed, remaining))) => {
+                    let bytes_changed = changed_bytes(&content, &edited);
+                    let status = if !remaining.is_empty() {
+                        FixStatus::Unfixable
+                    } else if bytes_changed > 0 {
+                        FixStatus::Changed
+                    } else {
+                        FixStatus::Clean
+                    };
+                    FileFix {
+                        path,
+                        status,
+                        fixed,
+                        remaining,
+                        bytes_changed,
+                        error: None,
+                        edited: (bytes_changed > 0).then_some(edited),
+                        original: (bytes_changed > 0).then_some(content),
+                    }
+                }
+                Err(e) => FileFix {
+                    path,
+                    status: FixStatus::Unfixable,
+                    fixed: Vec::new(),
+                    remaining: vec![LintFinding {
+                        diagnostic: Diagnostic::error("skill-file", e.to_string()),
+                        line: None,
+                    }],
+                    bytes_changed: 0,
+                    error: None,
+                    edited: None,
+                    original: None,
+                },
+            }
+        })
+        .collect();
+    Ok(FixReport { files })
+}
+
+/// Rewrite the unsuppressed findings of the fixable lint rules in
+/// SKILL.md `content`.
+fn fix_lint_findings(content: &str) -> Result<String> {
+    let mut content = content.to_string();
+    let report = lint_content(&content)?;
+    if report
+        .findings
+        .iter()
+        .any(|f| f.diagnostic.rule == "description-newline")
+    {
+        let (metadata, _) = parse_frontmatter(&content)?;
+        if let Some(description) = metadata.get("description").and_then(|v| v.as_str()) {
+            let one_line = description.split_whitespace().collect::<Vec<_>>().join(" ");
+            content = update_frontmatter(&content, "description", Some(&one_line.into()))?;
+        }
+    }
+
+    // Lines may have moved; find the headings to fix in the new content.
+    let setext_lines: Vec<usize> = lint_content(&content)?
+        .findings
+        .iter()
+        .filter(|f| f.diagnostic.rule == "setext-heading")
+        .filter_map(|f| f.line)
+        .collect();
+    if setext_lines.is_empty() {
+        return Ok(content);
+    }
+    let (_, rest) = split_frontmatter(&content)?;
+    let body = rest.trim_start();
+    let body_start = content.len() - body.len();
+    let first_line = content[..body_start].matches('\n').count() + 1;
+    let mut edits: Vec<(std::ops::Range<usize>, String)> = markdown::setext_headings(body)
+        .into_iter()
+        .filter(|h| {
+            setext_lines.contains(&(first_line + body[..h.line.start].matches('\n').count()))
+        })
+        .map(|h| {
+            let range = body_start + h.line.start..body_start + h.line.end;
+            (range, format!("{} {}", "#".repeat(h.level), h.text))
+        })
+        .collect();
+    // From the end, so earlier ranges stay valid.
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+    for (range, heading) in edits {
+        content.replace_range(range, &heading);
+    }
+    Ok(content)
+}
+
+/// Size of the region where `old` and `new` differ: the longer of the two
+/// after removing their common prefix and suffix.
+fn changed_bytes(old: &str, new: &str) -> usize {
+    let (old, new) = (old.as_bytes(), new.as_bytes());
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    (old.len() - prefix - suffix).max(new.len() - prefix - suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_skill(root: &Path, name: &str, content: &str) -> PathBuf {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("SKILL.md"), content).unwrap();
+        dir.join("SKILL.md")
+    }
+
+    #[test]
+    fn test_lint_tree() {
+        let dir = TempDir::new().unwrap();
+        let fixable = write_skill(
+            dir.path(),
+            "a",
+            "---\nname: a\ndescription: |\n  Fill PDF forms.\n  Use for PDFs.\n---\nUsage\n=====\nRun it.\n\nMore\n----\nText.\n",
+        );
+        let clean = write_skill(
+            dir.path(),
+            "b",
+            "---\nname: b\ndescription: Fill PDF forms\n---\n# B\n",
+        );
+        let unfixable = write_skill(
+            dir.path(),
+            "c",
+            "---\nname: c\ndescription: Fill PDF forms\n---\nOld\n===\n# One\n# Two\n",
+        );
+        write_skill(dir.path(), "d", "no frontmatter\n");
+
+        let planned = plan_lint_tree(dir.path()).unwrap();
+        assert!(
+            std::fs::read_to_string(&fixable)
+                .unwrap()
+                .contains("Usage\n=====")
+        );
+        let report = lint_tree(dir.path()).unwrap();
+        assert_eq!(report, planned);
+        assert!(!report.is_success());
+        assert_eq!(
+            report.summary(),
+            FixSummary {
+                changed: 1,
+                clean: 1,
+                unfixable: 2,
+                write_failed: 0
+            }
+        );
+
+        let statuses: Vec<FixStatus> = report.files.iter().map(|f| f.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                FixStatus::Changed,
+                FixStatus::Clean,
+                FixStatus::Unfixable,
+                FixStatus::Unfixable
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&fixable).unwrap(),
+            "---\nname: a\ndescription: Fill PDF forms. Use for PDFs.\n---\n# Usage\nRun it.\n\n## More\nText.\n"
+        );
+        let a = &report.files[0];
+        let fixed: Vec<&str> = a.fixed.iter().map(|f| f.diagnostic.rule.as_str()).collect();
+        assert_eq!(
+            fixed,
+            ["description-newline", "setext-heading", "setext-heading"]
+        );
+        assert!(a.bytes_changed > 0);
+        assert_eq!(report.files[1].bytes_changed, 0);
+
+        // The heading is fixed even though a finding remains.
+        let c = &report.files[2];
+        let remaining: Vec<(&str, Option<usize>)> = c
+            .remaining
+            .iter()
+            .map(|f| (f.diagnostic.rule.as_str(), f.line))
+            .collect();
+        assert_eq!(
+            remaining,
+            [("multiple-h1", Some(6)), ("multiple-h1", Some(7))]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&unfixable).unwrap(),
+            "---\nname: c\ndescription: Fill PDF forms\n---\n# Old\n# One\n# Two\n"
+        );
+        assert_eq!(report.files[3].remaining[0].diagnostic.rule, "skill-file");
+        assert!(std::fs::read_to_string(&clean).unwrap().ends_with("# B\n"));
+    }
+
+    #[test]
+    fn test_fmt_tree() {
+        let dir = TempDir::new().unwrap();
+        let skill_md = write_skill(
+            dir.path(),
+            "a",
+            "---\nname: a\ndescription: d\n---\nFill the form\nand save it.\n",
+        );
+        write_skill(
+            dir.path(),
+            "b",
+            "---\nname: b\ndescription: d\n---\nOne line.\n",
+        );
+
+        let report = fmt_tree(dir.path(), Some(Reflow::Unwrap), None).unwrap();
+        assert!(report.is_success());
+        assert_eq!(report.files[0].status, FixStatus::Changed);
+        assert_eq!(report.files[0].bytes_changed, 1);
+        assert_eq!(report.files[1].status, FixStatus::Clean);
+        assert_eq!(
+            std::fs::read_to_string(&skill_md).unwrap(),
+            "---\nname: a\ndescription: d\n---\nFill the form and save it.\n"
+        );
+    }
+
+    #[test]
+    fn test_write_refuses_files_changed_since_planning() {
+        let dir = TempDir::new().unwrap();
+        let skill_md = write_skill(
+            dir.path(),
+            "a",
+            "---\nname: a\ndescription: d\n---\nFill the form\nand save it.\n",
+        );
+
+        let mut report = plan_fmt_tree(dir.path(), Some(Reflow::Unwrap), None).unwrap();
+        let edited = "---\nname: a\ndescription: d\n---\nEdited\nmeanwhile.\n";
+        std::fs::write(&skill_md, edited).unwrap();
+        report.write();
+
+        assert_eq!(report.files[0].status, FixStatus::WriteFailed);
+        assert!(
+            report.files[0]
+                .error
+                .as_deref()
+                .unwrap()
+                .contains("changed since the fix was planned"),
+        );
+        assert_eq!(std::fs::read_to_string(&skill_md).unwrap(), edited);
+    }
+
+    #[test]
+    fn test_changed_bytes() {
+        assert_eq!(changed_bytes("abc", "abc"), 0);
+        assert_eq!(changed_bytes("abc", "abXc"), 1);
+        assert_eq!(changed_bytes("aaa", "aa"), 1);
+        assert_eq!(changed_bytes("", "new"), 3);
+    }
+}
@@ -0,0 +1,125 @@
+//! Small example skills embedded in the crate.
+//!
+//! The `self-test` command runs the whole pipeline against these skills, and
+//! downstream crates can enable the `examples_fixtures` feature to reuse them
+//! in their own tests. Each example declares the rules it is expected to
+//! trigger under the default [`ValidationConfig`](crate::ValidationConfig),
+//! so a change in those results points at a platform or build problem
+//! rather than a problem with the skill.
+
+#[cfg(feature = "fs-write")]
+use std::path::{Path, PathBuf};
+
+/// An example skill: its directory and SKILL.md content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExampleSkill {
+    /// Name of the skill directory.
+    pub dir_name: &'static str,
+
+    /// Name of the SKILL.md file in the directory.
+    pub file_name: &'static str,
+
+    /// SKILL.md content.
+    pub content: &'static str,
+
+    /// IDs of the rules the skill triggers, sorted.
+    pub rules: &'static [&'static str],
+
+    /// Whether the skill has no validation errors.
+    pub valid: bool,
+}
+
+/// Every example skill: valid ones first, then invalid ones.
+///
+/// * `pdf-forms` uses every common field.
+/// * `café-notes` has a non-ASCII name, which must still match its
+///   directory on filesystems that normalize Unicode file names.
+/// * `csv-summary` is stored as `skill.md`, the other spelling the spec
+///   allows, which must still be found on case-sensitive filesystems.
+/// * `broken-skill` has an invalid name that does not match its
+///   directory, and no description.
+pub const EXAMPLE_SKILLS: &[ExampleSkill] = &[
+    ExampleSkill {
+        dir_name: "pdf-forms",
+        file_name: "SKILL.md",
+        content: include_str!("../fixtures/examples/pdf-forms.md"),
+        rules: &[],
+        valid: true,
+    },
+    ExampleSkill {
+        dir_name: "café-notes",
+        file_name: "SKILL.md",
+        content: include_str!("../fixtures/examples/cafe-notes.md"),
+        rules: &[],
+        valid: true,
+    },
+    ExampleSkill {
+        dir_name: "csv-summary",
+        file_name: "skill.md",
+        content: include_str!("../fixtures/examples/csv-summary.md"),
+        rules: &[],
+        valid: true,
+    },
+    ExampleSkill {
+        dir_name: "broken-skill",
+        file_name: "SKILL.md",
+        content: include_str!("../fixtures/examples/broken-skill.md"),
+        rules: &[
+            "name-characters",
+            "name-directory-match",
+            "name-lowercase",
+            "required-field",
+        ],
+        valid: false,
+    },
+];
+
+/// The example skill in directory `dir_name`, if any.
+pub fn example_skill(dir_name: &str) -> Option<&'static ExampleSkill> {
+    EXAMPLE_SKILLS
+        .iter()
+        .find(|skill| skill.dir_name == dir_name)
+}
+
+impl ExampleSkill {
+    /// Write the skill into a new directory under `root` and return the
+    /// directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the directory or file cannot be created.
+    #[cfg(feature = "fs-write")]
+    pub fn write_to(&self, root: &Path) -> std::io::Result<PathBuf> {
+        let dir = root.join(self.dir_name);
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(self.file_name), self.content)?;
+        Ok(dir)
+    }
+}
+
+#[cfg(all(test, feature = "fs-write"))]
+mod tests {
+    use super::*;
+    use crate::{ValidationConfig, validate_detailed};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_examples_trigger_declared_rules() {
+        let dir = TempDir::new().unwrap();
+        for example in EXAMPLE_SKILLS {
+            let skill_dir = example.write_to(dir.path()).unwrap();
+            let diagnostics = validate_detailed(&skill_dir, &ValidationConfig::default());
+            let mut rules: Vec<&str> = diagnostics.iter().map(|d| d.rule.as_str()).collect();
+            rules.sort_unstable();
+            rules.dedup();
+            assert_eq!(rules, example.rules, "{}", example.dir_name);
+            assert_eq!(
+                !diagnostics.iter().any(|d| d.is_error()),
+                example.valid,
+                "{}",
+                example.dir_name
+            );
+        }
+        assert_eq!(example_skill("csv-summary").unwrap().file_name, "skill.md");
+    }
+}
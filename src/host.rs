@@ -0,0 +1,299 @@
+//! Host capability checks for skills.
+//!
+//! A host (the agent runtime loading skills) can describe which tools it
+//! provides. Skills declare the tools they need in `allowed-tools`; a skill
+//! asking for a tool the host lacks cannot run there.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SkillError};
+use crate::models::SkillProperties;
+
+/// A single entry of the `allowed-tools` field, e.g. `Bash(git:*)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowedTool {
+    /// Tool name (`Bash` in `Bash(git:*)`).
+    pub tool: String,
+
+    /// Argument pattern inside the parentheses (`git:*` in `Bash(git:*)`), if any.
+    pub arg_pattern: Option<String>,
+}
+
+impl std::fmt::Display for AllowedTool {
+    /// The entry as written in `allowed-tools`, e.g. `Bash(git:*)`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.arg_pattern {
+            Some(pattern) => write!(f, "{}({})", self.tool, pattern),
+            None => f.write_str(&self.tool),
+        }
+    }
+}
+
+/// Capabilities of the host that will run skills.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostCapabilities {
+    /// Names of the tools the host provides (e.g. `Bash`, `Read`).
+    pub tools: Vec<String>,
+}
+
+impl HostCapabilities {
+    /// Create host capabilities from a list of tool names.
+    pub fn with_tools<I, S>(tools: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            tools: tools.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Check whether the host provides a tool.
+    pub fn has_tool(&self, tool: &str) -> bool {
+        self.tools.iter().any(|t| t == tool)
+    }
+}
+
+/// Parse an `allowed-tools` value into its tool entries.
+///
+/// Entries are separated by whitespace or commas. Separators inside
+/// parentheses belong to the argument pattern, so `Bash(git log:*)` is a
+/// single entry.
+///
+/// # Errors
+///
+/// Returns `ParseError` if the parentheses are unbalanced or an entry has
+/// no tool name.
+pub fn parse_allowed_tools(value: &str) -> Result<Vec<AllowedTool>> {
+    let (entries, errors) = parse_allowed_tools_lenient(value);
+    match errors.into_iter().next() {
+        Some(error) => Err(SkillError::parse(error)),
+        None => Ok(entries),
+    }
+}
+
+/// Parse an `allowed-tools` value, skipping malformed entries.
+///
+/// Like [`parse_allowed_tools`], but a malformed entry is reported and
+/// parsing continues with the next one. An unclosed `(` swallows the rest
+/// of the value.
+///
+/// # Returns
+///
+/// Tuple of (well-formed entries, error messages in value order).
+pub fn parse_allowed_tools_lenient(value: &str) -> (Vec<AllowedTool>, Vec<String>) {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    // Whether the current entry already had a stray ')'.
+    let mut broken = false;
+
+    for c in value.chars() {
+        match c {
+            '(' => {
+                depth += 1;
+                current.push(c);
+            }
+            ')' if depth == 0 => {
+                if !broken {
+                    errors.push(format!("Unbalanced ')' in allowed-tools: {}", value));
+                    broken = true;
+                }
+            }
+            ')' => {
+                depth -= 1;
+                current.push(c);
+            }
+            c if depth == 0 && (c.is_whitespace() || c == ',') => {
+                finish_entry(&current, broken, &mut entries, &mut errors);
+                current.clear();
+                broken = false;
+            }
+            _ => current.push(c),
+        }
+    }
+
+    if depth != 0 {
+        errors.push(format!("Unclosed '(' in allowed-tools: {}", value));
+    } else {
+        finish_entry(&current, broken, &mut entries, &mut errors);
+    }
+
+    (entries, errors)
+}
+
+/// Parse a completed entry into `entries`, or its error into `errors`.
+fn finish_entry(
+    entry: &str,
+    broken: bool,
+    entries: &mut Vec<AllowedTool>,
+    errors: &mut Vec<String>,
+) {
+    if broken || entry.is_empty() {
+        return;
+    }
+    match parse_allowed_tool(entry) {
+        Ok(tool) => entries.push(tool),
+        Err(error) => errors.push(error),
+    }
+}
+
+/// Parse a single `Tool` or `Tool(pattern)` entry.
+fn parse_allowed_tool(entry: &str) -> std::result::Result<AllowedTool, String> {
+    let (tool, arg_pattern) = match entry.find('(') {
+        Some(open) if entry.ends_with(')') => (
+            &entry[..open],
+            Some(entry[open + 1..entry.len() - 1].to_string()),
+        ),
+        Some(_) => {
+            return Err(format!(
+                "Unexpected text after ')' in allowed-tools entry: {}",
+                entry
+            ));
+        }
+        None => (entry, None),
+    };
+
+    if tool.is_empty() {
+        return Err(format!(
+            "Missing tool name in allowed-tools entry: {}",
+            entry
+        ));
+    }
+
+    Ok(AllowedTool {
+        tool: tool.to_string(),
+        arg_pattern,
+    })
+}
+
+/// Check whether a skill can run on a host.
+///
+/// Only `allowed-tools` is machine-checkable today: every tool it names must
+/// be provided by the host. `compatibility` is free text and is not
+/// interpreted. A skill without `allowed-tools` is always compatible.
+///
+/// # Returns
+///
+/// List of reasons the skill is incompatible. Empty list means compatible.
+pub fn validate_for_host(props: &SkillProperties, host: &HostCapabilities) -> Vec<String> {
+    let Some(ref allowed_tools) = props.allowed_tools else {
+        return Vec::new();
+    };
+
+    let tools = match parse_allowed_tools(&allowed_tools.raw()) {
+        Ok(tools) => tools,
+        Err(e) => return vec![e.to_string()],
+    };
+
+    let mut missing: Vec<&str> = Vec::new();
+    for entry in &tools {
+        if !host.has_tool(&entry.tool) && !missing.contains(&entry.tool.as_str()) {
+            missing.push(&entry.tool);
+        }
+    }
+
+    if missing.is_empty() {
+        Vec::new()
+    } else {
+        vec![format!(
+            "Requires tools not provided by the host: {}",
+            missing.join(", ")
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, pattern: Option<&str>) -> AllowedTool {
+        AllowedTool {
+            tool: name.to_string(),
+            arg_pattern: pattern.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_parse_allowed_tools_lenient() {
+        let (tools, errors) = parse_allowed_tools_lenient("Read (x) Bash(git:*)) Grep, Edit(a)b");
+        assert_eq!(tools, vec![tool("Read", None), tool("Grep", None)]);
+        assert_eq!(
+            errors,
+            vec![
+                "Missing tool name in allowed-tools entry: (x)".to_string(),
+                "Unbalanced ')' in allowed-tools: Read (x) Bash(git:*)) Grep, Edit(a)b".to_string(),
+                "Unexpected text after ')' in allowed-tools entry: Edit(a)b".to_string(),
+            ]
+        );
+
+        let (tools, errors) = parse_allowed_tools_lenient("Read Bash(git");
+        assert_eq!(tools, vec![tool("Read", None)]);
+        assert_eq!(
+            errors,
+            vec!["Unclosed '(' in allowed-tools: Read Bash(git".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_allowed_tools_space_separated() {
+        let tools = parse_allowed_tools("Bash(git:*) Bash(jq:*) Read").unwrap();
+        assert_eq!(
+            tools,
+            vec![
+                tool("Bash", Some("git:*")),
+                tool("Bash", Some("jq:*")),
+                tool("Read", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_allowed_tools_comma_separated() {
+        let tools = parse_allowed_tools("Read, Grep,Glob").unwrap();
+        assert_eq!(
+            tools,
+            vec![tool("Read", None), tool("Grep", None), tool("Glob", None)]
+        );
+    }
+
+    #[test]
+    fn test_parse_allowed_tools_pattern_with_spaces() {
+        let tools = parse_allowed_tools("Bash(git log:*)").unwrap();
+        assert_eq!(tools, vec![tool("Bash", Some("git log:*"))]);
+    }
+
+    #[test]
+    fn test_parse_allowed_tools_unbalanced() {
+        assert!(parse_allowed_tools("Bash(git:*").is_err());
+        assert!(parse_allowed_tools("Bash)").is_err());
+        assert!(parse_allowed_tools("(git:*)").is_err());
+    }
+
+    #[test]
+    fn test_validate_for_host_no_allowed_tools() {
+        let props = SkillProperties::new("my-skill", "A test skill");
+        let host = HostCapabilities::default();
+        assert!(validate_for_host(&props, &host).is_empty());
+    }
+
+    #[test]
+    fn test_validate_for_host_tools_provided() {
+        let mut props = SkillProperties::new("my-skill", "A test skill");
+        props.allowed_tools = Some("Bash(git:*) Read".into());
+        let host = HostCapabilities::with_tools(["Bash", "Read"]);
+        assert!(validate_for_host(&props, &host).is_empty());
+    }
+
+    #[test]
+    fn test_validate_for_host_tools_missing() {
+        let mut props = SkillProperties::new("my-skill", "A test skill");
+        props.allowed_tools = Some("Bash(git:*) Bash(jq:*) WebFetch".into());
+        let host = HostCapabilities::with_tools(["Read"]);
+
+        let reasons = validate_for_host(&props, &host);
+        assert_eq!(reasons.len(), 1);
+        assert!(reasons[0].contains("Bash, WebFetch"));
+    }
+}
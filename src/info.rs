@@ -0,0 +1,97 @@
+//! Version information for tools that drive skills-ref.
+
+use serde::Serialize;
+
+use crate::prompt::{PROMPT_FORMAT_VERSION, PromptFormatVersion};
+use crate::skill_set::SNAPSHOT_FORMAT_VERSION;
+use crate::validator::rules;
+
+/// Agent Skills specification revisions this crate implements.
+pub const SPEC_VERSIONS: &[&str] = &["1.0"];
+
+/// Optional cargo features, paired with whether they are compiled in.
+const FEATURES: &[(&str, bool)] = &[
+    ("fs-write", cfg!(feature = "fs-write")),
+    ("toml", cfg!(feature = "toml")),
+    ("examples_fixtures", cfg!(feature = "examples_fixtures")),
+    ("disk-cache", cfg!(feature = "disk-cache")),
+];
+
+/// What this build of the crate implements.
+///
+/// Serialized as the `info` command output. Tools use it as a
+/// compatibility handshake, so its keys are part of the CLI contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CrateInfo {
+    /// Crate version.
+    pub version: &'static str,
+
+    /// Supported specification revisions (see [`SPEC_VERSIONS`]).
+    pub spec_versions: Vec<&'static str>,
+
+    /// Version of the default `to-prompt` output format.
+    pub prompt_format_version: u32,
+
+    /// All supported `to-prompt` output format versions.
+    pub prompt_format_versions: Vec<u32>,
+
+    /// Version of the skill set snapshot format.
+    pub snapshot_format_version: u32,
+
+    /// IDs of all validation rules.
+    pub rules: Vec<&'static str>,
+
+    /// Enabled optional cargo features.
+    pub features: Vec<&'static str>,
+}
+
+/// Describe this build of the crate.
+pub fn crate_info() -> CrateInfo {
+    CrateInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        spec_versions: SPEC_VERSIONS.to_vec(),
+        prompt_format_version: PROMPT_FORMAT_VERSION,
+        prompt_format_versions: PromptFormatVersion::ALL
+            .iter()
+            .map(|version| version.number())
+            .collect(),
+        snapshot_format_version: SNAPSHOT_FORMAT_VERSION,
+        rules: rules().iter().map(|rule| rule.id).collect(),
+        features: FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crate_info_keys() {
+        let json = serde_json::to_value(crate_info()).unwrap();
+        let mut keys: Vec<_> = json.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "features",
+                "prompt_format_version",
+                "prompt_format_versions",
+                "rules",
+                "snapshot_format_version",
+                "spec_versions",
+                "version",
+            ]
+        );
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert!(
+            json["rules"]
+                .as_array()
+                .unwrap()
+                .contains(&"name-lowercase".into())
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! One-stop inspection of a skill for debugging.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostic::Diagnostic;
+use crate::error::Result;
+use crate::markdown;
+use crate::models::SkillProperties;
+use crate::skill::{Heading, Skill};
+use crate::validator::{ValidationConfig, validate_detailed};
+
+/// Rough number of characters per token used for [`BodyStats::estimated_tokens`].
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Rough token count of `text`: characters / 4, rounded up.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// A file shipped with a skill besides SKILL.md.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceFile {
+    /// Path relative to the skill directory, with `/` separators.
+    pub path: String,
+
+    /// Size in bytes.
+    pub size: u64,
+}
+
+/// A heading in the skill body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BodyHeading {
+    /// Heading level, 1 through 6.
+    pub level: usize,
+
+    /// Heading text.
+    pub text: String,
+}
+
+/// Size statistics for a skill body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BodyStats {
+    /// Number of lines.
+    pub lines: usize,
+
+    /// Number of whitespace-separated words.
+    pub words: usize,
+
+    /// Rough token estimate (characters / 4, rounded up).
+    pub estimated_tokens: usize,
+
+    /// ATX headings outside fenced code blocks, in document order.
+    pub headings: Vec<BodyHeading>,
+}
+
+impl BodyStats {
+    /// Compute statistics for a markdown body.
+    pub fn from_body(body: &str) -> Self {
+        Self {
+            lines: body.lines().count(),
+            words: body.split_whitespace().count(),
+            estimated_tokens: estimate_tokens(body),
+            headings: markdown::headings(body)
+                .into_iter()
+                .map(|h| BodyHeading {
+                    level: h.level,
+                    text: h.text,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Everything known about a skill directory, gathered in one pass.
+///
+/// Serialized as the `inspect --json` output; field names are part of the
+/// command's contract.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InspectionReport {
+    /// Path to the skill directory.
+    pub path: PathBuf,
+
+    /// Parsed properties, or `None` if SKILL.md could not be loaded.
+    pub properties: Option<SkillProperties>,
+
+    /// Whether validation produced no errors.
+    pub valid: bool,
+
+    /// All validation diagnostics.
+    pub diagnostics: Vec<Diagnostic>,
+
+    /// Files in the skill directory other than SKILL.md, sorted by path.
+    pub resources: Vec<ResourceFile>,
+
+    /// Body statistics, or `None` if SKILL.md could not be loaded.
+    pub body: Option<BodyStats>,
+
+    /// Headings of the body with their lines (see [`Skill::outline`]), or
+    /// `None` if SKILL.md could not be loaded. The `inspect` command leaves
+    /// it out unless asked for with `--outline`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outline: Option<Vec<Heading>>,
+
+    /// Hex-encoded SHA-256 digest of SKILL.md, or `None` if it could not be
+    /// loaded.
+    pub digest: Option<String>,
+}
+
+/// Inspect the skill at `skill_dir`.
+///
+/// A skill that fails to load still produces a report: its problems are
+/// listed in [`InspectionReport::diagnostics`] and the parsed fields are
+/// `None`.
+///
+/// # Errors
+///
+/// Returns an error if the skill directory exists but cannot be listed.
+pub fn inspect(skill_dir: &Path, config: &ValidationConfig) -> Result<InspectionReport> {
+    let diagnostics = validate_detailed(skill_dir, config);
+    let skill = Skill::load(skill_dir).ok();
+
+    let mut resources = Vec::new();
+    if skill_dir.is_dir() {
+        let skill_md = skill.as_ref().map(|s| s.skill_md.as_path());
+        collect_resources(
+            skill_dir,
+            Path::new(""),
+            skill_md,
+            &mut resources,
+            &mut Vec::new(),
+        )?;
+        resources.sort_by(|a, b| crate::ordering::compare(&a.path, &b.path));
+    }
+
+    Ok(InspectionReport {
+        path: skill_dir.to_path_buf(),
+        valid: !diagnostics.iter().any(Diagnostic::is_error),
+        diagnostics,
+        resources,
+        body: skill.as_ref().map(|s| BodyStats::from_body(&s.body)),
+        outline: skill.as_ref().map(Skill::outline),
+        digest: skill.as_ref().map(|s| s.digest.clone()),
+        properties: skill.map(|s| s.properties),
+    })
+}
+
+/// Collect regular files under `dir`, skipping `skill_md`. Symlinks are not
+/// followed; they go to `links` instead, with the size of their target (0
+/// if it is missing).
+pub(crate) fn collect_resources(
+    dir: &Path,
+    rel: &Path,
+    skill_md: Option<&Path>,
+    resources: &mut Vec<ResourceFile>,
+    links: &mut Vec<ResourceFile>,
+) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        let rel = rel.join(entry.file_name());
+
+        if file_type.is_dir() {
+            collect_resources(&path, &rel, skill_md, resources, links)?;
+        } else if file_type.is_file() && Some(path.as_path()) != skill_md {
+            resources.push(ResourceFile {
+                path: rel.to_string_lossy().replace('\\', "/"),
+                size: entry.metadata()?.len(),
+            });
+        } else if file_type.is_symlink() {
+            links.push(ResourceFile {
+                path: rel.to_string_lossy().replace('\\', "/"),
+                size: std::fs::metadata(&path).map_or(0, |m| m.len()),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Every file under a skill directory, for size checks.
+pub(crate) struct FileInventory {
+    /// Regular files, SKILL.md included, and symlinks to files inside the
+    /// skill directory (with the size of their target).
+    pub files: Vec<ResourceFile>,
+
+    /// Symlinks whose target lies outside the skill directory, relative to
+    /// it. They are not followed.
+    pub outside_links: Vec<String>,
+}
+
+/// Take stock of the files under `skill_dir`.
+///
+/// Symlinked directories are not followed, and dangling symlinks are
+/// skipped.
+pub(crate) fn file_inventory(skill_dir: &Path) -> Result<FileInventory> {
+    let mut inventory = FileInventory {
+        files: Vec::new(),
+        outside_links: Vec::new(),
+    };
+    let real_dir = skill_dir.canonicalize()?;
+    walk_inventory(skill_dir, Path::new(""), &real_dir, &mut inventory)?;
+    crate::ordering::sort_strings(&mut inventory.outside_links);
+    Ok(inventory)
+}
+
+fn walk_inventory(
+    dir: &Path,
+    rel: &Path,
+    real_dir: &Path,
+    inventory: &mut FileInventory,
+) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        let rel = rel.join(entry.file_name());
+        let rel_str = rel.to_string_lossy().replace('\\', "/");
+
+        if file_type.is_dir() {
+            walk_inventory(&path, &rel, real_dir, inventory)?;
+        } else if file_type.is_file() {
+            inventory.files.push(ResourceFile {
+                path: rel_str,
+                size: entry.metadata()?.len(),
+            });
+        } else if file_type.is_symlink() {
+            let Ok(target) = path.canonicalize() else {
+                continue;
+            };
+            if !target.starts_with(real_dir) {
+                inventory.outside_links.push(rel_str);
+            } else if target.is_file() {
+                inventory.files.push(ResourceFile {
+                    path: rel_str,
+                    size: std::fs::metadata(&target)?.len(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::sha256_hex;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_body_stats() {
+        let stats = BodyStats::from_body("# Title\n\nSome words here.\n```\n# not\n```\n## Usage");
+        assert_eq!(stats.lines, 7);
+        assert_eq!(stats.words, 11);
+        assert_eq!(stats.estimated_tokens, 12);
+        assert_eq!(
+            stats.headings,
+            vec![
+                BodyHeading {
+                    level: 1,
+                    text: "Title".to_string()
+                },
+                BodyHeading {
+                    level: 2,
+                    text: "Usage".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inspect_json_contract() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+        let content =
+            "---\nname: my-skill\ndescription: A skill used in tests\nextra: 1\n---\n# Body\n";
+        std::fs::write(skill_dir.join("SKILL.md"), content).unwrap();
+        std::fs::write(skill_dir.join("scripts/run.py"), "print()\n").unwrap();
+        std::fs::write(skill_dir.join("reference.md"), "ref").unwrap();
+
+        let report = inspect(&skill_dir, &ValidationConfig::default()).unwrap();
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "path": skill_dir,
+                "properties": {"name": "my-skill", "description": "A skill used in tests"},
+                "valid": false,
+                "diagnostics": [{
+                    "rule": "unexpected-fields",
+                    "severity": "error",
+                    "message": report.diagnostics[0].message,
+                    "params": {
+                        "fields": ["extra"],
+                        "allowed": [
+                            "allowed-tools",
+                            "compatibility",
+                            "description",
+                            "license",
+                            "metadata",
+                            "name",
+                        ],
+                    },
+                }],
+                "resources": [
+                    {"path": "reference.md", "size": 3},
+                    {"path": "scripts/run.py", "size": 8},
+                ],
+                "body": {
+                    "lines": 1,
+                    "words": 2,
+                    "estimated_tokens": 2,
+                    "headings": [{"level": 1, "text": "Body"}],
+                },
+                "outline": [{"level": 1, "text": "Body", "line": 1}],
+                "digest": sha256_hex(content.as_bytes()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_inspect_unloadable_skill() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("broken");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skill_dir.join("notes.txt"), "x").unwrap();
+
+        let report = inspect(&skill_dir, &ValidationConfig::default()).unwrap();
+        assert!(!report.valid);
+        assert_eq!(report.diagnostics[0].rule, "skill-md-missing");
+        assert_eq!(report.properties, None);
+        assert_eq!(report.body, None);
+        assert_eq!(report.outline, None);
+        assert_eq!(report.resources.len(), 1);
+    }
+}
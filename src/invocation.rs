@@ -0,0 +1,246 @@
+//! Per-skill manifests for hosts that invoke a skill.
+//!
+//! When an agent picks a skill, the host's sandbox layer needs to know
+//! which files to mount and which tools to allow. [`InvocationManifest`]
+//! carries exactly that, with absolute paths so it can be used without
+//! knowing where the catalog was loaded from.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::host::{AllowedTool, parse_allowed_tools};
+use crate::inspect::{ResourceFile, collect_resources};
+use crate::ordering;
+use crate::paths::resolve_within;
+use crate::skill::Skill;
+
+/// Resource files larger than this are left out of invocation manifests
+/// unless another cap is given.
+pub const DEFAULT_MAX_RESOURCE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// What a host needs to run one skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvocationManifest {
+    /// Skill name.
+    pub name: String,
+
+    /// Absolute path to the skill directory.
+    pub dir: PathBuf,
+
+    /// Absolute path to SKILL.md.
+    pub skill_md: PathBuf,
+
+    /// Hex-encoded SHA-256 digest of SKILL.md.
+    pub digest: String,
+
+    /// Parsed `allowed-tools` entries; empty if the field is absent.
+    pub allowed_tools: Vec<AllowedTool>,
+
+    /// Files the host may mount, relative to `dir`.
+    pub resources: Vec<ResourceFile>,
+
+    /// Files left out of `resources`, with the reason.
+    pub excluded: Vec<ExcludedResource>,
+}
+
+/// A resource file left out of an invocation manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExcludedResource {
+    /// Path relative to the skill directory.
+    pub path: String,
+
+    /// File size in bytes.
+    pub size: u64,
+
+    /// Why the file was left out.
+    pub reason: String,
+}
+
+impl Skill {
+    /// Build the invocation manifest for this skill, leaving out resources
+    /// over [`DEFAULT_MAX_RESOURCE_BYTES`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Skill::invocation_manifest_with`].
+    pub fn invocation_manifest(&self) -> Result<InvocationManifest> {
+        self.invocation_manifest_with(DEFAULT_MAX_RESOURCE_BYTES)
+    }
+
+    /// Build the invocation manifest for this skill, leaving out resources
+    /// larger than `max_resource_bytes`.
+    ///
+    /// Every resource path goes through [`resolve_within`]; paths it
+    /// rejects are listed in `excluded` rather than `resources`, and so are
+    /// symlinks, which are never followed.
+    ///
+    /// # Errors
+    ///
+    /// * `IoError` - If the skill directory cannot be read
+    /// * `ParseError` - If `allowed-tools` cannot be parsed, since a host
+    ///   enforcing tool policy should not get a partial list
+    pub fn invocation_manifest_with(&self, max_resource_bytes: u64) -> Result<InvocationManifest> {
+        let dir = self.dir.canonicalize()?;
+        let skill_md = self.skill_md.canonicalize()?;
+        let allowed_tools = match &self.properties.allowed_tools {
+            Some(value) => parse_allowed_tools(&value.raw())?,
+            None => Vec::new(),
+        };
+
+        let mut found = Vec::new();
+        let mut links = Vec::new();
+        collect_resources(&dir, Path::new(""), Some(&skill_md), &mut found, &mut links)?;
+        let mut found: Vec<(ResourceFile, bool)> = found
+            .into_iter()
+            .map(|resource| (resource, false))
+            .chain(links.into_iter().map(|link| (link, true)))
+            .collect();
+        found.sort_by(|(a, _), (b, _)| ordering::compare(&a.path, &b.path));
+
+        let mut resources = Vec::new();
+        let mut excluded = Vec::new();
+        for (resource, is_link) in found {
+            let reason = if is_link {
+                "Symlinks are not followed".to_string()
+            } else if let Err(e) = resolve_within(&dir, &resource.path) {
+                e.to_string()
+            } else if resource.size > max_resource_bytes {
+                format!(
+                    "File is {} bytes, over the {} byte limit",
+                    resource.size, max_resource_bytes
+                )
+            } else {
+                resources.push(resource);
+                continue;
+            };
+            excluded.push(ExcludedResource {
+                path: resource.path,
+                size: resource.size,
+                reason,
+            });
+        }
+
+        Ok(InvocationManifest {
+            name: self.properties.name.clone(),
+            dir,
+            skill_md,
+            digest: self.digest.clone(),
+            allowed_tools,
+            resources,
+            excluded,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_invocation_manifest() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("pdf");
+        std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: pdf\ndescription: Work with PDFs\nallowed-tools: Bash(python:*) Read\n---\n# PDF\n",
+        )
+        .unwrap();
+        std::fs::write(skill_dir.join("scripts/fill.py"), "print()").unwrap();
+        std::fs::write(skill_dir.join("sample.pdf"), vec![0u8; 64]).unwrap();
+
+        let skill = Skill::load(&skill_dir).unwrap();
+        let manifest = skill.invocation_manifest_with(32).unwrap();
+
+        assert_eq!(manifest.name, "pdf");
+        assert!(manifest.skill_md.is_absolute());
+        assert!(manifest.skill_md.ends_with("pdf/SKILL.md"));
+        assert_eq!(manifest.digest, skill.digest);
+        assert_eq!(
+            manifest.allowed_tools,
+            vec![
+                AllowedTool {
+                    tool: "Bash".to_string(),
+                    arg_pattern: Some("python:*".to_string()),
+                },
+                AllowedTool {
+                    tool: "Read".to_string(),
+                    arg_pattern: None,
+                },
+            ]
+        );
+        assert_eq!(
+            manifest.resources,
+            vec![ResourceFile {
+                path: "scripts/fill.py".to_string(),
+                size: 7,
+            }]
+        );
+        assert_eq!(manifest.excluded.len(), 1);
+        assert_eq!(manifest.excluded[0].path, "sample.pdf");
+        assert_eq!(
+            manifest.excluded[0].reason,
+            "File is 64 bytes, over the 32 byte limit"
+        );
+
+        let manifest = skill.invocation_manifest().unwrap();
+        assert_eq!(manifest.resources.len(), 2);
+        assert!(manifest.excluded.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_invocation_manifest_reports_symlinks() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("pdf");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: pdf\ndescription: Work with PDFs\n---\n",
+        )
+        .unwrap();
+        std::fs::write(skill_dir.join("notes.md"), "notes").unwrap();
+        std::os::unix::fs::symlink("notes.md", skill_dir.join("link.md")).unwrap();
+        std::os::unix::fs::symlink("missing", skill_dir.join("dangling")).unwrap();
+
+        let manifest = Skill::load(&skill_dir)
+            .unwrap()
+            .invocation_manifest()
+            .unwrap();
+        assert_eq!(manifest.resources.len(), 1);
+        assert_eq!(manifest.resources[0].path, "notes.md");
+        assert_eq!(
+            manifest.excluded,
+            vec![
+                ExcludedResource {
+                    path: "dangling".to_string(),
+                    size: 0,
+                    reason: "Symlinks are not followed".to_string(),
+                },
+                ExcludedResource {
+                    path: "link.md".to_string(),
+                    size: 5,
+                    reason: "Symlinks are not followed".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invocation_manifest_rejects_bad_allowed_tools() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("pdf");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: pdf\ndescription: Work with PDFs\nallowed-tools: Bash(git\n---\n",
+        )
+        .unwrap();
+
+        let skill = Skill::load(&skill_dir).unwrap();
+        assert!(skill.invocation_manifest().is_err());
+    }
+}
@@ -10,6 +10,36 @@
 //! 2. **Read Properties** - Parse SKILL.md frontmatter and extract metadata as JSON
 //! 3. **To Prompt** - Generate XML blocks for embedding skill information in agent prompts
 //!
+//! ## Cargo features
+//!
+//! Everything that creates or rewrites skill files ([`write_skill`],
+//! [`update_frontmatter`], [`reflow_skill_md`], [`bulk_update`],
+//! [`SkillSet::save_snapshot`]) is behind the default `fs-write` feature.
+//! With `default-features = false` those items do not exist, so the crate
+//! can only read from disk.
+//!
+//! The default `examples_fixtures` feature embeds a few small example
+//! skills in [`fixtures`], for the `self-test` command and downstream
+//! tests.
+//!
+//! The default `disk-cache` feature adds [`DiskCache`], which keeps the
+//! results of [`read_properties_cached`] and [`validate_detailed_cached`]
+//! in files so they survive between runs. It writes those files, so it
+//! turns on `fs-write`.
+//!
+//! ## API stability
+//!
+//! * **Stable** - the items in [`prelude`]. They change incompatibly only
+//!   in a major release. Their enums and options structs are
+//!   `#[non_exhaustive]`, as is [`SkillError`], so new variants and
+//!   fields can arrive in minor releases; options are built from
+//!   `Default` with `with_*` methods or field assignment.
+//! * **Evolving** - every other public item. It may change in a minor
+//!   release.
+//! * **Output formats** - generated prompts are byte-stable per
+//!   [`PromptFormatVersion`], and porcelain CLI output changes only in a
+//!   major release.
+//!
 //! ## Example
 //!
 //! ```no_run
@@ -31,15 +61,125 @@
 //! println!("{}", xml);
 //! ```
 
+#[cfg(feature = "fs-write")]
+pub mod bulk;
+pub mod cache;
+pub mod cancel;
+pub mod changed;
+pub mod confusables;
+pub mod diagnostic;
+pub mod digest;
+pub mod discovery;
+pub mod duplicates;
+#[cfg(feature = "fs-write")]
+pub mod edit;
 pub mod error;
+pub mod export;
+#[cfg(feature = "fs-write")]
+pub mod fix;
+#[cfg(feature = "examples_fixtures")]
+pub mod fixtures;
+pub mod host;
+pub mod info;
+pub mod inspect;
+pub mod invocation;
+pub mod license;
+pub mod lint;
+pub mod manifest;
+mod markdown;
+pub mod metrics;
 pub mod models;
+pub mod ordering;
+pub mod overrides;
+pub mod parallel;
 pub mod parser;
+pub mod paths;
+pub mod prelude;
 pub mod prompt;
+pub mod promptset;
+pub mod redact;
+#[cfg(feature = "fs-write")]
+pub mod reflow;
+pub mod shared;
+pub mod skill;
+pub mod skill_set;
+pub mod source;
+pub mod stats;
+pub mod template;
+pub mod trust;
 pub mod validator;
+mod version;
+pub mod workspace;
+pub mod writer;
+mod yaml_emit;
 
 // Re-export main types and functions for convenience
+#[cfg(feature = "fs-write")]
+pub use bulk::{BulkReport, EditOutcome, FieldEdit, SkillEdit, bulk_update, plan_bulk_update};
+#[cfg(feature = "disk-cache")]
+pub use cache::DiskCache;
+pub use cache::{
+    MemoryCache, SkillCache, read_properties_cached, read_properties_cached_with,
+    validate_detailed_cached, validate_detailed_cached_with,
+};
+pub use cancel::CancellationToken;
+pub use changed::{ChangeSet, changed_skills};
+pub use diagnostic::{Diagnostic, EnglishRenderer, MessageRenderer, Param, Severity};
+pub use discovery::{
+    DiscoveredSkill, DiscoveryOptions, SkillIter, find_skills, find_skills_with, iter_skills,
+};
+pub use duplicates::{DuplicateGroup, DuplicateKind, DuplicateOptions};
+#[cfg(feature = "fs-write")]
+pub use edit::{convert_frontmatter, reformat_frontmatter, update_frontmatter};
 pub use error::{Result, SkillError};
-pub use models::SkillProperties;
-pub use parser::{find_skill_md, parse_frontmatter, read_properties};
-pub use prompt::to_prompt;
-pub use validator::{validate, validate_metadata};
+pub use export::to_markdown_bundle;
+#[cfg(feature = "fs-write")]
+pub use fix::{
+    FileFix, FixReport, FixStatus, FixSummary, fmt_tree, lint_tree, plan_fmt_tree, plan_lint_tree,
+};
+pub use host::{
+    AllowedTool, HostCapabilities, parse_allowed_tools, parse_allowed_tools_lenient,
+    validate_for_host,
+};
+pub use info::{CrateInfo, SPEC_VERSIONS, crate_info};
+pub use inspect::{BodyStats, InspectionReport, ResourceFile, estimate_tokens, inspect};
+pub use invocation::{DEFAULT_MAX_RESOURCE_BYTES, ExcludedResource, InvocationManifest};
+pub use license::{LicenseReport, license_report};
+pub use lint::{LintFinding, LintReport, lint};
+pub use manifest::{Manifest, ManifestEntry, NameAvailability, is_name_available};
+pub use metrics::{Metrics, NoopMetrics, RecordingMetrics};
+pub use models::{
+    AllowedToolsRepr, CONVENTIONAL_METADATA_KEYS, SkillProperties, SkillPropertiesBuilder,
+};
+pub use overrides::PromptOverrides;
+pub use parser::{
+    FrontmatterSyntax, MAX_SKILL_MD_BYTES, find_skill_md, parse_frontmatter, properties_from_bytes,
+    read_properties, resolve_skill_dir,
+};
+pub use paths::resolve_within;
+pub use prompt::{
+    EmptyPolicy, ExcludedSkill, IncludedSkill, InlineBody, PROMPT_FORMAT_VERSION,
+    PromptFormatVersion, PromptOptions, PromptRender, PromptSkillEntry, Sanitizer,
+    UnreadablePolicy, cache_key, parse_prompt, prompt_pages, render_prompt, to_prompt,
+    to_prompt_with_exclusions, to_prompt_with_options,
+};
+pub use promptset::{PromptComposition, PromptOrder, PromptSet};
+pub use redact::PathRedaction;
+#[cfg(feature = "fs-write")]
+pub use reflow::{Reflow, reflow, reflow_skill_md};
+pub use shared::{ReloadReport, SharedSkillSet};
+pub use skill::{Heading, Skill};
+pub use skill_set::{DuplicatePolicy, LoadOptions, ShadowedSkill, SkillSet};
+pub use source::{CanonicalizeMode, FsSource, SkillSource};
+pub use stats::{CatalogStats, stats, validate_with_stats};
+pub use trust::TrustLevel;
+pub use validator::{
+    NameMatch, RuleContext, RuleInfo, SizeLimits, ValidationConfig, ValidationInput,
+    ValidationReport, ValidationRule, slugify, validate, validate_bytes_at, validate_content_at,
+    validate_detailed, validate_input, validate_metadata, validate_properties, validate_report,
+    validate_with_config,
+};
+pub use workspace::{WorkspaceReport, validate_many_cancellable, validate_workspace};
+#[cfg(feature = "fs-write")]
+pub use writer::{OverwriteMode, atomic_write, write_skill, write_skill_with};
+pub use writer::{to_skill_md, to_skill_md_with};
@@ -0,0 +1,178 @@
+//! License aggregation across a tree of skills.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::discovery::find_skills;
+use crate::error::Result;
+use crate::parser::read_properties;
+use crate::skill_set::LoadFailure;
+
+/// Group key used for skills without a `license` field.
+pub const UNSPECIFIED_LICENSE: &str = "unspecified";
+
+/// A skill listed in a [`LicenseReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LicensedSkill {
+    /// Skill name.
+    pub name: String,
+
+    /// Path to the skill directory.
+    pub path: PathBuf,
+}
+
+/// Skills grouped by their `license` value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LicenseReport {
+    /// Skills keyed by license value; skills without a license are under
+    /// [`UNSPECIFIED_LICENSE`].
+    pub licenses: BTreeMap<String, Vec<LicensedSkill>>,
+
+    /// Skills that could not be read.
+    pub failures: Vec<LoadFailure>,
+}
+
+impl LicenseReport {
+    /// Find skills whose license matches any of the `deny` values.
+    ///
+    /// Matching is case-insensitive. `unspecified` matches skills without a
+    /// license. Any other value must equal one license identifier of the
+    /// skill's license expression exactly: `MIT` matches `MIT OR Apache-2.0`
+    /// but `GPL-3.0` does not match `LGPL-3.0`.
+    ///
+    /// # Returns
+    ///
+    /// Tuples of (license value, skill), in report order.
+    pub fn denied<'a>(&'a self, deny: &[&str]) -> Vec<(&'a str, &'a LicensedSkill)> {
+        let mut denied = Vec::new();
+        for (license, skills) in &self.licenses {
+            if deny.iter().any(|d| license_matches(license, d)) {
+                denied.extend(skills.iter().map(|skill| (license.as_str(), skill)));
+            }
+        }
+        denied
+    }
+}
+
+/// Split a license expression into its license identifiers.
+///
+/// SPDX operators (`AND`, `OR`, `WITH`) and parentheses are dropped, as is
+/// trailing punctuation, so `Proprietary. See LICENSE.txt` yields
+/// `Proprietary`, `See`, and `LICENSE.txt`.
+fn license_identifiers(expression: &str) -> impl Iterator<Item = &str> {
+    expression
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .map(|token| token.trim_end_matches(['.', ',', ';', ':']))
+        .filter(|token| {
+            !token.is_empty()
+                && !["and", "or", "with"]
+                    .iter()
+                    .any(|op| token.eq_ignore_ascii_case(op))
+        })
+}
+
+/// Check whether a license group key matches a deny value.
+fn license_matches(license: &str, deny: &str) -> bool {
+    if license == UNSPECIFIED_LICENSE {
+        return deny.eq_ignore_ascii_case(UNSPECIFIED_LICENSE);
+    }
+    license_identifiers(license).any(|id| id.eq_ignore_ascii_case(deny))
+}
+
+/// Group all skills under `root` by their `license` value.
+///
+/// # Errors
+///
+/// Returns an error if `root` cannot be scanned. Skills that fail to load
+/// are recorded in [`LicenseReport::failures`] instead.
+pub fn license_report(root: &Path) -> Result<LicenseReport> {
+    let mut report = LicenseReport::default();
+
+    for dir in find_skills(root)? {
+        match read_properties(&dir) {
+            Ok(props) => {
+                let license = props
+                    .license
+                    .map(|l| l.trim().to_string())
+                    .filter(|l| !l.is_empty())
+                    .unwrap_or_else(|| UNSPECIFIED_LICENSE.to_string());
+                report
+                    .licenses
+                    .entry(license)
+                    .or_default()
+                    .push(LicensedSkill {
+                        name: props.name,
+                        path: dir,
+                    });
+            }
+            Err(e) => report.failures.push(LoadFailure {
+                path: dir,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_skill(root: &Path, name: &str, license: Option<&str>) {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        let license = license
+            .map(|l| format!("license: {}\n", l))
+            .unwrap_or_default();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {}\ndescription: d\n{}---\n", name, license),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_license_report_groups() {
+        let dir = TempDir::new().unwrap();
+        create_skill(dir.path(), "a", Some("MIT"));
+        create_skill(dir.path(), "b", Some("MIT"));
+        create_skill(dir.path(), "c", None);
+        std::fs::create_dir_all(dir.path().join("broken")).unwrap();
+        std::fs::write(dir.path().join("broken/SKILL.md"), "no frontmatter").unwrap();
+
+        let report = license_report(dir.path()).unwrap();
+        assert_eq!(report.licenses["MIT"].len(), 2);
+        assert_eq!(report.licenses[UNSPECIFIED_LICENSE][0].name, "c");
+        assert_eq!(report.failures.len(), 1);
+    }
+
+    #[test]
+    fn test_denied_unspecified_case_insensitive() {
+        let dir = TempDir::new().unwrap();
+        create_skill(dir.path(), "a", Some("MIT"));
+        create_skill(dir.path(), "c", None);
+
+        let report = license_report(dir.path()).unwrap();
+        let denied = report.denied(&["Unspecified"]);
+        assert_eq!(denied.len(), 1);
+        assert_eq!(denied[0].1.name, "c");
+    }
+
+    #[test]
+    fn test_denied_matches_expression_members_exactly() {
+        assert!(license_matches("MIT OR Apache-2.0", "mit"));
+        assert!(license_matches("(MIT AND BSD-3-Clause)", "BSD-3-Clause"));
+        assert!(license_matches(
+            "Proprietary. LICENSE.txt has complete terms",
+            "proprietary"
+        ));
+        assert!(!license_matches("LGPL-3.0", "GPL-3.0"));
+        assert!(!license_matches("MIT-0", "MIT"));
+        assert!(!license_matches("MIT OR Apache-2.0", "or"));
+        assert!(!license_matches(UNSPECIFIED_LICENSE, "MIT"));
+    }
+}
@@ -0,0 +1,640 @@
+//! Lint rules for skill bodies and descriptions.
+//!
+//! Unlike validation rules, lint rules flag likely problems rather than
+//! spec violations, and a skill can opt out of them with comments in its
+//! body:
+//!
+//! * `<!-- skills-lint-disable rule-id -->` disables rules for the whole
+//!   file;
+//! * `<!-- skills-lint-disable-next-line rule-id -->` disables rules for
+//!   the line that follows.
+//!
+//! Several rule IDs may be listed, separated by spaces or commas. The
+//! comment must be alone on its line. Validation rules cannot be disabled
+//! this way, and naming one (or an unknown rule) is itself reported.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::diagnostic::{Diagnostic, Severity, shorten};
+use crate::error::{Result, SkillError};
+use crate::markdown;
+use crate::parser::{find_skill_md, parse_frontmatter, read_skill_md_file, split_frontmatter};
+use crate::validator::{DEFAULT_MESSAGE_VALUE_CHARS, RuleInfo, rule_info};
+
+const DISABLE: &str = "skills-lint-disable";
+const DISABLE_NEXT_LINE: &str = "skills-lint-disable-next-line";
+
+/// All built-in lint rules.
+const LINT_RULES: &[RuleInfo] = &[
+    RuleInfo {
+        id: "empty-body",
+        summary: "The SKILL.md body should not be empty",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "The body holds the instructions an agent follows once it picks the \
+            skill. A skill with only frontmatter gives the agent nothing to act on.",
+        failing_example: "---\nname: pdf\ndescription: Work with PDFs\n---",
+        passing_example: "---\nname: pdf\ndescription: Work with PDFs\n---\n# PDF\nUse fill.py.",
+    },
+    RuleInfo {
+        id: "multiple-h1",
+        summary: "The body should have at most one H1 heading",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "A single `#` heading titles the skill. A second H1 usually means a \
+            heading level is wrong; skills embedding a document template can disable the \
+            rule.",
+        failing_example: "# PDF\n...\n# Usage",
+        passing_example: "# PDF\n...\n## Usage",
+    },
+    RuleInfo {
+        id: "heading-level-skip",
+        summary: "Heading levels should increase one at a time",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "Jumping from `#` to `###` leaves a gap in the document outline, which \
+            makes sections harder to address with `Skill::section`.",
+        failing_example: "# PDF\n### Usage",
+        passing_example: "# PDF\n## Usage",
+    },
+    RuleInfo {
+        id: "setext-heading",
+        summary: "Headings should use `#` markers",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "Only `#` headings count as headings for `Skill::outline`, \
+            `Skill::section`, and the other heading rules; text underlined with `===` or \
+            `---` is read as a paragraph. A `---` underline is also easily mistaken for a \
+            frontmatter delimiter. `lint --fix` rewrites the heading with `#` markers.",
+        failing_example: "Usage\n-----",
+        passing_example: "## Usage",
+    },
+    RuleInfo {
+        id: "invalid-suppression",
+        summary: "Suppression comments must name lint rules",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "A `skills-lint-disable` comment naming an unknown rule is probably a \
+            typo, and validation rules cannot be suppressed at all. This rule cannot be \
+            disabled itself.",
+        failing_example: "<!-- skills-lint-disable multiple-h2 -->",
+        passing_example: "<!-- skills-lint-disable multiple-h1 -->",
+    },
+    RuleInfo {
+        id: "description-single-word",
+        summary: "The description should be more than one word",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "Agents pick skills by their description. A single word says neither \
+            what the skill does nor when to use it.",
+        failing_example: "description: PDFs",
+        passing_example: "description: Extract text from PDFs. Use when the user shares a PDF.",
+    },
+    RuleInfo {
+        id: "description-repeats-name",
+        summary: "The description should not just repeat the name",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "The name is already shown next to the description, so repeating it \
+            gives the agent nothing to choose the skill by. Case, hyphens, underscores, and \
+            a final period are ignored, so `PDF tools.` repeats `pdf-tools`.",
+        failing_example: "name: pdf-tools\ndescription: PDF tools",
+        passing_example: "name: pdf-tools\ndescription: Fill and merge PDF forms.",
+    },
+    RuleInfo {
+        id: "description-all-caps",
+        summary: "The description should not be written entirely in uppercase",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "An all-caps description reads as shouting inside the prompt and tends \
+            to be a placeholder or a heading pasted in by mistake.",
+        failing_example: "description: EXTRACT TEXT FROM PDFS",
+        passing_example: "description: Extract text from PDFs",
+    },
+    RuleInfo {
+        id: "description-markdown",
+        summary: "The description should be plain text",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "Descriptions are embedded in the XML prompt as text, so markdown \
+            emphasis, code spans, links, headings, and list markers show up literally.",
+        failing_example: "description: Extract **all** text from `.pdf` files",
+        passing_example: "description: Extract all text from PDF files",
+    },
+    RuleInfo {
+        id: "description-newline",
+        summary: "The description should be a single line",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "Line breaks inside a description survive into the prompt and split \
+            the entry oddly. Use a folded block (`>`) or one line instead of a literal \
+            block (`|`). `lint --fix` joins the lines with spaces.",
+        failing_example: "description: |\n  Extract text.\n  Use for PDFs.",
+        passing_example: "description: >\n  Extract text.\n  Use for PDFs.",
+    },
+];
+
+/// List metadata for all built-in lint rules.
+pub fn lint_rules() -> Vec<RuleInfo> {
+    LINT_RULES.to_vec()
+}
+
+/// Look up a lint rule by ID.
+pub fn lint_rule_info(id: &str) -> Option<RuleInfo> {
+    LINT_RULES.iter().find(|rule| rule.id == id).copied()
+}
+
+/// A lint diagnostic with its location.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintFinding {
+    /// The diagnostic.
+    #[serde(flatten)]
+    pub diagnostic: Diagnostic,
+
+    /// 1-based line in SKILL.md, if the finding has a location.
+    pub line: Option<usize>,
+}
+
+/// Result of linting a skill.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LintReport {
+    /// Findings that were not suppressed, in line order.
+    pub findings: Vec<LintFinding>,
+
+    /// Number of suppressed findings per rule ID.
+    pub suppressed: BTreeMap<String, usize>,
+}
+
+impl LintReport {
+    /// Total number of suppressed findings.
+    pub fn suppressed_count(&self) -> usize {
+        self.suppressed.values().sum()
+    }
+}
+
+/// Suppressions parsed from a body.
+#[derive(Default)]
+struct Suppressions {
+    file: Vec<String>,
+    /// (1-based line the suppression applies to, rule ID).
+    lines: Vec<(usize, String)>,
+}
+
+impl Suppressions {
+    fn covers(&self, rule: &str, line: Option<usize>) -> bool {
+        self.file.iter().any(|id| id == rule)
+            || line.is_some_and(|line| self.lines.iter().any(|(l, id)| *l == line && id == rule))
+    }
+}
+
+/// Parse suppression comments, reporting invalid rule IDs.
+fn parse_suppressions(body: &str, first_line: usize) -> (Suppressions, Vec<LintFinding>) {
+    let mut suppressions = Suppressions::default();
+    let mut findings = Vec::new();
+
+    for (i, line) in body.lines().enumerate() {
+        let line_no = first_line + i;
+        let Some(inner) = line
+            .trim()
+            .strip_prefix("<!--")
+            .and_then(|rest| rest.strip_suffix("-->"))
+        else {
+            continue;
+        };
+        let mut words = inner.split([' ', '\t', ',']).filter(|w| !w.is_empty());
+        let next_line = match words.next() {
+            Some(DISABLE) => false,
+            Some(DISABLE_NEXT_LINE) => true,
+            _ => continue,
+        };
+
+        for id in words {
+            let problem = if id == "invalid-suppression" {
+                Some(format!("Rule '{}' cannot be suppressed", id))
+            } else if lint_rule_info(id).is_some() {
+                None
+            } else if rule_info(id).is_some() {
+                Some(format!(
+                    "Rule '{}' is a validation rule and cannot be suppressed",
+                    id
+                ))
+            } else {
+                Some(format!("Unknown lint rule '{}' in suppression comment", id))
+            };
+
+            match problem {
+                Some(message) => findings.push(LintFinding {
+                    diagnostic: Diagnostic::warning("invalid-suppression", message),
+                    line: Some(line_no),
+                }),
+                None if next_line => suppressions.lines.push((line_no + 1, id.to_string())),
+                None => suppressions.file.push(id.to_string()),
+            }
+        }
+    }
+
+    (suppressions, findings)
+}
+
+/// Run the body rules, without suppressions.
+fn body_findings(body: &str, first_line: usize) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+
+    if body.trim().is_empty() {
+        findings.push(LintFinding {
+            diagnostic: Diagnostic::warning("empty-body", "SKILL.md has no body"),
+            line: None,
+        });
+        return findings;
+    }
+
+    let headings = markdown::headings(body);
+    let line_of = |offset: usize| first_line + body[..offset].matches('\n').count();
+
+    let mut seen_h1 = false;
+    let mut previous_level = 0;
+    for heading in &headings {
+        let line = Some(line_of(heading.line.start));
+        if heading.level == 1 {
+            if seen_h1 {
+                findings.push(LintFinding {
+                    diagnostic: Diagnostic::warning(
+                        "multiple-h1",
+                        format!("Second H1 heading '{}'", heading.text),
+                    ),
+                    line,
+                });
+            }
+            seen_h1 = true;
+        }
+        if previous_level > 0 && heading.level > previous_level + 1 {
+            findings.push(LintFinding {
+                diagnostic: Diagnostic::warning(
+                    "heading-level-skip",
+                    format!(
+                        "Heading '{}' jumps from level {} to {}",
+                        heading.text, previous_level, heading.level
+                    ),
+                ),
+                line,
+            });
+        }
+        previous_level = heading.level;
+    }
+
+    for heading in markdown::setext_headings(body) {
+        findings.push(LintFinding {
+            diagnostic: Diagnostic::warning(
+                "setext-heading",
+                format!(
+                    "Underlined heading '{}' is not recognized; write it as '{} {}'",
+                    heading.text,
+                    "#".repeat(heading.level),
+                    heading.text
+                ),
+            ),
+            line: Some(line_of(heading.line.start)),
+        });
+    }
+
+    findings
+}
+
+/// `s` lowercased, with hyphens and underscores as spaces, runs of
+/// whitespace collapsed, and a final period dropped, for comparing a
+/// description with the name.
+fn name_words(s: &str) -> String {
+    let words = s
+        .trim()
+        .trim_end_matches('.')
+        .to_lowercase()
+        .replace(['-', '_'], " ");
+    words.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Run the description rules, if the frontmatter parses.
+///
+/// Invalid frontmatter is left to validation.
+fn description_findings(content: &str) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    let Ok((metadata, _)) = parse_frontmatter(content) else {
+        return findings;
+    };
+    let Some(description) = metadata.get("description").and_then(|v| v.as_str()) else {
+        return findings;
+    };
+    let name = metadata.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    let text = description.trim();
+    if text.is_empty() {
+        return findings;
+    }
+    let line = field_line(content, "description");
+    let mut push = |rule: &'static str, message: String| {
+        findings.push(LintFinding {
+            diagnostic: Diagnostic::warning(rule, message),
+            line,
+        });
+    };
+
+    if name_words(text) == name_words(name) {
+        push(
+            "description-repeats-name",
+            format!(
+                "Description only repeats the name '{}'; agents pick skills by description, \
+                 so say what the skill does and when to use it",
+                shorten(name.trim(), DEFAULT_MESSAGE_VALUE_CHARS)
+            ),
+        );
+    } else if text.split_whitespace().count() == 1 {
+        push(
+            "description-single-word",
+            format!(
+                "Description '{}' is a single word; say what the skill does and when to use it",
+                shorten(text, DEFAULT_MESSAGE_VALUE_CHARS)
+            ),
+        );
+    }
+    let has_upper = text.chars().any(char::is_uppercase);
+    if has_upper && !text.chars().any(char::is_lowercase) && text.chars().count() > 1 {
+        push(
+            "description-all-caps",
+            "Description is written entirely in uppercase".to_string(),
+        );
+    }
+    if let Some(markup) = markdown_in(text) {
+        push(
+            "description-markdown",
+            format!("Description contains markdown ({})", markup),
+        );
+    }
+    if text.contains('\n') {
+        push(
+            "description-newline",
+            format!(
+                "Description spans {} lines; it is shown in the prompt as one entry",
+                text.lines().count()
+            ),
+        );
+    }
+
+    findings
+}
+
+/// Name the first kind of markdown formatting found in `text`.
+fn markdown_in(text: &str) -> Option<&'static str> {
+    if text.contains("**") || text.contains("__") {
+        return Some("emphasis");
+    }
+    if text.contains('`') {
+        return Some("code span");
+    }
+    if text.contains("](") {
+        return Some("link");
+    }
+    text.lines().find_map(|line| {
+        let line = line.trim_start();
+        if line.starts_with('#') {
+            Some("heading")
+        } else if line.starts_with("- ") || line.starts_with("* ") {
+            Some("list item")
+        } else {
+            None
+        }
+    })
+}
+
+/// 1-based line of the top-level frontmatter field `key`.
+fn field_line(content: &str, key: &str) -> Option<usize> {
+    content.lines().skip(1).enumerate().find_map(|(i, line)| {
+        let rest = line.strip_prefix(key)?;
+        let rest = rest.trim_start_matches([' ', '\t']);
+        (rest.starts_with(':') || rest.starts_with('=')).then_some(i + 2)
+    })
+}
+
+/// Lint SKILL.md content.
+///
+/// # Errors
+///
+/// Returns `ParseError` if the content has no valid frontmatter delimiters.
+pub fn lint_content(content: &str) -> Result<LintReport> {
+    let (_, rest) = split_frontmatter(content)?;
+    let body = rest.trim_start();
+    let first_line = content[..content.len() - body.len()].matches('\n').count() + 1;
+
+    let (suppressions, mut findings) = parse_suppressions(body, first_line);
+    let mut report = LintReport::default();
+    let mut checked = description_findings(content);
+    checked.extend(body_findings(body, first_line));
+    for finding in checked {
+        if suppressions.covers(&finding.diagnostic.rule, finding.line) {
+            *report
+                .suppressed
+                .entry(finding.diagnostic.rule.clone())
+                .or_default() += 1;
+        } else {
+            findings.push(finding);
+        }
+    }
+
+    findings.sort_by_key(|f| f.line);
+    report.findings = findings;
+    Ok(report)
+}
+
+/// Lint the SKILL.md in `skill_dir`.
+///
+/// # Errors
+///
+/// * `NotFound` - If the directory has no SKILL.md
+/// * `ParseError` - If SKILL.md has no valid frontmatter delimiters
+pub fn lint(skill_dir: &Path) -> Result<LintReport> {
+    let skill_md = find_skill_md(skill_dir).ok_or_else(|| {
+        SkillError::not_found(
+            skill_dir,
+            format!("SKILL.md not found in {}", skill_dir.display()),
+        )
+    })?;
+    lint_content(&read_skill_md_file(&skill_md)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules_and_lines(report: &LintReport) -> Vec<(&str, Option<usize>)> {
+        report
+            .findings
+            .iter()
+            .map(|f| (f.diagnostic.rule.as_str(), f.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_body_rules() {
+        let report = lint_content(
+            "---\nname: a\ndescription: Use for tests\n---\n\n# One\n### Deep\n# Two\n",
+        )
+        .unwrap();
+        assert_eq!(
+            rules_and_lines(&report),
+            vec![("heading-level-skip", Some(7)), ("multiple-h1", Some(8))]
+        );
+
+        let report = lint_content("---\nname: a\ndescription: Use for tests\n---\n").unwrap();
+        assert_eq!(rules_and_lines(&report), vec![("empty-body", None)]);
+    }
+
+    #[test]
+    fn test_setext_heading() {
+        // The underline right after the frontmatter is not mistaken for its
+        // closing delimiter, and the heading is reported, not outlined.
+        let content = "---\nname: a\ndescription: Use for tests\n---\nUsage\n---\nRun it.\n";
+        let report = lint_content(content).unwrap();
+        assert_eq!(rules_and_lines(&report), vec![("setext-heading", Some(5))]);
+        assert_eq!(
+            report.findings[0].diagnostic.message,
+            "Underlined heading 'Usage' is not recognized; write it as '## Usage'"
+        );
+        let (metadata, body) = parse_frontmatter(content).unwrap();
+        assert_eq!(metadata["description"], "Use for tests");
+        assert_eq!(body, "Usage\n---\nRun it.");
+
+        let report = lint_content(
+            "---\nname: a\ndescription: Use for tests\n---\n# A\n\n---\n\n<!-- skills-lint-disable-next-line setext-heading -->\nB\n===\n",
+        )
+        .unwrap();
+        assert!(report.findings.is_empty());
+        assert_eq!(report.suppressed["setext-heading"], 1);
+    }
+
+    #[test]
+    fn test_disable_next_line() {
+        let content = "---\nname: a\ndescription: Use for tests\n---\n# One\n\
+            <!-- skills-lint-disable-next-line multiple-h1 -->\n# Two\n# Three\n";
+        let report = lint_content(content).unwrap();
+        assert_eq!(rules_and_lines(&report), vec![("multiple-h1", Some(8))]);
+        assert_eq!(report.suppressed_count(), 1);
+    }
+
+    #[test]
+    fn test_disable_file_wide() {
+        let content = "---\nname: a\ndescription: Use for tests\n---\n\
+            <!-- skills-lint-disable multiple-h1, heading-level-skip -->\n# One\n# Two\n### Deep\n";
+        let report = lint_content(content).unwrap();
+        assert!(report.findings.is_empty());
+        assert_eq!(report.suppressed["multiple-h1"], 1);
+        assert_eq!(report.suppressed["heading-level-skip"], 1);
+    }
+
+    #[test]
+    fn test_invalid_suppressions() {
+        let content = "---\nname: a\ndescription: Use for tests\n---\n\
+            <!-- skills-lint-disable name-lowercase multiple-h2 invalid-suppression -->\n# One\n";
+        let report = lint_content(content).unwrap();
+        let messages: Vec<_> = report
+            .findings
+            .iter()
+            .map(|f| f.diagnostic.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Rule 'name-lowercase' is a validation rule and cannot be suppressed",
+                "Unknown lint rule 'multiple-h2' in suppression comment",
+                "Rule 'invalid-suppression' cannot be suppressed",
+            ]
+        );
+        assert!(report.findings.iter().all(|f| f.line == Some(5)));
+    }
+
+    #[test]
+    fn test_description_rules() {
+        let lint_description = |name: &str, description: &str| {
+            let content = format!(
+                "---\nname: {}\ndescription: {}\n---\n# Body\n",
+                name, description
+            );
+            rules_and_lines(&lint_content(&content).unwrap())
+                .into_iter()
+                .map(|(rule, _)| rule.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(lint_description("pdf", "Extract text from PDFs. Use for PDF files.").is_empty());
+        assert_eq!(lint_description("pdf", "PDFs"), ["description-single-word"]);
+        assert_eq!(
+            lint_description("pdf-tools", "PDF-Tools"),
+            ["description-repeats-name"]
+        );
+        assert_eq!(
+            lint_description("pdf-form_tools", "PDF  form tools."),
+            ["description-repeats-name"]
+        );
+        assert!(lint_description("pdf-tools", "PDF tools for forms").is_empty());
+        assert_eq!(
+            lint_description("pdf", "EXTRACT TEXT FROM PDFS"),
+            ["description-all-caps"]
+        );
+        assert_eq!(
+            lint_description("pdf", "Extract **all** text"),
+            ["description-markdown"]
+        );
+        assert_eq!(
+            lint_description("pdf", "|\n  Extract text.\n  - Use for PDFs."),
+            ["description-markdown", "description-newline"]
+        );
+        assert!(lint_description("pdf", ">\n  Extract text.\n  Use for PDFs.").is_empty());
+    }
+
+    #[test]
+    fn test_bare_name_as_description() {
+        let content = "---\nname: pdf\ndescription: pdf\n---\n# PDF\n";
+        let report = lint_content(content).unwrap();
+        assert_eq!(
+            rules_and_lines(&report),
+            vec![("description-repeats-name", Some(3))]
+        );
+        let diagnostics = crate::validator::validate_content_at(
+            content,
+            Path::new("/nonexistent/pdf"),
+            &crate::ValidationConfig::default(),
+        );
+        let rules: Vec<_> = diagnostics.iter().map(|d| d.rule.as_str()).collect();
+        assert_eq!(rules, ["description-min-length"]);
+        assert!(
+            crate::validator::validate_content_at(
+                content,
+                Path::new("/nonexistent/pdf"),
+                &crate::ValidationConfig::default().with_min_description_chars(0),
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_description_rules_point_at_the_field_and_can_be_disabled() {
+        let content = "---\nname: pdf\ndescription: PDFs\n---\n# Body\n";
+        let report = lint_content(content).unwrap();
+        assert_eq!(
+            rules_and_lines(&report),
+            vec![("description-single-word", Some(3))]
+        );
+
+        let content = "---\nname: pdf\ndescription: PDFs\n---\n\
+            <!-- skills-lint-disable description-single-word -->\n# Body\n";
+        let report = lint_content(content).unwrap();
+        assert!(report.findings.is_empty());
+        assert_eq!(report.suppressed["description-single-word"], 1);
+    }
+
+    #[test]
+    fn test_lint_rule_ids_distinct_from_validation_rules() {
+        for rule in lint_rules() {
+            assert!(rule_info(rule.id).is_none(), "{}", rule.id);
+        }
+    }
+}
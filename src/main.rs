@@ -1,17 +1,98 @@
 //! CLI for skills-ref-rs library.
 
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 use std::process;
 
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
 
-use skills_ref::{read_properties, to_prompt, validate};
+/// `println!` with `--redact-paths` applied.
+macro_rules! outln {
+    () => {
+        std::println!()
+    };
+    ($($arg:tt)*) => {
+        std::println!("{}", $crate::cli::output::redact(&format!($($arg)*)))
+    };
+}
+
+/// `print!` with `--redact-paths` applied.
+macro_rules! out {
+    ($($arg:tt)*) => {
+        std::print!("{}", $crate::cli::output::redact(&format!($($arg)*)))
+    };
+}
+
+/// `eprintln!` with `--redact-paths` applied.
+macro_rules! errln {
+    ($($arg:tt)*) => {
+        std::eprintln!("{}", $crate::cli::output::redact(&format!($($arg)*)))
+    };
+}
+
+mod cli;
+
+use cli::changes::{ApplyMode, Change, ChangePlan};
+use cli::output;
+
+use skills_ref::validator::{rule_info, rules};
+use skills_ref::{
+    DEFAULT_MAX_RESOURCE_BYTES, Diagnostic, DiscoveryOptions, DiskCache, DuplicateKind,
+    DuplicateOptions, DuplicatePolicy, EmptyPolicy, FieldEdit, FrontmatterSyntax, HostCapabilities,
+    InlineBody, MAX_SKILL_MD_BYTES, Manifest, NameMatch, PathRedaction, PromptOptions,
+    PromptOverrides, PromptSet, Reflow, SizeLimits, Skill, SkillProperties, SkillSet, TrustLevel,
+    UnreadablePolicy, ValidationConfig, changed_skills, crate_info, find_skill_md,
+    find_skills_with, inspect, license_report, lint, parse_allowed_tools_lenient, plan_fmt_tree,
+    plan_lint_tree, prompt_pages, properties_from_bytes, read_properties, read_properties_cached,
+    render_prompt, resolve_skill_dir, stats, to_markdown_bundle, to_prompt_with_options,
+    validate_bytes_at, validate_detailed, validate_detailed_cached, validate_with_stats,
+    workspace::{SkillValidation, path_length_findings, reference_findings, validate_skill_dirs},
+};
 
 #[derive(Parser)]
 #[command(name = "skills-ref-rs")]
 #[command(about = "Reference library for Agent Skills")]
 #[command(version)]
 struct Cli {
+    /// Show what mutating commands would change without writing anything
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Show a diff of each change made by mutating commands
+    #[arg(long, global = true, conflicts_with = "dry_run")]
+    diff: bool,
+
+    /// Replace this path prefix with <ROOT> in all output (prompt locations,
+    /// messages, and JSON), for snapshots that do not depend on the machine
+    #[arg(long, global = true, value_name = "PREFIX")]
+    redact_paths: Option<PathBuf>,
+
+    /// Make output the same on every machine: paths under ROOT (default:
+    /// the current directory) are written relative to it, as ./path. Also
+    /// on for manifest-one, to-prompt, and export when SOURCE_DATE_EPOCH is
+    /// set
+    #[arg(
+        long,
+        global = true,
+        value_name = "ROOT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = ".",
+        conflicts_with = "redact_paths"
+    )]
+    reproducible: Option<PathBuf>,
+
+    /// Reuse the properties and validation results of unchanged skills
+    /// from earlier runs of validate and read-properties, kept in the
+    /// user's cache directory
+    #[arg(long, global = true)]
+    cache: bool,
+
+    /// Keep cached results in DIR instead of the user's cache directory;
+    /// implies --cache
+    #[arg(long, global = true, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -23,18 +104,171 @@ enum Commands {
     /// Checks that the skill has a valid SKILL.md with proper frontmatter,
     /// correct naming conventions, and required fields.
     Validate {
+        /// Path to the skill directory or SKILL.md file
+        #[arg(required_unless_present = "stdin", conflicts_with = "stdin")]
+        skill_path: Option<PathBuf>,
+
+        /// Read the SKILL.md content from stdin instead of a skill
+        /// directory. Rules about the skill's other files are skipped
+        #[arg(long)]
+        stdin: bool,
+
+        /// With --stdin: the directory the skill will be installed in, to
+        /// check the skill name matches it
+        #[arg(long, value_name = "NAME", requires = "stdin")]
+        name: Option<PathBuf>,
+
+        /// Also list the {{NAME}} placeholders the body expects the host
+        /// to fill
+        #[arg(long)]
+        list_placeholders: bool,
+
+        /// Print tab-separated records in a stable format for scripts
+        #[arg(long)]
+        porcelain: bool,
+
+        #[command(flatten)]
+        rule_options: RuleOptions,
+    },
+
+    /// Explain why a skill fails validation.
+    ///
+    /// For each problem, shows the rule, the offending value, and a
+    /// suggested fix.
+    Explain {
         /// Path to the skill directory or SKILL.md file
         skill_path: PathBuf,
+
+        #[command(flatten)]
+        rule_options: RuleOptions,
+    },
+
+    /// Show everything about a skill at once.
+    ///
+    /// Prints the parsed properties, validation diagnostics, resource
+    /// files, body statistics, and digest.
+    Inspect {
+        /// Path to the skill directory or SKILL.md file
+        skill_path: PathBuf,
+
+        /// Output the report as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Include the body's headings with their line numbers in the JSON
+        /// report
+        #[arg(long, requires = "json")]
+        outline: bool,
+
+        #[command(flatten)]
+        rule_options: RuleOptions,
+    },
+
+    /// Print what a host needs to invoke a skill: SKILL.md path, resources,
+    /// allowed tools, and digest.
+    ManifestOne {
+        /// Path to the skill directory or SKILL.md file
+        skill_path: PathBuf,
+
+        /// Leave out resource files larger than this many bytes
+        #[arg(long, default_value_t = DEFAULT_MAX_RESOURCE_BYTES)]
+        max_resource_bytes: u64,
+
+        /// Output the manifest as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check a skill body and description for likely problems.
+    ///
+    /// Rules can be disabled for one skill with
+    /// `<!-- skills-lint-disable rule-id -->` comments in its body.
+    Lint {
+        /// Path to the skill directory or SKILL.md file (with --fix, also a
+        /// directory of skills)
+        skill_path: PathBuf,
+
+        /// Rewrite what can be fixed (setext headings, multi-line
+        /// descriptions) and report each file as changed, clean,
+        /// unfixable, or failed to write
+        #[arg(long)]
+        fix: bool,
+
+        /// Output the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Validate many skills at once.
+    ///
+    /// Validates every skill under a root directory, or the skill paths
+    /// read from stdin (one per line), and checks that no two skills share
+    /// a name or collide on case-insensitive file systems.
+    #[command(name = "validate-all")]
+    ValidateAll {
+        /// Root directory to search for skills
+        #[arg(
+            required_unless_present = "paths_from_stdin",
+            conflicts_with = "paths_from_stdin"
+        )]
+        root: Option<PathBuf>,
+
+        /// Read skill paths from stdin, one per line (also accepted as
+        /// --stdin)
+        #[arg(long, alias = "stdin")]
+        paths_from_stdin: bool,
+
+        /// Only validate the highest version of skills laid out as
+        /// <name>/<version>/SKILL.md
+        #[arg(long, conflicts_with = "paths_from_stdin")]
+        latest_versions: bool,
+
+        /// Also print catalog statistics, computed from the same pass
+        #[arg(long)]
+        stats: bool,
+
+        /// Print tab-separated records in a stable format for scripts
+        #[arg(long, conflicts_with = "stats")]
+        porcelain: bool,
+
+        #[command(flatten)]
+        rule_options: RuleOptions,
     },
 
     /// Read and print skill properties as JSON.
     ///
     /// Parses the YAML frontmatter from SKILL.md and outputs the
     /// properties as JSON.
+    ///
+    /// With more than one path, or `--paths-from-stdin`, prints a JSON
+    /// array with one object per skill holding its `path` and either its
+    /// `properties` or an `error`.
     #[command(name = "read-properties")]
     ReadProperties {
-        /// Path to the skill directory or SKILL.md file
-        skill_path: PathBuf,
+        /// Paths to skill directories or SKILL.md files
+        #[arg(
+            required_unless_present_any = ["stdin", "paths_from_stdin"],
+            conflicts_with_all = ["stdin", "paths_from_stdin"]
+        )]
+        skill_paths: Vec<PathBuf>,
+
+        /// Read the SKILL.md content from stdin instead of a skill
+        /// directory
+        #[arg(long, conflicts_with = "paths_from_stdin")]
+        stdin: bool,
+
+        /// Read skill paths from stdin, one per line
+        #[arg(long)]
+        paths_from_stdin: bool,
+
+        /// Exit successfully even if some skills could not be read
+        #[arg(long)]
+        ignore_errors: bool,
+
+        /// Add the parsed allowed-tools entries as `allowed-tools-parsed`,
+        /// with malformed entries listed in `parse_errors`
+        #[arg(long)]
+        expand_tools: bool,
     },
 
     /// Generate <available_skills> XML for agent prompts.
@@ -42,80 +276,1550 @@ enum Commands {
     /// Accepts one or more skill directories.
     #[command(name = "to-prompt")]
     ToPrompt {
+        /// Paths to skill directories or SKILL.md files
+        #[arg(required_unless_present = "promptset")]
+        skill_paths: Vec<PathBuf>,
+
+        /// Render a prompt described in a promptset.yaml file instead of
+        /// one given by paths and options
+        #[arg(long, value_name = "FILE", conflicts_with_all = [
+            "skill_paths", "tools", "inline_body", "max_chars",
+            "keep_highest_version", "include_deprecated", "include_ids",
+            "overrides", "fail_if_empty", "allow_empty", "trust",
+        ])]
+        promptset: Option<PathBuf>,
+
+        /// Set of the promptset file to render (default: its only set, or
+        /// the one called default)
+        #[arg(long = "set", value_name = "NAME", requires = "promptset")]
+        set_name: Option<String>,
+
+        /// Tools provided by the host, comma-separated (e.g. Bash,Read)
+        #[arg(long, value_delimiter = ',')]
+        tools: Option<Vec<String>>,
+
+        /// Leave out skills whose allowed-tools the host does not provide
+        #[arg(long, requires = "tools")]
+        exclude_incompatible: bool,
+
+        /// Inline skill bodies, optionally only those shorter than the given
+        /// number of characters
+        #[arg(long, value_name = "MAX_CHARS", num_args = 0..=1, require_equals = true)]
+        inline_body: Option<Option<usize>>,
+
+        /// Size budget for the prompt in characters; bodies that would
+        /// exceed it are not inlined
+        #[arg(long, value_name = "N")]
+        max_chars: Option<usize>,
+
+        /// Of skills sharing a name, keep only the one with the highest
+        /// metadata.version
+        #[arg(long)]
+        keep_highest_version: bool,
+
+        /// Keep skills marked metadata.deprecated, annotated with a
+        /// <deprecated> element
+        #[arg(long)]
+        include_deprecated: bool,
+
+        /// Emit an <id> element with each skill's metadata.id, for skills
+        /// that have one
+        #[arg(long)]
+        include_ids: bool,
+
+        /// Split the prompt into pages of at most N characters each
+        #[arg(long, value_name = "N")]
+        paginate: Option<usize>,
+
+        /// Page to print when paginating (starting at 1)
+        #[arg(long, value_name = "K", requires = "paginate", default_value_t = 1)]
+        page: usize,
+
+        /// YAML file excluding skills from the prompt and overriding their
+        /// descriptions (keys: exclude, override_description)
+        #[arg(long, value_name = "FILE")]
+        overrides: Option<PathBuf>,
+
+        /// Fail, listing why each skill was left out, if no skill ends up
+        /// in the prompt (the default)
+        #[arg(long, conflicts_with = "allow_empty")]
+        fail_if_empty: bool,
+
+        /// Print an empty <available_skills> block when every skill is left
+        /// out, instead of failing
+        #[arg(long)]
+        allow_empty: bool,
+
+        /// Mark skills under PATH with a <trust> element, where LEVEL is
+        /// builtin, verified, community, or local (repeatable; the longest
+        /// matching PATH wins)
+        #[arg(long, value_name = "PATH:LEVEL", value_parser = parse_trust_root)]
+        trust: Vec<(PathBuf, TrustLevel)>,
+
+        /// Check that each skill's SKILL.md can still be read just before
+        /// printing, and leave out skills whose file cannot
+        #[arg(long)]
+        verify_locations: bool,
+
+        /// With --verify-locations, fail naming the first skill whose
+        /// SKILL.md cannot be read, instead of leaving it out
+        #[arg(long, requires = "verify_locations")]
+        fail_on_unreadable: bool,
+
+        /// Write a JSON report of the skills included in the prompt (with
+        /// their paths, digests, and sizes) and of those left out to FILE
+        #[arg(long, value_name = "FILE", conflicts_with = "paginate")]
+        report: Option<PathBuf>,
+    },
+
+    /// Preview the generated prompt in the terminal.
+    ///
+    /// Highlights the XML structure, shows each skill's size in the margin,
+    /// and totals the prompt against an optional budget.
+    Preview {
         /// Paths to skill directories or SKILL.md files
         #[arg(required = true)]
         skill_paths: Vec<PathBuf>,
+
+        /// Size budget for the prompt in characters
+        #[arg(long, value_name = "N")]
+        budget: Option<usize>,
+
+        /// Print the plain XML instead
+        #[arg(long)]
+        raw: bool,
+
+        /// Terminal width (default: $COLUMNS, or 80)
+        #[arg(long, value_name = "COLUMNS")]
+        width: Option<usize>,
+    },
+
+    /// Create a new skill.
+    ///
+    /// Creates the skill directory with a SKILL.md template.
+    Init {
+        /// Path to the new skill directory
+        #[arg(required_unless_present = "list_templates")]
+        skill_path: Option<PathBuf>,
+
+        /// Skill name (defaults to the directory name, which it must match)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Skill description
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Refuse names taken in (or confusable with names in) this
+        /// manifest file
+        #[arg(long, value_name = "FILE")]
+        manifest: Option<PathBuf>,
+
+        /// Give the skill a random UUID as its metadata.id
+        #[arg(long)]
+        with_id: bool,
+
+        /// Built-in template to start from (see --list-templates)
+        #[arg(long, value_name = "NAME", conflicts_with = "template_dir")]
+        template: Option<String>,
+
+        /// Start from this directory's SKILL.md and files instead;
+        /// `{{name}}` and `{{description}}` in SKILL.md are filled in
+        #[arg(long, value_name = "DIR")]
+        template_dir: Option<PathBuf>,
+
+        /// List the built-in templates and exit
+        #[arg(long)]
+        list_templates: bool,
     },
+
+    /// Rename a skill.
+    ///
+    /// Rewrites the `name` field in SKILL.md and renames the skill
+    /// directory to match.
+    Rename {
+        /// Path to the skill directory or SKILL.md file
+        skill_path: PathBuf,
+
+        /// New skill name
+        new_name: String,
+    },
+
+    /// Set or remove one frontmatter field of a skill.
+    ///
+    /// Only the lines of that field change; comments and the layout of
+    /// other fields are kept.
+    Set {
+        /// Path to the skill directory or SKILL.md file
+        skill_path: PathBuf,
+
+        /// Field to change: a top-level field, or e.g. metadata.version
+        field: String,
+
+        /// New string value
+        #[arg(required_unless_present = "unset")]
+        value: Option<String>,
+
+        /// Remove the field instead
+        #[arg(long, conflicts_with = "value")]
+        unset: bool,
+
+        /// Re-emit the whole frontmatter in canonical form, dropping
+        /// comments (for layouts that cannot be edited in place)
+        #[arg(long)]
+        reformat: bool,
+    },
+
+    /// Set one frontmatter field in every skill under a directory.
+    ///
+    /// Each edited skill is re-validated first; a skill the edit would make
+    /// invalid is reported as failed and left unchanged.
+    SetField {
+        /// Directory to search for skills
+        root: PathBuf,
+
+        /// Field to set: a top-level field, or e.g. metadata.owner
+        #[arg(long)]
+        key: String,
+
+        /// New string value
+        #[arg(long)]
+        value: String,
+
+        /// Leave skills that already have the field alone
+        #[arg(long)]
+        only_missing: bool,
+
+        #[command(flatten)]
+        rule_options: RuleOptions,
+    },
+
+    /// Reflow the paragraphs of a skill body, or convert its frontmatter
+    /// between YAML and TOML.
+    ///
+    /// Code fences, headings, lists, block quotes, and tables are left as
+    /// they are, and so is the frontmatter unless --syntax is given. Given a
+    /// directory of skills, every skill under it is formatted and each file
+    /// reported as changed or clean.
+    #[command(group(
+        ArgGroup::new("mode")
+            .required(true)
+            .multiple(true)
+            .args(["wrap", "unwrap", "syntax"])
+    ))]
+    Fmt {
+        /// Path to the skill directory or SKILL.md file, or a directory of
+        /// skills
+        skill_path: PathBuf,
+
+        /// Report each file's status as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Wrap paragraphs at this width, or `off` to leave them alone
+        #[arg(long, value_name = "WIDTH|off", value_parser = cli::fmt::parse_wrap)]
+        wrap: Option<cli::fmt::WrapWidth>,
+
+        /// Join hard-wrapped paragraphs into single lines
+        #[arg(long, conflicts_with = "wrap")]
+        unwrap: bool,
+
+        /// Rewrite the frontmatter in this syntax (TOML needs the `toml`
+        /// feature); comments in a converted frontmatter are lost
+        #[arg(long, value_enum)]
+        syntax: Option<SyntaxArg>,
+    },
+
+    /// Create a new skill from a copy of an existing one.
+    ///
+    /// Copies the skill directory, rewrites the `name` field, and drops
+    /// `metadata.version`. Refuses to overwrite an existing directory.
+    Fork {
+        /// Path to the source skill directory or SKILL.md file
+        source_skill: PathBuf,
+
+        /// Name of the new skill
+        new_name: String,
+
+        /// Parent directory for the new skill (default: next to the source)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+
+        /// Copy only SKILL.md, not the skill's resource files
+        #[arg(long)]
+        no_resources: bool,
+    },
+
+    /// Export all skills under a root directory as one document.
+    Export {
+        /// Root directory to search for skills
+        root: PathBuf,
+
+        /// Output format
+        #[arg(long, value_enum)]
+        format: ExportFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Print catalog statistics for the skills under a root directory.
+    ///
+    /// Counts valid and invalid skills, licenses, and allowed-tools, and
+    /// summarizes description lengths, resource sizes, and body tokens.
+    Stats {
+        /// Root directory to search for skills
+        root: PathBuf,
+
+        /// Output the statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Report the licenses used by skills under a root directory.
+    ///
+    /// Groups discovered skills by their `license` field.
+    Licenses {
+        /// Root directory to search for skills
+        root: PathBuf,
+
+        /// Output the report as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Fail if any skill uses this license (repeatable; `unspecified`
+        /// matches skills without a license)
+        #[arg(long, value_name = "LICENSE")]
+        deny: Vec<String>,
+    },
+
+    /// List the skills under a root directory.
+    ///
+    /// Prints each skill's name and path, flagging deprecated skills, or with
+    /// --duplicates, groups of skills that look like duplicates.
+    List {
+        /// Root directory to search for skills
+        root: PathBuf,
+
+        /// Print groups of skills with identical bodies
+        #[arg(long)]
+        duplicates: bool,
+
+        /// With --duplicates, also group skills whose descriptions are at
+        /// least this similar (0.0 to 1.0)
+        #[arg(long, value_name = "THRESHOLD", requires = "duplicates")]
+        similarity: Option<f64>,
+
+        /// Print tab-separated records in a stable format for scripts
+        #[arg(long, conflicts_with = "duplicates")]
+        porcelain: bool,
+    },
+
+    /// List skills that differ between two directory trees.
+    ///
+    /// Compares every file in each skill directory, so edits to scripts
+    /// and references count as changes.
+    Changed {
+        /// Root of the old tree
+        old: PathBuf,
+
+        /// Root of the new tree
+        new: PathBuf,
+
+        /// Print only added and modified skill paths, for piping into
+        /// `validate-all --paths-from-stdin`
+        #[arg(long)]
+        paths_only: bool,
+    },
+
+    /// Check skill roots and the health of the catalog under them.
+    ///
+    /// Reports unreadable or empty roots, invalid skills, rules that many
+    /// skills fail, and problems between skills, with suggested next steps.
+    Doctor {
+        /// Directories to search for skills
+        #[arg(default_value = ".")]
+        roots: Vec<PathBuf>,
+
+        /// Output the report as JSON
+        #[arg(long)]
+        json: bool,
+
+        #[command(flatten)]
+        rule_options: RuleOptions,
+    },
+
+    /// Check that this binary works on this platform.
+    ///
+    /// Writes bundled example skills to a scratch directory and runs
+    /// validate, read-properties, to-prompt, and a rewrite on them,
+    /// printing pass or fail for each check.
+    SelfTest {
+        /// Output the results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print version information as JSON.
+    ///
+    /// Reports the crate version, supported spec versions, output format
+    /// versions, rule IDs, and enabled features.
+    Info,
+
+    /// List the validation rules.
+    ///
+    /// Prints each rule's ID, severity, and summary.
+    Rules {
+        /// Output the rule table as JSON
+        #[arg(long, conflicts_with = "explain")]
+        json: bool,
+
+        /// Print a detailed explanation of a single rule
+        #[arg(long, value_name = "RULE_ID")]
+        explain: Option<String>,
+    },
+}
+
+/// Flags enabling opt-in validation rules.
+#[derive(Args)]
+struct RuleOptions {
+    /// Also check the format of conventional metadata values
+    /// (maintainer-email, homepage)
+    #[arg(long)]
+    check_conventions: bool,
+
+    /// Accept the skill name if it matches any of the nearest N directory
+    /// names (e.g. 2 for skills/pdf/1.2.0/SKILL.md); 0 disables the check
+    #[arg(long, value_name = "N")]
+    name_match_ancestors: Option<usize>,
+
+    /// Also limit skill names to N bytes of UTF-8 (after normalization)
+    #[arg(long, value_name = "N")]
+    max_name_bytes: Option<usize>,
+
+    /// Accept top-level `x-` prefixed fields as client extensions
+    #[arg(long)]
+    allow_x_fields: bool,
+
+    /// Warn about descriptions shorter than N characters (20 unless set;
+    /// 0 disables the check)
+    #[arg(long, value_name = "N")]
+    min_description_chars: Option<usize>,
+
+    /// Also check total size, per-file size, and file count of the skill
+    /// directory (50 MiB, 10 MiB, and 1000 files unless set below)
+    #[arg(long)]
+    check_size: bool,
+
+    /// Size limit for --check-size: total bytes of all files
+    #[arg(long, value_name = "BYTES", requires = "check_size")]
+    max_skill_bytes: Option<u64>,
+
+    /// Size limit for --check-size: bytes of any single file
+    #[arg(long, value_name = "BYTES", requires = "check_size")]
+    max_file_bytes: Option<u64>,
+
+    /// Size limit for --check-size: number of files
+    #[arg(long, value_name = "N", requires = "check_size")]
+    max_files: Option<usize>,
+}
+
+impl RuleOptions {
+    fn config(&self) -> ValidationConfig {
+        let name_match = match self.name_match_ancestors {
+            None => NameMatch::ImmediateDir,
+            Some(0) => NameMatch::Disabled,
+            Some(n) => NameMatch::AnyAncestorWithin(n),
+        };
+        let mut config = ValidationConfig::default()
+            .with_metadata_conventions(self.check_conventions)
+            .with_allow_x_prefixed_fields(self.allow_x_fields)
+            .with_name_match(name_match);
+        config.max_name_bytes = self.max_name_bytes;
+        config.min_description_chars = self.min_description_chars;
+        config.size_limits = self.check_size.then(|| {
+            let defaults = SizeLimits::default();
+            defaults
+                .with_max_total_bytes(self.max_skill_bytes.unwrap_or(defaults.max_total_bytes))
+                .with_max_file_bytes(self.max_file_bytes.unwrap_or(defaults.max_file_bytes))
+                .with_max_files(self.max_files.unwrap_or(defaults.max_files))
+        });
+        config
+    }
+}
+
+impl Commands {
+    /// Whether the command generates artifacts a build may keep, which
+    /// SOURCE_DATE_EPOCH makes reproducible.
+    fn writes_artifacts(&self) -> bool {
+        matches!(
+            self,
+            Commands::ManifestOne { .. } | Commands::ToPrompt { .. } | Commands::Export { .. }
+        )
+    }
 }
 
-/// Check if a path points directly to a SKILL.md or skill.md file.
-fn is_skill_md_file(path: &Path) -> bool {
-    path.is_file()
-        && path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .map(|n| n.to_lowercase() == "skill.md")
-            .unwrap_or(false)
+/// Document formats for `export`.
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    /// One markdown document with a table of contents and a section per skill
+    MarkdownBundle,
+}
+
+/// Frontmatter syntaxes for `fmt --syntax`.
+#[derive(Clone, Copy, ValueEnum)]
+enum SyntaxArg {
+    /// YAML between `---` lines
+    Yaml,
+    /// TOML between `+++` lines
+    Toml,
+}
+
+impl From<SyntaxArg> for FrontmatterSyntax {
+    fn from(syntax: SyntaxArg) -> Self {
+        match syntax {
+            SyntaxArg::Yaml => FrontmatterSyntax::Yaml,
+            SyntaxArg::Toml => FrontmatterSyntax::Toml,
+        }
+    }
 }
 
 /// Resolve a skill path - if it's a SKILL.md file, return its parent directory.
+///
+/// Paths that cannot be resolved are returned unchanged so that `validate`
+/// can report the problem in its usual format.
 fn resolve_skill_path(path: PathBuf) -> PathBuf {
-    if is_skill_md_file(&path) {
-        path.parent().map(|p| p.to_path_buf()).unwrap_or(path)
-    } else {
-        path
+    resolve_skill_dir(&path).unwrap_or(path)
+}
+
+/// Parse a `--trust` value: a path, a colon, and a trust level. The level
+/// follows the last colon, so paths may contain colons.
+fn parse_trust_root(value: &str) -> Result<(PathBuf, TrustLevel), String> {
+    let (path, level) = value
+        .rsplit_once(':')
+        .filter(|(path, _)| !path.is_empty())
+        .ok_or_else(|| format!("expected PATH:LEVEL, got '{}'", value))?;
+    let level = level.parse::<TrustLevel>().map_err(|e| e.to_string())?;
+    Ok((PathBuf::from(path), level))
+}
+
+/// Skill properties as JSON, with the `allowed-tools` patterns listed and
+/// optionally parsed.
+fn properties_json(props: &SkillProperties, expand_tools: bool) -> serde_json::Value {
+    let mut json = serde_json::to_value(props).unwrap();
+    let (Some(allowed_tools), Some(object)) = (&props.allowed_tools, json.as_object_mut()) else {
+        return json;
+    };
+    object.insert(
+        "allowed-tools-patterns".to_string(),
+        allowed_tools.patterns().into(),
+    );
+    if expand_tools {
+        let (tools, errors) = parse_allowed_tools_lenient(&allowed_tools.raw());
+        object.insert(
+            "allowed-tools-parsed".to_string(),
+            serde_json::to_value(tools).unwrap(),
+        );
+        if !errors.is_empty() {
+            object.insert("parse_errors".to_string(), errors.into());
+        }
     }
+    json
+}
+
+/// How SKILL.md content read from stdin is named in output.
+const STDIN_LABEL: &str = "<stdin>";
+
+/// Read stdin as SKILL.md content.
+///
+/// Reading stops one byte past [`MAX_SKILL_MD_BYTES`], so oversized input
+/// gets the same error as an oversized file without being held in full.
+fn read_stdin_bytes() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut stdin = std::io::Read::take(std::io::stdin(), MAX_SKILL_MD_BYTES + 1);
+    if let Err(e) = std::io::Read::read_to_end(&mut stdin, &mut bytes) {
+        errln!("Error: failed to read stdin: {}", e);
+        process::exit(1);
+    }
+    bytes
+}
+
+/// Read skill paths from stdin, one per line, skipping blank lines.
+fn read_stdin_paths() -> Vec<PathBuf> {
+    std::io::stdin()
+        .lines()
+        .map_while(|line| line.ok())
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect()
 }
 
 fn main() {
     let cli = Cli::parse();
+    // Reproducible builds set SOURCE_DATE_EPOCH. Nothing the CLI writes
+    // holds a timestamp, so only paths need to be made machine-independent,
+    // and only in the artifacts a build would keep.
+    let reproducible = cli.reproducible.clone().or_else(|| {
+        (cli.command.writes_artifacts() && std::env::var_os("SOURCE_DATE_EPOCH").is_some())
+            .then(|| PathBuf::from("."))
+    });
+    if let Some(prefix) = &cli.redact_paths {
+        output::set_redaction(PathRedaction::new(prefix));
+    } else if let Some(root) = reproducible {
+        output::set_redaction(PathRedaction::relative_to(&root));
+    }
+    let cache = match (cli.cache_dir.clone(), cli.cache) {
+        (Some(dir), _) => Some(DiskCache::new(dir)),
+        (None, true) => match DiskCache::in_default_dir() {
+            Some(cache) => Some(cache),
+            None => {
+                errln!("Error: cannot determine the cache directory; pass --cache-dir");
+                process::exit(1);
+            }
+        },
+        (None, false) => None,
+    };
+    let read_properties = |path: &Path| match &cache {
+        Some(cache) => read_properties_cached(path, cache),
+        None => read_properties(path),
+    };
+    let apply_mode = ApplyMode {
+        dry_run: cli.dry_run,
+        show_diff: cli.diff,
+    };
 
     match cli.command {
-        Commands::Validate { skill_path } => {
-            let skill_path = resolve_skill_path(skill_path);
-            let errors = validate(&skill_path);
+        Commands::Validate {
+            skill_path,
+            stdin,
+            name,
+            list_placeholders,
+            porcelain,
+            rule_options,
+        } => {
+            let config = rule_options
+                .config()
+                .with_list_placeholders(list_placeholders);
+            let (skill_path, diagnostics) = match skill_path {
+                _ if stdin => {
+                    let bytes = read_stdin_bytes();
+                    let diagnostics =
+                        validate_bytes_at(bytes, STDIN_LABEL, name.as_deref(), &config);
+                    (PathBuf::from(STDIN_LABEL), diagnostics)
+                }
+                Some(skill_path) => {
+                    let skill_path = resolve_skill_path(skill_path);
+                    let diagnostics = match &cache {
+                        Some(cache) => validate_detailed_cached(&skill_path, &config, cache),
+                        None => validate_detailed(&skill_path, &config),
+                    };
+                    (skill_path, diagnostics)
+                }
+                None => unreachable!("clap requires a skill path without --stdin"),
+            };
+            if porcelain {
+                let skill = SkillValidation {
+                    path: skill_path,
+                    diagnostics,
+                };
+                out!(
+                    "{}",
+                    cli::report::render_validation(std::slice::from_ref(&skill), &[])
+                );
+                if !skill.is_valid() {
+                    process::exit(1);
+                }
+                return;
+            }
+            let (errors, notes): (Vec<_>, Vec<_>) =
+                diagnostics.into_iter().partition(Diagnostic::is_error);
 
             if errors.is_empty() {
-                println!("Valid skill: {}", skill_path.display());
+                outln!("Valid skill: {}", skill_path.display());
+                for note in notes {
+                    outln!("  {}: {}", note.severity, note.message);
+                }
             } else {
-                eprintln!("Validation failed for {}:", skill_path.display());
+                errln!("Validation failed for {}:", skill_path.display());
                 for error in errors {
-                    eprintln!("  - {}", error);
+                    errln!("  - {}", error.message);
                 }
                 process::exit(1);
             }
         }
 
-        Commands::ReadProperties { skill_path } => {
+        Commands::Explain {
+            skill_path,
+            rule_options,
+        } => {
             let skill_path = resolve_skill_path(skill_path);
+            let (text, count) = cli::explain::explain(&skill_path, &rule_options.config());
 
-            match read_properties(&skill_path) {
-                Ok(props) => {
-                    let json = serde_json::to_string_pretty(&props).unwrap();
-                    println!("{}", json);
+            if count == 0 {
+                outln!("Valid skill: {}", skill_path.display());
+            } else {
+                let noun = if count == 1 { "problem" } else { "problems" };
+                outln!("{} {} in {}:\n", count, noun, skill_path.display());
+                out!("{}", text);
+                process::exit(1);
+            }
+        }
+
+        Commands::Inspect {
+            skill_path,
+            json,
+            outline,
+            rule_options,
+        } => {
+            let skill_path = resolve_skill_path(skill_path);
+            match inspect(&skill_path, &rule_options.config()) {
+                Ok(mut report) if json => {
+                    if !outline {
+                        report.outline = None;
+                    }
+                    output::print_json(&report);
+                }
+                Ok(report) => out!("{}", cli::inspect::render_text(&report)),
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+
+        Commands::ManifestOne {
+            skill_path,
+            max_resource_bytes,
+            json,
+        } => {
+            let skill_path = resolve_skill_path(skill_path);
+            match Skill::load(&skill_path)
+                .and_then(|skill| skill.invocation_manifest_with(max_resource_bytes))
+            {
+                Ok(manifest) if json => {
+                    output::print_json(&manifest);
+                }
+                Ok(manifest) => out!("{}", cli::invocation::render_text(&manifest)),
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+
+        Commands::Lint {
+            skill_path,
+            fix,
+            json,
+        } => {
+            if fix {
+                // Planned here and written by `finish`, after any diff
+                // is shown.
+                match plan_lint_tree(&resolve_skill_path(skill_path)) {
+                    Ok(report) => {
+                        if !cli::fix::finish(report, apply_mode, json) {
+                            process::exit(1);
+                        }
+                    }
+                    Err(e) => {
+                        errln!("Error: {}", e);
+                        process::exit(1);
+                    }
                 }
+                return;
+            }
+            let skill_path = resolve_skill_path(skill_path);
+            let report = match lint(&skill_path) {
+                Ok(report) => report,
                 Err(e) => {
-                    eprintln!("Error: {}", e);
+                    errln!("Error: {}", e);
                     process::exit(1);
                 }
+            };
+
+            if json {
+                output::print_json(&report);
+            } else {
+                for finding in &report.findings {
+                    let location = match finding.line {
+                        Some(line) => format!("{}:{}", skill_path.display(), line),
+                        None => skill_path.display().to_string(),
+                    };
+                    outln!(
+                        "{}: {} [{}] {}",
+                        location,
+                        finding.diagnostic.severity,
+                        finding.diagnostic.rule,
+                        finding.diagnostic.message
+                    );
+                }
+                if report.suppressed_count() > 0 {
+                    let counts: Vec<String> = report
+                        .suppressed
+                        .iter()
+                        .map(|(rule, count)| format!("{}: {}", rule, count))
+                        .collect();
+                    outln!(
+                        "{} finding(s) suppressed ({})",
+                        report.suppressed_count(),
+                        counts.join(", ")
+                    );
+                }
+            }
+
+            if !report.findings.is_empty() {
+                process::exit(1);
             }
         }
 
-        Commands::ToPrompt { skill_paths } => {
-            let resolved_paths: Vec<PathBuf> =
+        Commands::ValidateAll {
+            root,
+            paths_from_stdin,
+            latest_versions,
+            stats,
+            porcelain,
+            rule_options,
+        } => {
+            let skill_paths = if paths_from_stdin {
+                read_stdin_paths()
+            } else {
+                let root = root
+                    .as_ref()
+                    .expect("clap requires root without --paths-from-stdin");
+                let options = DiscoveryOptions::default().with_latest_version_only(latest_versions);
+                match find_skills_with(root, &options) {
+                    Ok(dirs) => dirs,
+                    Err(e) => {
+                        errln!("Error: {}", e);
+                        process::exit(1);
+                    }
+                }
+            };
+
+            let skill_paths: Vec<PathBuf> =
                 skill_paths.into_iter().map(resolve_skill_path).collect();
+            let config = rule_options.config();
+            let (mut report, catalog_stats) = if stats {
+                let (report, catalog_stats) = validate_with_stats(&skill_paths, &config);
+                (report, Some(catalog_stats))
+            } else {
+                (validate_skill_dirs(&skill_paths, &config), None)
+            };
+            if let Some(root) = &root {
+                let references = reference_findings(root, &report);
+                report
+                    .findings
+                    .extend(path_length_findings(root, &skill_paths));
+                report.findings.extend(references);
+            }
+            if porcelain {
+                out!(
+                    "{}",
+                    cli::report::render_validation(&report.skills, &report.findings)
+                );
+                if !report.is_valid() {
+                    process::exit(1);
+                }
+                return;
+            }
+            for skill in &report.skills {
+                if skill.is_valid() {
+                    outln!("Valid skill: {}", skill.path.display());
+                    for note in &skill.diagnostics {
+                        outln!("  {}: {}", note.severity, note.message);
+                    }
+                } else {
+                    errln!("Validation failed for {}:", skill.path.display());
+                    for diagnostic in skill.diagnostics.iter().filter(|d| d.is_error()) {
+                        errln!("  - {}", diagnostic.message);
+                    }
+                }
+            }
+            for finding in &report.findings {
+                errln!(
+                    "{} [{}]: {}",
+                    finding.diagnostic.severity,
+                    finding.diagnostic.rule,
+                    finding.diagnostic.message
+                );
+                for path in &finding.paths {
+                    errln!("  - {}", path.display());
+                }
+            }
+
+            if let Some(catalog_stats) = &catalog_stats {
+                out!("\n{}", cli::stats::render_text(catalog_stats));
+            }
+
+            let failed = report.failed_count();
+            if failed > 0 {
+                errln!(
+                    "{} of {} skills failed validation",
+                    failed,
+                    skill_paths.len()
+                );
+            }
+            if !report.is_valid() {
+                process::exit(1);
+            }
+        }
+
+        Commands::ReadProperties {
+            skill_paths,
+            stdin,
+            paths_from_stdin,
+            ignore_errors,
+            expand_tools,
+        } if stdin || (!paths_from_stdin && skill_paths.len() == 1) => {
+            let props = if stdin {
+                properties_from_bytes(read_stdin_bytes())
+            } else {
+                read_properties(&skill_paths[0])
+            };
+            match props {
+                Ok(props) => {
+                    let json = properties_json(&props, expand_tools);
+                    output::print_json(&json);
+                }
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    if !ignore_errors {
+                        process::exit(1);
+                    }
+                }
+            }
+        }
+
+        Commands::ReadProperties {
+            skill_paths,
+            paths_from_stdin,
+            ignore_errors,
+            expand_tools,
+            ..
+        } => {
+            let skill_paths = if paths_from_stdin {
+                read_stdin_paths()
+            } else {
+                skill_paths
+            };
+
+            let mut failed = false;
+            let entries: Vec<serde_json::Value> = skill_paths
+                .iter()
+                .map(|path| match read_properties(path) {
+                    Ok(props) => serde_json::json!({
+                        "path": path,
+                        "properties": properties_json(&props, expand_tools),
+                    }),
+                    Err(e) => {
+                        failed = true;
+                        serde_json::json!({ "path": path, "error": e.to_string() })
+                    }
+                })
+                .collect();
+            output::print_json(&entries);
+
+            if failed && !ignore_errors {
+                process::exit(1);
+            }
+        }
+
+        Commands::ToPrompt {
+            skill_paths,
+            promptset,
+            set_name,
+            tools,
+            exclude_incompatible,
+            inline_body,
+            max_chars,
+            keep_highest_version,
+            include_deprecated,
+            include_ids,
+            paginate,
+            page,
+            overrides,
+            fail_if_empty,
+            allow_empty,
+            trust,
+            verify_locations,
+            fail_on_unreadable,
+            report,
+        } => {
+            let (skill_paths, mut options) = match promptset {
+                Some(path) => {
+                    let composition = PromptSet::load(&path).and_then(|set| {
+                        let composition = set.get(set_name.as_deref())?.clone();
+                        Ok((composition.skill_dirs()?, composition.options))
+                    });
+                    match composition {
+                        Ok(composition) => composition,
+                        Err(e) => {
+                            errln!("Error: {}", e);
+                            process::exit(1);
+                        }
+                    }
+                }
+                None => {
+                    let mut options = PromptOptions::default();
+                    if exclude_incompatible {
+                        options.host = tools.map(HostCapabilities::with_tools);
+                    }
+                    options.inline_body = match inline_body {
+                        None => InlineBody::Never,
+                        Some(None) => InlineBody::Always,
+                        Some(Some(max)) => InlineBody::UnderChars(max),
+                    };
+                    options.max_chars = max_chars;
+                    if keep_highest_version {
+                        options.duplicates = DuplicatePolicy::KeepHighestVersion;
+                    }
+                    options.include_deprecated = include_deprecated;
+                    options.include_ids = include_ids;
+                    options.trust = trust;
+                    // Every path given was left out: virtually always a mistake.
+                    options.on_empty = if allow_empty && !fail_if_empty {
+                        EmptyPolicy::Allow
+                    } else {
+                        EmptyPolicy::Error
+                    };
+                    if let Some(path) = overrides {
+                        match PromptOverrides::load(&path) {
+                            Ok(overrides) => options.overrides = Some(overrides),
+                            Err(e) => {
+                                errln!("Error: {}: {}", path.display(), e);
+                                process::exit(1);
+                            }
+                        }
+                    }
+
+                    (skill_paths, options)
+                }
+            };
+            options.redact_paths = output::redaction().cloned();
+            options.verify_locations |= verify_locations;
+            if fail_on_unreadable {
+                options.on_unreadable = UnreadablePolicy::Error;
+            }
+            let path_refs: Vec<&std::path::Path> =
+                skill_paths.iter().map(|p| p.as_path()).collect();
+
+            if let Some(max_chars_per_page) = paginate {
+                let pages = match prompt_pages(&path_refs, &options, max_chars_per_page) {
+                    Ok(pages) => pages,
+                    Err(e) => {
+                        errln!("Error: {}", e);
+                        process::exit(1);
+                    }
+                };
+                match page.checked_sub(1).and_then(|i| pages.get(i)) {
+                    // Locations were redacted before escaping; the XML
+                    // itself must not be rewritten.
+                    Some(xml) => std::println!("{}", xml),
+                    None => {
+                        errln!("Error: Page {} out of range (1-{})", page, pages.len());
+                        process::exit(1);
+                    }
+                }
+                return;
+            }
 
+            match render_prompt(&path_refs, &options) {
+                Ok(render) => {
+                    if let Some(path) = report {
+                        // The sidecar describes the prompt; it leaves out
+                        // the XML itself.
+                        let sidecar = serde_json::json!({
+                            "included": render.included,
+                            "excluded": render.excluded,
+                            "notices": render.notices,
+                            "warnings": render.warnings,
+                        });
+                        if let Err(e) = output::write_json(&path, &sidecar) {
+                            errln!("Error: {}: {}", path.display(), e);
+                            process::exit(1);
+                        }
+                    }
+                    for skill in render.excluded {
+                        errln!(
+                            "Excluded {} ({}): {}",
+                            skill.name,
+                            skill.path.display(),
+                            skill.reason
+                        );
+                    }
+                    for notice in render.notices {
+                        errln!("Notice: {}", notice);
+                    }
+                    for warning in render.warnings {
+                        errln!("Warning: {}", warning);
+                    }
+                    std::println!("{}", render.xml);
+                }
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+
+        Commands::Preview {
+            skill_paths,
+            budget,
+            raw,
+            width,
+        } => {
             let path_refs: Vec<&std::path::Path> =
-                resolved_paths.iter().map(|p| p.as_path()).collect();
+                skill_paths.iter().map(|p| p.as_path()).collect();
+            let mut options = PromptOptions::default();
+            options.redact_paths = output::redaction().cloned();
+            let xml = match to_prompt_with_options(&path_refs, &options) {
+                Ok(xml) => xml,
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            };
+
+            if raw {
+                std::println!("{}", xml);
+            } else {
+                let width = width
+                    .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+                    .unwrap_or(80);
+                let color =
+                    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+                let options = cli::preview::PreviewOptions {
+                    width,
+                    color,
+                    budget,
+                };
+                std::print!("{}", cli::preview::render_preview(&xml, &options));
+            }
+        }
+
+        Commands::Init {
+            skill_path,
+            name,
+            description,
+            manifest,
+            with_id,
+            template,
+            template_dir,
+            list_templates,
+        } => {
+            if list_templates {
+                out!("{}", cli::templates::render_list());
+                return;
+            }
+            let skill_path = skill_path.expect("clap requires skill_path without --list-templates");
+            let template = match (&template, &template_dir) {
+                (_, Some(dir)) => cli::templates::TemplateSource::Dir(dir),
+                (Some(name), None) => match cli::templates::find_template(name) {
+                    Ok(template) => cli::templates::TemplateSource::Builtin(template),
+                    Err(e) => {
+                        errln!("Error: {}", e);
+                        process::exit(1);
+                    }
+                },
+                (None, None) => cli::templates::TemplateSource::default(),
+            };
+            let manifest = match manifest.map(|path| Manifest::load(&path)).transpose() {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            };
+            match cli::init::plan_init(
+                &skill_path,
+                name.as_deref(),
+                description.as_deref(),
+                manifest.as_ref(),
+                with_id.then(|| uuid::Uuid::new_v4().to_string()).as_deref(),
+                template,
+            ) {
+                Ok((name, plan)) => {
+                    if let Err(e) = plan.apply(apply_mode) {
+                        errln!("Error: {}", e);
+                        process::exit(1);
+                    }
+                    if !apply_mode.dry_run {
+                        outln!("Created skill {}: {}", name, skill_path.display());
+                    }
+                }
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
 
-            match to_prompt(&path_refs) {
-                Ok(output) => {
-                    println!("{}", output);
+        Commands::Rename {
+            skill_path,
+            new_name,
+        } => match cli::rename::plan_rename(&skill_path, &new_name) {
+            Ok((new_dir, plan)) => {
+                if let Err(e) = plan.apply(apply_mode) {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+                if !apply_mode.dry_run {
+                    outln!("Renamed skill to {}: {}", new_name, new_dir.display());
+                }
+            }
+            Err(e) => {
+                errln!("Error: {}", e);
+                process::exit(1);
+            }
+        },
+
+        Commands::Set {
+            skill_path,
+            field,
+            value,
+            unset: _,
+            reformat,
+        } => {
+            let plan = match cli::set::plan_set(&skill_path, &field, value.as_deref(), reformat) {
+                Ok(plan) => plan,
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            };
+            if let Err(e) = plan.apply(apply_mode) {
+                errln!("Error: {}", e);
+                process::exit(1);
+            }
+        }
+
+        Commands::SetField {
+            root,
+            key,
+            value,
+            only_missing,
+            rule_options,
+        } => {
+            let edit = FieldEdit {
+                path: key,
+                value: value.into(),
+                only_missing,
+            };
+            let (report, plan) =
+                match cli::set_field::plan_set_field(&root, &edit, &rule_options.config()) {
+                    Ok(planned) => planned,
+                    Err(e) => {
+                        errln!("Error: {}", e);
+                        process::exit(1);
+                    }
+                };
+            if let Err(e) = plan.apply(apply_mode) {
+                errln!("Error: {}", e);
+                process::exit(1);
+            }
+            out!("{}", cli::set_field::render_text(&report));
+            if report.failed() > 0 {
+                process::exit(1);
+            }
+        }
+
+        Commands::Fmt {
+            skill_path,
+            json,
+            wrap,
+            unwrap,
+            syntax,
+        } => {
+            let mode = if unwrap {
+                Some(Reflow::Unwrap)
+            } else {
+                wrap.and_then(|w| w.0).map(Reflow::Wrap)
+            };
+            let syntax = syntax.map(FrontmatterSyntax::from);
+            if json || (skill_path.is_dir() && find_skill_md(&skill_path).is_none()) {
+                match plan_fmt_tree(&resolve_skill_path(skill_path), mode, syntax) {
+                    Ok(report) => {
+                        if !cli::fix::finish(report, apply_mode, json) {
+                            process::exit(1);
+                        }
+                    }
+                    Err(e) => {
+                        errln!("Error: {}", e);
+                        process::exit(1);
+                    }
+                }
+                return;
+            }
+            let plan = match cli::fmt::plan_fmt(&skill_path, mode, syntax) {
+                Ok(plan) => plan,
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            };
+            if let Err(e) = plan.apply(apply_mode) {
+                errln!("Error: {}", e);
+                process::exit(1);
+            }
+        }
+
+        Commands::Fork {
+            source_skill,
+            new_name,
+            dir,
+            no_resources,
+        } => {
+            let options = cli::fork::ForkOptions {
+                dir: dir.as_deref(),
+                no_resources,
+            };
+            match cli::fork::plan_fork(&source_skill, &new_name, options) {
+                Ok((new_dir, plan)) => {
+                    if let Err(e) = plan.apply(apply_mode) {
+                        errln!("Error: {}", e);
+                        process::exit(1);
+                    }
+                    if !apply_mode.dry_run {
+                        outln!("{}", new_dir.display());
+                    }
+                }
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+
+        Commands::Licenses { root, json, deny } => {
+            let report = match license_report(&root) {
+                Ok(report) => report,
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            };
+
+            if json {
+                output::print_json(&report);
+            } else {
+                for (license, skills) in &report.licenses {
+                    outln!("{} ({})", license, skills.len());
+                    for skill in skills {
+                        outln!("  {}  {}", skill.name, skill.path.display());
+                    }
+                }
+                for failure in &report.failures {
+                    errln!(
+                        "Warning: could not read {}: {}",
+                        failure.path.display(),
+                        failure.error
+                    );
+                }
+            }
+
+            let deny: Vec<&str> = deny.iter().map(String::as_str).collect();
+            let denied = report.denied(&deny);
+            if !denied.is_empty() {
+                errln!("Denied licenses found:");
+                for (license, skill) in denied {
+                    errln!("  - {} ({}): {}", skill.name, skill.path.display(), license);
+                }
+                process::exit(1);
+            }
+        }
+
+        Commands::Stats { root, json } => match stats(&root) {
+            Ok(catalog_stats) if json => {
+                output::print_json(&catalog_stats);
+            }
+            Ok(catalog_stats) => out!("{}", cli::stats::render_text(&catalog_stats)),
+            Err(e) => {
+                errln!("Error: {}", e);
+                process::exit(1);
+            }
+        },
+
+        Commands::Export { root, format, out } => {
+            let (set, failures) = match SkillSet::discover(&root) {
+                Ok(result) => result,
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            };
+            for failure in &failures {
+                errln!(
+                    "Warning: could not read {}: {}",
+                    failure.path.display(),
+                    failure.error
+                );
+            }
+
+            let document = match format {
+                ExportFormat::MarkdownBundle => to_markdown_bundle(&set),
+            };
+            match out {
+                Some(path) => {
+                    let mut plan = ChangePlan::new();
+                    plan.push(Change::WriteFile {
+                        path,
+                        contents: document,
+                    });
+                    if let Err(e) = plan.apply(apply_mode) {
+                        errln!("Error: {}", e);
+                        process::exit(1);
+                    }
+                }
+                None => out!("{}", document),
+            }
+        }
+
+        Commands::List {
+            root,
+            duplicates,
+            similarity,
+            porcelain,
+        } => {
+            let (set, failures) = match SkillSet::discover(&root) {
+                Ok(result) => result,
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            };
+            for failure in &failures {
+                errln!(
+                    "Warning: could not read {}: {}",
+                    failure.path.display(),
+                    failure.error
+                );
+            }
+
+            if porcelain {
+                out!("{}", cli::report::render_list(&set));
+            } else if duplicates {
+                let mut options = DuplicateOptions::default();
+                options.similarity_threshold = similarity;
+                for group in set.find_duplicates_with(&options) {
+                    match group.kind {
+                        DuplicateKind::Exact => outln!("Identical bodies:"),
+                        DuplicateKind::Similar => {
+                            outln!("Similar descriptions ({:.2}):", group.similarity)
+                        }
+                    }
+                    for skill in group.skills {
+                        outln!("  {}  {}", skill.name(), skill.dir.display());
+                    }
+                }
+            } else {
+                out!("{}", cli::list::render_text(&set));
+            }
+        }
+
+        Commands::Changed {
+            old,
+            new,
+            paths_only,
+        } => {
+            let changes = match changed_skills(&old, &new) {
+                Ok(changes) => changes,
+                Err(e) => {
+                    errln!("Error: {}", e);
+                    process::exit(1);
+                }
+            };
+
+            if paths_only {
+                for path in changes.affected() {
+                    outln!("{}", path.display());
                 }
+            } else {
+                for path in &changes.added {
+                    outln!("added     {}", path.display());
+                }
+                for path in &changes.modified {
+                    outln!("modified  {}", path.display());
+                }
+                for path in &changes.removed {
+                    outln!("removed   {}", path.display());
+                }
+            }
+        }
+
+        Commands::Doctor {
+            roots,
+            json,
+            rule_options,
+        } => {
+            let report = cli::doctor::doctor(&roots, &rule_options.config());
+            if json {
+                output::print_json(&report);
+            } else {
+                out!("{}", report.render_text());
+            }
+            if !report.is_healthy() {
+                process::exit(1);
+            }
+        }
+
+        Commands::SelfTest { json } => {
+            let scratch = match tempfile::TempDir::with_prefix("skills-ref-self-test-") {
+                Ok(dir) => dir,
                 Err(e) => {
-                    eprintln!("Error: {}", e);
+                    errln!("Error: {}", e);
                     process::exit(1);
                 }
+            };
+            let report = cli::self_test::self_test(scratch.path());
+            drop(scratch);
+            if json {
+                output::print_json(&report);
+            } else {
+                out!("{}", report.render_text());
+            }
+            if !report.passed() {
+                process::exit(1);
+            }
+        }
+
+        Commands::Info => {
+            output::print_json(&crate_info());
+        }
+
+        Commands::Rules { json, explain } => {
+            if let Some(id) = explain {
+                let Some(rule) = rule_info(&id) else {
+                    errln!("Error: Unknown rule: {}", id);
+                    process::exit(1);
+                };
+                outln!("{} ({})", rule.id, rule.default_severity);
+                outln!();
+                outln!("{}", rule.summary);
+                outln!();
+                outln!("{}", rule.description);
+                outln!();
+                outln!("Failing example:");
+                for line in rule.failing_example.lines() {
+                    outln!("  {}", line);
+                }
+                outln!();
+                outln!("Passing example:");
+                for line in rule.passing_example.lines() {
+                    outln!("  {}", line);
+                }
+            } else if json {
+                output::print_json(&rules());
+            } else {
+                for rule in rules() {
+                    outln!(
+                        "{:<26} {:<8} {}",
+                        rule.id,
+                        rule.default_severity.to_string(),
+                        rule.summary
+                    );
+                }
             }
         }
     }
@@ -0,0 +1,215 @@
+//! Skill catalogs stored as JSON manifests, and name availability checks.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::confusables::is_confusable;
+use crate::error::{Result, SkillError};
+use crate::skill_set::SkillSet;
+
+/// A catalog of known skills.
+///
+/// Stored as JSON:
+///
+/// ```json
+/// {"skills": [{"name": "pdf-tools", "path": "skills/pdf-tools"}]}
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Skills in the catalog.
+    pub skills: Vec<ManifestEntry>,
+}
+
+/// A skill listed in a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Skill name.
+    pub name: String,
+
+    /// Path to the skill directory (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+
+    /// Skill description (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Stable identifier from the skill's `metadata.id` (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+/// Whether a name can be used for a new skill.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameAvailability {
+    /// No skill in the manifest uses or resembles the name.
+    Available,
+
+    /// A skill already has this name, after NFKC normalization and case
+    /// folding.
+    Taken {
+        /// Name of the existing skill.
+        existing: String,
+    },
+
+    /// A skill has a name that looks like this one (see
+    /// [`confusables::skeleton`](crate::confusables::skeleton)).
+    Confusable {
+        /// Name of the existing skill.
+        existing: String,
+    },
+}
+
+impl NameAvailability {
+    /// Check if the name is available.
+    pub fn is_available(&self) -> bool {
+        matches!(self, NameAvailability::Available)
+    }
+}
+
+/// Normalize a name for exact comparison: NFKC, then lowercase.
+fn fold(name: &str) -> String {
+    name.nfkc().collect::<String>().to_lowercase()
+}
+
+/// Check whether `name` is free to use given the skills in `manifest`.
+///
+/// Exact conflicts take precedence over confusable ones.
+pub fn is_name_available(name: &str, manifest: &Manifest) -> NameAvailability {
+    let folded = fold(name);
+    if let Some(entry) = manifest.skills.iter().find(|e| fold(&e.name) == folded) {
+        return NameAvailability::Taken {
+            existing: entry.name.clone(),
+        };
+    }
+    if let Some(entry) = manifest
+        .skills
+        .iter()
+        .find(|e| is_confusable(&e.name, name))
+    {
+        return NameAvailability::Confusable {
+            existing: entry.name.clone(),
+        };
+    }
+    NameAvailability::Available
+}
+
+impl Manifest {
+    /// Load a manifest from a JSON file.
+    ///
+    /// # Errors
+    ///
+    /// * `IoError` - If the file cannot be read
+    /// * `ParseError` - If the file is not a valid manifest
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path)?;
+        serde_json::from_str(&data)
+            .map_err(|e| SkillError::parse(format!("Invalid manifest {}: {}", path.display(), e)))
+    }
+
+    /// Build a manifest listing every skill in `set`.
+    pub fn from_skill_set(set: &SkillSet) -> Self {
+        Self {
+            skills: set
+                .iter()
+                .map(|skill| ManifestEntry {
+                    name: skill.properties.name.clone(),
+                    path: Some(skill.dir.clone()),
+                    description: Some(skill.properties.description.clone()),
+                    id: skill.properties.stable_id().map(str::to_string),
+                })
+                .collect(),
+        }
+    }
+
+    /// Check if `name` is taken by, or confusable with, a skill in the
+    /// manifest.
+    pub fn contains_name(&self, name: &str) -> bool {
+        !is_name_available(name, self).is_available()
+    }
+
+    /// Suggest up to `count` available variants of `name`, of the form
+    /// `name-2`, `name-3`, and so on.
+    pub fn suggest_names(&self, name: &str, count: usize) -> Vec<String> {
+        (2..)
+            .take(count + self.skills.len())
+            .map(|n| format!("{}-{}", name, n))
+            .filter(|candidate| !self.contains_name(candidate))
+            .take(count)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(names: &[&str]) -> Manifest {
+        Manifest {
+            skills: names
+                .iter()
+                .map(|name| ManifestEntry {
+                    name: name.to_string(),
+                    path: None,
+                    description: None,
+                    id: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_name_availability() {
+        let manifest = manifest(&["pdf-tools", "paypal"]);
+
+        assert_eq!(
+            is_name_available("PDF-Tools", &manifest),
+            NameAvailability::Taken {
+                existing: "pdf-tools".to_string()
+            }
+        );
+        assert_eq!(
+            is_name_available("раураl", &manifest),
+            NameAvailability::Confusable {
+                existing: "paypal".to_string()
+            }
+        );
+        assert!(is_name_available("docx-tools", &manifest).is_available());
+        assert!(manifest.contains_name("pdf-t00ls"));
+        assert!(!manifest.contains_name("pdf"));
+    }
+
+    #[test]
+    fn test_suggest_names_skips_taken() {
+        let manifest = manifest(&["pdf-tools", "pdf-tools-2"]);
+        assert_eq!(
+            manifest.suggest_names("pdf-tools", 2),
+            vec!["pdf-tools-3", "pdf-tools-4"]
+        );
+    }
+
+    #[test]
+    fn test_load_manifest() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("skills.json");
+        std::fs::write(
+            &path,
+            r#"{"skills": [{"name": "pdf-tools", "path": "skills/pdf-tools", "id": "com.example.pdf"}]}"#,
+        )
+        .unwrap();
+
+        let manifest = Manifest::load(&path).unwrap();
+        assert_eq!(manifest.skills[0].name, "pdf-tools");
+        assert_eq!(
+            manifest.skills[0].path.as_deref(),
+            Some(Path::new("skills/pdf-tools"))
+        );
+        assert_eq!(manifest.skills[0].id.as_deref(), Some("com.example.pdf"));
+
+        std::fs::write(&path, "[]").unwrap();
+        let err = Manifest::load(&path).unwrap_err();
+        assert!(err.to_string().contains("Invalid manifest"));
+    }
+}
@@ -0,0 +1,348 @@
+//! Minimal markdown structure scanning for skill bodies.
+//!
+//! Only ATX headings (`# Title`) and inline links (`[text](target)`) are
+//! recognized. Lines inside fenced code blocks (```` ``` ```` or `~~~`) are
+//! never treated as headings or links, and neither are code spans.
+//!
+//! Setext headings (a line underlined with `===` or `---`) are not
+//! headings here: a `---` line is too easily confused with a frontmatter
+//! delimiter or a thematic break. They are found separately, by
+//! [`setext_headings`], so lint can suggest rewriting them.
+
+use std::ops::Range;
+
+/// An ATX heading found in a markdown document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Heading {
+    /// Heading level, 1 through 6.
+    pub level: usize,
+
+    /// Heading text with the `#` markers and surrounding whitespace removed.
+    pub text: String,
+
+    /// Byte range of the whole heading line, excluding its line ending.
+    pub line: Range<usize>,
+}
+
+/// An inline link or image found in a markdown document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Link {
+    /// Link destination, without angle brackets or title.
+    pub target: String,
+
+    /// Line of the link, counting from 1.
+    pub line: usize,
+}
+
+/// An open fenced code block: fence character and fence length.
+pub(crate) struct Fence {
+    ch: char,
+    len: usize,
+}
+
+/// Headings found by [`scan_headings`].
+#[derive(Default)]
+struct HeadingScan {
+    atx: Vec<Heading>,
+    setext: Vec<Heading>,
+}
+
+/// Find all ATX headings in `text`, in document order.
+pub(crate) fn headings(text: &str) -> Vec<Heading> {
+    scan_headings(text).atx
+}
+
+/// Find all setext headings in `text`, in document order.
+///
+/// Each heading's text joins the lines of the paragraph above the
+/// underline, and its range runs from the paragraph's first line through
+/// the underline. `===` gives level 1 and `---` level 2.
+pub(crate) fn setext_headings(text: &str) -> Vec<Heading> {
+    scan_headings(text).setext
+}
+
+fn scan_headings(text: &str) -> HeadingScan {
+    let mut scan = HeadingScan::default();
+    let mut fence: Option<Fence> = None;
+    // Start offset and lines of the paragraph the current line may close.
+    let mut paragraph: Option<(usize, Vec<&str>)> = None;
+    let mut offset = 0;
+
+    for raw in text.split_inclusive('\n') {
+        let start = offset;
+        offset += raw.len();
+        let line = raw.trim_end_matches(['\n', '\r']);
+
+        if let Some(open) = &fence {
+            if is_closing_fence(line, open) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(open) = opening_fence(line) {
+            fence = Some(open);
+            paragraph = None;
+            continue;
+        }
+        if let Some((level, heading)) = parse_atx_heading(line) {
+            scan.atx.push(Heading {
+                level,
+                text: heading.to_string(),
+                line: start..start + line.len(),
+            });
+            paragraph = None;
+            continue;
+        }
+        if let Some((para_start, lines)) = &paragraph
+            && let Some(level) = setext_underline(line)
+        {
+            let text: Vec<&str> = lines.iter().map(|l| l.trim()).collect();
+            scan.setext.push(Heading {
+                level,
+                text: text.join(" "),
+                line: *para_start..start + line.len(),
+            });
+            paragraph = None;
+            continue;
+        }
+        if is_paragraph_line(line) {
+            paragraph
+                .get_or_insert_with(|| (start, Vec::new()))
+                .1
+                .push(line);
+        } else {
+            paragraph = None;
+        }
+    }
+
+    scan
+}
+
+/// The level a setext underline gives, if `line` is one.
+fn setext_underline(line: &str) -> Option<usize> {
+    let rest = strip_indent(line)?.trim_end();
+    if !rest.is_empty() && rest.chars().all(|c| c == '=') {
+        Some(1)
+    } else if !rest.is_empty() && rest.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Check if `line` can be part of a paragraph a setext underline turns
+/// into a heading: not blank, not indented code, and not a list item,
+/// block quote, HTML block, or table row.
+fn is_paragraph_line(line: &str) -> bool {
+    let Some(rest) = strip_indent(line) else {
+        return false;
+    };
+    let rest = rest.trim_end();
+    if rest.is_empty() || rest.starts_with(['>', '<', '|']) {
+        return false;
+    }
+    if setext_underline(rest).is_some() {
+        return false;
+    }
+    let marker = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+    let is_list_item = if marker.len() < rest.len() {
+        marker.starts_with(". ") || marker.starts_with(") ")
+    } else {
+        rest.starts_with("- ") || rest.starts_with("* ") || rest.starts_with("+ ")
+    };
+    !is_list_item
+}
+
+/// Find all inline links and images in `text`, in document order.
+pub(crate) fn links(text: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut fence: Option<Fence> = None;
+
+    for (i, raw) in text.lines().enumerate() {
+        if let Some(open) = &fence {
+            if is_closing_fence(raw, open) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(open) = opening_fence(raw) {
+            fence = Some(open);
+            continue;
+        }
+
+        // Code spans alternate with text between backtick runs.
+        for (j, segment) in raw.split('`').enumerate() {
+            if j % 2 == 1 {
+                continue;
+            }
+            let mut rest = segment;
+            while let Some(pos) = rest.find("](") {
+                rest = &rest[pos + 2..];
+                let Some(end) = rest.find(')') else {
+                    break;
+                };
+                let destination = rest[..end].trim();
+                let target = match destination.strip_prefix('<') {
+                    Some(inner) => inner.split('>').next().unwrap_or(""),
+                    None => destination.split_whitespace().next().unwrap_or(""),
+                };
+                if !target.is_empty() {
+                    links.push(Link {
+                        target: target.to_string(),
+                        line: i + 1,
+                    });
+                }
+                rest = &rest[end + 1..];
+            }
+        }
+    }
+
+    links
+}
+
+/// Strip up to three spaces of indentation, as CommonMark allows for block
+/// markers. Returns `None` for lines indented further.
+fn strip_indent(line: &str) -> Option<&str> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    (indent <= 3).then(|| &line[indent..])
+}
+
+pub(crate) fn opening_fence(line: &str) -> Option<Fence> {
+    let rest = strip_indent(line)?;
+    let ch = rest.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = rest.len() - rest.trim_start_matches(ch).len();
+    if len < 3 {
+        return None;
+    }
+    // Backtick fences cannot have backticks in their info string.
+    if ch == '`' && rest[len..].contains('`') {
+        return None;
+    }
+    Some(Fence { ch, len })
+}
+
+pub(crate) fn is_closing_fence(line: &str, open: &Fence) -> bool {
+    let Some(rest) = strip_indent(line) else {
+        return false;
+    };
+    let len = rest.len() - rest.trim_start_matches(open.ch).len();
+    len >= open.len && rest[len..].trim().is_empty()
+}
+
+fn parse_atx_heading(line: &str) -> Option<(usize, &str)> {
+    let rest = strip_indent(line)?;
+    let level = rest.len() - rest.trim_start_matches('#').len();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &rest[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+
+    // Drop an optional closing sequence of `#`s preceded by whitespace.
+    let mut text = rest.trim();
+    let without_closing = text.trim_end_matches('#');
+    if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        text = without_closing.trim_end();
+    }
+    Some((level, text))
+}
+
+/// Split `text` into sections, one per heading.
+///
+/// Each section's range covers the text after its heading line up to the
+/// next heading of the same or higher level (or the end of the document),
+/// so a section includes its subsections.
+pub(crate) fn sections(text: &str) -> Vec<(String, Range<usize>)> {
+    let headings = headings(text);
+
+    headings
+        .iter()
+        .enumerate()
+        .map(|(i, heading)| {
+            let start = text[heading.line.end..]
+                .find('\n')
+                .map_or(text.len(), |n| heading.line.end + n + 1);
+            let end = headings[i + 1..]
+                .iter()
+                .find(|next| next.level <= heading.level)
+                .map_or(text.len(), |next| next.line.start);
+            (heading.text.clone(), start..end)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links() {
+        let text = "See [pdf](../pdf/SKILL.md) and ![logo](<img/a b.png> \"Logo\").\n\
+                    `[not](code)`\n```\n[not](fenced)\n```\n[csv](skill:csv \"CSV\")\n";
+        let found: Vec<_> = links(text)
+            .into_iter()
+            .map(|l| (l.line, l.target))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (1, "../pdf/SKILL.md".to_string()),
+                (1, "img/a b.png".to_string()),
+                (6, "skill:csv".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_headings_levels_and_text() {
+        let found = headings("# One\ntext\n## Two ##\n###Not\n####### Seven\n    # Code\n");
+        let summary: Vec<_> = found.iter().map(|h| (h.level, h.text.as_str())).collect();
+        assert_eq!(summary, vec![(1, "One"), (2, "Two")]);
+    }
+
+    #[test]
+    fn test_headings_skip_fenced_code() {
+        let text = "# Real\n```bash\n# comment\n```\n~~~~\n## Also code\n~~~\n~~~~\n## After\n";
+        let found = headings(text);
+        let names: Vec<_> = found.iter().map(|h| h.text.as_str()).collect();
+        assert_eq!(names, vec!["Real", "After"]);
+    }
+
+    #[test]
+    fn test_setext_headings() {
+        let text =
+            "Title\n=====\nTwo\nlines\n---\n\n---\n- item\n---\n```\ncode\n---\n```\n# ATX\n---\n";
+        let found: Vec<_> = setext_headings(text)
+            .into_iter()
+            .map(|h| (h.level, h.text, &text[h.line]))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (1, "Title".to_string(), "Title\n====="),
+                (2, "Two lines".to_string(), "Two\nlines\n---"),
+            ]
+        );
+        let names: Vec<_> = headings(text).into_iter().map(|h| h.text).collect();
+        assert_eq!(names, ["ATX"]);
+    }
+
+    #[test]
+    fn test_heading_closing_hashes() {
+        assert_eq!(parse_atx_heading("# C#"), Some((1, "C#")));
+        assert_eq!(parse_atx_heading("## Title ###"), Some((2, "Title")));
+        assert_eq!(parse_atx_heading("#"), Some((1, "")));
+    }
+
+    #[test]
+    fn test_sections_ranges() {
+        let text = "# A\na\n## B\nb\n# C\nc";
+        let sections = sections(text);
+        assert_eq!(sections.len(), 3);
+        assert_eq!(&text[sections[0].1.clone()], "a\n## B\nb\n");
+        assert_eq!(&text[sections[1].1.clone()], "b\n");
+        assert_eq!(&text[sections[2].1.clone()], "c");
+    }
+}
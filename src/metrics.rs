@@ -0,0 +1,119 @@
+//! Hooks for reporting counters and timings to a metrics backend.
+//!
+//! The crate has no metrics dependency: operations that accept a
+//! [`Metrics`] call it with the keys below, and callers adapt the calls to
+//! their backend (Prometheus, StatsD, ...).
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Counter: skills validated.
+pub const SKILLS_VALIDATED: &str = "skills_validated";
+
+/// Counter, labeled by rule ID: error diagnostics from validation.
+pub const VALIDATION_FAILURES: &str = "validation_failures";
+
+/// Counter: skills loaded.
+pub const SKILLS_LOADED: &str = "skills_loaded";
+
+/// Counter: skills that failed to load.
+pub const LOAD_FAILURES: &str = "load_failures";
+
+/// Counter, labeled `properties` or `validation`: results found in a
+/// [`SkillCache`](crate::cache::SkillCache).
+pub const CACHE_HITS: &str = "cache_hits";
+
+/// Counter, labeled `properties` or `validation`: results computed because
+/// a [`SkillCache`](crate::cache::SkillCache) had no usable entry.
+pub const CACHE_MISSES: &str = "cache_misses";
+
+/// Observation: seconds spent reading and parsing one SKILL.md.
+pub const PARSE_SECONDS: &str = "parse_seconds";
+
+/// A sink for counters and observations.
+///
+/// Implementations are called from several threads at once.
+pub trait Metrics: Sync {
+    /// Add one to the counter `key`.
+    fn incr(&self, key: &'static str);
+
+    /// Add one to the counter `key` for `label`, such as a rule ID.
+    ///
+    /// Defaults to [`incr`](Self::incr) without the label.
+    fn incr_labeled(&self, key: &'static str, label: &'static str) {
+        let _ = label;
+        self.incr(key);
+    }
+
+    /// Record one observation of `value` for `key`.
+    fn observe(&self, key: &'static str, value: f64);
+}
+
+/// Metrics that discard everything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopMetrics;
+
+impl Metrics for NoopMetrics {
+    fn incr(&self, _key: &'static str) {}
+
+    fn observe(&self, _key: &'static str, _value: f64) {}
+}
+
+/// Metrics kept in memory, for tests.
+///
+/// Labeled counters are stored under `key{label}`.
+#[derive(Debug, Default)]
+pub struct RecordingMetrics {
+    counters: Mutex<BTreeMap<String, u64>>,
+    observations: Mutex<BTreeMap<String, Vec<f64>>>,
+}
+
+impl RecordingMetrics {
+    /// Create an empty recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current value of the counter `key` (or `key{label}`).
+    pub fn count(&self, key: &str) -> u64 {
+        self.counters.lock().unwrap().get(key).copied().unwrap_or(0)
+    }
+
+    /// All counters, by key.
+    pub fn counters(&self) -> BTreeMap<String, u64> {
+        self.counters.lock().unwrap().clone()
+    }
+
+    /// Observations recorded for `key`, in the order they arrived.
+    pub fn observations(&self, key: &str) -> Vec<f64> {
+        self.observations
+            .lock()
+            .unwrap()
+            .get(key)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn add(&self, key: String) {
+        *self.counters.lock().unwrap().entry(key).or_default() += 1;
+    }
+}
+
+impl Metrics for RecordingMetrics {
+    fn incr(&self, key: &'static str) {
+        self.add(key.to_string());
+    }
+
+    fn incr_labeled(&self, key: &'static str, label: &'static str) {
+        self.add(format!("{}{{{}}}", key, label));
+    }
+
+    fn observe(&self, key: &'static str, value: f64) {
+        self.observations
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .push(value);
+    }
+}
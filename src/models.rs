@@ -1,8 +1,27 @@
 //! Data models for Agent Skills.
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 
+use crate::error::{Result, SkillError};
+use crate::host::parse_allowed_tools_lenient;
+use crate::validator::validate_properties;
+
+/// Conventional `metadata` keys with a shared meaning across skills.
+///
+/// UIs may render these specially; [`SkillProperties`] has an accessor for
+/// each.
+pub const CONVENTIONAL_METADATA_KEYS: &[&str] = &[
+    "author",
+    "maintainer-email",
+    "version",
+    "homepage",
+    "deprecated",
+    "deprecated-message",
+    "id",
+];
+
 /// Properties parsed from a skill's SKILL.md frontmatter.
 ///
 /// # Fields
@@ -12,7 +31,9 @@ use std::collections::HashMap;
 /// * `license` - License for the skill (optional)
 /// * `compatibility` - Compatibility information for the skill (optional)
 /// * `allowed_tools` - Tool patterns the skill requires (optional, experimental)
-/// * `metadata` - Key-value pairs for client-specific properties (optional)
+/// * `metadata` - Key-value pairs for client-specific properties (optional);
+///   values may be nested up to [`MAX_METADATA_DEPTH`](crate::validator::MAX_METADATA_DEPTH) levels
+/// * `extensions` - Top-level `x-` prefixed client extension fields (optional)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SkillProperties {
     /// Skill name in kebab-case (required).
@@ -29,13 +50,111 @@ pub struct SkillProperties {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub compatibility: Option<String>,
 
-    /// Tool patterns the skill requires (optional, experimental).
+    /// Tool patterns the skill requires (optional, experimental), in the
+    /// form the author wrote them.
     #[serde(rename = "allowed-tools", skip_serializing_if = "Option::is_none")]
-    pub allowed_tools: Option<String>,
+    pub allowed_tools: Option<AllowedToolsRepr>,
 
     /// Key-value pairs for client-specific properties (optional).
+    ///
+    /// Values keep their YAML structure: strings, numbers, booleans, and
+    /// nested sequences and mappings.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub metadata: Option<HashMap<String, String>>,
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+
+    /// Top-level client extension fields, keyed by their `x-` prefixed
+    /// names (optional). Serialized under their original keys.
+    #[serde(flatten, deserialize_with = "deserialize_extensions")]
+    pub extensions: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// The `allowed-tools` field as written: one string of entries separated by
+/// spaces or commas, or a YAML list with one entry per item.
+///
+/// Both forms mean the same; the form is kept so that rewriting a skill
+/// does not change it. Serialized as the string or list it holds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AllowedToolsRepr {
+    /// A single string, such as `Bash(git:*) Read`.
+    Raw(String),
+
+    /// A list of entries, such as `[Bash(git:*), Read]`.
+    List(Vec<String>),
+}
+
+impl AllowedToolsRepr {
+    /// The value as one string: a string as written, or list entries
+    /// joined with spaces.
+    pub fn raw(&self) -> Cow<'_, str> {
+        match self {
+            AllowedToolsRepr::Raw(raw) => Cow::Borrowed(raw),
+            AllowedToolsRepr::List(entries) => Cow::Owned(entries.join(" ")),
+        }
+    }
+
+    /// The tool patterns, one per entry, such as `Bash(git:*)`.
+    ///
+    /// List entries are returned trimmed; a string is split like
+    /// [`parse_allowed_tools`](crate::parse_allowed_tools), leaving out
+    /// malformed entries.
+    pub fn patterns(&self) -> Vec<String> {
+        match self {
+            AllowedToolsRepr::Raw(raw) => parse_allowed_tools_lenient(raw)
+                .0
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            AllowedToolsRepr::List(entries) => entries
+                .iter()
+                .map(|entry| entry.trim().to_string())
+                .collect(),
+        }
+    }
+
+    /// Check if the value was written as a list.
+    pub fn is_list(&self) -> bool {
+        matches!(self, AllowedToolsRepr::List(_))
+    }
+}
+
+impl From<String> for AllowedToolsRepr {
+    fn from(raw: String) -> Self {
+        AllowedToolsRepr::Raw(raw)
+    }
+}
+
+impl From<&str> for AllowedToolsRepr {
+    fn from(raw: &str) -> Self {
+        AllowedToolsRepr::Raw(raw.to_string())
+    }
+}
+
+impl From<Vec<String>> for AllowedToolsRepr {
+    fn from(entries: Vec<String>) -> Self {
+        AllowedToolsRepr::List(entries)
+    }
+}
+
+/// Prefix marking a top-level frontmatter field as a client extension.
+pub const EXTENSION_PREFIX: &str = "x-";
+
+/// Check if a top-level field name is a client extension (`x-` followed by
+/// at least one character).
+pub fn is_extension_field(field: &str) -> bool {
+    field.len() > EXTENSION_PREFIX.len() && field.starts_with(EXTENSION_PREFIX)
+}
+
+/// Collect the `x-` prefixed keys left over by the flattened fields.
+fn deserialize_extensions<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<HashMap<String, serde_json::Value>>, D::Error> {
+    let rest: HashMap<String, serde_json::Value> = HashMap::deserialize(deserializer)?;
+    let extensions: HashMap<String, serde_json::Value> = rest
+        .into_iter()
+        .filter(|(key, _)| is_extension_field(key))
+        .collect();
+    Ok((!extensions.is_empty()).then_some(extensions))
 }
 
 impl SkillProperties {
@@ -48,9 +167,113 @@ impl SkillProperties {
             compatibility: None,
             allowed_tools: None,
             metadata: None,
+            extensions: None,
+        }
+    }
+
+    /// Start building properties with the required fields.
+    ///
+    /// [`SkillPropertiesBuilder::build`] validates the result.
+    pub fn builder(
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) -> SkillPropertiesBuilder {
+        SkillPropertiesBuilder {
+            props: Self::new(name, description),
+        }
+    }
+
+    /// Parse and validate properties from JSON in the format produced by
+    /// `read-properties`.
+    ///
+    /// # Errors
+    ///
+    /// * `ParseError` - If the JSON is malformed or lacks required fields
+    /// * `ValidationError` - If the properties break a validation rule
+    pub fn from_json(json: &str) -> Result<Self> {
+        let props: Self = serde_json::from_str(json)
+            .map_err(|e| SkillError::parse(format!("Invalid skill properties JSON: {}", e)))?;
+        props.validated()
+    }
+
+    /// Return `self` if it passes [`validate_properties`], or a validation
+    /// error listing every failure.
+    fn validated(self) -> Result<Self> {
+        self.validate_for_write(None)?;
+        Ok(self)
+    }
+
+    /// Check the properties with [`validate_properties`], returning a
+    /// validation error listing every failure.
+    pub(crate) fn validate_for_write(&self, expected_dir_name: Option<&str>) -> Result<()> {
+        let errors: Vec<String> = validate_properties(self, expected_dir_name)
+            .into_iter()
+            .filter(|d| d.is_error())
+            .map(|d| d.message)
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(SkillError::validation_multiple(
+                format!("Invalid skill properties: {}", errors.join("; ")),
+                errors,
+            ))
         }
     }
 
+    /// Look up a `metadata` value by key.
+    pub fn metadata_value(&self, key: &str) -> Option<&str> {
+        self.metadata.as_ref()?.get(key)?.as_str()
+    }
+
+    /// Skill author, from `metadata.author`.
+    pub fn author(&self) -> Option<&str> {
+        self.metadata_value("author")
+    }
+
+    /// Maintainer contact address, from `metadata.maintainer-email`.
+    pub fn maintainer_email(&self) -> Option<&str> {
+        self.metadata_value("maintainer-email")
+    }
+
+    /// Skill version, from `metadata.version`.
+    ///
+    /// The spec has no top-level `version` field, so the metadata
+    /// convention is the only source.
+    pub fn version(&self) -> Option<&str> {
+        self.metadata_value("version")
+    }
+
+    /// Skill homepage URL, from `metadata.homepage`.
+    pub fn homepage(&self) -> Option<&str> {
+        self.metadata_value("homepage")
+    }
+
+    /// Stable machine identifier, from `metadata.id`: a UUID or a
+    /// reverse-DNS string such as `com.example.pdf-tools`.
+    ///
+    /// Unlike the name, the identifier survives renames, so catalogs and
+    /// prompts can refer to a skill by it.
+    pub fn stable_id(&self) -> Option<&str> {
+        self.metadata_value("id")
+    }
+
+    /// Whether the skill is retired, from `metadata.deprecated`.
+    ///
+    /// Both the string `"true"` and a YAML boolean `true` count.
+    pub fn is_deprecated(&self) -> bool {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.get("deprecated"))
+            .is_some_and(|v| v.as_str() == Some("true") || v.as_bool() == Some(true))
+    }
+
+    /// What to use instead of a deprecated skill, from
+    /// `metadata.deprecated-message`.
+    pub fn deprecation_message(&self) -> Option<&str> {
+        self.metadata_value("deprecated-message")
+    }
+
     /// Convert to a dictionary (HashMap), excluding None values.
     ///
     /// This matches the Python `to_dict()` method behavior.
@@ -83,22 +306,76 @@ impl SkillProperties {
         if let Some(ref allowed_tools) = self.allowed_tools {
             result.insert(
                 "allowed-tools".to_string(),
-                serde_json::Value::String(allowed_tools.clone()),
+                serde_json::Value::String(allowed_tools.raw().into_owned()),
+            );
+            result.insert(
+                "allowed-tools-patterns".to_string(),
+                allowed_tools.patterns().into(),
             );
         }
 
         if let Some(ref metadata) = self.metadata {
             let meta_map: serde_json::Map<String, serde_json::Value> = metadata
                 .iter()
-                .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+                .map(|(k, v)| (k.clone(), v.clone()))
                 .collect();
             result.insert("metadata".to_string(), serde_json::Value::Object(meta_map));
         }
 
+        if let Some(ref extensions) = self.extensions {
+            for (key, value) in extensions {
+                result.insert(key.clone(), value.clone());
+            }
+        }
+
         result
     }
 }
 
+/// Builder for [`SkillProperties`], created by [`SkillProperties::builder`].
+#[derive(Debug, Clone)]
+pub struct SkillPropertiesBuilder {
+    props: SkillProperties,
+}
+
+impl SkillPropertiesBuilder {
+    /// Set the license.
+    pub fn license(mut self, license: impl Into<String>) -> Self {
+        self.props.license = Some(license.into());
+        self
+    }
+
+    /// Set the compatibility note.
+    pub fn compatibility(mut self, compatibility: impl Into<String>) -> Self {
+        self.props.compatibility = Some(compatibility.into());
+        self
+    }
+
+    /// Set the allowed tools, as a string or a list of entries.
+    pub fn allowed_tools(mut self, allowed_tools: impl Into<AllowedToolsRepr>) -> Self {
+        self.props.allowed_tools = Some(allowed_tools.into());
+        self
+    }
+
+    /// Add a `metadata` entry.
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+        self.props
+            .metadata
+            .get_or_insert_with(HashMap::new)
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Validate and return the properties.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ValidationError` listing every rule the properties break.
+    pub fn build(self) -> Result<SkillProperties> {
+        self.props.validated()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,18 +404,149 @@ mod tests {
         let mut props = SkillProperties::new("my-skill", "A test skill");
         props.license = Some("MIT".to_string());
         props.compatibility = Some("Python 3.11+".to_string());
-        props.allowed_tools = Some("Bash(git:*)".to_string());
+        props.allowed_tools = Some("Bash(git:*)".into());
 
         let mut metadata = HashMap::new();
-        metadata.insert("author".to_string(), "Test".to_string());
+        metadata.insert("author".to_string(), "Test".into());
         props.metadata = Some(metadata);
 
         let dict = props.to_dict();
 
-        assert_eq!(dict.len(), 6);
+        assert_eq!(dict.len(), 7);
         assert_eq!(dict.get("license").unwrap(), "MIT");
         assert_eq!(dict.get("compatibility").unwrap(), "Python 3.11+");
         assert_eq!(dict.get("allowed-tools").unwrap(), "Bash(git:*)");
+        assert_eq!(
+            dict.get("allowed-tools-patterns").unwrap(),
+            &serde_json::json!(["Bash(git:*)"])
+        );
+    }
+
+    #[test]
+    fn test_allowed_tools_forms() {
+        let raw = AllowedToolsRepr::from("Bash(git log:*), Read");
+        assert_eq!(raw.raw(), "Bash(git log:*), Read");
+        assert_eq!(raw.patterns(), ["Bash(git log:*)", "Read"]);
+        assert!(!raw.is_list());
+
+        let list = AllowedToolsRepr::from(vec!["Bash(git log:*)".to_string(), "Read".to_string()]);
+        assert_eq!(list.raw(), "Bash(git log:*) Read");
+        assert_eq!(list.patterns(), raw.patterns());
+        assert!(list.is_list());
+
+        let mut props = SkillProperties::new("my-skill", "A test skill");
+        props.allowed_tools = Some(list);
+        let json = serde_json::to_string(&props).unwrap();
+        assert!(json.contains(r#""allowed-tools":["Bash(git log:*)","Read"]"#));
+        let back: SkillProperties = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, props);
+    }
+
+    #[test]
+    fn test_convention_accessors() {
+        let mut props = SkillProperties::new("my-skill", "A test skill");
+        assert!(props.author().is_none());
+
+        let mut metadata = HashMap::new();
+        metadata.insert("author".to_string(), "Jane".into());
+        metadata.insert("version".to_string(), "1.2.0".into());
+        metadata.insert("homepage".to_string(), "https://example.com/skill".into());
+        metadata.insert("maintainer-email".to_string(), "jane@example.com".into());
+        metadata.insert("deprecated".to_string(), "true".into());
+        metadata.insert("deprecated-message".to_string(), "Use pdf-v2".into());
+        metadata.insert("id".to_string(), "com.example.pdf".into());
+        props.metadata = Some(metadata);
+
+        assert_eq!(props.author(), Some("Jane"));
+        assert_eq!(props.version(), Some("1.2.0"));
+        assert_eq!(props.homepage(), Some("https://example.com/skill"));
+        assert_eq!(props.maintainer_email(), Some("jane@example.com"));
+        assert!(props.is_deprecated());
+        assert_eq!(props.deprecation_message(), Some("Use pdf-v2"));
+        assert_eq!(props.stable_id(), Some("com.example.pdf"));
+        for key in CONVENTIONAL_METADATA_KEYS {
+            assert!(props.metadata_value(key).is_some(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_is_deprecated() {
+        let props = SkillProperties::new("my-skill", "A test skill");
+        assert!(!props.is_deprecated());
+
+        for (value, expected) in [
+            (serde_json::json!("true"), true),
+            (serde_json::json!(true), true),
+            (serde_json::json!("false"), false),
+            (serde_json::json!(false), false),
+            (serde_json::json!("yes"), false),
+        ] {
+            let props = SkillProperties::builder("my-skill", "A test skill")
+                .metadata("deprecated", value.clone())
+                .build()
+                .unwrap();
+            assert_eq!(props.is_deprecated(), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_builder() {
+        let props = SkillProperties::builder("my-skill", "A test skill")
+            .license("MIT")
+            .metadata("author", "Jane")
+            .build()
+            .unwrap();
+        assert_eq!(props.license.as_deref(), Some("MIT"));
+        assert_eq!(props.author(), Some("Jane"));
+
+        let err = SkillProperties::builder("My_Skill", "A test skill")
+            .build()
+            .unwrap_err();
+        let errors = err.errors().unwrap();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[1].contains("lowercase"));
+    }
+
+    #[test]
+    fn test_from_json() {
+        let props =
+            SkillProperties::from_json(r#"{"name": "my-skill", "description": "A test skill"}"#)
+                .unwrap();
+        assert_eq!(props, SkillProperties::new("my-skill", "A test skill"));
+
+        let err = SkillProperties::from_json(r#"{"name": "my-skill"}"#).unwrap_err();
+        assert!(matches!(err, SkillError::Parse(_)));
+
+        let err = SkillProperties::from_json(
+            r#"{"name": "my-skill", "description": "d", "compatibility": "__"}"#
+                .replace("__", &"c".repeat(600))
+                .as_str(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Compatibility exceeds"));
+    }
+
+    #[test]
+    fn test_extensions_use_original_keys() {
+        let mut props = SkillProperties::new("my-skill", "A test skill");
+        let mut extensions = HashMap::new();
+        extensions.insert("x-acme-team".to_string(), serde_json::json!("search"));
+        props.extensions = Some(extensions);
+
+        assert_eq!(props.to_dict().get("x-acme-team").unwrap(), "search");
+
+        let json = serde_json::to_string(&props).unwrap();
+        assert!(json.contains(r#""x-acme-team":"search""#));
+        let back: SkillProperties = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, props);
+    }
+
+    #[test]
+    fn test_deserialize_without_extensions() {
+        let props: SkillProperties =
+            serde_json::from_str(r#"{"name": "my-skill", "description": "d", "other": 1}"#)
+                .unwrap();
+        assert!(props.extensions.is_none());
     }
 
     #[test]
@@ -0,0 +1,123 @@
+//! Stable, locale-independent ordering for user-visible lists.
+//!
+//! Strings are compared by their NFKC normalization with each character
+//! lowercased, so `Ｐdf`, `pdf`, and `PDF` sort together. Ties are broken by
+//! the codepoint order of the original strings, so two different strings
+//! never compare equal and the result is a total order. Nothing depends on
+//! the process locale or platform, so the same names sort the same way on
+//! every machine.
+//!
+//! Canonical serialization (emitted frontmatter keys) and digests keep plain
+//! codepoint order instead, so their bytes do not change.
+
+use std::cmp::Ordering;
+use std::path::Path;
+
+use unicode_normalization::UnicodeNormalization;
+
+/// The key a string sorts by before tie-breaking: NFKC, then lowercased.
+pub fn sort_key(s: &str) -> String {
+    s.nfkc().flat_map(char::to_lowercase).collect()
+}
+
+/// Compare two strings by [`sort_key`], then by codepoint order.
+pub fn compare(a: &str, b: &str) -> Ordering {
+    sort_key(a).cmp(&sort_key(b)).then_with(|| a.cmp(b))
+}
+
+/// Compare two paths component by component with [`compare`].
+///
+/// Non-UTF-8 components are compared lossily, then by their raw bytes.
+pub fn compare_paths(a: &Path, b: &Path) -> Ordering {
+    let mut left = a.components();
+    let mut right = b.components();
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ord = compare(
+                    &x.as_os_str().to_string_lossy(),
+                    &y.as_os_str().to_string_lossy(),
+                );
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+        }
+    }
+}
+
+/// Sort strings in place with [`compare`].
+pub fn sort_strings<S: AsRef<str>>(items: &mut [S]) {
+    items.sort_by(|a, b| compare(a.as_ref(), b.as_ref()));
+}
+
+/// Sort paths in place with [`compare_paths`].
+pub fn sort_paths<P: AsRef<Path>>(items: &mut [P]) {
+    items.sort_by(|a, b| compare_paths(a.as_ref(), b.as_ref()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const MIXED: [&str; 8] = [
+        "Zeta",
+        "данные",
+        "alpha",
+        "表格",
+        "Данные-2",
+        "ｐｄｆ",
+        "Beta",
+        "pdf",
+    ];
+
+    const EXPECTED: [&str; 8] = [
+        "alpha",
+        "Beta",
+        "pdf",
+        "ｐｄｆ",
+        "Zeta",
+        "данные",
+        "Данные-2",
+        "表格",
+    ];
+
+    #[test]
+    fn test_mixed_scripts_sort_the_same_from_any_starting_order() {
+        let mut forward = MIXED.to_vec();
+        let mut reversed: Vec<&str> = MIXED.iter().rev().copied().collect();
+        sort_strings(&mut forward);
+        sort_strings(&mut reversed);
+        assert_eq!(forward, EXPECTED);
+        assert_eq!(reversed, EXPECTED);
+    }
+
+    #[test]
+    fn test_case_and_width_variants_sort_together_then_by_codepoint() {
+        let mut names = vec!["PDF", "zip", "ｐｄｆ", "pdf"];
+        sort_strings(&mut names);
+        assert_eq!(names, ["PDF", "pdf", "ｐｄｆ", "zip"]);
+        assert_eq!(compare("pdf", "pdf"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_paths_sort_by_component() {
+        let mut paths: Vec<PathBuf> =
+            ["skills/Zeta", "skills/表格", "skills/alpha/b", "skills-x/a"]
+                .iter()
+                .map(PathBuf::from)
+                .collect();
+        sort_paths(&mut paths);
+        assert_eq!(
+            paths,
+            ["skills/alpha/b", "skills/Zeta", "skills/表格", "skills-x/a"]
+                .iter()
+                .map(PathBuf::from)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Prompt-only overrides kept next to a skill catalog.
+//!
+//! A `prompt-overrides.yaml` file excludes skills from generated prompts
+//! and replaces descriptions with phrasing tuned for the model, without
+//! editing the skills' SKILL.md files:
+//!
+//! ```yaml
+//! exclude:
+//!   - legacy-pdf
+//! override_description:
+//!   pdf-tools: Fill and extract PDF forms.
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::{Result, SkillError};
+use crate::validator::{MAX_DESCRIPTION_LENGTH, normalize_field};
+
+/// Skills to leave out of prompts and descriptions to use instead of the
+/// skills' own, keyed by skill name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PromptOverrides {
+    /// Names of skills to leave out of prompts.
+    pub exclude: Vec<String>,
+
+    /// Description to show in prompts, by skill name.
+    pub override_description: BTreeMap<String, String>,
+}
+
+impl PromptOverrides {
+    /// Read overrides from a YAML file.
+    ///
+    /// # Errors
+    ///
+    /// * `IoError` - If the file cannot be read
+    /// * See [`PromptOverrides::from_yaml`]
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_yaml(&text)
+    }
+
+    /// Parse overrides from YAML text.
+    ///
+    /// # Errors
+    ///
+    /// * `ParseError` - If the text is not valid YAML or has keys other
+    ///   than `exclude` and `override_description`
+    /// * `ValidationError` - If an override description is empty or longer
+    ///   than [`MAX_DESCRIPTION_LENGTH`], which a SKILL.md description may
+    ///   not be either
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        let overrides: Self = if text.trim().is_empty() {
+            Self::default()
+        } else {
+            serde_yaml::from_str(text)
+                .map_err(|e| SkillError::parse(format!("Invalid prompt overrides: {}", e)))?
+        };
+
+        let errors: Vec<String> = overrides
+            .override_description
+            .iter()
+            .filter_map(|(name, description)| {
+                let description = normalize_field(description);
+                if description.is_empty() {
+                    Some(format!("Override description for '{}' is empty", name))
+                } else if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+                    Some(format!(
+                        "Override description for '{}' exceeds {} character limit ({} chars)",
+                        name,
+                        MAX_DESCRIPTION_LENGTH,
+                        description.chars().count()
+                    ))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        if !errors.is_empty() {
+            return Err(SkillError::validation_multiple(
+                format!("Invalid prompt overrides: {}", errors.join("; ")),
+                errors,
+            ));
+        }
+        Ok(overrides)
+    }
+
+    /// Check if the skill named `name` is excluded.
+    pub fn excludes(&self, name: &str) -> bool {
+        self.exclude.iter().any(|excluded| excluded == name)
+    }
+
+    /// The prompt description for the skill named `name`, if overridden.
+    pub fn description(&self, name: &str) -> Option<&str> {
+        self.override_description
+            .get(name)
+            .map(|description| normalize_field(description))
+    }
+
+    /// Names in the overrides that match none of `known`, in file order
+    /// (exclusions first), so stale entries can be reported.
+    pub fn unknown_names<'a>(&self, known: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let known: Vec<&str> = known.into_iter().collect();
+        self.exclude
+            .iter()
+            .chain(self.override_description.keys())
+            .filter(|name| !known.contains(&name.as_str()))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_yaml() {
+        let overrides = PromptOverrides::from_yaml(
+            "exclude: [legacy-pdf]\noverride_description:\n  pdf-tools: '  Fill PDF forms. '\n",
+        )
+        .unwrap();
+        assert!(overrides.excludes("legacy-pdf"));
+        assert!(!overrides.excludes("pdf-tools"));
+        assert_eq!(overrides.description("pdf-tools"), Some("Fill PDF forms."));
+        assert_eq!(
+            overrides.unknown_names(["pdf-tools", "csv"]),
+            ["legacy-pdf"]
+        );
+        assert_eq!(
+            PromptOverrides::from_yaml("").unwrap(),
+            PromptOverrides::default()
+        );
+    }
+
+    #[test]
+    fn test_from_yaml_rejects_bad_files() {
+        assert!(PromptOverrides::from_yaml("excludes: [a]\n").is_err());
+
+        let err = PromptOverrides::from_yaml(&format!(
+            "override_description:\n  a: ''\n  b: {}\n",
+            "x".repeat(MAX_DESCRIPTION_LENGTH + 1)
+        ))
+        .unwrap_err();
+        assert_eq!(
+            err.errors().unwrap(),
+            [
+                "Override description for 'a' is empty",
+                "Override description for 'b' exceeds 1024 character limit (1025 chars)"
+            ]
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Bounded-concurrency mapping for IO-bound work over many skills.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Upper bound on the default number of worker threads.
+pub const MAX_DEFAULT_CONCURRENCY: usize = 16;
+
+/// Default number of skills loaded at once: the number of CPUs, capped at
+/// [`MAX_DEFAULT_CONCURRENCY`].
+pub fn default_concurrency() -> usize {
+    std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(MAX_DEFAULT_CONCURRENCY)
+}
+
+/// Apply `f` to every item on at most `limit` threads, returning the
+/// results in input order.
+///
+/// Every item is processed even if `f` returns an error for some of them.
+/// `on_done` is called once per item, from the worker thread, with the
+/// number of items finished so far.
+pub(crate) fn map_bounded<I, T, F>(
+    items: &[I],
+    limit: usize,
+    f: F,
+    on_done: impl Fn(usize) + Sync,
+) -> Vec<T>
+where
+    I: Sync,
+    T: Send,
+    F: Fn(&I) -> T + Sync,
+{
+    let workers = limit.max(1).min(items.len());
+    if workers <= 1 {
+        return items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let result = f(item);
+                on_done(i + 1);
+                result
+            })
+            .collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let finished = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<T>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(i) else {
+                        break;
+                    };
+                    let result = f(item);
+                    results.lock().unwrap()[i] = Some(result);
+                    on_done(finished.fetch_add(1, Ordering::Relaxed) + 1);
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.expect("every item is processed"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_bounded_preserves_order() {
+        let items: Vec<usize> = (0..100).collect();
+        let calls = AtomicUsize::new(0);
+        let results = map_bounded(
+            &items,
+            8,
+            |i| {
+                // Finish out of order.
+                std::thread::sleep(std::time::Duration::from_micros(
+                    ((100 - i) % 7) as u64 * 50,
+                ));
+                i * 2
+            },
+            |_| {
+                calls.fetch_add(1, Ordering::Relaxed);
+            },
+        );
+        assert_eq!(results, (0..100).map(|i| i * 2).collect::<Vec<_>>());
+        assert_eq!(calls.into_inner(), 100);
+    }
+
+    #[test]
+    fn test_map_bounded_empty_and_serial() {
+        let empty: Vec<u8> = Vec::new();
+        assert!(map_bounded(&empty, 4, |x| *x, |_| {}).is_empty());
+        assert_eq!(map_bounded(&[1, 2, 3], 0, |x| x + 1, |_| {}), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_default_concurrency_is_bounded() {
+        let n = default_concurrency();
+        assert!((1..=MAX_DEFAULT_CONCURRENCY).contains(&n));
+    }
+}
@@ -1,14 +1,24 @@
 //! YAML frontmatter parsing for SKILL.md files.
 
 use std::collections::HashMap;
-use std::path::Path;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
 
 use crate::error::{Result, SkillError};
-use crate::models::SkillProperties;
+use crate::models::{AllowedToolsRepr, SkillProperties, is_extension_field};
+use crate::source::{FsSource, SkillSource, locate_skill, skill_md_name};
+use crate::validator::{
+    ALLOWED_FIELDS, DEFAULT_MESSAGE_VALUE_CHARS, field_type_error, missing_fields_message,
+    normalize_field, validate_metadata_structure,
+};
 
 /// Find the SKILL.md file in a skill directory.
 ///
-/// Prefers SKILL.md (uppercase) but accepts skill.md (lowercase).
+/// Prefers SKILL.md (uppercase), then skill.md (lowercase), then any other
+/// casing such as `Skill.MD`, which validation warns about. File names are
+/// matched by reading the directory rather than probing paths, so the
+/// result is the same on case-sensitive and case-insensitive file systems
+/// and always carries the name as it is on disk.
 ///
 /// # Arguments
 ///
@@ -17,18 +27,398 @@ use crate::models::SkillProperties;
 /// # Returns
 ///
 /// Path to the SKILL.md file, or None if not found.
-pub fn find_skill_md(skill_dir: &Path) -> Option<std::path::PathBuf> {
-    for name in ["SKILL.md", "skill.md"] {
-        let path = skill_dir.join(name);
-        if path.exists() {
-            return Some(path);
+pub fn find_skill_md(skill_dir: &Path) -> Option<PathBuf> {
+    let entries = FsSource.read_dir(skill_dir).ok()?;
+    skill_md_name(&entries).map(|name| skill_dir.join(name))
+}
+
+/// Check if a SKILL.md file name is one of the two spellings the spec
+/// allows, `SKILL.md` and `skill.md`.
+pub(crate) fn is_canonical_skill_md_name(name: &str) -> bool {
+    name == "SKILL.md" || name == "skill.md"
+}
+
+/// Resolve a user-supplied skill path to its skill directory.
+///
+/// Accepts either a skill directory or a path pointing directly at its
+/// SKILL.md file.
+///
+/// # Arguments
+///
+/// * `path` - Path to a skill directory or SKILL.md file
+///
+/// # Returns
+///
+/// Path to the skill directory.
+///
+/// # Errors
+///
+/// * `NotFound` - If the path does not exist (with a suggestion when a
+///   sibling directory differs only in case or hyphens), or the directory
+///   contains no SKILL.md
+/// * `InvalidPath` - If the path is a file that is not a SKILL.md
+pub fn resolve_skill_dir(path: &Path) -> Result<PathBuf> {
+    locate_skill(&FsSource, path).map(|(dir, _)| dir)
+}
+
+/// Language of a SKILL.md frontmatter block, chosen by its delimiter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FrontmatterSyntax {
+    /// YAML between `---` lines, as the spec defines.
+    #[default]
+    Yaml,
+
+    /// TOML between `+++` lines. An extension to the spec; parsing and
+    /// writing it needs the `toml` feature.
+    Toml,
+}
+
+impl FrontmatterSyntax {
+    /// The delimiter line that opens and closes the frontmatter.
+    pub fn delimiter(self) -> &'static str {
+        match self {
+            FrontmatterSyntax::Yaml => "---",
+            FrontmatterSyntax::Toml => "+++",
         }
     }
+
+    /// The syntax whose delimiter `line` is, if any.
+    pub(crate) fn of_delimiter(line: &str) -> Option<Self> {
+        [FrontmatterSyntax::Yaml, FrontmatterSyntax::Toml]
+            .into_iter()
+            .find(|syntax| is_delimiter(line, *syntax))
+    }
+}
+
+/// Check if a line is a delimiter for frontmatter in `syntax`.
+///
+/// A delimiter is exactly three dashes (or plus signs for TOML), optionally
+/// followed by spaces or tabs. Lines such as `----` or `--- junk` are not
+/// delimiters.
+fn is_delimiter(line: &str, syntax: FrontmatterSyntax) -> bool {
+    let line = line.trim_end_matches(['\n', '\r']);
+    line.strip_prefix(syntax.delimiter())
+        .is_some_and(|rest| rest.chars().all(|c| c == ' ' || c == '\t'))
+}
+
+/// Check if a line is a YAML frontmatter delimiter.
+pub(crate) fn is_frontmatter_delimiter(line: &str) -> bool {
+    is_delimiter(line, FrontmatterSyntax::Yaml)
+}
+
+/// Split SKILL.md content into its raw frontmatter and body.
+///
+/// The frontmatter is everything between the opening delimiter on the first
+/// line and the next delimiter line of the same syntax; the body is
+/// everything after it. TOML frontmatter left unclosed but followed by a
+/// `---` line is reported as closed with the wrong delimiter.
+pub(crate) fn split_frontmatter(content: &str) -> Result<(&str, &str)> {
+    let mut lines = content.split_inclusive('\n');
+
+    let opening = lines.next().unwrap_or("");
+    let Some(syntax) = FrontmatterSyntax::of_delimiter(opening) else {
+        return Err(SkillError::parse(
+            "SKILL.md must start with YAML frontmatter (---)",
+        ));
+    };
+
+    let frontmatter_start = opening.len();
+    let mut offset = frontmatter_start;
+    let mut yaml_closing = None;
+    for (i, line) in lines.enumerate() {
+        if is_delimiter(line, syntax) {
+            return Ok((
+                &content[frontmatter_start..offset],
+                &content[offset + line.len()..],
+            ));
+        }
+        if syntax == FrontmatterSyntax::Toml
+            && yaml_closing.is_none()
+            && is_frontmatter_delimiter(line)
+        {
+            yaml_closing = Some(i + 2);
+        }
+        offset += line.len();
+    }
+
+    if let Some(line) = yaml_closing {
+        return Err(SkillError::parse(format!(
+            "SKILL.md frontmatter opened with +++ must be closed with +++, not --- (line {})",
+            line
+        )));
+    }
+    Err(SkillError::parse(format!(
+        "SKILL.md frontmatter not properly closed with {}",
+        syntax.delimiter()
+    )))
+}
+
+/// Check if a line is blank or a YAML comment.
+pub(crate) fn is_blank_or_comment(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+/// Check if a line starts a top-level frontmatter field, e.g. `description:`.
+fn starts_known_field(line: &str) -> bool {
+    ALLOWED_FIELDS.iter().any(|field| {
+        line.strip_prefix(field)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t', '\r', '\n']))
+    })
+}
+
+/// Find the 1-based line number of a marker that starts a second YAML
+/// document in the frontmatter.
+///
+/// Three shapes are detected:
+///
+/// * a `...` document-end marker followed by more YAML before the closing
+///   delimiter;
+/// * a `---` document-start marker with content on the same line (e.g.
+///   `--- !tag`), which is not a delimiter and so stays inside the
+///   frontmatter;
+/// * a closing delimiter followed by a block that starts with a frontmatter
+///   field, holds nothing but fields and their indented values, and is
+///   closed by another delimiter line, i.e. the author split one
+///   frontmatter block in two. The closing delimiter is reported in this
+///   case. A body that merely starts with `description:` and later has a
+///   `---` rule is prose, not a second document.
+fn find_second_document(content: &str) -> Option<usize> {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+
+    for (i, line) in lines.iter().enumerate().skip(1) {
+        if is_frontmatter_delimiter(line) {
+            let rest = &lines[i + 1..];
+            let end = rest.iter().position(|l| is_frontmatter_delimiter(l))?;
+            let mut block = rest[..end].iter().filter(|l| !is_blank_or_comment(l));
+            let starts_with_field = block.next().is_some_and(|l| starts_known_field(l));
+            let only_fields = block.all(|l| starts_known_field(l) || l.starts_with([' ', '\t']));
+            return (starts_with_field && only_fields).then_some(i + 1);
+        }
+
+        let text = line.trim_end_matches(['\n', '\r']);
+        if text.trim_end() == "..." {
+            let more_yaml = lines[i + 1..]
+                .iter()
+                .take_while(|l| !is_frontmatter_delimiter(l))
+                .any(|l| !is_blank_or_comment(l));
+            if more_yaml {
+                return Some(i + 1);
+            }
+        } else if text.starts_with("--- ") || text.starts_with("---\t") {
+            return Some(i + 1);
+        }
+    }
+
     None
 }
 
+/// Where [`read_frontmatter_only`] is in the file.
+enum HeadState {
+    Opening,
+    Frontmatter(FrontmatterSyntax),
+    AfterClosing,
+}
+
+/// Read just enough of a SKILL.md file for [`parse_frontmatter`] to return
+/// the same metadata, or the same error, as it would for the whole file.
+///
+/// That is the frontmatter, its closing delimiter, and the first non-blank
+/// line after it. If that line starts a frontmatter field, the whole file
+/// is read, since [`find_second_document`] then needs to look for a later
+/// delimiter. An unclosed frontmatter is read to EOF, so the error still
+/// says it is not properly closed.
+///
+/// The rest of the file is checked for valid UTF-8 in fixed-size chunks
+/// without being kept. On any encoding problem the whole file is read with
+/// [`read_skill_md_file`], so the error matches a full read. The body
+/// returned by [`parse_frontmatter`] for this content is truncated and
+/// must not be used.
+pub(crate) fn read_frontmatter_only(path: &Path) -> Result<String> {
+    let file = std::fs::File::open(path)?;
+    if file.metadata()?.len() > MAX_SKILL_MD_BYTES {
+        return Err(too_large());
+    }
+    let mut reader = BufReader::new(file);
+    if sniff_encoding(reader.fill_buf()?).is_some() {
+        return read_skill_md_file(path);
+    }
+
+    let mut head = Vec::new();
+    let mut line = Vec::new();
+    let mut state = HeadState::Opening;
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        head.extend_from_slice(&line);
+        let text = String::from_utf8_lossy(&line);
+
+        match state {
+            HeadState::Opening => match FrontmatterSyntax::of_delimiter(&text) {
+                Some(syntax) => state = HeadState::Frontmatter(syntax),
+                None => break,
+            },
+            HeadState::Frontmatter(syntax) => {
+                if is_delimiter(&text, syntax) {
+                    state = HeadState::AfterClosing;
+                }
+            }
+            HeadState::AfterClosing if text.trim().is_empty() => {}
+            HeadState::AfterClosing => {
+                if starts_known_field(&text) {
+                    reader.read_to_end(&mut head)?;
+                }
+                break;
+            }
+        }
+    }
+
+    if !is_utf8(reader)? {
+        return read_skill_md_file(path);
+    }
+    match String::from_utf8(head) {
+        Ok(head) => Ok(head),
+        Err(_) => read_skill_md_file(path),
+    }
+}
+
+/// Check that the rest of `reader` is valid UTF-8 without keeping it.
+fn is_utf8(mut reader: impl Read) -> std::io::Result<bool> {
+    let mut buf = [0u8; 8192];
+    // Bytes of a character split across two reads.
+    let mut carry = 0;
+
+    loop {
+        let n = reader.read(&mut buf[carry..])?;
+        if n == 0 {
+            return Ok(carry == 0);
+        }
+        let filled = carry + n;
+        match std::str::from_utf8(&buf[..filled]) {
+            Ok(_) => carry = 0,
+            Err(e) if e.error_len().is_none() => {
+                let valid = e.valid_up_to();
+                buf.copy_within(valid..filled, 0);
+                carry = filled - valid;
+            }
+            Err(_) => return Ok(false),
+        }
+    }
+}
+
+/// Largest SKILL.md that is read, in bytes, whether from a file or from
+/// memory.
+pub const MAX_SKILL_MD_BYTES: u64 = 10 * 1024 * 1024;
+
+/// The error for SKILL.md content over [`MAX_SKILL_MD_BYTES`].
+fn too_large() -> SkillError {
+    SkillError::parse(format!(
+        "SKILL.md is larger than {} bytes",
+        MAX_SKILL_MD_BYTES
+    ))
+}
+
+/// Number of leading bytes [`sniff_encoding`] looks at.
+const SNIFF_LEN: usize = 64;
+
+/// Characters of the line before an invalid byte quoted in UTF-8 errors.
+const UTF8_CONTEXT_CHARS: usize = 30;
+
+/// Recognize SKILL.md content saved as UTF-16 or UTF-32 from its first
+/// bytes: a byte order mark, or NUL bytes in at least a quarter of them.
+fn sniff_encoding(bytes: &[u8]) -> Option<&'static str> {
+    let prefix = &bytes[..bytes.len().min(SNIFF_LEN)];
+    if prefix.starts_with(&[0xFF, 0xFE, 0, 0]) || prefix.starts_with(&[0, 0, 0xFE, 0xFF]) {
+        return Some("UTF-32");
+    }
+    if prefix.starts_with(&[0xFF, 0xFE]) || prefix.starts_with(&[0xFE, 0xFF]) {
+        return Some("UTF-16");
+    }
+    let nuls = prefix.iter().filter(|&&b| b == 0).count();
+    (prefix.len() >= 4 && nuls * 4 >= prefix.len()).then_some("UTF-16")
+}
+
+/// Read a SKILL.md file, explaining encoding problems.
+pub(crate) fn read_skill_md_file(path: &Path) -> Result<String> {
+    if std::fs::metadata(path)?.len() > MAX_SKILL_MD_BYTES {
+        return Err(too_large());
+    }
+    decode_skill_md(std::fs::read(path)?)
+}
+
+/// Decode SKILL.md bytes as UTF-8.
+///
+/// # Errors
+///
+/// Returns `ParseError` if there are more than [`MAX_SKILL_MD_BYTES`],
+/// naming the encoding for UTF-16 and UTF-32 content, or giving the offset,
+/// line, and preceding text of the first invalid byte.
+pub(crate) fn decode_skill_md(bytes: Vec<u8>) -> Result<String> {
+    if bytes.len() as u64 > MAX_SKILL_MD_BYTES {
+        return Err(too_large());
+    }
+    if let Some(encoding) = sniff_encoding(&bytes) {
+        return Err(SkillError::parse(format!(
+            "SKILL.md appears to be {} encoded; files must be UTF-8",
+            encoding
+        )));
+    }
+
+    String::from_utf8(bytes).map_err(|e| {
+        let error = e.utf8_error();
+        let bytes = e.as_bytes();
+        let offset = error.valid_up_to();
+        let valid = std::str::from_utf8(&bytes[..offset]).expect("valid up to the error");
+        let line_text = valid.rsplit('\n').next().unwrap_or("");
+        let skip = line_text.chars().count().saturating_sub(UTF8_CONTEXT_CHARS);
+        let context: String = line_text.chars().skip(skip).collect();
+        let end = error.error_len().map_or(bytes.len(), |len| offset + len);
+        let invalid: Vec<String> = bytes[offset..end]
+            .iter()
+            .map(|b| format!("0x{:02X}", b))
+            .collect();
+
+        let mut message = format!(
+            "SKILL.md is not valid UTF-8: invalid byte {} at offset {} (line {}, after {:?})",
+            invalid.join(" "),
+            offset,
+            valid.matches('\n').count() + 1,
+            context
+        );
+        if error.error_len() == Some(1) {
+            message.push_str(
+                "; it may be Latin-1 or Windows-1252 encoded, and must be saved as UTF-8",
+            );
+        }
+        SkillError::parse(message)
+    })
+}
+
 /// Parse YAML frontmatter from SKILL.md content.
 ///
+/// The accepted grammar is line-based:
+///
+/// * The first line must be a delimiter: exactly `---`, optionally followed
+///   by spaces or tabs.
+/// * The frontmatter ends at the next delimiter line, which follows the same
+///   rule. Lines of four or more dashes, or `---` followed by other text, are
+///   not delimiters.
+/// * Everything after the closing delimiter line is the body, which may be
+///   empty. Both `\n` and `\r\n` line endings are accepted.
+/// * The frontmatter must be a single YAML document. A `...` end marker
+///   followed by more YAML, a `---` marker with trailing content, or a
+///   second block of frontmatter fields after the closing delimiter is
+///   rejected, naming the line of the second document marker.
+///
+/// With the `toml` feature, frontmatter opened by a `+++` line is read as
+/// TOML up to the next `+++` line instead, and its tables map onto the
+/// same values as YAML mappings. A `---` line closing it is an error.
+///
 /// # Arguments
 ///
 /// * `content` - Raw content of SKILL.md file
@@ -41,21 +431,19 @@ pub fn find_skill_md(skill_dir: &Path) -> Option<std::path::PathBuf> {
 ///
 /// Returns `ParseError` if frontmatter is missing or invalid.
 pub fn parse_frontmatter(content: &str) -> Result<(HashMap<String, serde_yaml::Value>, String)> {
-    if !content.starts_with("---") {
-        return Err(SkillError::parse(
-            "SKILL.md must start with YAML frontmatter (---)",
-        ));
-    }
+    let (frontmatter_str, body) = split_frontmatter(content)?;
+    let body = body.trim().to_string();
 
-    let parts: Vec<&str> = content.splitn(3, "---").collect();
-    if parts.len() < 3 {
-        return Err(SkillError::parse(
-            "SKILL.md frontmatter not properly closed with ---",
-        ));
+    if content.starts_with(FrontmatterSyntax::Toml.delimiter()) {
+        return Ok((parse_toml_frontmatter(frontmatter_str)?, body));
     }
 
-    let frontmatter_str = parts[1];
-    let body = parts[2].trim().to_string();
+    if let Some(line) = find_second_document(content) {
+        return Err(SkillError::parse(format!(
+            "SKILL.md frontmatter must be a single YAML document; a second document starts at line {}",
+            line
+        )));
+    }
 
     let metadata: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(frontmatter_str)
         .map_err(|e| SkillError::parse(format!("Invalid YAML in frontmatter: {}", e)))?;
@@ -63,6 +451,28 @@ pub fn parse_frontmatter(content: &str) -> Result<(HashMap<String, serde_yaml::V
     Ok((metadata, body))
 }
 
+/// Parse TOML frontmatter into the same values YAML frontmatter yields.
+#[cfg(feature = "toml")]
+fn parse_toml_frontmatter(frontmatter: &str) -> Result<HashMap<String, serde_yaml::Value>> {
+    let table: toml::Table = toml::from_str(frontmatter)
+        .map_err(|e| SkillError::parse(format!("Invalid TOML in frontmatter: {}", e)))?;
+    table
+        .into_iter()
+        .map(|(key, value)| {
+            serde_yaml::to_value(value)
+                .map(|value| (key, value))
+                .map_err(|e| SkillError::parse(format!("Invalid TOML in frontmatter: {}", e)))
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "toml"))]
+fn parse_toml_frontmatter(_frontmatter: &str) -> Result<HashMap<String, serde_yaml::Value>> {
+    Err(SkillError::parse(
+        "SKILL.md has TOML frontmatter (+++), which needs the `toml` feature",
+    ))
+}
+
 /// Read skill properties from SKILL.md frontmatter.
 ///
 /// This function parses the frontmatter and returns properties.
@@ -70,7 +480,7 @@ pub fn parse_frontmatter(content: &str) -> Result<(HashMap<String, serde_yaml::V
 ///
 /// # Arguments
 ///
-/// * `skill_dir` - Path to the skill directory
+/// * `skill_dir` - Path to the skill directory (or its SKILL.md file)
 ///
 /// # Returns
 ///
@@ -78,64 +488,111 @@ pub fn parse_frontmatter(content: &str) -> Result<(HashMap<String, serde_yaml::V
 ///
 /// # Errors
 ///
-/// * `ParseError` - If SKILL.md is missing or has invalid YAML
+/// * `NotFound` / `InvalidPath` - If the path cannot be resolved to a skill
+///   directory (see [`resolve_skill_dir`])
+/// * `ParseError` - If SKILL.md has invalid YAML
 /// * `ValidationError` - If required fields (name, description) are missing
 pub fn read_properties(skill_dir: &Path) -> Result<SkillProperties> {
-    let skill_md = find_skill_md(skill_dir).ok_or_else(|| {
-        SkillError::parse(format!("SKILL.md not found in {}", skill_dir.display()))
-    })?;
+    let (_, skill_md) = locate_skill(&FsSource, skill_dir)?;
+    let content = read_frontmatter_only(&skill_md)?;
+    let (metadata, _) = parse_frontmatter(&content)?;
 
-    let content = std::fs::read_to_string(&skill_md)?;
+    properties_from_metadata(&metadata)
+}
+
+/// Parse the properties of SKILL.md content that is not on disk, such as
+/// content piped to the CLI.
+///
+/// # Errors
+///
+/// Same as [`read_properties`] for a SKILL.md holding `bytes`: a
+/// `ParseError` naming the encoding or first invalid byte if `bytes` are
+/// not UTF-8, or for invalid YAML, and a `ValidationError` if required
+/// fields are missing.
+pub fn properties_from_bytes(bytes: Vec<u8>) -> Result<SkillProperties> {
+    let content = decode_skill_md(bytes)?;
     let (metadata, _) = parse_frontmatter(&content)?;
 
+    properties_from_metadata(&metadata)
+}
+
+/// Build `SkillProperties` from parsed frontmatter.
+///
+/// Checks that the required fields are present and non-empty, but does not
+/// apply the full validation rules.
+pub(crate) fn properties_from_metadata(
+    metadata: &HashMap<String, serde_yaml::Value>,
+) -> Result<SkillProperties> {
     // Check required fields
-    if !metadata.contains_key("name") {
-        return Err(SkillError::validation(
-            "Missing required field in frontmatter: name",
-        ));
-    }
-    if !metadata.contains_key("description") {
-        return Err(SkillError::validation(
-            "Missing required field in frontmatter: description",
-        ));
+    let missing: Vec<&str> = ["name", "description"]
+        .into_iter()
+        .filter(|field| !metadata.contains_key(*field))
+        .collect();
+    if !missing.is_empty() {
+        return Err(SkillError::validation(missing_fields_message(&missing)));
     }
 
     // Extract and validate name
-    let name = extract_string(&metadata, "name")
-        .ok_or_else(|| SkillError::validation("Field 'name' must be a non-empty string"))?;
-    if name.trim().is_empty() {
+    let type_error =
+        |field: &str| SkillError::validation(field_type_error(field, metadata.get(field)).message);
+    let name = extract_string(metadata, "name").ok_or_else(|| type_error("name"))?;
+    let name = normalize_field(&name);
+    if name.is_empty() {
         return Err(SkillError::validation(
             "Field 'name' must be a non-empty string",
         ));
     }
 
     // Extract and validate description
-    let description = extract_string(&metadata, "description")
-        .ok_or_else(|| SkillError::validation("Field 'description' must be a non-empty string"))?;
-    if description.trim().is_empty() {
+    let description =
+        extract_string(metadata, "description").ok_or_else(|| type_error("description"))?;
+    let description = normalize_field(&description);
+    if description.is_empty() {
         return Err(SkillError::validation(
             "Field 'description' must be a non-empty string",
         ));
     }
 
     // Extract optional fields
-    let license = extract_string(&metadata, "license");
-    let compatibility = extract_string(&metadata, "compatibility");
-    let allowed_tools = extract_string(&metadata, "allowed-tools");
+    let license = extract_string(metadata, "license");
+    let compatibility =
+        extract_string(metadata, "compatibility").map(|c| normalize_field(&c).to_string());
+    let allowed_tools = extract_allowed_tools(metadata);
 
     // Extract metadata field
-    let skill_metadata = extract_metadata(&metadata);
+    let skill_metadata = extract_metadata(metadata)?;
+    let extensions = extract_extensions(metadata);
 
     Ok(SkillProperties {
-        name: name.trim().to_string(),
-        description: description.trim().to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
         license,
         compatibility,
         allowed_tools,
         metadata: skill_metadata,
+        extensions,
     })
 }
 
+/// Extract the `x-` prefixed client extension fields.
+///
+/// Values that cannot be represented as JSON (e.g. mappings with non-string
+/// keys) are skipped.
+fn extract_extensions(
+    metadata: &HashMap<String, serde_yaml::Value>,
+) -> Option<HashMap<String, serde_json::Value>> {
+    let extensions: HashMap<String, serde_json::Value> = metadata
+        .iter()
+        .filter(|(key, _)| is_extension_field(key))
+        .filter_map(|(key, value)| Some((key.clone(), serde_json::to_value(value).ok()?)))
+        .collect();
+    if extensions.is_empty() {
+        None
+    } else {
+        Some(extensions)
+    }
+}
+
 /// Extract a string value from a YAML mapping.
 fn extract_string(metadata: &HashMap<String, serde_yaml::Value>, key: &str) -> Option<String> {
     metadata.get(key).and_then(|v| match v {
@@ -144,30 +601,59 @@ fn extract_string(metadata: &HashMap<String, serde_yaml::Value>, key: &str) -> O
     })
 }
 
-/// Extract the metadata field as a HashMap<String, String>.
-fn extract_metadata(
+/// Extract `allowed-tools`, written as a string or a list of strings.
+///
+/// List items that are not strings (which validation reports) are kept as
+/// their YAML text, so rewriting the frontmatter does not drop the list.
+fn extract_allowed_tools(
     metadata: &HashMap<String, serde_yaml::Value>,
-) -> Option<HashMap<String, String>> {
-    metadata.get("metadata").and_then(|v| match v {
-        serde_yaml::Value::Mapping(m) => {
-            let map: HashMap<String, String> = m
+) -> Option<AllowedToolsRepr> {
+    match metadata.get("allowed-tools")? {
+        serde_yaml::Value::String(s) => Some(AllowedToolsRepr::Raw(s.clone())),
+        serde_yaml::Value::Sequence(items) => Some(AllowedToolsRepr::List(
+            items
                 .iter()
-                .filter_map(|(k, v)| {
-                    let key = match k {
-                        serde_yaml::Value::String(s) => s.clone(),
-                        _ => k.as_str()?.to_string(),
-                    };
-                    let value = match v {
-                        serde_yaml::Value::String(s) => s.clone(),
-                        _ => format!("{:?}", v),
-                    };
-                    Some((key, value))
+                .map(|item| match item {
+                    serde_yaml::Value::String(s) => s.clone(),
+                    other => serde_yaml::to_string(other)
+                        .map(|text| text.trim_end().to_string())
+                        .unwrap_or_default(),
                 })
-                .collect();
-            if map.is_empty() { None } else { Some(map) }
-        }
+                .collect(),
+        )),
         _ => None,
-    })
+    }
+}
+
+/// Extract the metadata field, keeping nested values as they are.
+///
+/// # Errors
+///
+/// Returns a validation error if the metadata breaks a structure rule (see
+/// [`validate_metadata_structure`]).
+fn extract_metadata(
+    metadata: &HashMap<String, serde_yaml::Value>,
+) -> Result<Option<HashMap<String, serde_json::Value>>> {
+    let Some(value @ serde_yaml::Value::Mapping(mapping)) = metadata.get("metadata") else {
+        return Ok(None);
+    };
+
+    let errors: Vec<String> = validate_metadata_structure(value, DEFAULT_MESSAGE_VALUE_CHARS)
+        .into_iter()
+        .map(|d| d.message)
+        .collect();
+    if !errors.is_empty() {
+        return Err(SkillError::validation_multiple(
+            format!("Invalid metadata: {}", errors.join("; ")),
+            errors,
+        ));
+    }
+
+    let map: HashMap<String, serde_json::Value> = mapping
+        .iter()
+        .filter_map(|(k, v)| Some((k.as_str()?.to_string(), serde_json::to_value(v).ok()?)))
+        .collect();
+    Ok(if map.is_empty() { None } else { Some(map) })
 }
 
 #[cfg(test)]
@@ -202,11 +688,20 @@ mod tests {
         std::fs::write(skill_dir.join("skill.md"), "test").unwrap();
 
         let result = find_skill_md(&skill_dir);
-        assert!(result.is_some());
-        // On case-insensitive filesystems (macOS), this might return SKILL.md
-        let path = result.unwrap();
-        let filename = path.file_name().unwrap().to_str().unwrap().to_lowercase();
-        assert_eq!(filename, "skill.md");
+        assert_eq!(result, Some(skill_dir.join("skill.md")));
+    }
+
+    #[test]
+    fn test_find_skill_md_reports_on_disk_casing() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skill_dir.join("Skill.MD"), "test").unwrap();
+
+        let path = find_skill_md(&skill_dir).unwrap();
+        assert_eq!(path.file_name().unwrap(), "Skill.MD");
+        assert!(!is_canonical_skill_md_name("Skill.MD"));
+        assert!(find_skill_md(&dir.path().join("missing")).is_none());
     }
 
     #[test]
@@ -232,6 +727,92 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_resolve_skill_dir_directory() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill_dir(&dir, "my-skill", "test");
+
+        assert_eq!(resolve_skill_dir(&skill_dir).unwrap(), skill_dir);
+    }
+
+    #[test]
+    fn test_resolve_skill_dir_skill_md_file() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill_dir(&dir, "my-skill", "test");
+
+        let resolved = resolve_skill_dir(&skill_dir.join("SKILL.md")).unwrap();
+        assert_eq!(resolved, skill_dir);
+    }
+
+    #[test]
+    fn test_resolve_skill_dir_other_file() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill_dir(&dir, "my-skill", "test");
+        let readme = skill_dir.join("README.md");
+        std::fs::write(&readme, "test").unwrap();
+
+        let err = resolve_skill_dir(&readme).unwrap_err();
+        assert!(matches!(err, SkillError::InvalidPath { .. }));
+        assert!(
+            err.to_string()
+                .contains("Expected a skill directory or a SKILL.md file")
+        );
+    }
+
+    #[test]
+    fn test_resolve_skill_dir_missing_skill_md() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+
+        let err = resolve_skill_dir(&skill_dir).unwrap_err();
+        assert!(matches!(err, SkillError::NotFound { .. }));
+        assert!(err.to_string().contains("SKILL.md not found"));
+    }
+
+    #[test]
+    fn test_resolve_skill_dir_nonexistent() {
+        let dir = TempDir::new().unwrap();
+
+        let err = resolve_skill_dir(&dir.path().join("my-skill")).unwrap_err();
+        assert!(matches!(err, SkillError::NotFound { .. }));
+        assert!(err.to_string().contains("Path does not exist"));
+        assert!(!err.to_string().contains("did you mean"));
+    }
+
+    #[test]
+    fn test_resolve_skill_dir_suggests_case_variant() {
+        let dir = TempDir::new().unwrap();
+        create_skill_dir(&dir, "My-Skill", "test");
+
+        let err = resolve_skill_dir(&dir.path().join("my-skill")).unwrap_err();
+        assert!(err.to_string().contains("did you mean"));
+        assert!(err.to_string().contains("My-Skill"));
+    }
+
+    #[test]
+    fn test_resolve_skill_dir_suggests_hyphen_variant() {
+        let dir = TempDir::new().unwrap();
+        create_skill_dir(&dir, "my-skill", "test");
+
+        let err = resolve_skill_dir(&dir.path().join("myskill")).unwrap_err();
+        assert!(err.to_string().contains("did you mean"));
+        assert!(err.to_string().contains("my-skill"));
+    }
+
+    #[test]
+    fn test_read_properties_from_skill_md_file() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill_dir(
+            &dir,
+            "my-skill",
+            "---\nname: my-skill\ndescription: A test skill\n---\n",
+        );
+
+        let props = read_properties(&skill_dir.join("SKILL.md")).unwrap();
+        assert_eq!(props.name, "my-skill");
+    }
+
     #[test]
     fn test_parse_frontmatter_valid() {
         let content = r#"---
@@ -275,6 +856,310 @@ description: A test skill
         );
     }
 
+    #[test]
+    fn test_parse_frontmatter_toml_closed_with_yaml_delimiter() {
+        let content = "+++\nname = \"my-skill\"\n---\n# Body\n";
+        let err = parse_frontmatter(content).unwrap_err().to_string();
+        assert!(
+            err.contains("opened with +++ must be closed with +++, not --- (line 3)"),
+            "{}",
+            err
+        );
+    }
+
+    #[cfg(not(feature = "toml"))]
+    #[test]
+    fn test_parse_frontmatter_toml_needs_feature() {
+        let content = "+++\nname = \"my-skill\"\n+++\n";
+        let err = parse_frontmatter(content).unwrap_err().to_string();
+        assert!(err.contains("needs the `toml` feature"), "{}", err);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_read_properties_toml_frontmatter() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill_dir(
+            &dir,
+            "my-skill",
+            r#"+++
+name = "my-skill"
+description = "A test skill"
+license = "MIT"
+
+[metadata]
+author = "Jane"
+
+[metadata.review]
+owners = ["a", "b"]
++++
+# Body
+---
+"#,
+        );
+
+        assert!(crate::validator::validate(&skill_dir).is_empty());
+        let props = read_properties(&skill_dir).unwrap();
+        assert_eq!(props.name, "my-skill");
+        assert_eq!(props.license.as_deref(), Some("MIT"));
+        assert_eq!(props.author(), Some("Jane"));
+        assert_eq!(
+            props.metadata.unwrap()["review"],
+            serde_json::json!({"owners": ["a", "b"]})
+        );
+
+        let (_, body) =
+            parse_frontmatter(&std::fs::read_to_string(skill_dir.join("SKILL.md")).unwrap())
+                .unwrap();
+        assert_eq!(body, "# Body\n---");
+    }
+
+    /// Compare the frontmatter-only read path with a full read.
+    fn assert_same_as_full_read(content: &[u8]) -> String {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("SKILL.md");
+        std::fs::write(&path, content).unwrap();
+
+        let metadata = |r: Result<String>| {
+            r.and_then(|c| parse_frontmatter(&c).map(|(m, _)| m))
+                .map_err(|e| e.to_string())
+        };
+        let full = metadata(read_skill_md_file(&path));
+        let head = read_frontmatter_only(&path);
+        let head_len = head.as_ref().map_or(0, |h| h.len());
+        assert_eq!(metadata(head), full);
+        format!("{}:{:?}", head_len, full.err())
+    }
+
+    #[test]
+    fn test_read_frontmatter_only_large_body() {
+        let mut content = b"---\nname: big\ndescription: d\n---\n# Reference\n".to_vec();
+        content.extend("Long reference material.\n".repeat(10_000).bytes());
+
+        let summary = assert_same_as_full_read(&content);
+        assert!(summary.starts_with("45:"), "{}", summary);
+    }
+
+    #[test]
+    fn test_read_frontmatter_only_unclosed() {
+        let mut content = b"---\nname: big\ndescription: d\n".to_vec();
+        content.extend("# Body\n".repeat(30_000).bytes());
+
+        let summary = assert_same_as_full_read(&content);
+        assert!(summary.contains("not properly closed"), "{}", summary);
+    }
+
+    #[test]
+    fn test_read_frontmatter_only_matches_full_read() {
+        let big = "text\n".repeat(20_000);
+        let cases = [
+            "no frontmatter\n".to_string() + &big,
+            "---\nname: a\ndescription: d\n---\n\n\nlicense: MIT\n".to_string() + &big + "---\n",
+            "---\nname: a\ndescription: d\n---\n\nlicense: MIT\n".to_string() + &big,
+            "---\nname: a\n---\n\nlicense: MIT\n---\n".to_string() + &big,
+            "---\nname: a\n...\ndescription: d\n---\n".to_string() + &big,
+            "---\r\nname: a\r\ndescription: d\r\n---\r\n".to_string() + &big,
+            "---\nname: [unclosed\n---\n".to_string() + &big,
+            "---\nname: a\ndescription: d\n---".to_string(),
+            String::new(),
+        ];
+        for content in cases {
+            assert_same_as_full_read(content.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_utf16_and_utf32_detected() {
+        let text = "---\nname: a\ndescription: d\n---\n";
+        let utf16le: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let utf16be: Vec<u8> = text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        let utf32le: Vec<u8> = text
+            .chars()
+            .flat_map(|c| (c as u32).to_le_bytes())
+            .collect();
+
+        for (encoding, bom, bytes) in [
+            ("UTF-16", &[0xFF, 0xFE][..], &utf16le),
+            ("UTF-16", &[0xFE, 0xFF][..], &utf16be),
+            ("UTF-16", &[][..], &utf16le),
+            ("UTF-32", &[0xFF, 0xFE, 0, 0][..], &utf32le),
+        ] {
+            let content = [bom, bytes.as_slice()].concat();
+            let summary = assert_same_as_full_read(&content);
+            assert!(
+                summary.contains(&format!(
+                    "SKILL.md appears to be {} encoded; files must be UTF-8",
+                    encoding
+                )),
+                "{}",
+                summary
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_utf8_reports_offset_and_context() {
+        let mut content = b"---\nname: a\ndescription: Caf".to_vec();
+        content.push(0xE9);
+        content.extend(b" menu\n---\n");
+
+        let err = decode_skill_md(content.clone()).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "SKILL.md is not valid UTF-8: invalid byte 0xE9 at offset 28 (line 3, after \"description: Caf\"); it may be Latin-1 or Windows-1252 encoded, and must be saved as UTF-8"
+        );
+        assert_same_as_full_read(&content);
+
+        content.truncate(28);
+        content.extend([0xE2, 0x82]);
+        let err = decode_skill_md(content).unwrap_err().to_string();
+        assert!(
+            err.contains("invalid byte 0xE2 0x82 at offset 28"),
+            "{}",
+            err
+        );
+        assert!(!err.contains("Latin-1"), "{}", err);
+    }
+
+    #[test]
+    fn test_read_frontmatter_only_invalid_utf8_in_body() {
+        let mut content = b"---\nname: a\ndescription: d\n---\n".to_vec();
+        content.extend("é".repeat(5_000).bytes());
+        assert_same_as_full_read(&content);
+
+        content.push(0xff);
+        let summary = assert_same_as_full_read(&content);
+        assert!(summary.contains("not valid UTF-8"), "{}", summary);
+
+        content.pop();
+        content.push(0xc3);
+        assert_same_as_full_read(&content);
+    }
+
+    #[test]
+    fn test_parse_frontmatter_closing_trailing_spaces() {
+        let content = "---\nname: my-skill\n---   \n# Body\n";
+        let (metadata, body) = parse_frontmatter(content).unwrap();
+        assert_eq!(metadata.get("name").unwrap().as_str().unwrap(), "my-skill");
+        assert_eq!(body, "# Body");
+    }
+
+    #[test]
+    fn test_parse_frontmatter_closing_trailing_tab() {
+        let content = "---\nname: my-skill\n---\t\n# Body\n";
+        let (_, body) = parse_frontmatter(content).unwrap();
+        assert_eq!(body, "# Body");
+    }
+
+    #[test]
+    fn test_parse_frontmatter_opening_trailing_spaces() {
+        let content = "---  \nname: my-skill\n---\n# Body\n";
+        let (metadata, _) = parse_frontmatter(content).unwrap();
+        assert_eq!(metadata.get("name").unwrap().as_str().unwrap(), "my-skill");
+    }
+
+    #[test]
+    fn test_parse_frontmatter_four_dashes_not_delimiter() {
+        let content = "---\nname: my-skill\n----\n# Body\n";
+        let err = parse_frontmatter(content).unwrap_err();
+        assert!(err.to_string().contains("not properly closed"));
+
+        let content = "----\nname: my-skill\n---\n# Body\n";
+        let err = parse_frontmatter(content).unwrap_err();
+        assert!(err.to_string().contains("must start with YAML frontmatter"));
+    }
+
+    #[test]
+    fn test_parse_frontmatter_dashes_with_text_not_delimiter() {
+        let content = "---\nname: my-skill\n--- junk\n# Body\n";
+        let err = parse_frontmatter(content).unwrap_err();
+        assert!(err.to_string().contains("not properly closed"));
+    }
+
+    #[test]
+    fn test_parse_frontmatter_delimiter_is_last_line() {
+        let (_, body) = parse_frontmatter("---\nname: my-skill\n---").unwrap();
+        assert_eq!(body, "");
+
+        let (_, body) = parse_frontmatter("---\nname: my-skill\n---\n").unwrap();
+        assert_eq!(body, "");
+    }
+
+    #[test]
+    fn test_parse_frontmatter_ends_after_opening() {
+        for content in ["---", "---\n", "---  \n"] {
+            let err = parse_frontmatter(content).unwrap_err();
+            assert!(err.to_string().contains("not properly closed"));
+        }
+    }
+
+    #[test]
+    fn test_parse_frontmatter_dashes_inside_values() {
+        let content = "---\nname: my-skill\ndescription: before---after\n---\n# Body\n---\nMore\n";
+        let (metadata, body) = parse_frontmatter(content).unwrap();
+        assert_eq!(
+            metadata.get("description").unwrap().as_str().unwrap(),
+            "before---after"
+        );
+        assert_eq!(body, "# Body\n---\nMore");
+    }
+
+    #[test]
+    fn test_parse_frontmatter_split_into_two_documents() {
+        let content = "---\nname: my-skill\n---\ndescription: A test skill\n---\n# Body\n";
+        let err = parse_frontmatter(content).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("frontmatter must be a single YAML document")
+        );
+        assert!(err.to_string().contains("line 3"));
+    }
+
+    #[test]
+    fn test_parse_frontmatter_document_end_marker() {
+        let content = "---\nname: my-skill\n...\ndescription: A test skill\n---\n# Body\n";
+        let err = parse_frontmatter(content).unwrap_err();
+        assert!(err.to_string().contains("single YAML document"));
+        assert!(err.to_string().contains("line 3"));
+
+        // An end marker followed only by comments is still one document.
+        let content = "---\nname: my-skill\n...\n# trailing comment\n---\n# Body\n";
+        let (metadata, _) = parse_frontmatter(content).unwrap();
+        assert_eq!(metadata.get("name").unwrap().as_str().unwrap(), "my-skill");
+    }
+
+    #[test]
+    fn test_parse_frontmatter_document_start_with_content() {
+        let content = "---\nname: my-skill\n--- !extra\ndescription: d\n---\n# Body\n";
+        let err = parse_frontmatter(content).unwrap_err();
+        assert!(err.to_string().contains("line 3"));
+    }
+
+    #[test]
+    fn test_parse_frontmatter_body_rule_is_not_second_document() {
+        let content = "---\nname: my-skill\n---\nIntro\n---\nname: is just text here\n";
+        assert!(parse_frontmatter(content).is_ok());
+
+        let content = "---\nname: my-skill\n---\ndescription: no closing delimiter\n";
+        assert!(parse_frontmatter(content).is_ok());
+
+        // A body that opens with field-like prose and has a rule later
+        let content = "---\nname: my-skill\ndescription: d\n---\ndescription: this skill converts files.\n\nRun the script.\n\n---\n\nname: see above\n";
+        let (metadata, body) = parse_frontmatter(content).unwrap();
+        assert_eq!(metadata.get("name").unwrap().as_str().unwrap(), "my-skill");
+        assert!(body.starts_with("description: this skill converts files."));
+        let content = "---\nname: my-skill\ndescription: d\n---\nname: my-skill is a converter\nIt reads files.\n---\n";
+        assert!(parse_frontmatter(content).is_ok());
+    }
+
+    #[test]
+    fn test_parse_frontmatter_crlf() {
+        let content = "---\r\nname: my-skill\r\n---\r\n# Body\r\n";
+        let (metadata, body) = parse_frontmatter(content).unwrap();
+        assert_eq!(metadata.get("name").unwrap().as_str().unwrap(), "my-skill");
+        assert_eq!(body, "# Body");
+    }
+
     #[test]
     fn test_read_properties_valid() {
         let dir = TempDir::new().unwrap();
@@ -296,6 +1181,25 @@ license: MIT
         assert_eq!(props.license, Some("MIT".to_string()));
     }
 
+    #[test]
+    fn test_properties_from_bytes_matches_read_properties() {
+        let dir = TempDir::new().unwrap();
+        let content = "---\nname: my-skill\ndescription: A test skill\n---\n# Body\n";
+        let skill_dir = create_skill_dir(&dir, "my-skill", content);
+        assert_eq!(
+            properties_from_bytes(content.as_bytes().to_vec()).unwrap(),
+            read_properties(&skill_dir).unwrap()
+        );
+
+        // Encoding problems get the same error as from a file
+        let bytes = b"---\nname: caf\xE9\n---\n".to_vec();
+        std::fs::write(skill_dir.join("SKILL.md"), &bytes).unwrap();
+        assert_eq!(
+            properties_from_bytes(bytes).unwrap_err().to_string(),
+            read_properties(&skill_dir).unwrap_err().to_string()
+        );
+    }
+
     #[test]
     fn test_read_properties_with_metadata() {
         let dir = TempDir::new().unwrap();
@@ -319,6 +1223,75 @@ metadata:
         assert_eq!(metadata.get("version").unwrap(), "1.0");
     }
 
+    #[test]
+    fn test_read_properties_metadata_nested_values() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill_dir(
+            &dir,
+            "my-skill",
+            "---\nname: my-skill\ndescription: d\nmetadata:\n  owner:\n    team:\n      name: search\n  tags: [pdf, text]\n  tier: 2\n---\n",
+        );
+
+        let metadata = read_properties(&skill_dir).unwrap().metadata.unwrap();
+        assert_eq!(
+            metadata["owner"],
+            serde_json::json!({ "team": { "name": "search" } })
+        );
+        assert_eq!(metadata["tags"], serde_json::json!(["pdf", "text"]));
+        assert_eq!(metadata["tier"], serde_json::json!(2));
+    }
+
+    #[test]
+    fn test_read_properties_metadata_integer_key() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill_dir(
+            &dir,
+            "my-skill",
+            "---\nname: my-skill\ndescription: d\nmetadata:\n  1: first\n---\n",
+        );
+
+        let err = read_properties(&skill_dir).unwrap_err();
+        assert_eq!(
+            err.errors().unwrap(),
+            ["Key '1' in metadata must be a string, not integer"]
+        );
+    }
+
+    #[test]
+    fn test_read_properties_metadata_too_deep() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill_dir(
+            &dir,
+            "my-skill",
+            "---\nname: my-skill\ndescription: d\nmetadata:\n  a:\n    b:\n      - [deep]\n---\n",
+        );
+
+        let err = read_properties(&skill_dir).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("metadata.a.b.0.0 nests deeper than 3 levels")
+        );
+    }
+
+    #[test]
+    fn test_read_properties_with_extensions() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: my-skill\ndescription: d\nx-acme:\n  team: search\n  tier: 2\n---\n",
+        )
+        .unwrap();
+
+        let props = read_properties(&skill_dir).unwrap();
+        let extensions = props.extensions.unwrap();
+        assert_eq!(
+            extensions["x-acme"],
+            serde_json::json!({"team": "search", "tier": 2})
+        );
+    }
+
     #[test]
     fn test_read_properties_missing_name() {
         let dir = TempDir::new().unwrap();
@@ -0,0 +1,190 @@
+//! Safe resolution of paths relative to a skill directory.
+//!
+//! Skill bodies reference files (`scripts/fill.py`, `reference.md`) that
+//! hosts may read or copy. Every such path must go through
+//! [`resolve_within`] so a skill cannot point a host outside its own
+//! directory.
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::{Result, SkillError};
+
+/// Resolve a skill-relative path, refusing anything that escapes the skill
+/// directory.
+///
+/// The path is rejected if it:
+///
+/// * contains a NUL byte,
+/// * is absolute (including Windows drive or UNC prefixes),
+/// * climbs above `skill_dir` with `..` after lexical normalization, or
+/// * exists and, once symlinks are resolved, lies outside `skill_dir`.
+///
+/// # Arguments
+///
+/// * `skill_dir` - Path to the skill directory
+/// * `rel` - Path as written in the skill (e.g. in a markdown link)
+///
+/// # Returns
+///
+/// `skill_dir` joined with the normalized relative path.
+///
+/// # Errors
+///
+/// Returns `InvalidPath` describing why the path was rejected.
+pub fn resolve_within(skill_dir: &Path, rel: &str) -> Result<PathBuf> {
+    let reject = |reason: &str| {
+        Err(SkillError::invalid_path(
+            Path::new(rel),
+            format!("Path '{}' {}", rel.replace('\0', "\\0"), reason),
+        ))
+    };
+
+    if rel.contains('\0') {
+        return reject("contains a NUL byte");
+    }
+    if looks_absolute(rel) {
+        return reject("must be relative to the skill directory");
+    }
+
+    let mut normalized = PathBuf::new();
+    for component in Path::new(rel).components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(part) => normalized.push(part),
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return reject("escapes the skill directory");
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return reject("must be relative to the skill directory");
+            }
+        }
+    }
+
+    let resolved = skill_dir.join(&normalized);
+
+    if let Ok(real) = resolved.canonicalize() {
+        let real_dir = skill_dir.canonicalize()?;
+        if !real.starts_with(&real_dir) {
+            return reject("resolves outside the skill directory through a symlink");
+        }
+    }
+
+    Ok(resolved)
+}
+
+/// Check for absolute paths in both Unix and Windows spellings, regardless
+/// of the current platform.
+fn looks_absolute(rel: &str) -> bool {
+    let bytes = rel.as_bytes();
+    Path::new(rel).is_absolute()
+        || rel.starts_with('/')
+        || rel.starts_with('\\')
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn skill_dir(dir: &TempDir) -> PathBuf {
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+        std::fs::write(skill_dir.join("scripts/run.py"), "print()").unwrap();
+        skill_dir
+    }
+
+    #[test]
+    fn test_resolve_simple_path() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = skill_dir(&dir);
+
+        assert_eq!(
+            resolve_within(&skill_dir, "scripts/run.py").unwrap(),
+            skill_dir.join("scripts/run.py")
+        );
+        assert_eq!(
+            resolve_within(&skill_dir, "./scripts/../scripts/run.py").unwrap(),
+            skill_dir.join("scripts/run.py")
+        );
+    }
+
+    #[test]
+    fn test_resolve_missing_file_inside() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = skill_dir(&dir);
+
+        assert_eq!(
+            resolve_within(&skill_dir, "docs/missing.md").unwrap(),
+            skill_dir.join("docs/missing.md")
+        );
+    }
+
+    #[test]
+    fn test_reject_parent_escape() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = skill_dir(&dir);
+
+        for rel in [
+            "../other/SKILL.md",
+            "scripts/../../x",
+            "../../../../etc/passwd",
+        ] {
+            let err = resolve_within(&skill_dir, rel).unwrap_err();
+            assert!(matches!(err, SkillError::InvalidPath { .. }));
+            assert!(err.to_string().contains("escapes"), "{}", rel);
+        }
+    }
+
+    #[test]
+    fn test_reject_absolute() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = skill_dir(&dir);
+
+        for rel in ["/etc/passwd", "\\\\server\\share", "C:\\Windows", "c:/x"] {
+            let err = resolve_within(&skill_dir, rel).unwrap_err();
+            assert!(err.to_string().contains("must be relative"), "{}", rel);
+        }
+    }
+
+    #[test]
+    fn test_reject_nul() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = skill_dir(&dir);
+
+        let err = resolve_within(&skill_dir, "scripts/run.py\0.md").unwrap_err();
+        assert!(err.to_string().contains("NUL"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reject_symlink_outside() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = skill_dir(&dir);
+        let secret = dir.path().join("secret.txt");
+        std::fs::write(&secret, "secret").unwrap();
+        std::os::unix::fs::symlink(&secret, skill_dir.join("link.txt")).unwrap();
+        std::os::unix::fs::symlink(dir.path(), skill_dir.join("up")).unwrap();
+
+        let err = resolve_within(&skill_dir, "link.txt").unwrap_err();
+        assert!(err.to_string().contains("through a symlink"));
+        let err = resolve_within(&skill_dir, "up/secret.txt").unwrap_err();
+        assert!(err.to_string().contains("through a symlink"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_allow_symlink_inside() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = skill_dir(&dir);
+        std::os::unix::fs::symlink(skill_dir.join("scripts/run.py"), skill_dir.join("run.py"))
+            .unwrap();
+
+        assert_eq!(
+            resolve_within(&skill_dir, "run.py").unwrap(),
+            skill_dir.join("run.py")
+        );
+    }
+}
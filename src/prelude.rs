@@ -0,0 +1,65 @@
+//! The stable core of the API, for glob import.
+//!
+//! Everything here follows the stable tier described in the crate docs.
+//! Enums and options structs are `#[non_exhaustive]`: build options from
+//! their defaults and keep a wildcard arm when matching, and the code
+//! keeps compiling as variants and fields are added.
+//!
+//! ```
+//! use skills_ref::prelude::*;
+//!
+//! let config = ValidationConfig::default().with_list_placeholders(true);
+//! let options = PromptOptions::default()
+//!     .with_include_ids(true)
+//!     .with_trust("skills/builtin", TrustLevel::Builtin);
+//! # let _ = (config, options);
+//!
+//! fn describe(error: &SkillError) -> &'static str {
+//!     match error {
+//!         SkillError::NotFound { .. } => "missing",
+//!         SkillError::Parse(_) => "unreadable",
+//!         _ => "invalid",
+//!     }
+//! }
+//! # assert_eq!(describe(&SkillError::parse("x")), "unreadable");
+//! ```
+//!
+//! Matching every variant without a wildcard arm, or building options with
+//! a struct expression, does not compile outside this crate:
+//!
+//! ```compile_fail,E0004
+//! use skills_ref::prelude::*;
+//!
+//! fn keeps_all(policy: DuplicatePolicy) -> bool {
+//!     match policy {
+//!         DuplicatePolicy::KeepAll => true,
+//!         DuplicatePolicy::KeepHighestVersion => false,
+//!     }
+//! }
+//! ```
+//!
+//! ```compile_fail,E0639
+//! use skills_ref::prelude::*;
+//!
+//! let options = PromptOptions {
+//!     include_ids: true,
+//!     ..Default::default()
+//! };
+//! ```
+
+pub use crate::diagnostic::{Diagnostic, Severity};
+pub use crate::discovery::{DiscoveryOptions, find_skills, find_skills_with};
+pub use crate::error::{Result, SkillError};
+pub use crate::models::SkillProperties;
+pub use crate::parser::read_properties;
+pub use crate::prompt::{
+    EmptyPolicy, InlineBody, PromptFormatVersion, PromptOptions, to_prompt, to_prompt_with_options,
+};
+pub use crate::skill::Skill;
+pub use crate::skill_set::{DuplicatePolicy, LoadOptions, SkillSet};
+pub use crate::trust::TrustLevel;
+pub use crate::validator::{
+    NameMatch, SizeLimits, ValidationConfig, ValidationReport, validate, validate_detailed,
+    validate_report,
+};
+pub use crate::workspace::{WorkspaceReport, validate_workspace};
@@ -1,17 +1,661 @@
 //! Generate `<available_skills>` XML prompt block for agent system prompts.
 
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
-use crate::error::Result;
-use crate::parser::{find_skill_md, read_properties};
+use serde::Serialize;
 
-/// Escape special HTML characters.
+use crate::digest::sha256_hex;
+use crate::error::{Result, SkillError};
+use crate::host::{HostCapabilities, validate_for_host};
+use crate::overrides::PromptOverrides;
+use crate::parallel::{default_concurrency, map_bounded};
+use crate::redact::PathRedaction;
+use crate::skill::Skill;
+use crate::skill_set::{DuplicatePolicy, apply_duplicate_policy};
+use crate::source::{CanonicalizeMode, FsSource, SkillSource};
+use crate::trust::TrustLevel;
+
+/// Version number of the default prompt output format,
+/// [`PromptFormatVersion::default`].
+pub const PROMPT_FORMAT_VERSION: u32 = 1;
+
+/// Layout of the generated `<available_skills>` XML.
+///
+/// Each version's output is byte-stable: for the same skills and options it
+/// never changes between releases, which golden-file tests enforce. Any
+/// change to element order, whitespace, or escaping lands as a new version
+/// instead, selected through [`PromptOptions::format_version`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PromptFormatVersion {
+    /// One element or text node per line, with HTML-escaped text except
+    /// for an unredacted `<location>`, whose path is emitted as is.
+    #[default]
+    V1,
+
+    /// Like [`V1`](Self::V1), with the `<location>` path HTML-escaped too,
+    /// so paths containing `&`, `<`, or `>` stay well-formed.
+    V2,
+}
+
+impl PromptFormatVersion {
+    /// All supported versions, oldest first.
+    pub const ALL: &'static [PromptFormatVersion] =
+        &[PromptFormatVersion::V1, PromptFormatVersion::V2];
+
+    /// The version number, e.g. 1 for [`V1`](Self::V1).
+    pub fn number(self) -> u32 {
+        match self {
+            PromptFormatVersion::V1 => 1,
+            PromptFormatVersion::V2 => 2,
+        }
+    }
+}
+
+/// When to inline a skill's body into the prompt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InlineBody {
+    /// Only emit the skill's location (the default).
+    #[default]
+    Never,
+
+    /// Inline every skill's body.
+    Always,
+
+    /// Inline bodies shorter than this many characters.
+    UnderChars(usize),
+}
+
+impl InlineBody {
+    fn applies_to(self, body: &str) -> bool {
+        match self {
+            InlineBody::Never => false,
+            InlineBody::Always => true,
+            InlineBody::UnderChars(max) => body.chars().count() < max,
+        }
+    }
+}
+
+/// What to do when no skill is left for the prompt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EmptyPolicy {
+    /// Return an empty `<available_skills>` block (the default).
+    #[default]
+    Allow,
+
+    /// Return a `ValidationError` listing every skill that was left out
+    /// and why.
+    Error,
+}
+
+/// What to do with a skill whose SKILL.md can no longer be read when
+/// [`PromptOptions::verify_locations`] is set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnreadablePolicy {
+    /// Leave the skill out and report it as excluded (the default).
+    #[default]
+    Exclude,
+
+    /// Fail the whole prompt with an error naming the skill.
+    Error,
+}
+
+/// How descriptions containing tag-like text, such as `<result>`, are
+/// handled.
+///
+/// Escaping keeps such text correct in the prompt either way; the other
+/// modes are for prompts that people review or that tools scan with
+/// regular expressions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Sanitizer {
+    /// Escape the description like any other text (the default).
+    #[default]
+    EscapeOnly,
+
+    /// Remove tag-like tokens before escaping. The original description is
+    /// kept in [`IncludedSkill::original_description`]. A description made
+    /// only of tag-like tokens would come out empty, so it is escaped as
+    /// is instead.
+    StripMarkup,
+
+    /// Exclude skills whose description has a tag-like token.
+    Reject,
+}
+
+/// Options controlling prompt generation.
+///
+/// Start from [`PromptOptions::default`] and set options with the `with_*`
+/// methods or by assigning fields.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct PromptOptions {
+    /// Capabilities of the host the prompt is for. When set, skills that
+    /// cannot run on the host are left out of the prompt.
+    pub host: Option<HostCapabilities>,
+
+    /// Whether to inline skill bodies in an `<instructions>` element.
+    pub inline_body: InlineBody,
+
+    /// Size budget for the whole prompt, in characters. Bodies are inlined
+    /// in order only while the prompt stays within the budget; skills never
+    /// are dropped to meet it.
+    pub max_chars: Option<usize>,
+
+    /// Layout of the generated XML.
+    pub format_version: PromptFormatVersion,
+
+    /// What to do with skills that share a name. Skills left out are
+    /// reported as excluded.
+    pub duplicates: DuplicatePolicy,
+
+    /// Keep skills marked `metadata.deprecated`, annotated with a
+    /// `<deprecated>` element. By default they are excluded.
+    pub include_deprecated: bool,
+
+    /// Emit an `<id>` element with the skill's `metadata.id` (see
+    /// [`SkillProperties::stable_id`](crate::SkillProperties::stable_id))
+    /// for skills that have one.
+    pub include_ids: bool,
+
+    /// Replace a path prefix in `<location>` elements, for output that
+    /// must not depend on where skills live. The location is redacted
+    /// and then XML-escaped, in every format version.
+    pub redact_paths: Option<PathRedaction>,
+
+    /// Skills to exclude and descriptions to replace, by skill name.
+    /// Replaced descriptions are escaped like the skills' own.
+    pub overrides: Option<PromptOverrides>,
+
+    /// What to do when every skill is excluded, or none is given.
+    pub on_empty: EmptyPolicy,
+
+    /// Trust level of the skills under each path, emitted as a `<trust>`
+    /// element. A skill gets the level of the longest path its directory
+    /// is under; skills under none of the paths get no element.
+    pub trust: Vec<(PathBuf, TrustLevel)>,
+
+    /// Whether skill paths, and so `<location>` elements, are made
+    /// canonical. [`CanonicalizeMode::Never`] keeps them as given.
+    pub canonicalize: CanonicalizeMode,
+
+    /// How descriptions with tag-like text are handled. Applies after
+    /// [`overrides`](Self::overrides) replace a description.
+    pub description_sanitizer: Sanitizer,
+
+    /// Open each SKILL.md again just before rendering, and handle skills
+    /// whose `<location>` can no longer be read per
+    /// [`on_unreadable`](Self::on_unreadable). Skills are loaded from their
+    /// files either way; this catches files deleted or made unreadable
+    /// while the prompt was being assembled.
+    pub verify_locations: bool,
+
+    /// What to do with skills that fail
+    /// [`verify_locations`](Self::verify_locations).
+    pub on_unreadable: UnreadablePolicy,
+}
+
+impl PromptOptions {
+    /// Set [`host`](Self::host).
+    pub fn with_host(mut self, host: HostCapabilities) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    /// Set [`inline_body`](Self::inline_body).
+    pub fn with_inline_body(mut self, inline_body: InlineBody) -> Self {
+        self.inline_body = inline_body;
+        self
+    }
+
+    /// Set [`max_chars`](Self::max_chars).
+    pub fn with_max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = Some(max_chars);
+        self
+    }
+
+    /// Set [`format_version`](Self::format_version).
+    pub fn with_format_version(mut self, format_version: PromptFormatVersion) -> Self {
+        self.format_version = format_version;
+        self
+    }
+
+    /// Set [`duplicates`](Self::duplicates).
+    pub fn with_duplicates(mut self, duplicates: DuplicatePolicy) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Set [`include_deprecated`](Self::include_deprecated).
+    pub fn with_include_deprecated(mut self, include_deprecated: bool) -> Self {
+        self.include_deprecated = include_deprecated;
+        self
+    }
+
+    /// Set [`include_ids`](Self::include_ids).
+    pub fn with_include_ids(mut self, include_ids: bool) -> Self {
+        self.include_ids = include_ids;
+        self
+    }
+
+    /// Set [`redact_paths`](Self::redact_paths).
+    pub fn with_redact_paths(mut self, redact_paths: PathRedaction) -> Self {
+        self.redact_paths = Some(redact_paths);
+        self
+    }
+
+    /// Set [`overrides`](Self::overrides).
+    pub fn with_overrides(mut self, overrides: PromptOverrides) -> Self {
+        self.overrides = Some(overrides);
+        self
+    }
+
+    /// Set [`on_empty`](Self::on_empty).
+    pub fn with_on_empty(mut self, on_empty: EmptyPolicy) -> Self {
+        self.on_empty = on_empty;
+        self
+    }
+
+    /// Add a path to [`trust`](Self::trust) whose skills get `level`.
+    pub fn with_trust(mut self, path: impl Into<PathBuf>, level: TrustLevel) -> Self {
+        self.trust.push((path.into(), level));
+        self
+    }
+
+    /// Set [`canonicalize`](Self::canonicalize).
+    pub fn with_canonicalize(mut self, canonicalize: CanonicalizeMode) -> Self {
+        self.canonicalize = canonicalize;
+        self
+    }
+
+    /// Set [`description_sanitizer`](Self::description_sanitizer).
+    pub fn with_description_sanitizer(mut self, description_sanitizer: Sanitizer) -> Self {
+        self.description_sanitizer = description_sanitizer;
+        self
+    }
+
+    /// Set [`verify_locations`](Self::verify_locations).
+    pub fn with_verify_locations(mut self, verify_locations: bool) -> Self {
+        self.verify_locations = verify_locations;
+        self
+    }
+
+    /// Set [`on_unreadable`](Self::on_unreadable).
+    pub fn with_on_unreadable(mut self, on_unreadable: UnreadablePolicy) -> Self {
+        self.on_unreadable = on_unreadable;
+        self
+    }
+}
+
+/// A skill that went into a generated prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IncludedSkill {
+    /// Skill name, after any [`PromptOptions::overrides`].
+    pub name: String,
+
+    /// Path to the skill directory.
+    pub path: PathBuf,
+
+    /// Hex-encoded SHA-256 digest of the SKILL.md the entry was made from.
+    pub digest: String,
+
+    /// Characters the skill's `<skill>` element takes, including an
+    /// inlined body.
+    pub chars: usize,
+
+    /// The description before [`Sanitizer::StripMarkup`] changed it, if
+    /// it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_description: Option<String>,
+
+    /// Whether the skill's location passed the check of
+    /// [`PromptOptions::verify_locations`]. Skills that fail it are
+    /// excluded instead.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub location_verified: bool,
+}
+
+/// A skill that was left out of a generated prompt.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExcludedSkill {
+    /// Path to the skill directory.
+    pub path: PathBuf,
+
+    /// Skill name.
+    pub name: String,
+
+    /// Why the skill was excluded.
+    pub reason: String,
+}
+
+/// A generated prompt with details on how it was assembled.
+///
+/// Every way a skill can be left out of the prompt or shortened in it
+/// (deprecation, host filtering, duplicate policies, overrides, the size
+/// budget) is recorded here, so the render can be logged as an audit of
+/// what the model was shown.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PromptRender {
+    /// The `<available_skills>` XML block.
+    pub xml: String,
+
+    /// Skills in the prompt, in prompt order.
+    pub included: Vec<IncludedSkill>,
+
+    /// Skills left out of the prompt.
+    pub excluded: Vec<ExcludedSkill>,
+
+    /// Skills included in the prompt whose output differs from what the
+    /// options asked for, e.g. a body that was not inlined because of
+    /// `max_chars`.
+    pub notices: Vec<String>,
+
+    /// Problems with the options themselves, such as
+    /// [`PromptOptions::overrides`] entries naming no loaded skill.
+    pub warnings: Vec<String>,
+}
+
+/// A skill entry read back from an `<available_skills>` block by
+/// [`parse_prompt`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PromptSkillEntry {
+    /// Skill name.
+    pub name: String,
+
+    /// Skill description.
+    pub description: String,
+
+    /// Stable skill identifier, if the entry has one.
+    pub id: Option<String>,
+
+    /// Location of the skill's SKILL.md, if the entry has one.
+    pub location: Option<String>,
+
+    /// Inlined skill body, if the entry has one.
+    pub instructions: Option<String>,
+
+    /// Deprecation message, if the entry is marked deprecated.
+    pub deprecated: Option<String>,
+
+    /// Trust level, if the entry has one.
+    pub trust: Option<String>,
+}
+
+/// Escape special HTML characters and drop characters XML cannot hold.
+///
+/// Used for every text node, including inlined bodies. Bodies are escaped
+/// rather than wrapped in CDATA so that a body containing `]]>` cannot
+/// close the section early.
+///
+/// XML 1.0 allows no C0 control character other than tab, newline, and
+/// carriage return, not even as a character reference, and neither the
+/// noncharacters U+FFFE and U+FFFF. Such characters (a backspace pasted
+/// into a description, say) are removed, so the prompt stays well-formed
+/// for strict parsers.
 fn html_escape(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&#x27;")
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#x27;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Byte ranges of the tag-like tokens in `text`: `<`, an optional `/`, a
+/// letter, and anything up to the next `>` on the same line, skipping
+/// over quoted attribute values. A `<` followed by a space, as in
+/// `a < b`, starts no token.
+fn markup_tokens(text: &str) -> Vec<std::ops::Range<usize>> {
+    let bytes = text.as_bytes();
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    while let Some(offset) = text[start..].find('<') {
+        let open = start + offset;
+        start = open + 1;
+        let mut i = open + 1;
+        if bytes.get(i) == Some(&b'/') {
+            i += 1;
+        }
+        if !bytes.get(i).is_some_and(u8::is_ascii_alphabetic) {
+            continue;
+        }
+        let mut quote = None;
+        let end = loop {
+            match (bytes.get(i), quote) {
+                (None | Some(b'\n'), _) => break None,
+                (Some(&c), Some(q)) if c == q => quote = None,
+                (Some(_), Some(_)) => {}
+                (Some(b'>'), None) => break Some(i + 1),
+                (Some(b'<'), None) => break None,
+                (Some(&c @ (b'"' | b'\'')), None) => quote = Some(c),
+                (Some(_), None) => {}
+            }
+            i += 1;
+        };
+        if let Some(end) = end {
+            tokens.push(open..end);
+            start = end;
+        }
+    }
+    tokens
+}
+
+/// `text` without its tag-like tokens. A space left on both sides of a
+/// removed token is kept once, and the result is trimmed.
+fn strip_markup(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = 0;
+    for token in markup_tokens(text) {
+        let mut piece = &text[rest..token.start];
+        if out.ends_with(' ') {
+            piece = piece.strip_prefix(' ').unwrap_or(piece);
+        }
+        out.push_str(piece);
+        rest = token.end;
+    }
+    let mut piece = &text[rest..];
+    if out.ends_with(' ') {
+        piece = piece.strip_prefix(' ').unwrap_or(piece);
+    }
+    out.push_str(piece);
+    out.trim().to_string()
+}
+
+/// The description `options` puts in the prompt for `skill`.
+fn prompt_description<'a>(skill: &'a Skill, options: &PromptOptions) -> Cow<'a, str> {
+    let description = &skill.properties.description;
+    match options.description_sanitizer {
+        Sanitizer::StripMarkup if !markup_tokens(description).is_empty() => {
+            match strip_markup(description) {
+                stripped if stripped.is_empty() => Cow::Borrowed(description),
+                stripped => Cow::Owned(stripped),
+            }
+        }
+        _ => Cow::Borrowed(description),
+    }
+}
+
+/// Reverse [`html_escape`]. `line` is the 1-based line of `s`, for errors.
+fn html_unescape(s: &str, line: usize) -> Result<String> {
+    if let Some(c) = s.chars().find(|c| matches!(c, '<' | '>')) {
+        return Err(SkillError::parse(format!(
+            "Line {}: unescaped '{}' in text",
+            line, c
+        )));
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest.find(';').map(|end| &rest[..=end]).unwrap_or(rest);
+        out.push(match entity {
+            "&amp;" => '&',
+            "&lt;" => '<',
+            "&gt;" => '>',
+            "&quot;" => '"',
+            "&#x27;" => '\'',
+            _ => {
+                return Err(SkillError::parse(format!(
+                    "Line {}: unknown entity '{}'",
+                    line, entity
+                )));
+            }
+        });
+        rest = &rest[entity.len()..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Line cursor over a prompt being parsed, for [`parse_prompt`].
+struct PromptLines<'a> {
+    lines: std::iter::Enumerate<std::str::Split<'a, char>>,
+    count: usize,
+}
+
+impl<'a> PromptLines<'a> {
+    fn new(xml: &'a str) -> Self {
+        Self {
+            lines: xml.split('\n').enumerate(),
+            count: xml.split('\n').count(),
+        }
+    }
+
+    /// The next line and its 1-based number.
+    fn next(&mut self) -> Option<(usize, &'a str)> {
+        self.lines.next().map(|(i, line)| (i + 1, line))
+    }
+
+    fn error(&self, n: usize, expected: &str, found: Option<&str>) -> SkillError {
+        match found {
+            Some(line) => SkillError::parse(format!(
+                "Line {}: expected {}, found '{}'",
+                n, expected, line
+            )),
+            None => SkillError::parse(format!(
+                "Line {}: expected {}, found end of input",
+                n, expected
+            )),
+        }
+    }
+
+    /// Consume a line that must be exactly `tag`.
+    fn expect(&mut self, tag: &str) -> Result<()> {
+        match self.next() {
+            Some((_, line)) if line == tag => Ok(()),
+            Some((n, line)) => Err(self.error(n, tag, Some(line))),
+            None => Err(self.error(self.count, tag, None)),
+        }
+    }
+
+    /// Consume unescaped text lines up to and including `close`.
+    fn text(&mut self, close: &str) -> Result<String> {
+        let mut parts = Vec::new();
+        while let Some((n, line)) = self.next() {
+            if line == close {
+                return Ok(parts.join("\n"));
+            }
+            if line.starts_with('<') && line.ends_with('>') {
+                return Err(self.error(n, close, Some(line)));
+            }
+            parts.push(html_unescape(line, n)?);
+        }
+        Err(self.error(self.count, close, None))
+    }
+}
+
+/// Parse an `<available_skills>` block produced by [`to_prompt`] back into
+/// its skill entries.
+///
+/// Text is unescaped, so names and descriptions come back exactly as they
+/// were in the skill properties. Locations are unescaped too, which reads
+/// both format versions alike unless the path contains `&`, `<`, or `>`:
+/// [`PromptFormatVersion::V1`] emits those unescaped, so parse
+/// [`PromptFormatVersion::V2`] output for such paths.
+///
+/// # Errors
+///
+/// Returns a `ParseError` naming the line of the first element or text
+/// that does not fit the format.
+pub fn parse_prompt(xml: &str) -> Result<Vec<PromptSkillEntry>> {
+    let mut lines = PromptLines::new(xml.strip_suffix('\n').unwrap_or(xml));
+
+    lines.expect("<available_skills>")?;
+    let mut entries = Vec::new();
+    loop {
+        match lines.next() {
+            Some((_, "</available_skills>")) => break,
+            Some((_, "<skill>")) => {}
+            Some((n, line)) => {
+                return Err(lines.error(n, "<skill> or </available_skills>", Some(line)));
+            }
+            None => return Err(lines.error(lines.count, "</available_skills>", None)),
+        }
+
+        lines.expect("<name>")?;
+        let name = lines.text("</name>")?;
+        lines.expect("<description>")?;
+        let description = lines.text("</description>")?;
+        let mut entry = PromptSkillEntry {
+            name,
+            description,
+            id: None,
+            location: None,
+            instructions: None,
+            deprecated: None,
+            trust: None,
+        };
+        loop {
+            match lines.next() {
+                Some((_, "</skill>")) => break,
+                Some((_, "<id>")) if entry.id.is_none() => {
+                    entry.id = Some(lines.text("</id>")?);
+                }
+                Some((_, "<deprecated>")) if entry.deprecated.is_none() => {
+                    entry.deprecated = Some(lines.text("</deprecated>")?);
+                }
+                Some((_, "<trust>")) if entry.trust.is_none() => {
+                    entry.trust = Some(lines.text("</trust>")?);
+                }
+                Some((_, "<location>")) if entry.location.is_none() => {
+                    entry.location = Some(lines.text("</location>")?);
+                }
+                Some((_, "<instructions>")) if entry.instructions.is_none() => {
+                    entry.instructions = Some(lines.text("</instructions>")?);
+                }
+                Some((n, line)) => {
+                    return Err(lines.error(
+                        n,
+                        "<id>, <deprecated>, <trust>, <location>, <instructions>, or </skill>",
+                        Some(line),
+                    ));
+                }
+                None => return Err(lines.error(lines.count, "</skill>", None)),
+            }
+        }
+        entries.push(entry);
+    }
+
+    if let Some((n, line)) = lines.next() {
+        return Err(lines.error(n, "end of input", Some(line)));
+    }
+    Ok(entries)
 }
 
 /// Generate the `<available_skills>` XML block for inclusion in agent prompts.
@@ -22,7 +666,7 @@ fn html_escape(s: &str) -> String {
 ///
 /// # Arguments
 ///
-/// * `skill_dirs` - List of paths to skill directories
+/// * `skill_dirs` - List of paths to skill directories (or their SKILL.md files)
 ///
 /// # Returns
 ///
@@ -41,43 +685,618 @@ fn html_escape(s: &str) -> String {
 /// </available_skills>
 /// ```
 pub fn to_prompt(skill_dirs: &[&Path]) -> Result<String> {
-    if skill_dirs.is_empty() {
-        return Ok("<available_skills>\n</available_skills>".to_string());
+    to_prompt_with_options(skill_dirs, &PromptOptions::default())
+}
+
+/// Generate the `<available_skills>` XML block using the given options.
+///
+/// See [`to_prompt`] for the output format and
+/// [`to_prompt_with_exclusions`] to learn which skills were left out.
+///
+/// # Errors
+///
+/// Fails like [`render_prompt`].
+pub fn to_prompt_with_options(skill_dirs: &[&Path], options: &PromptOptions) -> Result<String> {
+    render_prompt(skill_dirs, options).map(|render| render.xml)
+}
+
+/// Generate the `<available_skills>` XML block and report excluded skills.
+///
+/// See [`render_prompt`] for which skills are excluded.
+///
+/// # Returns
+///
+/// Tuple of (XML string, skills left out of it).
+pub fn to_prompt_with_exclusions(
+    skill_dirs: &[&Path],
+    options: &PromptOptions,
+) -> Result<(String, Vec<ExcludedSkill>)> {
+    render_prompt(skill_dirs, options).map(|render| (render.xml, render.excluded))
+}
+
+/// Generate the `<available_skills>` XML block with full details.
+///
+/// Skills are excluded when:
+///
+/// * they are deprecated and `options.include_deprecated` is not set
+/// * `options.host` is set and [`validate_for_host`] reports them as
+///   incompatible (skills without `allowed-tools` are always compatible)
+/// * `options.duplicates` drops them in favor of a same-named skill
+/// * `options.overrides` excludes them
+/// * `options.description_sanitizer` is [`Sanitizer::Reject`] and their
+///   description has tag-like text
+/// * `options.verify_locations` is set, their SKILL.md can no longer be
+///   read, and `options.on_unreadable` is [`UnreadablePolicy::Exclude`]
+///
+/// Included deprecated skills carry a `<deprecated>` element after
+/// `<description>`, and skills under a path in `options.trust` carry a
+/// `<trust>` element after that.
+///
+/// When `options.inline_body` selects a skill, its escaped body is emitted
+/// in an `<instructions>` element after `<location>`. If inlining a body
+/// would take the prompt over `options.max_chars`, the skill keeps only
+/// its location and a notice is recorded.
+///
+/// # Errors
+///
+/// * Any error loading a skill, naming that skill
+/// * `ValidationError` - If no skill is left for the prompt and
+///   `options.on_empty` is [`EmptyPolicy::Error`]; its errors list each
+///   excluded skill as `path: reason`
+/// * `ValidationError` - If `options.verify_locations` is set, a skill's
+///   SKILL.md can no longer be read, and `options.on_unreadable` is
+///   [`UnreadablePolicy::Error`]; the error names that skill
+pub fn render_prompt(skill_dirs: &[&Path], options: &PromptOptions) -> Result<PromptRender> {
+    let Included {
+        skills,
+        excluded,
+        verified,
+    } = load_included(skill_dirs, options)?;
+    let mut render = render_skills(&skills, options, &verified);
+    render.excluded = excluded;
+
+    render.warnings = match &options.overrides {
+        Some(overrides) => overrides
+            .unknown_names(
+                skills
+                    .iter()
+                    .map(|skill| skill.properties.name.as_str())
+                    .chain(render.excluded.iter().map(|skill| skill.name.as_str())),
+            )
+            .into_iter()
+            .map(|name| format!("Prompt overrides name unknown skill '{}'", name))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ok(render)
+}
+
+/// Split the `<available_skills>` XML block into pages of at most
+/// `max_chars_per_page` characters.
+///
+/// Skills are assigned to pages greedily in input order: each page is a
+/// complete `<available_skills>` block holding as many consecutive skills
+/// as fit, and a skill is never split across pages. Within a page, bodies
+/// are inlined as in [`render_prompt`], with the page limit (or
+/// `options.max_chars`, if smaller) as the budget. Excluded skills appear
+/// on no page. An empty input yields a single empty page.
+///
+/// # Errors
+///
+/// Returns an error if a single skill does not fit on a page by itself,
+/// naming that skill. Otherwise fails like [`render_prompt`].
+pub fn prompt_pages(
+    skill_dirs: &[&Path],
+    options: &PromptOptions,
+    max_chars_per_page: usize,
+) -> Result<Vec<String>> {
+    let Included {
+        skills, verified, ..
+    } = load_included(skill_dirs, options)?;
+    let mut page_options = options.clone();
+    page_options.max_chars = Some(
+        options
+            .max_chars
+            .map_or(max_chars_per_page, |max| max.min(max_chars_per_page)),
+    );
+
+    let mut pages = Vec::new();
+    let mut start = 0;
+    let mut current: Option<String> = None;
+    let mut end = 0;
+    while end < skills.len() {
+        let xml = render_skills(&skills[start..=end], &page_options, &verified).xml;
+        if xml.chars().count() <= max_chars_per_page {
+            current = Some(xml);
+            end += 1;
+        } else if let Some(page) = current.take() {
+            pages.push(page);
+            start = end;
+        } else {
+            return Err(SkillError::validation(format!(
+                "Skill '{}' does not fit on a page of {} characters ({} chars alone)",
+                skills[end].properties.name,
+                max_chars_per_page,
+                xml.chars().count()
+            )));
+        }
     }
+    pages.push(current.unwrap_or_else(|| render_skills(&[], &page_options, &verified).xml));
 
-    let mut lines = vec!["<available_skills>".to_string()];
+    Ok(pages)
+}
 
-    for skill_dir in skill_dirs {
-        let skill_dir = skill_dir
-            .canonicalize()
-            .unwrap_or_else(|_| skill_dir.to_path_buf());
-        let props = read_properties(&skill_dir)?;
-
-        lines.push("<skill>".to_string());
-        lines.push("<name>".to_string());
-        lines.push(html_escape(&props.name));
-        lines.push("</name>".to_string());
-        lines.push("<description>".to_string());
-        lines.push(html_escape(&props.description));
-        lines.push("</description>".to_string());
-
-        if let Some(skill_md_path) = find_skill_md(&skill_dir) {
-            lines.push("<location>".to_string());
-            lines.push(skill_md_path.to_string_lossy().to_string());
-            lines.push("</location>".to_string());
+/// The skills left for a prompt by [`load_included`].
+struct Included {
+    skills: Vec<Skill>,
+    excluded: Vec<ExcludedSkill>,
+    /// SKILL.md paths whose location check passed.
+    verified: HashSet<PathBuf>,
+}
+
+/// Load the skills in `skill_dirs`, separating out those `options` leaves
+/// out of the prompt.
+fn load_included(skill_dirs: &[&Path], options: &PromptOptions) -> Result<Included> {
+    let mut excluded = Vec::new();
+    let mut skills = Vec::new();
+    for skill in load_all_for_prompt(skill_dirs, options.canonicalize) {
+        let mut skill = skill?;
+        let skill_dir = skill.dir.clone();
+
+        if let Some(overrides) = &options.overrides {
+            if overrides.excludes(&skill.properties.name) {
+                excluded.push(ExcludedSkill {
+                    path: skill_dir,
+                    name: skill.properties.name,
+                    reason: "Excluded by prompt overrides".to_string(),
+                });
+                continue;
+            }
+            if let Some(description) = overrides.description(&skill.properties.name) {
+                skill.properties.description = description.to_string();
+            }
         }
 
-        lines.push("</skill>".to_string());
+        if options.description_sanitizer == Sanitizer::Reject
+            && let Some(token) = markup_tokens(&skill.properties.description).first()
+        {
+            let reason = format!(
+                "Description contains tag-like text '{}'",
+                &skill.properties.description[token.clone()]
+            );
+            excluded.push(ExcludedSkill {
+                path: skill_dir,
+                name: skill.properties.name,
+                reason,
+            });
+            continue;
+        }
+
+        if skill.properties.is_deprecated() && !options.include_deprecated {
+            let reason = match skill.properties.deprecation_message() {
+                Some(message) => format!("Deprecated: {}", message),
+                None => "Deprecated".to_string(),
+            };
+            excluded.push(ExcludedSkill {
+                path: skill_dir,
+                name: skill.properties.name,
+                reason,
+            });
+            continue;
+        }
+
+        if let Some(ref host) = options.host {
+            let reasons = validate_for_host(&skill.properties, host);
+            if !reasons.is_empty() {
+                excluded.push(ExcludedSkill {
+                    path: skill_dir,
+                    name: skill.properties.name,
+                    reason: reasons.join("; "),
+                });
+                continue;
+            }
+        }
+
+        skills.push(skill);
+    }
+
+    let (skills, shadowed) = apply_duplicate_policy(skills, options.duplicates);
+    excluded.extend(shadowed.into_iter().map(|skill| ExcludedSkill {
+        reason: format!(
+            "Shadowed by a higher version at {}",
+            skill.shadowed_by.display()
+        ),
+        path: skill.path,
+        name: skill.name,
+    }));
+
+    // Last, so the check is as close to rendering as it can be.
+    let mut verified = HashSet::new();
+    let skills = if options.verify_locations {
+        let (readable, failed) = verify_locations(skills, options.on_unreadable)?;
+        excluded.extend(failed);
+        verified.extend(readable.iter().map(|skill| skill.skill_md.clone()));
+        readable
+    } else {
+        skills
+    };
+
+    if skills.is_empty() && options.on_empty == EmptyPolicy::Error {
+        return Err(empty_prompt_error(&excluded));
+    }
+    Ok(Included {
+        skills,
+        excluded,
+        verified,
+    })
+}
+
+/// Split `skills` into those whose SKILL.md can be opened and read, and
+/// exclusions for the rest, or fail on the first of the rest if
+/// `unreadable` is [`UnreadablePolicy::Error`].
+///
+/// Reads the file system directly, whatever the skills were loaded
+/// through. A SKILL.md emptied since loading has no frontmatter, so it
+/// fails too.
+fn verify_locations(
+    skills: Vec<Skill>,
+    unreadable: UnreadablePolicy,
+) -> Result<(Vec<Skill>, Vec<ExcludedSkill>)> {
+    let readable = |path: &Path| std::fs::File::open(path)?.read_exact(&mut [0u8; 1]);
+    let mut verified = Vec::new();
+    let mut excluded = Vec::new();
+    for skill in skills {
+        match readable(&skill.skill_md) {
+            Ok(()) => verified.push(skill),
+            Err(e) => {
+                let reason = format!(
+                    "Location {} cannot be read: {}",
+                    skill.skill_md.display(),
+                    e
+                );
+                if unreadable == UnreadablePolicy::Error {
+                    return Err(SkillError::validation(reason).in_skill(&skill.dir));
+                }
+                excluded.push(ExcludedSkill {
+                    reason,
+                    path: skill.dir,
+                    name: skill.properties.name,
+                });
+            }
+        }
+    }
+    Ok((verified, excluded))
+}
+
+/// The error for a prompt left without skills, listing why each was left
+/// out.
+fn empty_prompt_error(excluded: &[ExcludedSkill]) -> SkillError {
+    if excluded.is_empty() {
+        return SkillError::validation("No skills given for the prompt");
+    }
+    let errors: Vec<String> = excluded
+        .iter()
+        .map(|skill| format!("{}: {}", skill.path.display(), skill.reason))
+        .collect();
+    SkillError::validation_multiple(
+        format!(
+            "No skills left for the prompt; all {} were excluded: {}",
+            errors.len(),
+            errors.join("; ")
+        ),
+        errors,
+    )
+}
+
+/// Render the XML block for already loaded skills, recording each skill
+/// and a notice for each body left out to stay within `options.max_chars`.
+/// Skills whose SKILL.md is in `verified` are recorded as having a
+/// verified location.
+///
+/// The render has no excluded skills or warnings; those are up to the
+/// caller.
+fn render_skills(
+    skills: &[Skill],
+    options: &PromptOptions,
+    verified: &HashSet<PathBuf>,
+) -> PromptRender {
+    let mut notices = Vec::new();
+    let mut entries: Vec<Vec<String>> = skills
+        .iter()
+        .map(|skill| skill_lines(skill, options))
+        .collect();
+
+    // Size of the prompt without any inlined bodies: every line plus its
+    // newline, minus the final newline.
+    let mut size = ["<available_skills>", "</available_skills>"]
+        .iter()
+        .map(|l| l.len() + 1)
+        .chain(entries.iter().flatten().map(|l| l.chars().count() + 1))
+        .sum::<usize>()
+        - 1;
+
+    for (skill, entry) in skills.iter().zip(entries.iter_mut()) {
+        if skill.body.is_empty() || !options.inline_body.applies_to(&skill.body) {
+            continue;
+        }
+
+        let body = html_escape(&skill.body);
+        let added = "<instructions>".len() + "</instructions>".len() + body.chars().count() + 3;
+        if let Some(max) = options.max_chars
+            && size + added > max
+        {
+            notices.push(format!(
+                "Body of '{}' not inlined: prompt would exceed {} characters",
+                skill.properties.name, max
+            ));
+            continue;
+        }
+
+        size += added;
+        let close = entry.pop().expect("skill entry ends with </skill>");
+        entry.push("<instructions>".to_string());
+        entry.push(body);
+        entry.push("</instructions>".to_string());
+        entry.push(close);
     }
 
+    let included = skills
+        .iter()
+        .zip(&entries)
+        .map(|(skill, entry)| IncludedSkill {
+            name: skill.properties.name.clone(),
+            path: skill.dir.clone(),
+            digest: skill.digest.clone(),
+            chars: entry.iter().map(|l| l.chars().count() + 1).sum::<usize>() - 1,
+            original_description: match prompt_description(skill, options) {
+                Cow::Owned(_) => Some(skill.properties.description.clone()),
+                Cow::Borrowed(_) => None,
+            },
+            location_verified: verified.contains(&skill.skill_md),
+        })
+        .collect();
+
+    let mut lines = vec!["<available_skills>".to_string()];
+    lines.extend(entries.into_iter().flatten());
     lines.push("</available_skills>".to_string());
+    PromptRender {
+        xml: lines.join("\n"),
+        included,
+        excluded: Vec::new(),
+        notices,
+        warnings: Vec::new(),
+    }
+}
+
+/// Load a skill the way prompt generation sees it, with a canonical path
+/// unless `canonicalize` is [`CanonicalizeMode::Never`].
+fn load_for_prompt(path: &Path, canonicalize: CanonicalizeMode) -> Result<Skill> {
+    let mut skill = Skill::load_from(&FsSource, path)?;
+    if canonicalize == CanonicalizeMode::Always
+        && let Ok(dir) = FsSource.canonicalize(&skill.dir)
+    {
+        if let Some(name) = skill.skill_md.file_name() {
+            skill.skill_md = dir.join(name);
+        }
+        skill.dir = dir;
+    }
+    Ok(skill)
+}
+
+/// Load skills for prompt generation in parallel, in input order.
+fn load_all_for_prompt(skill_dirs: &[&Path], canonicalize: CanonicalizeMode) -> Vec<Result<Skill>> {
+    map_bounded(
+        skill_dirs,
+        default_concurrency(),
+        |dir| load_for_prompt(dir, canonicalize).map_err(|e| e.in_skill(dir)),
+        |_| {},
+    )
+}
+
+/// Compute a cache key for the prompt generated from `skill_dirs` and
+/// `options`.
+///
+/// The key is a hex SHA-256 over the prompt format version, every option
+/// value, and each skill's resolved SKILL.md location and content digest,
+/// in order. Running it twice over unchanged inputs yields the same key;
+/// any change that could alter the rendered prompt (editing a skill,
+/// moving it, reordering skills, or changing an option) yields a new one.
+///
+/// # Errors
+///
+/// Same as [`render_prompt`] for skills that cannot be loaded.
+pub fn cache_key(skill_dirs: &[&Path], options: &PromptOptions) -> Result<String> {
+    let mut input = format!(
+        "skills-ref prompt v{}\n{}\n",
+        options.format_version.number(),
+        options_key(options)
+    );
+    for skill in load_all_for_prompt(skill_dirs, options.canonicalize) {
+        let skill = skill?;
+        input.push_str(&format!(
+            "skill: {}\0{}\n",
+            skill.skill_md.to_string_lossy(),
+            skill.digest
+        ));
+    }
+    Ok(sha256_hex(input.as_bytes()))
+}
+
+/// Every option in `options`, one `name=value` line each, with strings
+/// and lists as JSON.
+fn options_key(options: &PromptOptions) -> String {
+    // Destructured so a new option cannot be left out of the key.
+    let PromptOptions {
+        host,
+        inline_body,
+        max_chars,
+        format_version,
+        duplicates,
+        include_deprecated,
+        include_ids,
+        redact_paths,
+        overrides,
+        on_empty,
+        trust,
+        canonicalize,
+        description_sanitizer,
+        verify_locations,
+        on_unreadable,
+    } = options;
+    let json = |value: serde_json::Value| value.to_string();
+    let host = host
+        .as_ref()
+        .map_or("none".to_string(), |host| json(host.tools.clone().into()));
+    let inline_body = match inline_body {
+        InlineBody::Never => "never".to_string(),
+        InlineBody::Always => "always".to_string(),
+        InlineBody::UnderChars(max) => format!("under-chars:{max}"),
+    };
+    let max_chars = max_chars.map_or("none".to_string(), |max| max.to_string());
+    let duplicates = match duplicates {
+        DuplicatePolicy::KeepAll => "keep-all",
+        DuplicatePolicy::KeepHighestVersion => "keep-highest-version",
+    };
+    let redact_paths = redact_paths
+        .as_ref()
+        .map_or("none".to_string(), PathRedaction::key);
+    let overrides = overrides.as_ref().map_or("none".to_string(), |overrides| {
+        json(serde_json::json!([
+            overrides.exclude,
+            overrides.override_description
+        ]))
+    });
+    let on_empty = match on_empty {
+        EmptyPolicy::Allow => "allow",
+        EmptyPolicy::Error => "error",
+    };
+    let trust = json(
+        trust
+            .iter()
+            .map(|(path, level)| serde_json::json!([path.to_string_lossy(), level.as_str()]))
+            .collect(),
+    );
+    let canonicalize = match canonicalize {
+        CanonicalizeMode::Always => "always",
+        CanonicalizeMode::Never => "never",
+    };
+    let description_sanitizer = match description_sanitizer {
+        Sanitizer::EscapeOnly => "escape-only",
+        Sanitizer::StripMarkup => "strip-markup",
+        Sanitizer::Reject => "reject",
+    };
+    let on_unreadable = match on_unreadable {
+        UnreadablePolicy::Exclude => "exclude",
+        UnreadablePolicy::Error => "error",
+    };
+
+    [
+        format!("host={host}"),
+        format!("inline_body={inline_body}"),
+        format!("max_chars={max_chars}"),
+        format!("format_version={}", format_version.number()),
+        format!("duplicates={duplicates}"),
+        format!("include_deprecated={include_deprecated}"),
+        format!("include_ids={include_ids}"),
+        format!("redact_paths={redact_paths}"),
+        format!("overrides={overrides}"),
+        format!("on_empty={on_empty}"),
+        format!("trust={trust}"),
+        format!("canonicalize={canonicalize}"),
+        format!("description_sanitizer={description_sanitizer}"),
+        format!("verify_locations={verify_locations}"),
+        format!("on_unreadable={on_unreadable}"),
+    ]
+    .join("\n")
+}
+
+/// Text of the `<deprecated>` element for skills without a
+/// `metadata.deprecated-message`.
+const DEFAULT_DEPRECATION_MESSAGE: &str = "This skill is deprecated.";
+
+/// The trust level `options` assigns to `skill`: that of the longest
+/// trusted path its directory is under.
+fn trust_level(skill: &Skill, options: &PromptOptions) -> Option<TrustLevel> {
+    options
+        .trust
+        .iter()
+        .filter_map(|(path, level)| {
+            // Compare paths in the form skill directories are in.
+            let path = match options.canonicalize {
+                CanonicalizeMode::Always => path.canonicalize().unwrap_or_else(|_| path.clone()),
+                CanonicalizeMode::Never => path.clone(),
+            };
+            skill
+                .dir
+                .starts_with(&path)
+                .then(|| (path.components().count(), *level))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, level)| level)
+}
+
+/// Push a text element as three lines: opening tag, escaped text, and
+/// closing tag. Every field of a `<skill>` goes through here, so all of
+/// them are escaped the same way.
+fn push_element(lines: &mut Vec<String>, tag: &str, text: &str) {
+    lines.push(format!("<{}>", tag));
+    lines.push(html_escape(text));
+    lines.push(format!("</{}>", tag));
+}
+
+/// Lines for a single `<skill>` element, without an inlined body.
+fn skill_lines(skill: &Skill, options: &PromptOptions) -> Vec<String> {
+    let mut lines = vec!["<skill>".to_string()];
+    push_element(&mut lines, "name", &skill.properties.name);
+    push_element(
+        &mut lines,
+        "description",
+        &prompt_description(skill, options),
+    );
+
+    if options.include_ids
+        && let Some(id) = skill.properties.stable_id()
+    {
+        push_element(&mut lines, "id", id);
+    }
+
+    if skill.properties.is_deprecated() {
+        push_element(
+            &mut lines,
+            "deprecated",
+            skill
+                .properties
+                .deprecation_message()
+                .unwrap_or(DEFAULT_DEPRECATION_MESSAGE),
+        );
+    }
+
+    if let Some(level) = trust_level(skill, options) {
+        push_element(&mut lines, "trust", level.as_str());
+    }
+
+    let location = skill.skill_md.to_string_lossy();
+    match &options.redact_paths {
+        // The placeholder is tag-like, so redacted locations are escaped
+        // in every version.
+        Some(redaction) => push_element(&mut lines, "location", &redaction.redact(&location)),
+        None if options.format_version == PromptFormatVersion::V1 => {
+            lines.push("<location>".to_string());
+            lines.push(location.into_owned());
+            lines.push("</location>".to_string());
+        }
+        None => push_element(&mut lines, "location", &location),
+    }
 
-    Ok(lines.join("\n"))
+    lines.push("</skill>".to_string());
+    lines
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     use tempfile::TempDir;
 
     fn create_skill(dir: &TempDir, name: &str, description: &str) -> std::path::PathBuf {
@@ -168,6 +1387,968 @@ description: A skill with <special> & "characters"
         assert!(result.contains("&quot;characters&quot;"));
     }
 
+    #[test]
+    fn test_escaping_drops_xml_invalid_characters() {
+        assert_eq!(
+            html_escape("a\u{0}b\u{8}c\u{1b}[0m\u{1f}d\u{fffe}e\u{ffff}f"),
+            "abc[0mdef"
+        );
+        assert_eq!(html_escape("tab\tnew\nline\r"), "tab\tnew\nline\r");
+        // DEL, C1 controls, and other noncharacters are allowed in XML.
+        assert_eq!(
+            html_escape("\u{7f}\u{85}\u{fdd0}\u{1fffe}"),
+            "\u{7f}\u{85}\u{fdd0}\u{1fffe}"
+        );
+
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("test-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        // YAML escapes in the frontmatter, a raw BEL in the body.
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: test-skill\ndescription: \"Fill\\b forms\\uFFFF\"\nmetadata:\n  deprecated: \"true\"\n  deprecated-message: \"Use\\e pdf\"\n---\nStep\u{7} one\n",
+        )
+        .unwrap();
+
+        let options = PromptOptions {
+            include_deprecated: true,
+            inline_body: InlineBody::Always,
+            ..Default::default()
+        };
+        let xml = to_prompt_with_options(&[skill_dir.as_path()], &options).unwrap();
+        assert!(
+            !xml.chars()
+                .any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+        );
+        let entries = parse_prompt(&xml).unwrap();
+        assert_eq!(entries[0].description, "Fill forms");
+        assert_eq!(entries[0].deprecated.as_deref(), Some("Use pdf"));
+        assert_eq!(entries[0].instructions.as_deref(), Some("Step one"));
+    }
+
+    /// A skill whose description has tags, quotes inside and around
+    /// them, and a comparison that is not a tag.
+    fn create_markup_skill(dir: &TempDir) -> std::path::PathBuf {
+        let skill_dir = dir.path().join("markup-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: markup-skill\ndescription: 'Wraps output in <result kind=\"a > b\">''quoted'' \"text\"</result> tags when a < b'\n---\n# Body\n",
+        )
+        .unwrap();
+        skill_dir
+    }
+
+    #[test]
+    fn test_markup_tokens() {
+        let text = r#"in <result kind="a > b">'x'</result> <br/> a < b, x<y, <a title='<'>"#;
+        let tokens: Vec<&str> = markup_tokens(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect();
+        assert_eq!(
+            tokens,
+            [
+                r#"<result kind="a > b">"#,
+                "</result>",
+                "<br/>",
+                "<a title='<'>"
+            ]
+        );
+        assert!(markup_tokens("a < b > c, <unclosed\n>, <\"quoted\">").is_empty());
+        assert_eq!(
+            strip_markup("Wraps <b>bold</b> text in <tags>"),
+            "Wraps bold text in"
+        );
+    }
+
+    #[test]
+    fn test_description_sanitizer_escape_only_is_unchanged() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_markup_skill(&dir);
+        let default = render_prompt(&[skill_dir.as_path()], &PromptOptions::default()).unwrap();
+        let escape_only = render_prompt(
+            &[skill_dir.as_path()],
+            &PromptOptions::default().with_description_sanitizer(Sanitizer::EscapeOnly),
+        )
+        .unwrap();
+        assert_eq!(escape_only, default);
+        assert!(default.xml.contains(
+            "<description>\nWraps output in &lt;result kind=&quot;a &gt; b&quot;&gt;&#x27;quoted&#x27; &quot;text&quot;&lt;/result&gt; tags when a &lt; b\n</description>"
+        ));
+        assert_eq!(default.included[0].original_description, None);
+    }
+
+    #[test]
+    fn test_description_sanitizer_strip_markup() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_markup_skill(&dir);
+        let plain = create_skill(&dir, "plain-skill", "No tags here");
+        let options = PromptOptions::default().with_description_sanitizer(Sanitizer::StripMarkup);
+        let render = render_prompt(&[skill_dir.as_path(), plain.as_path()], &options).unwrap();
+
+        assert!(render.xml.contains(
+            "<description>\nWraps output in &#x27;quoted&#x27; &quot;text&quot; tags when a &lt; b\n</description>"
+        ));
+        assert_eq!(
+            render.included[0].original_description.as_deref(),
+            Some(
+                r#"Wraps output in <result kind="a > b">'quoted' "text"</result> tags when a < b"#
+            )
+        );
+        assert_eq!(render.included[1].original_description, None);
+
+        // A description of nothing but tags is escaped rather than emptied.
+        let tags_only = create_skill(&dir, "tags-only", "<result></result>");
+        let tags_render = render_prompt(&[tags_only.as_path()], &options).unwrap();
+        assert!(
+            tags_render
+                .xml
+                .contains("<description>\n&lt;result&gt;&lt;/result&gt;\n</description>"),
+            "{}",
+            tags_render.xml
+        );
+        assert_eq!(tags_render.included[0].original_description, None);
+        assert!(
+            render
+                .xml
+                .contains("<description>\nNo tags here\n</description>")
+        );
+    }
+
+    #[test]
+    fn test_description_sanitizer_reject() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_markup_skill(&dir);
+        let plain = create_skill(&dir, "plain-skill", "No tags here");
+        let options = PromptOptions::default().with_description_sanitizer(Sanitizer::Reject);
+        let render = render_prompt(&[skill_dir.as_path(), plain.as_path()], &options).unwrap();
+
+        assert_eq!(render.included.len(), 1);
+        assert_eq!(render.included[0].name, "plain-skill");
+        assert_eq!(render.excluded.len(), 1);
+        assert_eq!(render.excluded[0].name, "markup-skill");
+        assert_eq!(
+            render.excluded[0].reason,
+            r#"Description contains tag-like text '<result kind="a > b">'"#
+        );
+    }
+
+    #[test]
+    fn test_host_excludes_incompatible_skills() {
+        let dir = TempDir::new().unwrap();
+        let plain = create_skill(&dir, "plain-skill", "No tool requirements");
+        let git = dir.path().join("git-skill");
+        std::fs::create_dir_all(&git).unwrap();
+        std::fs::write(
+            git.join("SKILL.md"),
+            "---\nname: git-skill\ndescription: Uses git\nallowed-tools: Bash(git:*)\n---\n",
+        )
+        .unwrap();
+        let fetch = dir.path().join("fetch-skill");
+        std::fs::create_dir_all(&fetch).unwrap();
+        std::fs::write(
+            fetch.join("SKILL.md"),
+            "---\nname: fetch-skill\ndescription: Fetches\nallowed-tools: WebFetch\n---\n",
+        )
+        .unwrap();
+
+        let options = PromptOptions {
+            host: Some(HostCapabilities::with_tools(["Bash", "Read"])),
+            ..Default::default()
+        };
+        let (xml, excluded) =
+            to_prompt_with_exclusions(&[plain.as_path(), git.as_path(), fetch.as_path()], &options)
+                .unwrap();
+
+        assert!(xml.contains("plain-skill"));
+        assert!(xml.contains("git-skill"));
+        assert!(!xml.contains("fetch-skill"));
+        assert_eq!(excluded.len(), 1);
+        assert_eq!(excluded[0].name, "fetch-skill");
+        assert!(excluded[0].reason.contains("WebFetch"));
+    }
+
+    #[test]
+    fn test_without_host_nothing_excluded() {
+        let dir = TempDir::new().unwrap();
+        let fetch = dir.path().join("fetch-skill");
+        std::fs::create_dir_all(&fetch).unwrap();
+        std::fs::write(
+            fetch.join("SKILL.md"),
+            "---\nname: fetch-skill\ndescription: Fetches\nallowed-tools: WebFetch\n---\n",
+        )
+        .unwrap();
+
+        let (xml, excluded) =
+            to_prompt_with_exclusions(&[fetch.as_path()], &PromptOptions::default()).unwrap();
+        assert!(xml.contains("fetch-skill"));
+        assert!(excluded.is_empty());
+    }
+
+    fn create_skill_with_body(dir: &TempDir, name: &str, body: &str) -> std::path::PathBuf {
+        let skill_dir = dir.path().join(name);
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            format!("---\nname: {}\ndescription: d\n---\n{}\n", name, body),
+        )
+        .unwrap();
+        skill_dir
+    }
+
+    /// Render the prompt fixtures and compare with a golden file, with the
+    /// fixtures directory replaced by `{FIXTURES}`.
+    fn assert_golden(options: &PromptOptions, golden: &str) {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+        let fixtures = root.join("fixtures/prompt").canonicalize().unwrap();
+        let dirs: Vec<_> = ["pdf-tools", "escaping", "inline-body"]
+            .iter()
+            .map(|name| fixtures.join(name))
+            .collect();
+        let refs: Vec<&Path> = dirs.iter().map(|p| p.as_path()).collect();
+
+        let xml = to_prompt_with_options(&refs, options)
+            .unwrap()
+            .replace(fixtures.to_string_lossy().as_ref(), "{FIXTURES}");
+        let expected = std::fs::read_to_string(root.join("golden").join(golden)).unwrap();
+        assert_eq!(
+            xml,
+            expected.trim_end_matches('\n'),
+            "prompt format {:?} output changed; add a new PromptFormatVersion instead",
+            options.format_version
+        );
+    }
+
+    #[test]
+    fn test_prompt_v1_golden() {
+        let options = PromptOptions {
+            format_version: PromptFormatVersion::V1,
+            ..Default::default()
+        };
+        assert_golden(&options, "prompt_v1.xml");
+        assert_golden(
+            &PromptOptions {
+                inline_body: InlineBody::Always,
+                ..options
+            },
+            "prompt_v1_inline.xml",
+        );
+    }
+
+    #[test]
+    fn test_prompt_v2_golden() {
+        let options = PromptOptions {
+            format_version: PromptFormatVersion::V2,
+            ..Default::default()
+        };
+        assert_golden(&options, "prompt_v2.xml");
+    }
+
+    #[test]
+    fn test_inline_body_never_by_default() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(&dir, "my-skill", "A test skill");
+
+        let result = to_prompt(&[skill_dir.as_path()]).unwrap();
+        assert!(!result.contains("<instructions>"));
+    }
+
+    #[test]
+    fn test_inline_body_always() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill_with_body(&dir, "my-skill", "# Steps\n1. Do it");
+
+        let options = PromptOptions {
+            inline_body: InlineBody::Always,
+            ..Default::default()
+        };
+        let render = render_prompt(&[skill_dir.as_path()], &options).unwrap();
+        let lines: Vec<&str> = render.xml.lines().collect();
+
+        let start = lines.iter().position(|l| *l == "<instructions>").unwrap();
+        assert_eq!(lines[start - 1], "</location>");
+        assert_eq!(lines[start + 1], "# Steps");
+        assert_eq!(lines[start + 2], "1. Do it");
+        assert_eq!(lines[start + 3], "</instructions>");
+        assert_eq!(lines[start + 4], "</skill>");
+        assert!(render.notices.is_empty());
+    }
+
+    #[test]
+    fn test_inline_body_is_escaped() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill_with_body(&dir, "my-skill", "Use <tag> & stop at ]]> here");
+
+        let options = PromptOptions {
+            inline_body: InlineBody::Always,
+            ..Default::default()
+        };
+        let xml = to_prompt_with_options(&[skill_dir.as_path()], &options).unwrap();
+        assert!(xml.contains("Use &lt;tag&gt; &amp; stop at ]]&gt; here"));
+        assert!(!xml.contains("<tag>"));
+        assert!(!xml.contains("CDATA"));
+    }
+
+    #[test]
+    fn test_inline_body_under_chars() {
+        let dir = TempDir::new().unwrap();
+        let short = create_skill_with_body(&dir, "short-skill", "Short");
+        let long = create_skill_with_body(&dir, "long-skill", &"x".repeat(50));
+
+        let options = PromptOptions {
+            inline_body: InlineBody::UnderChars(20),
+            ..Default::default()
+        };
+        let xml = to_prompt_with_options(&[short.as_path(), long.as_path()], &options).unwrap();
+        assert_eq!(xml.matches("<instructions>").count(), 1);
+        assert!(xml.contains("Short"));
+        assert!(!xml.contains(&"x".repeat(50)));
+    }
+
+    #[test]
+    fn test_inline_body_respects_budget() {
+        let dir = TempDir::new().unwrap();
+        let first = create_skill_with_body(&dir, "first-skill", "First body");
+        let second = create_skill_with_body(&dir, "second-skill", &"y".repeat(200));
+
+        let base = to_prompt(&[first.as_path(), second.as_path()]).unwrap();
+        let options = PromptOptions {
+            inline_body: InlineBody::Always,
+            max_chars: Some(base.chars().count() + 100),
+            ..Default::default()
+        };
+        let render = render_prompt(&[first.as_path(), second.as_path()], &options).unwrap();
+
+        assert!(render.xml.contains("First body"));
+        assert!(!render.xml.contains(&"y".repeat(200)));
+        assert!(render.xml.chars().count() <= options.max_chars.unwrap());
+        assert_eq!(render.notices.len(), 1);
+        assert!(render.notices[0].contains("second-skill"));
+        // The skill itself stays in the prompt with its location.
+        assert_eq!(render.xml.matches("<location>").count(), 2);
+    }
+
+    #[test]
+    fn test_render_reports_included_skills() {
+        let dir = TempDir::new().unwrap();
+        let first = create_skill_with_body(&dir, "first-skill", "First body");
+        let second = create_skill_with_body(&dir, "second-skill", &"y".repeat(200));
+        let retired = dir.path().join("retired");
+        std::fs::create_dir_all(&retired).unwrap();
+        std::fs::write(
+            retired.join("SKILL.md"),
+            "---\nname: retired\ndescription: Old\nmetadata:\n  deprecated: \"true\"\n---\n",
+        )
+        .unwrap();
+
+        let base = to_prompt(&[first.as_path(), second.as_path()]).unwrap();
+        let options = PromptOptions {
+            inline_body: InlineBody::Always,
+            max_chars: Some(base.chars().count() + 100),
+            ..Default::default()
+        };
+        let refs = [first.as_path(), retired.as_path(), second.as_path()];
+        let render = render_prompt(&refs, &options).unwrap();
+
+        let included: Vec<_> = render
+            .included
+            .iter()
+            .map(|s| (s.name.as_str(), s.path.clone(), s.digest.clone()))
+            .collect();
+        assert_eq!(
+            included,
+            [
+                (
+                    "first-skill",
+                    first.canonicalize().unwrap(),
+                    Skill::load(&first).unwrap().digest
+                ),
+                (
+                    "second-skill",
+                    second.canonicalize().unwrap(),
+                    Skill::load(&second).unwrap().digest
+                ),
+            ]
+        );
+        // Only the first body was inlined, and each entry's size adds up to
+        // the whole prompt with the enclosing element and newlines.
+        assert!(render.included[0].chars > render.included[1].chars);
+        let entries: usize = render.included.iter().map(|s| s.chars + 1).sum();
+        assert_eq!(
+            render.xml.chars().count(),
+            "<available_skills>\n</available_skills>".len() + entries
+        );
+        assert_eq!(render.excluded.len(), 1);
+        assert_eq!(render.excluded[0].name, "retired");
+        assert_eq!(render.notices.len(), 1);
+    }
+
+    #[test]
+    fn test_inline_body_budget_is_exact() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill_with_body(&dir, "my-skill", "Body");
+
+        let always = PromptOptions {
+            inline_body: InlineBody::Always,
+            ..Default::default()
+        };
+        let full = to_prompt_with_options(&[skill_dir.as_path()], &always).unwrap();
+        let fits = PromptOptions {
+            max_chars: Some(full.chars().count()),
+            ..always.clone()
+        };
+        let render = render_prompt(&[skill_dir.as_path()], &fits).unwrap();
+        assert_eq!(render.xml, full);
+        assert!(render.notices.is_empty());
+    }
+
+    #[test]
+    fn test_prompt_pages() {
+        let dir = TempDir::new().unwrap();
+        let skills: Vec<_> = ["skill-a", "skill-b", "skill-c"]
+            .iter()
+            .map(|name| create_skill(&dir, name, "A skill"))
+            .collect();
+        let refs: Vec<&Path> = skills.iter().map(|p| p.as_path()).collect();
+        let options = PromptOptions::default();
+
+        let two = to_prompt(&refs[..2]).unwrap().chars().count();
+        let pages = prompt_pages(&refs, &options, two).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0], to_prompt(&refs[..2]).unwrap());
+        assert_eq!(pages[1], to_prompt(&refs[2..]).unwrap());
+        assert!(pages.iter().all(|p| p.chars().count() <= two));
+
+        let all = to_prompt(&refs).unwrap();
+        let pages = prompt_pages(&refs, &options, all.chars().count()).unwrap();
+        assert_eq!(pages, vec![all]);
+
+        assert_eq!(
+            prompt_pages(&[], &options, 100).unwrap(),
+            vec![to_prompt(&[]).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_prompt_pages_skill_too_large() {
+        let dir = TempDir::new().unwrap();
+        let small = create_skill(&dir, "small-skill", "A");
+        let large = create_skill(&dir, "large-skill", &"d".repeat(300));
+
+        let limit = to_prompt(&[small.as_path()]).unwrap().chars().count() + 10;
+        let err = prompt_pages(
+            &[small.as_path(), large.as_path()],
+            &PromptOptions::default(),
+            limit,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("'large-skill'"));
+    }
+
+    #[test]
+    fn test_duplicates_keep_highest_version() {
+        let dir = TempDir::new().unwrap();
+        let mut dirs = Vec::new();
+        for (rel, version) in [("old", "1.9.0"), ("new", "1.10.0")] {
+            let skill_dir = dir.path().join(rel).join("pdf");
+            std::fs::create_dir_all(&skill_dir).unwrap();
+            std::fs::write(
+                skill_dir.join("SKILL.md"),
+                format!(
+                    "---\nname: pdf\ndescription: {rel}\nmetadata:\n  version: {version}\n---\n"
+                ),
+            )
+            .unwrap();
+            dirs.push(skill_dir);
+        }
+        let refs: Vec<&Path> = dirs.iter().map(|d| d.as_path()).collect();
+
+        let options = PromptOptions {
+            duplicates: DuplicatePolicy::KeepHighestVersion,
+            ..Default::default()
+        };
+        let render = render_prompt(&refs, &options).unwrap();
+        assert_eq!(render.xml.matches("<skill>").count(), 1);
+        assert!(render.xml.contains("\nnew\n"));
+        assert_eq!(render.excluded.len(), 1);
+        assert!(render.excluded[0].reason.contains("higher version"));
+
+        let xml = to_prompt(&refs).unwrap();
+        assert_eq!(xml.matches("<skill>").count(), 2);
+    }
+
+    #[test]
+    fn test_load_error_names_skill() {
+        let dir = TempDir::new().unwrap();
+        let good = create_skill(&dir, "good-skill", "Fine");
+        let bad = dir.path().join("bad-skill");
+        std::fs::create_dir_all(&bad).unwrap();
+        std::fs::write(bad.join("SKILL.md"), "---\nname: [unclosed\n---\n").unwrap();
+
+        let err = to_prompt(&[good.as_path(), bad.as_path()]).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("bad-skill"), "{}", message);
+        assert!(message.contains("Invalid YAML"), "{}", message);
+    }
+
+    #[test]
+    fn test_deprecated_excluded_unless_included() {
+        let dir = TempDir::new().unwrap();
+        let current = create_skill(&dir, "pdf-tools", "Current skill");
+        let retired = dir.path().join("pdf");
+        std::fs::create_dir_all(&retired).unwrap();
+        std::fs::write(
+            retired.join("SKILL.md"),
+            "---\nname: pdf\ndescription: Old skill\nmetadata:\n  deprecated: \"true\"\n  deprecated-message: Use <pdf-tools>\n---\n",
+        )
+        .unwrap();
+        let refs = [current.as_path(), retired.as_path()];
+
+        let render = render_prompt(&refs, &PromptOptions::default()).unwrap();
+        assert_eq!(render.xml.matches("<skill>").count(), 1);
+        assert!(!render.xml.contains("<deprecated>"));
+        assert_eq!(render.excluded.len(), 1);
+        assert_eq!(render.excluded[0].name, "pdf");
+        assert_eq!(render.excluded[0].reason, "Deprecated: Use <pdf-tools>");
+
+        let options = PromptOptions {
+            include_deprecated: true,
+            ..Default::default()
+        };
+        let render = render_prompt(&refs, &options).unwrap();
+        assert!(render.excluded.is_empty());
+        assert!(render.xml.contains(
+            "</description>\n<deprecated>\nUse &lt;pdf-tools&gt;\n</deprecated>\n<location>"
+        ));
+
+        let entries = parse_prompt(&render.xml).unwrap();
+        assert_eq!(entries[0].deprecated, None);
+        assert_eq!(entries[1].deprecated.as_deref(), Some("Use <pdf-tools>"));
+    }
+
+    #[test]
+    fn test_empty_policy() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("old");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: old\ndescription: d\nmetadata:\n  deprecated: \"true\"\n---\n",
+        )
+        .unwrap();
+        let dirs = [skill_dir.as_path()];
+
+        let render = render_prompt(&dirs, &PromptOptions::default()).unwrap();
+        assert_eq!(render.xml, "<available_skills>\n</available_skills>");
+
+        let options = PromptOptions {
+            on_empty: EmptyPolicy::Error,
+            ..Default::default()
+        };
+        let err = render_prompt(&dirs, &options).unwrap_err();
+        let location = skill_dir.canonicalize().unwrap();
+        assert_eq!(
+            err.errors().unwrap(),
+            [format!("{}: Deprecated", location.display())]
+        );
+        assert!(prompt_pages(&dirs, &options, 1000).is_err());
+        assert_eq!(
+            render_prompt(&[], &options).unwrap_err().to_string(),
+            "No skills given for the prompt"
+        );
+
+        let options = PromptOptions {
+            include_deprecated: true,
+            ..options
+        };
+        assert!(render_prompt(&dirs, &options).is_ok());
+    }
+
+    #[test]
+    fn test_verify_locations() {
+        let dir = TempDir::new().unwrap();
+        let a = create_skill(&dir, "skill-a", "A");
+        let b = create_skill(&dir, "skill-b", "B");
+        let dirs = [a.as_path(), b.as_path()];
+
+        let render = render_prompt(&dirs, &PromptOptions::default()).unwrap();
+        assert!(render.included.iter().all(|skill| !skill.location_verified));
+        let options = PromptOptions::default().with_verify_locations(true);
+        let render = render_prompt(&dirs, &options).unwrap();
+        assert!(render.included.iter().all(|skill| skill.location_verified));
+        assert!(render.excluded.is_empty());
+
+        // A SKILL.md deleted after the skill was loaded
+        let skills = load_all_for_prompt(&dirs, CanonicalizeMode::Never)
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        std::fs::remove_file(b.join("SKILL.md")).unwrap();
+        let err = verify_locations(skills.clone(), UnreadablePolicy::Error).unwrap_err();
+        assert!(err.to_string().contains("cannot be read"), "{}", err);
+        assert!(matches!(err, SkillError::InSkill { ref path, .. } if path == &b));
+        let (verified, excluded) = verify_locations(skills, UnreadablePolicy::Exclude).unwrap();
+        assert_eq!(verified.len(), 1);
+        assert_eq!(verified[0].properties.name, "skill-a");
+        assert_eq!(excluded.len(), 1);
+        assert_eq!(excluded[0].name, "skill-b");
+        assert!(
+            excluded[0].reason.starts_with(&format!(
+                "Location {} cannot be read: ",
+                b.join("SKILL.md").display()
+            )),
+            "{}",
+            excluded[0].reason
+        );
+    }
+
+    #[test]
+    fn test_prompt_overrides() {
+        let dir = TempDir::new().unwrap();
+        let pdf = create_skill(&dir, "pdf-tools", "Work with PDF files in many ways");
+        let legacy = create_skill(&dir, "legacy-pdf", "Old skill");
+        let overrides = PromptOverrides::from_yaml(
+            "exclude: [legacy-pdf, gone]\noverride_description:\n  pdf-tools: Fill <PDF> forms\n",
+        )
+        .unwrap();
+        let options = PromptOptions {
+            overrides: Some(overrides),
+            ..Default::default()
+        };
+
+        let render = render_prompt(&[pdf.as_path(), legacy.as_path()], &options).unwrap();
+        assert!(
+            render
+                .xml
+                .contains("<description>\nFill &lt;PDF&gt; forms\n</description>")
+        );
+        assert!(!render.xml.contains("legacy-pdf"));
+        assert_eq!(render.excluded.len(), 1);
+        assert_eq!(render.excluded[0].reason, "Excluded by prompt overrides");
+        assert_eq!(
+            render.warnings,
+            ["Prompt overrides name unknown skill 'gone'"]
+        );
+    }
+
+    #[test]
+    fn test_parse_prompt_round_trip_with_location_and_body() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("a&b<c>").join("my-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: my-skill\ndescription: Use <tags> & \"quotes\"\n---\nLine 1\n\nLine <3>\n",
+        )
+        .unwrap();
+
+        let options = PromptOptions {
+            inline_body: InlineBody::Always,
+            format_version: PromptFormatVersion::V2,
+            ..Default::default()
+        };
+        let xml = to_prompt_with_options(&[skill_dir.as_path()], &options).unwrap();
+        assert!(xml.contains("a&amp;b&lt;c&gt;"), "{}", xml);
+        let entries = parse_prompt(&xml).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "my-skill");
+        assert_eq!(entries[0].description, "Use <tags> & \"quotes\"");
+        let location = skill_dir.canonicalize().unwrap().join("SKILL.md");
+        assert_eq!(
+            entries[0].location.as_deref(),
+            Some(location.to_string_lossy().as_ref())
+        );
+        assert_eq!(
+            entries[0].instructions.as_deref(),
+            Some("Line 1\n\nLine <3>")
+        );
+
+        assert!(parse_prompt(&to_prompt(&[]).unwrap()).unwrap().is_empty());
+
+        // V1 keeps emitting the location as is.
+        let options = options.with_format_version(PromptFormatVersion::V1);
+        let xml = to_prompt_with_options(&[skill_dir.as_path()], &options).unwrap();
+        assert!(
+            xml.contains(&format!("<location>\n{}\n</location>", location.display())),
+            "{}",
+            xml
+        );
+    }
+
+    #[test]
+    fn test_include_ids() {
+        let dir = TempDir::new().unwrap();
+        let with_id = dir.path().join("pdf");
+        std::fs::create_dir_all(&with_id).unwrap();
+        std::fs::write(
+            with_id.join("SKILL.md"),
+            "---\nname: pdf\ndescription: d\nmetadata:\n  id: com.example.pdf\n---\n",
+        )
+        .unwrap();
+        let without_id = create_skill(&dir, "csv", "d");
+        let dirs = [with_id.as_path(), without_id.as_path()];
+
+        let xml = to_prompt(&dirs).unwrap();
+        assert!(!xml.contains("<id>"));
+
+        let options = PromptOptions {
+            include_ids: true,
+            ..Default::default()
+        };
+        let xml = to_prompt_with_options(&dirs, &options).unwrap();
+        assert!(xml.contains("</description>\n<id>\ncom.example.pdf\n</id>\n<location>"));
+        let entries = parse_prompt(&xml).unwrap();
+        assert_eq!(entries[0].id.as_deref(), Some("com.example.pdf"));
+        assert_eq!(entries[1].id, None);
+    }
+
+    #[test]
+    fn test_trust_levels() {
+        let dir = TempDir::new().unwrap();
+        let builtin = dir.path().join("builtin");
+        std::fs::create_dir_all(&builtin).unwrap();
+        let pdf = create_skill(&dir, "builtin/pdf", "d");
+        let csv = create_skill(&dir, "csv", "d");
+        let dirs = [pdf.as_path(), csv.as_path()];
+
+        let options = PromptOptions {
+            trust: vec![
+                (dir.path().to_path_buf(), TrustLevel::Community),
+                (builtin, TrustLevel::Builtin),
+            ],
+            ..Default::default()
+        };
+        let xml = to_prompt_with_options(&dirs, &options).unwrap();
+        assert!(xml.contains("</description>\n<trust>\nbuiltin\n</trust>\n<location>"));
+        let entries = parse_prompt(&xml).unwrap();
+        assert_eq!(entries[0].trust.as_deref(), Some("builtin"));
+        assert_eq!(entries[1].trust.as_deref(), Some("community"));
+
+        let entries = parse_prompt(&to_prompt(&dirs).unwrap()).unwrap();
+        assert!(entries.iter().all(|e| e.trust.is_none()));
+    }
+
+    #[test]
+    fn test_canonicalize_mode() {
+        let dir = TempDir::new().unwrap();
+        create_skill(&dir, "my-skill", "d");
+        std::fs::create_dir_all(dir.path().join("other")).unwrap();
+        let given = dir.path().join("other/../my-skill");
+
+        let location = |canonicalize| {
+            let options = PromptOptions {
+                canonicalize,
+                ..Default::default()
+            };
+            let xml = to_prompt_with_options(&[given.as_path()], &options).unwrap();
+            parse_prompt(&xml).unwrap()[0].location.clone().unwrap()
+        };
+        assert_eq!(
+            location(CanonicalizeMode::Always),
+            dir.path()
+                .canonicalize()
+                .unwrap()
+                .join("my-skill/SKILL.md")
+                .to_string_lossy()
+        );
+        assert_eq!(
+            location(CanonicalizeMode::Never),
+            given.join("SKILL.md").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_redacted_location_is_escaped() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("a&b");
+        let skill_dir = root.join("my-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: my-skill\ndescription: d\n---\n",
+        )
+        .unwrap();
+
+        let options = PromptOptions {
+            redact_paths: Some(PathRedaction::new(&root)),
+            ..Default::default()
+        };
+        let xml = to_prompt_with_options(&[skill_dir.as_path()], &options).unwrap();
+        assert!(
+            xml.contains("<location>\n&lt;ROOT&gt;/my-skill/SKILL.md\n</location>"),
+            "{}",
+            xml
+        );
+        let entries = parse_prompt(&xml).unwrap();
+        assert_eq!(
+            entries[0].location.as_deref(),
+            Some("<ROOT>/my-skill/SKILL.md")
+        );
+    }
+
+    #[test]
+    fn test_parse_prompt_errors() {
+        let cases = [
+            (
+                "<skills>",
+                "Line 1: expected <available_skills>, found '<skills>'",
+            ),
+            (
+                "<available_skills>\n<skill>\n<name>\na\n</name>\n<description>\nd\n</skill>",
+                "Line 8: expected </description>, found '</skill>'",
+            ),
+            (
+                "<available_skills>\n<skill>",
+                "Line 2: expected <name>, found end of input",
+            ),
+            (
+                "<available_skills>\n<skill>\n<name>\na<b\n</name>",
+                "Line 4: unescaped '<' in text",
+            ),
+            (
+                "<available_skills>\n<skill>\n<name>\n&nbsp;\n</name>",
+                "Line 4: unknown entity '&nbsp;'",
+            ),
+            (
+                "<available_skills>\n</available_skills>\nextra",
+                "Line 3: expected end of input, found 'extra'",
+            ),
+        ];
+        for (xml, message) in cases {
+            let err = parse_prompt(xml).unwrap_err();
+            assert!(err.to_string().contains(message), "{}: {}", message, err);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_prompt_round_trip(
+            name in "[a-z0-9]{1,8}(-[a-z0-9]{1,8}){0,3}",
+            description in "[a-zA-Z0-9 &<>\"';#\n\t\r\u{e9}\u{1F600}]{1,60}"
+                .prop_map(|s| s.trim().to_string())
+                .prop_filter("non-empty", |s| !s.is_empty()),
+        ) {
+            let skill = Skill {
+                dir: PathBuf::from("/nonexistent"),
+                skill_md: PathBuf::from("/nonexistent/SKILL.md"),
+                properties: crate::SkillProperties::new(&name, &description),
+                body: String::new(),
+                digest: String::new(),
+                root: None,
+                body_omitted: false,
+            };
+            let xml = render_skills(&[skill], &PromptOptions::default(), &HashSet::new()).xml;
+            let entries = parse_prompt(&xml).unwrap();
+            prop_assert_eq!(entries.len(), 1);
+            prop_assert_eq!(&entries[0].name, &name);
+            prop_assert_eq!(&entries[0].description, &description);
+        }
+    }
+
+    #[test]
+    fn test_cache_key_stable() {
+        let dir = TempDir::new().unwrap();
+        let a = create_skill(&dir, "skill-a", "A");
+        let b = create_skill(&dir, "skill-b", "B");
+        let options = PromptOptions::default();
+
+        let first = cache_key(&[a.as_path(), b.as_path()], &options).unwrap();
+        let second = cache_key(&[a.as_path(), b.as_path()], &options).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 64);
+
+        // The same skill through its SKILL.md path resolves identically.
+        let via_file = cache_key(&[a.join("SKILL.md").as_path(), b.as_path()], &options).unwrap();
+        assert_eq!(first, via_file);
+    }
+
+    #[test]
+    fn test_cache_key_tracks_inputs() {
+        let dir = TempDir::new().unwrap();
+        let a = create_skill(&dir, "skill-a", "A");
+        let b = create_skill(&dir, "skill-b", "B");
+        let base = cache_key(&[a.as_path(), b.as_path()], &PromptOptions::default()).unwrap();
+
+        let reordered = cache_key(&[b.as_path(), a.as_path()], &PromptOptions::default()).unwrap();
+        assert_ne!(base, reordered);
+
+        create_skill(&dir, "skill-a", "Edited");
+        let edited = cache_key(&[a.as_path(), b.as_path()], &PromptOptions::default()).unwrap();
+        assert_ne!(base, edited);
+    }
+
+    #[test]
+    fn test_cache_key_tracks_every_option() {
+        let dir = TempDir::new().unwrap();
+        let a = create_skill(&dir, "skill-a", "A");
+        let key = |options: &PromptOptions| cache_key(&[a.as_path()], options).unwrap();
+        let base = key(&PromptOptions::default());
+
+        let variants = [
+            PromptOptions {
+                host: Some(HostCapabilities::with_tools(["Bash"])),
+                ..Default::default()
+            },
+            PromptOptions {
+                inline_body: InlineBody::Always,
+                ..Default::default()
+            },
+            PromptOptions {
+                inline_body: InlineBody::UnderChars(10),
+                ..Default::default()
+            },
+            PromptOptions {
+                max_chars: Some(100),
+                ..Default::default()
+            },
+            PromptOptions {
+                include_ids: true,
+                ..Default::default()
+            },
+            PromptOptions {
+                trust: vec![(dir.path().to_path_buf(), TrustLevel::Local)],
+                ..Default::default()
+            },
+            PromptOptions {
+                canonicalize: CanonicalizeMode::Never,
+                ..Default::default()
+            },
+            PromptOptions::default().with_format_version(PromptFormatVersion::V2),
+            PromptOptions::default().with_redact_paths(PathRedaction::new(dir.path())),
+            PromptOptions::default().with_overrides(PromptOverrides {
+                exclude: vec!["skill-b".to_string()],
+                ..Default::default()
+            }),
+            PromptOptions::default().with_on_empty(EmptyPolicy::Error),
+            PromptOptions::default().with_description_sanitizer(Sanitizer::Reject),
+            PromptOptions::default().with_verify_locations(true),
+            PromptOptions::default().with_on_unreadable(UnreadablePolicy::Error),
+        ];
+        let mut keys = vec![base];
+        for options in &variants {
+            let k = key(options);
+            assert!(!keys.contains(&k), "{:?} did not change the key", options);
+            keys.push(k);
+        }
+    }
+
+    #[test]
+    fn test_options_key_is_stable() {
+        let options = PromptOptions::default()
+            .with_host(HostCapabilities::with_tools(["Bash", "Read"]))
+            .with_trust("/skills", TrustLevel::Builtin);
+        assert_eq!(
+            options_key(&options),
+            "host=[\"Bash\",\"Read\"]\ninline_body=never\nmax_chars=none\n\
+             format_version=1\nduplicates=keep-all\ninclude_deprecated=false\n\
+             include_ids=false\nredact_paths=none\noverrides=none\non_empty=allow\n\
+             trust=[[\"/skills\",\"builtin\"]]\ncanonicalize=always\n\
+             description_sanitizer=escape-only\nverify_locations=false\n\
+             on_unreadable=exclude"
+        );
+    }
+
     #[test]
     fn test_output_format() {
         let dir = TempDir::new().unwrap();
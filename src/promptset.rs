@@ -0,0 +1,574 @@
+//! Named prompt compositions kept in a `promptset.yaml` file.
+//!
+//! Instead of passing skill paths and options to every prompt render, a
+//! checked-in file describes each prompt once:
+//!
+//! ```yaml
+//! sets:
+//!   default:
+//!     roots: [skills]
+//!   support-bot:
+//!     skills: [skills/pdf-tools, extra/ticket-triage]
+//!     order: name
+//!     overrides: prompt-overrides.yaml
+//!     inline_body: 2000
+//!     max_chars: 8000
+//!     tools: [Bash, Read]
+//!     trust:
+//!       skills: builtin
+//!       extra: community
+//! ```
+//!
+//! Paths are relative to the directory holding the file. A set takes the
+//! skills listed under `skills`, then every skill found under each of its
+//! `roots`. Its other keys match the options of `to-prompt`: `overrides`
+//! (a file, or the overrides written inline), `inline_body` (`true`,
+//! `false`, or a character limit), `max_chars`, `tools` (skills needing
+//! other tools are left out), `keep_highest_version`,
+//! `include_deprecated`, `include_ids`, `allow_empty`, `trust` (paths to
+//! trust levels), and `format_version`.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+use crate::discovery::find_skills;
+use crate::error::{Result, SkillError};
+use crate::host::HostCapabilities;
+use crate::overrides::PromptOverrides;
+use crate::parser::{read_properties, resolve_skill_dir};
+use crate::prompt::{
+    EmptyPolicy, InlineBody, PromptFormatVersion, PromptOptions, PromptRender, render_prompt,
+};
+use crate::skill_set::DuplicatePolicy;
+use crate::trust::TrustLevel;
+
+/// Name of the set used when a file has several and none is chosen.
+pub const DEFAULT_SET: &str = "default";
+
+/// Order of the skills in a composed prompt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum PromptOrder {
+    /// The listed skills in file order, then each root's skills in
+    /// discovery order (the default). A skill reached twice keeps its
+    /// first place.
+    #[default]
+    Given,
+
+    /// By skill name. Skills whose name cannot be read come last, by
+    /// path.
+    Name,
+}
+
+/// One named prompt of a [`PromptSet`], with its paths resolved.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PromptComposition {
+    /// Name of the set in the file.
+    pub name: String,
+
+    /// Directories whose skills are all included.
+    pub roots: Vec<PathBuf>,
+
+    /// Skill directories included by path.
+    pub skills: Vec<PathBuf>,
+
+    /// Order of the skills in the prompt.
+    pub order: PromptOrder,
+
+    /// Options the prompt is rendered with.
+    pub options: PromptOptions,
+}
+
+impl PromptComposition {
+    /// The skill directories of the prompt, in prompt order, each once.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`find_skills`] if a root cannot be searched.
+    pub fn skill_dirs(&self) -> Result<Vec<PathBuf>> {
+        let mut dirs = self.skills.clone();
+        for root in &self.roots {
+            dirs.extend(find_skills(root)?);
+        }
+        // A skill listed by path and found under a root again, or reached
+        // through different paths, is rendered once.
+        let mut seen = HashSet::new();
+        dirs.retain(|dir| seen.insert(dir.canonicalize().unwrap_or_else(|_| dir.clone())));
+        if self.order == PromptOrder::Name {
+            let mut named: Vec<(Option<String>, PathBuf)> = dirs
+                .into_iter()
+                .map(|dir| (read_properties(&dir).ok().map(|p| p.name), dir))
+                .collect();
+            named.sort_by(|(a, a_dir), (b, b_dir)| match (a, b) {
+                (Some(a), Some(b)) => crate::ordering::compare(a, b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => a_dir.cmp(b_dir),
+            });
+            dirs = named.into_iter().map(|(_, dir)| dir).collect();
+        }
+        Ok(dirs)
+    }
+
+    /// Render the prompt.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`skill_dirs`](Self::skill_dirs) and [`render_prompt`].
+    pub fn render(&self) -> Result<PromptRender> {
+        let dirs = self.skill_dirs()?;
+        let dirs: Vec<&Path> = dirs.iter().map(PathBuf::as_path).collect();
+        render_prompt(&dirs, &self.options)
+    }
+}
+
+/// The prompt compositions of a `promptset.yaml` file.
+#[derive(Debug, Clone)]
+pub struct PromptSet {
+    /// The file the sets were read from.
+    pub path: PathBuf,
+
+    /// The sets, by name.
+    pub sets: Vec<PromptComposition>,
+}
+
+impl PromptSet {
+    /// Read a prompt set file, resolving its paths against the file's
+    /// directory.
+    ///
+    /// # Errors
+    ///
+    /// * `IoError` - If the file cannot be read
+    /// * `ParseError` - If the file is not valid YAML or has unknown keys;
+    ///   the message gives the line
+    /// * `ValidationError` - Listing, with their lines in the file, every
+    ///   listed skill that does not exist, every root that is not a
+    ///   directory, overrides that cannot be loaded, and unsupported
+    ///   format versions
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let invalid = |detail: String| format!("Invalid prompt set {}: {}", path.display(), detail);
+        let file: RawFile =
+            serde_yaml::from_str(&text).map_err(|e| SkillError::parse(invalid(e.to_string())))?;
+        if file.sets.is_empty() {
+            return Err(SkillError::validation(invalid("no sets".to_string())));
+        }
+
+        let base = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let mut errors = Vec::new();
+        let mut sets = Vec::new();
+        for (name, raw) in file.sets {
+            let at = |key: &[Key]| {
+                let mut full = vec![Key::Field("sets"), Key::Field(&name)];
+                full.extend_from_slice(key);
+                match line_at(&text, &full) {
+                    Some(line) => format!("{}:{}", path.display(), line),
+                    None => path.display().to_string(),
+                }
+            };
+            let set = raw.resolve(name.clone(), base, &at, &mut errors);
+            sets.push(set);
+        }
+        if !errors.is_empty() {
+            return Err(SkillError::validation_multiple(
+                invalid(errors.join("; ")),
+                errors,
+            ));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            sets,
+        })
+    }
+
+    /// Names of the sets, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        self.sets.iter().map(|set| set.name.as_str()).collect()
+    }
+
+    /// The set called `name`, or without a name the file's only set or
+    /// the one called [`DEFAULT_SET`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` listing the sets if there is no such
+    /// set.
+    pub fn get(&self, name: Option<&str>) -> Result<&PromptComposition> {
+        let found = match name {
+            Some(name) => self.sets.iter().find(|set| set.name == name),
+            None if self.sets.len() == 1 => self.sets.first(),
+            None => self.sets.iter().find(|set| set.name == DEFAULT_SET),
+        };
+        found.ok_or_else(|| {
+            let names = self.names().join(", ");
+            SkillError::validation(match name {
+                Some(name) => format!(
+                    "No set '{}' in {}; sets: {}",
+                    name,
+                    self.path.display(),
+                    names
+                ),
+                None => format!(
+                    "{} has several sets and none called '{}'; choose one of: {}",
+                    self.path.display(),
+                    DEFAULT_SET,
+                    names
+                ),
+            })
+        })
+    }
+
+    /// Render the file's only set, or the one called [`DEFAULT_SET`].
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`get`](Self::get) and [`PromptComposition::render`].
+    pub fn render(&self) -> Result<PromptRender> {
+        self.get(None)?.render()
+    }
+
+    /// Render the set called `name`.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`get`](Self::get) and [`PromptComposition::render`].
+    pub fn render_set(&self, name: &str) -> Result<PromptRender> {
+        self.get(Some(name))?.render()
+    }
+}
+
+/// A prompt set file as written.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawFile {
+    sets: BTreeMap<String, RawSet>,
+}
+
+/// One set as written, before its paths are resolved.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawSet {
+    roots: Vec<String>,
+    skills: Vec<String>,
+    order: PromptOrder,
+    overrides: Option<serde_yaml::Value>,
+    inline_body: Option<RawInlineBody>,
+    max_chars: Option<usize>,
+    tools: Option<Vec<String>>,
+    keep_highest_version: bool,
+    include_deprecated: bool,
+    include_ids: bool,
+    allow_empty: bool,
+    trust: BTreeMap<String, TrustLevel>,
+    format_version: Option<u32>,
+}
+
+/// `inline_body` as written: on, off, or a character limit.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawInlineBody {
+    Switch(bool),
+    UnderChars(usize),
+}
+
+impl RawSet {
+    /// Resolve the set's paths against `base` and build its options,
+    /// adding a message located by `at` to `errors` for each problem.
+    fn resolve(
+        self,
+        name: String,
+        base: &Path,
+        at: &dyn Fn(&[Key]) -> String,
+        errors: &mut Vec<String>,
+    ) -> PromptComposition {
+        let mut skills = Vec::new();
+        for (i, entry) in self.skills.iter().enumerate() {
+            match resolve_skill_dir(&base.join(entry)) {
+                Ok(dir) => skills.push(dir),
+                Err(e) => errors.push(format!(
+                    "{}: skill '{}' not found: {}",
+                    at(&[Key::Field("skills"), Key::Index(i)]),
+                    entry,
+                    e
+                )),
+            }
+        }
+        let mut roots = Vec::new();
+        for (i, entry) in self.roots.iter().enumerate() {
+            let root = base.join(entry);
+            if root.is_dir() {
+                roots.push(root);
+            } else {
+                errors.push(format!(
+                    "{}: root '{}' is not a directory",
+                    at(&[Key::Field("roots"), Key::Index(i)]),
+                    entry
+                ));
+            }
+        }
+
+        let mut options = PromptOptions::default();
+        match &self.overrides {
+            None => {}
+            Some(serde_yaml::Value::String(file)) => {
+                match PromptOverrides::load(&base.join(file)) {
+                    Ok(overrides) => options.overrides = Some(overrides),
+                    Err(e) => errors.push(format!(
+                        "{}: overrides '{}': {}",
+                        at(&[Key::Field("overrides")]),
+                        file,
+                        e
+                    )),
+                }
+            }
+            Some(inline) => {
+                let text = serde_yaml::to_string(inline).unwrap_or_default();
+                match PromptOverrides::from_yaml(&text) {
+                    Ok(overrides) => options.overrides = Some(overrides),
+                    Err(e) => errors.push(format!("{}: {}", at(&[Key::Field("overrides")]), e)),
+                }
+            }
+        }
+        options.inline_body = match self.inline_body {
+            None | Some(RawInlineBody::Switch(false)) => InlineBody::Never,
+            Some(RawInlineBody::Switch(true)) => InlineBody::Always,
+            Some(RawInlineBody::UnderChars(max)) => InlineBody::UnderChars(max),
+        };
+        options.max_chars = self.max_chars;
+        options.host = self.tools.map(HostCapabilities::with_tools);
+        if self.keep_highest_version {
+            options.duplicates = DuplicatePolicy::KeepHighestVersion;
+        }
+        options.include_deprecated = self.include_deprecated;
+        options.include_ids = self.include_ids;
+        // As with `to-prompt`, a prompt left empty is an error unless
+        // asked for.
+        options.on_empty = if self.allow_empty {
+            EmptyPolicy::Allow
+        } else {
+            EmptyPolicy::Error
+        };
+        options.trust = self
+            .trust
+            .into_iter()
+            .map(|(path, level)| (base.join(path), level))
+            .collect();
+        if let Some(number) = self.format_version {
+            match PromptFormatVersion::ALL
+                .iter()
+                .find(|version| version.number() == number)
+            {
+                Some(version) => options.format_version = *version,
+                None => errors.push(format!(
+                    "{}: unsupported format_version {}",
+                    at(&[Key::Field("format_version")]),
+                    number
+                )),
+            }
+        }
+
+        PromptComposition {
+            name,
+            roots,
+            skills,
+            order: self.order,
+            options,
+        }
+    }
+}
+
+/// A step of the path to a value in a prompt set file.
+#[derive(Debug, Clone, Copy)]
+enum Key<'a> {
+    /// The value of a mapping key.
+    Field(&'a str),
+    /// An item of a sequence.
+    Index(usize),
+}
+
+/// The 1-based line where the value at `path` starts in YAML `text`, as
+/// the parser reports it.
+fn line_at(text: &str, path: &[Key]) -> Option<usize> {
+    // The parser only reports positions with errors, so fail on purpose
+    // when the value is reached.
+    let err = Probe(path)
+        .deserialize(serde_yaml::Deserializer::from_str(text))
+        .err()?;
+    err.location().map(|location| location.line())
+}
+
+/// Walks to the value at its path and fails there; every `visit_*` that
+/// is not overridden fails too.
+struct Probe<'a>(&'a [Key<'a>]);
+
+impl<'de> DeserializeSeed<'de> for Probe<'_> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Probe<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a mapping or sequence on the path")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<(), A::Error> {
+        let Some((key, rest)) = self.0.split_first() else {
+            return Err(de::Error::custom("reached"));
+        };
+        while let Some(name) = map.next_key::<serde_yaml::Value>()? {
+            if matches!(key, Key::Field(field) if name.as_str() == Some(*field)) {
+                map.next_value_seed(Probe(rest))?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+        let Some((key, rest)) = self.0.split_first() else {
+            return Err(de::Error::custom("reached"));
+        };
+        let mut index = 0;
+        loop {
+            let more = if matches!(key, Key::Index(i) if *i == index) {
+                seq.next_element_seed(Probe(rest))?.is_some()
+            } else {
+                seq.next_element::<IgnoredAny>()?.is_some()
+            };
+            if !more {
+                return Ok(());
+            }
+            index += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, rel: &str, content: &str) {
+        let path = dir.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn skill(dir: &Path, rel: &str, name: &str) {
+        write(
+            dir,
+            &format!("{}/SKILL.md", rel),
+            &format!(
+                "---\nname: {}\ndescription: Fill and merge PDF forms\n---\n",
+                name
+            ),
+        );
+    }
+
+    #[test]
+    fn test_load_and_render() {
+        let dir = TempDir::new().unwrap();
+        skill(dir.path(), "skills/pdf-tools", "pdf-tools");
+        skill(dir.path(), "skills/csv-tools", "csv-tools");
+        skill(dir.path(), "extra/archive", "archive");
+        write(
+            dir.path(),
+            "overrides.yaml",
+            "override_description:\n  archive: Zip files.\n",
+        );
+        write(
+            dir.path(),
+            "prompts/promptset.yaml",
+            "sets:\n  default:\n    roots: [../skills]\n  small:\n    skills:\n      - ../skills/pdf-tools/SKILL.md\n      - ../extra/archive\n    roots: [../skills]\n    order: name\n    overrides: ../overrides.yaml\n    include_ids: true\n",
+        );
+
+        let set = PromptSet::load(&dir.path().join("prompts/promptset.yaml")).unwrap();
+        assert_eq!(set.names(), ["default", "small"]);
+        let names = |render: PromptRender| -> Vec<String> {
+            render.included.into_iter().map(|s| s.name).collect()
+        };
+        assert_eq!(names(set.render().unwrap()), ["csv-tools", "pdf-tools"]);
+
+        let small = set.get(Some("small")).unwrap();
+        assert!(small.options.include_ids);
+        let render = set.render_set("small").unwrap();
+        assert!(render.xml.contains("Zip files."));
+        // pdf-tools is listed and found under the root; it is kept once.
+        assert_eq!(small.skill_dirs().unwrap().len(), 3);
+        assert_eq!(names(render), ["archive", "csv-tools", "pdf-tools"]);
+
+        let err = set.get(Some("nope")).unwrap_err().to_string();
+        assert!(err.ends_with("sets: default, small"), "{}", err);
+    }
+
+    #[test]
+    fn test_errors_name_lines() {
+        let dir = TempDir::new().unwrap();
+        skill(dir.path(), "skills/pdf-tools", "pdf-tools");
+        let path = dir.path().join("promptset.yaml");
+
+        std::fs::write(&path, "sets:\n  default:\n    rots: [skills]\n").unwrap();
+        let err = PromptSet::load(&path).unwrap_err().to_string();
+        assert!(err.contains("unknown field `rots`"), "{}", err);
+        assert!(err.contains("line 3"), "{}", err);
+
+        std::fs::write(
+            &path,
+            "sets:\n  default:\n    skills:\n      - skills/pdf-tools\n      - skills/missing\n    roots: [nowhere]\n",
+        )
+        .unwrap();
+        let err = PromptSet::load(&path).unwrap_err();
+        let errors = err.errors().unwrap();
+        assert_eq!(errors.len(), 2);
+        assert!(
+            errors[0].starts_with(&format!(
+                "{}:5: skill 'skills/missing' not found",
+                path.display()
+            )),
+            "{}",
+            errors[0]
+        );
+        assert_eq!(
+            errors[1],
+            format!("{}:6: root 'nowhere' is not a directory", path.display())
+        );
+
+        // The same path twice is located at each occurrence.
+        std::fs::write(
+            &path,
+            "sets:\n  a:\n    roots: [nowhere]\n  b:\n    roots: [skills, nowhere]\n    format_version: 9\n",
+        )
+        .unwrap();
+        let err = PromptSet::load(&path).unwrap_err();
+        let errors = err.errors().unwrap();
+        assert_eq!(
+            errors,
+            [
+                format!("{}:3: root 'nowhere' is not a directory", path.display()),
+                format!("{}:5: root 'nowhere' is not a directory", path.display()),
+                format!("{}:6: unsupported format_version 9", path.display()),
+            ]
+        );
+
+        std::fs::write(&path, "sets:\n  a:\n    roots: [skills]\n  b: {}\n").unwrap();
+        let err = PromptSet::load(&path).unwrap().render().unwrap_err();
+        assert!(err.to_string().contains("choose one of: a, b"), "{}", err);
+    }
+}
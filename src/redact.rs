@@ -0,0 +1,207 @@
+//! Replacing a path prefix in output, for machine-independent snapshots.
+//!
+//! Output that names files (prompt locations, validation messages, JSON
+//! reports) contains absolute paths, which differ between machines and
+//! temporary directories. A [`PathRedaction`] replaces a chosen prefix with
+//! a placeholder such as `<ROOT>`.
+//!
+//! Redaction works on unescaped text: prompts redact a location before
+//! XML-escaping it, and JSON is redacted value by value before it is
+//! serialized, so the placeholder is escaped like any other text.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Placeholder used unless another is given.
+pub const DEFAULT_PLACEHOLDER: &str = "<ROOT>";
+
+/// Replaces a path prefix with a placeholder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathRedaction {
+    /// Spellings of the prefix to replace: as given and, if different,
+    /// canonicalized.
+    prefixes: Vec<String>,
+    placeholder: String,
+}
+
+impl PathRedaction {
+    /// Redact `prefix` as [`DEFAULT_PLACEHOLDER`].
+    ///
+    /// If `prefix` exists, its canonical spelling (with symlinks such as
+    /// macOS's `/var` -> `/private/var` resolved) is redacted too.
+    pub fn new(prefix: &Path) -> Self {
+        let mut prefixes = vec![trim_separator(&prefix.to_string_lossy())];
+        if let Ok(real) = prefix.canonicalize() {
+            let real = trim_separator(&real.to_string_lossy());
+            if !prefixes.contains(&real) {
+                prefixes.push(real);
+            }
+        }
+        // Longer spellings first, so one that extends another wins.
+        prefixes.sort_by_key(|p| std::cmp::Reverse(p.len()));
+        prefixes.retain(|p| !p.is_empty());
+        Self {
+            prefixes,
+            placeholder: DEFAULT_PLACEHOLDER.to_string(),
+        }
+    }
+
+    /// Write paths under `root` relative to it, as `./pdf` for
+    /// `root/pdf`, for output that is the same wherever it is generated.
+    ///
+    /// A relative `root` is taken relative to the current directory.
+    pub fn relative_to(root: &Path) -> Self {
+        let root = std::env::current_dir()
+            .map(|cwd| cwd.join(root))
+            .unwrap_or_else(|_| root.to_path_buf());
+        let root = normalize(&root);
+        Self::new(&root).with_placeholder(".")
+    }
+
+    /// The prefixes and placeholder as JSON, for cache keys.
+    pub(crate) fn key(&self) -> String {
+        serde_json::json!([self.prefixes, self.placeholder]).to_string()
+    }
+
+    /// Use `placeholder` instead of [`DEFAULT_PLACEHOLDER`].
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Replace every occurrence of the prefix in `text`.
+    ///
+    /// An occurrence only counts if it ends at a path boundary, so a prefix
+    /// `/tmp/a` leaves `/tmp/ab` alone.
+    pub fn redact(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        'scan: while !rest.is_empty() {
+            for prefix in &self.prefixes {
+                if let Some(after) = rest.strip_prefix(prefix.as_str())
+                    && ends_path(after)
+                {
+                    out.push_str(&self.placeholder);
+                    rest = after;
+                    continue 'scan;
+                }
+            }
+            let c = rest.chars().next().unwrap_or_default();
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+        out
+    }
+
+    /// Redact every string (object keys included) in a JSON value.
+    pub fn redact_json(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::String(s) => *s = self.redact(s),
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.redact_json(item);
+                }
+            }
+            serde_json::Value::Object(map) => {
+                let entries = std::mem::take(map);
+                for (key, mut item) in entries {
+                    self.redact_json(&mut item);
+                    map.insert(self.redact(&key), item);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Drop one trailing separator, so `/tmp/x/` matches `/tmp/x`.
+fn trim_separator(prefix: &str) -> String {
+    let trimmed = prefix.trim_end_matches(['/', '\\']);
+    if trimmed.is_empty() {
+        prefix.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Whether a prefix match followed by `after` ends at a path boundary.
+fn ends_path(after: &str) -> bool {
+    match after.chars().next() {
+        None => true,
+        Some(c) => !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')),
+    }
+}
+
+/// `path` with `.` and `..` removed by spelling alone, to match how it
+/// appears in output text. Not for resolving paths that will be read.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_redact_text_at_path_boundaries() {
+        let redaction = PathRedaction::new(Path::new("/tmp/run-1/"));
+        assert_eq!(
+            redaction.redact("Valid skill: /tmp/run-1/pdf (not /tmp/run-12/pdf)"),
+            "Valid skill: <ROOT>/pdf (not /tmp/run-12/pdf)"
+        );
+        assert_eq!(redaction.redact("/tmp/run-1"), "<ROOT>");
+        assert_eq!(
+            redaction.with_placeholder("$ROOT").redact("'/tmp/run-1/a'"),
+            "'$ROOT/a'"
+        );
+    }
+
+    #[test]
+    fn test_redact_canonical_spelling() {
+        let dir = TempDir::new().unwrap();
+        let real = dir.path().canonicalize().unwrap();
+        let redaction = PathRedaction::new(dir.path());
+        assert_eq!(
+            redaction.redact(&format!("{}/pdf", real.display())),
+            "<ROOT>/pdf"
+        );
+    }
+
+    #[test]
+    fn test_relative_to() {
+        let dir = TempDir::new().unwrap();
+        let redaction = PathRedaction::relative_to(&dir.path().join("skills/.."));
+        assert_eq!(
+            redaction.redact(&format!("Valid skill: {}/pdf", dir.path().display())),
+            "Valid skill: ./pdf"
+        );
+        assert_eq!(redaction.redact(&dir.path().display().to_string()), ".");
+    }
+
+    #[test]
+    fn test_redact_json_before_escaping() {
+        let redaction = PathRedaction::new(Path::new(r"C:\skills"));
+        let mut value = serde_json::json!({
+            "path": r"C:\skills\pdf",
+            "errors": [r#"Bad "name" in C:\skills\pdf"#],
+            r"C:\skills\a": 1,
+        });
+        redaction.redact_json(&mut value);
+        assert_eq!(
+            value.to_string(),
+            r#"{"<ROOT>\\a":1,"errors":["Bad \"name\" in <ROOT>\\pdf"],"path":"<ROOT>\\pdf"}"#
+        );
+    }
+}
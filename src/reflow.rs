@@ -0,0 +1,374 @@
+//! Reflowing paragraphs of a skill body.
+//!
+//! Long single-line paragraphs make diffs hard to read, while hard-wrapped
+//! ones put mid-sentence newlines into prompts. [`reflow`] rewraps plain
+//! paragraphs to a width or joins them into single lines.
+//!
+//! Only paragraphs of unindented prose change. Fenced code blocks,
+//! headings, block quotes, tables, lists, HTML blocks, and indented lines
+//! are copied as they are; a block quote, table, list, or HTML block runs
+//! until the next blank line. Hard line breaks (two trailing spaces or a
+//! trailing backslash) are kept. Reflowing twice gives the same result as
+//! reflowing once.
+
+use crate::error::Result;
+use crate::markdown::{Fence, is_closing_fence, opening_fence};
+use crate::parser::split_frontmatter;
+
+/// How to reflow paragraphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Reflow {
+    /// Wrap paragraphs at this many characters. Words longer than the
+    /// width get a line of their own.
+    Wrap(usize),
+
+    /// Join each paragraph into a single line.
+    Unwrap,
+}
+
+/// Reflow the paragraphs of a markdown `body`.
+pub fn reflow(body: &str, mode: Reflow) -> String {
+    // Each line without its ending, and the ending (`\n`, `\r\n`, or none
+    // for a last line without one), which is kept as it is.
+    let (lines, endings): (Vec<&str>, Vec<&str>) = body
+        .split_inclusive('\n')
+        .map(|line| {
+            let content = line.strip_suffix('\n').unwrap_or(line);
+            let content = content.strip_suffix('\r').unwrap_or(content);
+            (content, &line[content.len()..])
+        })
+        .unzip();
+    let mut out = String::with_capacity(body.len());
+    let mut fence: Option<Fence> = None;
+    let mut verbatim_block = false;
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        if let Some(open) = &fence {
+            if is_closing_fence(line, open) {
+                fence = None;
+            }
+            out.push_str(line);
+            out.push_str(endings[i]);
+            i += 1;
+            continue;
+        }
+        if line.trim().is_empty() {
+            verbatim_block = false;
+            out.push_str(line);
+            out.push_str(endings[i]);
+            i += 1;
+            continue;
+        }
+        if let Some(open) = opening_fence(line) {
+            fence = Some(open);
+            out.push_str(line);
+            out.push_str(endings[i]);
+            i += 1;
+            continue;
+        }
+        if verbatim_block || line.starts_with([' ', '\t']) {
+            out.push_str(line);
+            out.push_str(endings[i]);
+            i += 1;
+            continue;
+        }
+        match block_start(line) {
+            Some(Block::SingleLine) => {
+                out.push_str(line);
+                out.push_str(endings[i]);
+                i += 1;
+                continue;
+            }
+            Some(Block::UntilBlank) => {
+                verbatim_block = true;
+                out.push_str(line);
+                out.push_str(endings[i]);
+                i += 1;
+                continue;
+            }
+            None => {}
+        }
+
+        // A paragraph: unindented lines up to a blank line or another block.
+        let start = i;
+        i += 1;
+        while i < lines.len() {
+            let next = lines[i];
+            if next.trim().is_empty()
+                || next.starts_with([' ', '\t'])
+                || opening_fence(next).is_some()
+                || block_start(next).is_some()
+            {
+                break;
+            }
+            i += 1;
+        }
+        let paragraph = &lines[start..i];
+
+        // A paragraph underlined with `===` or `---` is a setext heading;
+        // leave it.
+        let setext = lines.get(i).is_some_and(|next| {
+            let next = next.trim();
+            !next.is_empty() && (next.chars().all(|c| c == '=') || next.chars().all(|c| c == '-'))
+        });
+        if setext {
+            for (line, ending) in paragraph.iter().zip(&endings[start..i]) {
+                out.push_str(line);
+                out.push_str(ending);
+            }
+        } else {
+            // New lines end like the paragraph's first line; the last keeps
+            // its own ending.
+            let newline = match endings[start] {
+                "" => "\n",
+                ending => ending,
+            };
+            out.push_str(&reflow_paragraph(paragraph, mode).join(newline));
+            out.push_str(endings[i - 1]);
+        }
+    }
+
+    out
+}
+
+/// Reflow the body of SKILL.md `content`, leaving the frontmatter as it is.
+///
+/// # Errors
+///
+/// Returns `ParseError` if the content has no valid frontmatter delimiters.
+pub fn reflow_skill_md(content: &str, mode: Reflow) -> Result<String> {
+    let (_, body) = split_frontmatter(content)?;
+    let head = &content[..content.len() - body.len()];
+    Ok(format!("{}{}", head, reflow(body, mode)))
+}
+
+/// A block that is never reflowed.
+enum Block {
+    /// A heading or thematic break: just this line.
+    SingleLine,
+    /// A block quote, list, table, or HTML block: up to the next blank line.
+    UntilBlank,
+}
+
+/// The kind of verbatim block an unindented `line` starts, if any.
+fn block_start(line: &str) -> Option<Block> {
+    let trimmed = line.trim_end();
+    if trimmed.starts_with('#') || is_thematic_break(trimmed) || is_setext_underline(trimmed) {
+        return Some(Block::SingleLine);
+    }
+    let first_word = trimmed.split_whitespace().next().unwrap_or("");
+    let is_list_item = is_list_marker(first_word)
+        && (trimmed.len() == first_word.len() || trimmed[first_word.len()..].starts_with(' '));
+    if trimmed.starts_with(['>', '|', '<']) || is_list_item {
+        return Some(Block::UntilBlank);
+    }
+    None
+}
+
+/// `---`, `***`, or `___`, possibly with spaces between the characters.
+fn is_thematic_break(line: &str) -> bool {
+    let chars: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    chars.len() >= 3 && ['-', '*', '_'].iter().any(|m| chars.iter().all(|c| c == m))
+}
+
+/// A line of only `=`, which underlines a setext heading.
+fn is_setext_underline(line: &str) -> bool {
+    !line.is_empty() && line.chars().all(|c| c == '=')
+}
+
+/// `-`, `*`, `+`, or a number followed by `.` or `)`.
+fn is_list_marker(word: &str) -> bool {
+    if matches!(word, "-" | "*" | "+") {
+        return true;
+    }
+    let digits = word.trim_end_matches(['.', ')']);
+    word.len() == digits.len() + 1
+        && (1..=9).contains(&digits.len())
+        && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Whether a line starting with `word` could be read as something other
+/// than paragraph text. Wrapping never starts a line with such a word.
+fn could_start_block(word: &str) -> bool {
+    word.starts_with(['#', '>', '|', '<', '=', '`', '~'])
+        || is_list_marker(word)
+        || is_thematic_break(word)
+}
+
+/// Reflow the lines of one paragraph, keeping hard line breaks.
+fn reflow_paragraph(lines: &[&str], mode: Reflow) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut segment: Vec<&str> = Vec::new();
+    for line in lines {
+        let content = line.trim_end();
+        let hard_break = if line.ends_with("  ") {
+            Some(&line[content.len()..])
+        } else if content.ends_with('\\') {
+            Some("")
+        } else {
+            None
+        };
+        segment.push(content);
+        if let Some(suffix) = hard_break {
+            out.extend(reflow_segment(&segment, mode));
+            if let Some(last) = out.last_mut() {
+                last.push_str(suffix);
+            }
+            segment.clear();
+        }
+    }
+    if !segment.is_empty() {
+        out.extend(reflow_segment(&segment, mode));
+    }
+    out
+}
+
+/// Reflow lines that contain no hard break.
+fn reflow_segment(lines: &[&str], mode: Reflow) -> Vec<String> {
+    let width = match mode {
+        Reflow::Unwrap => return vec![lines.join(" ")],
+        Reflow::Wrap(width) => width,
+    };
+
+    let mut out: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    for word in lines.iter().flat_map(|l| l.split_whitespace()) {
+        let len = word.chars().count();
+        let fits = current_len + 1 + len <= width;
+        if current.is_empty() {
+            current.push_str(word);
+            current_len = len;
+        } else if fits || could_start_block(word) {
+            current.push(' ');
+            current.push_str(word);
+            current_len += 1 + len;
+        } else {
+            out.push(std::mem::take(&mut current));
+            current.push_str(word);
+            current_len = len;
+        }
+    }
+    out.push(current);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = "# Fill PDF forms
+
+This skill fills PDF forms using the bundled script and checks that every required field has a value before saving.
+
+Hard-wrapped
+paragraph that an author
+wrapped by hand.
+
+- List items stay as they are, even when they are much longer than the requested width.
+  - Nested items keep their indentation too.
+1. Numbered items as well
+lazy continuation line
+
+```python
+# A comment that is very long and must never be wrapped by the formatter at all
+fill(form,   fields)
+```
+
+> A block quote that is also rather long and stays exactly as written by its author.
+
+| Column | Another column that is long |
+|--------|-----------------------------|
+
+Line with a hard break\\
+next line.
+";
+
+    #[test]
+    fn test_wrap_leaves_blocks_alone() {
+        let wrapped = reflow(BODY, Reflow::Wrap(40));
+        let expected = "# Fill PDF forms
+
+This skill fills PDF forms using the
+bundled script and checks that every
+required field has a value before
+saving.
+
+Hard-wrapped paragraph that an author
+wrapped by hand.
+
+- List items stay as they are, even when they are much longer than the requested width.
+  - Nested items keep their indentation too.
+1. Numbered items as well
+lazy continuation line
+
+```python
+# A comment that is very long and must never be wrapped by the formatter at all
+fill(form,   fields)
+```
+
+> A block quote that is also rather long and stays exactly as written by its author.
+
+| Column | Another column that is long |
+|--------|-----------------------------|
+
+Line with a hard break\\
+next line.
+";
+        assert_eq!(wrapped, expected);
+        assert_eq!(reflow(&wrapped, Reflow::Wrap(40)), wrapped);
+    }
+
+    #[test]
+    fn test_unwrap_joins_paragraphs() {
+        let unwrapped = reflow(BODY, Reflow::Unwrap);
+        assert!(unwrapped.contains("\nHard-wrapped paragraph that an author wrapped by hand.\n"));
+        assert!(unwrapped.contains("\n- List items stay"));
+        assert!(unwrapped.contains("\n1. Numbered items as well\nlazy continuation line\n"));
+        assert!(unwrapped.contains("Line with a hard break\\\nnext line.\n"));
+        assert_eq!(reflow(&unwrapped, Reflow::Unwrap), unwrapped);
+        assert_eq!(
+            reflow(&reflow(BODY, Reflow::Wrap(40)), Reflow::Unwrap),
+            unwrapped
+        );
+    }
+
+    #[test]
+    fn test_wrap_never_starts_a_line_with_a_block_marker() {
+        let body = "Steps are listed below - then run it\n";
+        let wrapped = reflow(body, Reflow::Wrap(22));
+        assert_eq!(wrapped, "Steps are listed below -\nthen run it\n");
+        let body = "Call the script with arguments - 1. input 2. output\n";
+        let wrapped = reflow(body, Reflow::Wrap(30));
+        assert!(
+            wrapped.lines().skip(1).all(|l| block_start(l).is_none()),
+            "{}",
+            wrapped
+        );
+        assert_eq!(reflow(&wrapped, Reflow::Wrap(30)), wrapped);
+    }
+
+    #[test]
+    fn test_reflow_skill_md_keeps_frontmatter() {
+        let content = "---\nname: pdf\n# a comment\ndescription: d\n---\none\ntwo\n";
+        assert_eq!(
+            reflow_skill_md(content, Reflow::Unwrap).unwrap(),
+            "---\nname: pdf\n# a comment\ndescription: d\n---\none two\n"
+        );
+    }
+    #[test]
+    fn test_reflow_keeps_line_endings() {
+        let body = "one\r\ntwo\r\n\r\n```\r\ncode\r\n```\r\nthree\nfour";
+        assert_eq!(
+            reflow(body, Reflow::Unwrap),
+            "one two\r\n\r\n```\r\ncode\r\n```\r\nthree four"
+        );
+        assert_eq!(
+            reflow("one two three\r\n", Reflow::Wrap(8)),
+            "one two\r\nthree\r\n"
+        );
+    }
+}
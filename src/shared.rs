@@ -0,0 +1,236 @@
+//! A [`SkillSet`] shared between threads and reloaded in place.
+//!
+//! Long-running hosts keep one catalog for all requests and refresh it when
+//! skills change on disk. [`SharedSkillSet`] hands readers an
+//! `Arc<SkillSet>` snapshot and replaces it atomically on reload, so a
+//! reader never sees a half-loaded catalog and is never blocked while
+//! skills are being read from disk.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::ordering;
+use crate::skill_set::{DuplicatePolicy, LoadFailure, LoadOptions, SkillSet};
+
+/// A skill catalog under a root directory that can be reloaded while other
+/// threads read it.
+#[derive(Debug)]
+pub struct SharedSkillSet {
+    root: PathBuf,
+    duplicates: DuplicatePolicy,
+    current: RwLock<Arc<SkillSet>>,
+    /// Held for a whole reload, so concurrent reloads do not report the
+    /// same change twice.
+    reloading: Mutex<()>,
+}
+
+/// What changed in a [`SharedSkillSet::reload`].
+///
+/// Skills are matched by directory and compared by SKILL.md digest. Each
+/// list is sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReloadReport {
+    /// Skills that were not in the previous snapshot.
+    pub added: Vec<PathBuf>,
+
+    /// Skills that are no longer loaded.
+    pub removed: Vec<PathBuf>,
+
+    /// Skills whose SKILL.md digest changed.
+    pub changed: Vec<PathBuf>,
+
+    /// Skills that failed to load; they are not in the new snapshot.
+    pub failures: Vec<LoadFailure>,
+}
+
+impl ReloadReport {
+    /// Check if the reload changed nothing.
+    pub fn is_unchanged(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl SharedSkillSet {
+    /// Load every skill under `root`, as [`SkillSet::discover`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `root` cannot be scanned.
+    pub fn discover(root: &Path) -> Result<(Self, Vec<LoadFailure>)> {
+        Self::discover_with(root, &LoadOptions::default())
+    }
+
+    /// Load every skill under `root` with explicit options.
+    ///
+    /// `options.duplicates` is kept and applied on every reload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `root` cannot be scanned.
+    pub fn discover_with(root: &Path, options: &LoadOptions) -> Result<(Self, Vec<LoadFailure>)> {
+        let (set, failures) = SkillSet::discover_with(root, options)?;
+        let shared = Self {
+            root: root.to_path_buf(),
+            duplicates: options.duplicates,
+            current: RwLock::new(Arc::new(set)),
+            reloading: Mutex::new(()),
+        };
+        Ok((shared, failures))
+    }
+
+    /// Root directory skills are discovered under.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The current snapshot.
+    ///
+    /// The snapshot does not change after it is returned; call `load` again
+    /// to see later reloads.
+    pub fn load(&self) -> Arc<SkillSet> {
+        Arc::clone(&self.current.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Re-discover and re-load all skills, then replace the snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the root cannot be scanned; the current snapshot
+    /// is kept in that case.
+    pub fn reload(&self) -> Result<ReloadReport> {
+        self.reload_with(&LoadOptions {
+            duplicates: self.duplicates,
+            ..LoadOptions::default()
+        })
+    }
+
+    /// Like [`SharedSkillSet::reload`], with explicit options for this
+    /// reload (for example to report metrics).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the root cannot be scanned; the current snapshot
+    /// is kept in that case.
+    pub fn reload_with(&self, options: &LoadOptions) -> Result<ReloadReport> {
+        let _reloading = self
+            .reloading
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let (set, failures) = SkillSet::discover_with(&self.root, options)?;
+        let previous = self.load();
+        let report = diff(&previous, &set, failures);
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(set);
+        Ok(report)
+    }
+}
+
+/// Compare two snapshots by skill directory and digest.
+fn diff(old: &SkillSet, new: &SkillSet, failures: Vec<LoadFailure>) -> ReloadReport {
+    let old_digests: HashMap<&Path, &str> = old
+        .iter()
+        .map(|s| (s.dir.as_path(), s.digest.as_str()))
+        .collect();
+    let new_digests: HashMap<&Path, &str> = new
+        .iter()
+        .map(|s| (s.dir.as_path(), s.digest.as_str()))
+        .collect();
+
+    let mut report = ReloadReport {
+        failures,
+        ..ReloadReport::default()
+    };
+    for (dir, digest) in &new_digests {
+        match old_digests.get(dir) {
+            None => report.added.push(dir.to_path_buf()),
+            Some(old) if old != digest => report.changed.push(dir.to_path_buf()),
+            Some(_) => {}
+        }
+    }
+    report.removed = old_digests
+        .keys()
+        .filter(|dir| !new_digests.contains_key(*dir))
+        .map(|dir| dir.to_path_buf())
+        .collect();
+
+    ordering::sort_paths(&mut report.added);
+    ordering::sort_paths(&mut report.removed);
+    ordering::sort_paths(&mut report.changed);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_skill(root: &Path, name: &str, description: &str) {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {}\ndescription: {}\n---\n", name, description),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_reload_reports_changes_and_keeps_old_snapshots() {
+        let dir = TempDir::new().unwrap();
+        write_skill(dir.path(), "alpha", "First");
+        write_skill(dir.path(), "beta", "Second");
+        write_skill(dir.path(), "gamma", "Third");
+
+        let (shared, failures) = SharedSkillSet::discover(dir.path()).unwrap();
+        assert!(failures.is_empty());
+        let before = shared.load();
+        assert!(shared.reload().unwrap().is_unchanged());
+
+        write_skill(dir.path(), "beta", "Second, edited");
+        write_skill(dir.path(), "delta", "Fourth");
+        std::fs::remove_dir_all(dir.path().join("gamma")).unwrap();
+        std::fs::create_dir_all(dir.path().join("broken")).unwrap();
+        std::fs::write(dir.path().join("broken/SKILL.md"), "no frontmatter").unwrap();
+
+        let report = shared.reload().unwrap();
+        assert_eq!(report.added, vec![dir.path().join("delta")]);
+        assert_eq!(report.removed, vec![dir.path().join("gamma")]);
+        assert_eq!(report.changed, vec![dir.path().join("beta")]);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].path, dir.path().join("broken"));
+
+        assert_eq!(before.len(), 3);
+        assert_eq!(before.get("beta").unwrap().properties.description, "Second");
+        let after = shared.load();
+        assert_eq!(after.len(), 3);
+        assert_eq!(
+            after.get("beta").unwrap().properties.description,
+            "Second, edited"
+        );
+    }
+
+    #[test]
+    fn test_readers_see_whole_snapshots_during_reloads() {
+        let dir = TempDir::new().unwrap();
+        for name in ["alpha", "beta", "gamma"] {
+            write_skill(dir.path(), name, "Skill");
+        }
+        let (shared, _) = SharedSkillSet::discover(dir.path()).unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..50 {
+                        assert_eq!(shared.load().len(), 3);
+                    }
+                });
+            }
+            for _ in 0..5 {
+                shared.reload().unwrap();
+            }
+        });
+    }
+}
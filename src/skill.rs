@@ -0,0 +1,302 @@
+//! A fully loaded skill: properties, body, and provenance.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::digest::sha256_hex;
+use crate::error::Result;
+use crate::markdown;
+use crate::models::SkillProperties;
+use crate::parser::{decode_skill_md, parse_frontmatter, properties_from_metadata};
+use crate::source::{FsSource, SkillSource, locate_skill};
+use crate::template;
+
+/// A skill loaded from disk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Skill {
+    /// Path to the skill directory.
+    pub dir: PathBuf,
+
+    /// Path to the skill's SKILL.md file.
+    pub skill_md: PathBuf,
+
+    /// Properties parsed from the frontmatter.
+    pub properties: SkillProperties,
+
+    /// Markdown body following the frontmatter.
+    pub body: String,
+
+    /// Hex-encoded SHA-256 digest of the SKILL.md file contents.
+    pub digest: String,
+
+    /// Root the skill was discovered under, if it was loaded by scanning a
+    /// root rather than from its own directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+
+    /// Set when the body was not loaded, see [`Skill::is_body_loaded`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) body_omitted: bool,
+}
+
+/// A heading in a skill body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Heading {
+    /// Heading level, 1 through 6.
+    pub level: usize,
+
+    /// Heading text with the `#` markers removed.
+    pub text: String,
+
+    /// Line of the heading in [`Skill::body`], counting from 1.
+    pub line: usize,
+}
+
+impl Skill {
+    /// Load a skill from a skill directory (or its SKILL.md file).
+    ///
+    /// Reads SKILL.md once and parses both frontmatter and body. Like
+    /// [`read_properties`](crate::read_properties), this does not run the
+    /// full validation rules.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`read_properties`](crate::read_properties).
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::load_from(&FsSource, path)
+    }
+
+    /// Load a skill through `source`.
+    ///
+    /// Takes one directory listing and one read of SKILL.md (see
+    /// [`source`](crate::source)).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`read_properties`](crate::read_properties).
+    pub fn load_from(source: &dyn SkillSource, path: &Path) -> Result<Self> {
+        let (dir, skill_md) = locate_skill(source, path)?;
+        let content = decode_skill_md(source.read(&skill_md)?)?;
+        Self::from_content(dir, skill_md, &content)
+    }
+
+    /// Build a skill from already-read SKILL.md content.
+    pub(crate) fn from_content(dir: PathBuf, skill_md: PathBuf, content: &str) -> Result<Self> {
+        let (metadata, body) = parse_frontmatter(content)?;
+        let properties = properties_from_metadata(&metadata)?;
+
+        Ok(Self {
+            dir,
+            skill_md,
+            properties,
+            digest: sha256_hex(content.as_bytes()),
+            root: None,
+            body,
+            body_omitted: false,
+        })
+    }
+
+    /// Skill name (shorthand for `self.properties.name`).
+    pub fn name(&self) -> &str {
+        &self.properties.name
+    }
+
+    /// Check if [`body`](Self::body) holds the skill's body.
+    ///
+    /// Skills restored from a snapshot saved without bodies (see
+    /// [`SkillSet::load_snapshot`](crate::SkillSet::load_snapshot)) have
+    /// an empty `body` that says nothing about the SKILL.md.
+    pub fn is_body_loaded(&self) -> bool {
+        !self.body_omitted
+    }
+
+    /// Headings of the body, in document order, for a table of contents.
+    ///
+    /// Read from the current [`body`](Self::body), so edits to it show
+    /// up here. Only ATX headings (`## Usage`) count, and headings inside
+    /// fenced code blocks are skipped. Setext headings (text underlined
+    /// with `===` or `---`) are not supported; the `setext-heading` lint
+    /// rule suggests rewriting them.
+    pub fn outline(&self) -> Vec<Heading> {
+        markdown::headings(&self.body)
+            .into_iter()
+            .map(|h| Heading {
+                line: self.body[..h.line.start].matches('\n').count() + 1,
+                level: h.level,
+                text: h.text,
+            })
+            .collect()
+    }
+
+    /// Headings of the body with the byte range of each section's text.
+    ///
+    /// Only ATX headings (`## Usage`) are recognized, and headings inside
+    /// fenced code blocks are ignored. A section runs from the line after
+    /// its heading to the next heading of the same or higher level, so it
+    /// includes its subsections.
+    pub fn sections(&self) -> Vec<(String, Range<usize>)> {
+        markdown::sections(&self.body)
+    }
+
+    /// Text of the first section whose heading matches `heading`,
+    /// case-insensitively, with surrounding whitespace trimmed.
+    ///
+    /// See [`Skill::sections`] for how sections are delimited.
+    pub fn section(&self, heading: &str) -> Option<&str> {
+        let heading = heading.trim().to_lowercase();
+        self.sections()
+            .into_iter()
+            .find(|(text, _)| text.to_lowercase() == heading)
+            .map(|(_, range)| self.body[range].trim())
+    }
+
+    /// Body with `{{NAME}}` placeholders replaced by values from `vars`.
+    ///
+    /// `\{{` and `{{{{` render as a literal `{{`. See [`crate::template`]
+    /// for the full syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` listing every placeholder in the body
+    /// that has no value in `vars`.
+    pub fn render_body(&self, vars: &HashMap<&str, &str>) -> Result<String> {
+        template::render(&self.body, vars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SkillError;
+    use crate::source::tests::CountingSource;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_skill() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        let content = "---\nname: my-skill\ndescription: A test skill\n---\n# Body\n";
+        std::fs::write(skill_dir.join("SKILL.md"), content).unwrap();
+
+        let skill = Skill::load(&skill_dir).unwrap();
+        assert_eq!(skill.name(), "my-skill");
+        assert_eq!(skill.body, "# Body");
+        assert_eq!(skill.dir, skill_dir);
+        assert_eq!(skill.skill_md, skill_dir.join("SKILL.md"));
+        assert_eq!(skill.digest, sha256_hex(content.as_bytes()));
+    }
+
+    fn skill_with_body(body: &str) -> Skill {
+        let content = format!("---\nname: my-skill\ndescription: d\n---\n{}", body);
+        Skill::from_content(
+            PathBuf::from("my-skill"),
+            PathBuf::from("SKILL.md"),
+            &content,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render_body() {
+        let skill = skill_with_body("Run `{{SKILL_DIR}}/run.sh` in {{WORKSPACE}}.");
+        let vars = HashMap::from([("SKILL_DIR", "/s"), ("WORKSPACE", "/w")]);
+        assert_eq!(skill.render_body(&vars).unwrap(), "Run `/s/run.sh` in /w.");
+
+        let err = skill.render_body(&HashMap::new()).unwrap_err();
+        assert_eq!(err.errors().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_section_nested_headings() {
+        let skill = skill_with_body(
+            "# My Skill\nIntro\n## Usage\nRun it.\n### Flags\n--fast\n## Notes\nNone\n",
+        );
+
+        assert_eq!(skill.section("usage"), Some("Run it.\n### Flags\n--fast"));
+        assert_eq!(skill.section("Flags"), Some("--fast"));
+        assert_eq!(skill.section("NOTES"), Some("None"));
+        assert!(skill.section("My Skill").unwrap().ends_with("None"));
+        assert_eq!(skill.section("Missing"), None);
+    }
+
+    #[test]
+    fn test_outline() {
+        let skill = skill_with_body(
+            "# PDF\nIntro\n```md\n# not a heading\n```\n## Usage ##\nSetext\n------\n### Flags\n",
+        );
+        let outline: Vec<_> = skill
+            .outline()
+            .into_iter()
+            .map(|h| (h.level, h.text, h.line))
+            .collect();
+        assert_eq!(
+            outline,
+            [
+                (1, "PDF".to_string(), 1),
+                (2, "Usage".to_string(), 6),
+                (3, "Flags".to_string(), 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outline_follows_body_edits() {
+        let mut skill = skill_with_body("# Old\n");
+        skill.body = "Intro\n## New\n".to_string();
+        let outline = skill.outline();
+        assert_eq!(outline.len(), 1);
+        assert_eq!((outline[0].level, outline[0].line), (2, 2));
+        assert_eq!(outline[0].text, "New");
+    }
+
+    #[test]
+    fn test_section_duplicate_heading_first_wins() {
+        let skill = skill_with_body("## Usage\nfirst\n## Other\nx\n## Usage\nsecond\n");
+        assert_eq!(skill.section("Usage"), Some("first"));
+        assert_eq!(skill.sections().len(), 3);
+    }
+
+    #[test]
+    fn test_section_ignores_fenced_headings() {
+        let skill = skill_with_body("## Usage\n```sh\n# install\npip install x\n```\n## Next\n");
+        assert_eq!(
+            skill.section("Usage"),
+            Some("```sh\n# install\npip install x\n```")
+        );
+        assert_eq!(skill.section("install"), None);
+    }
+
+    #[test]
+    fn test_load_skill_missing() {
+        let dir = TempDir::new().unwrap();
+        let err = Skill::load(&dir.path().join("missing")).unwrap_err();
+        assert!(matches!(err, SkillError::NotFound { .. }));
+    }
+
+    #[test]
+    fn test_load_source_operations() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: my-skill\ndescription: d\n---\n",
+        )
+        .unwrap();
+        std::fs::write(skill_dir.join("scripts/run.py"), "").unwrap();
+
+        // One listing and one read per skill, whichever path is given.
+        // Raise these only with a reason: each operation is a round trip
+        // on network file systems.
+        let source = CountingSource::default();
+        Skill::load_from(&source, &skill_dir).unwrap();
+        assert_eq!(source.counts(), (1, 1, 0, 0));
+        let source = CountingSource::default();
+        Skill::load_from(&source, &skill_dir.join("SKILL.md")).unwrap();
+        assert_eq!(source.counts(), (1, 1, 0, 0));
+    }
+}
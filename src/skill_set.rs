@@ -0,0 +1,808 @@
+//! Collections of loaded skills.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::digest::sha256_hex;
+use crate::discovery::find_skills;
+use crate::error::{Result, SkillError};
+use crate::metrics::{LOAD_FAILURES, Metrics, PARSE_SECONDS, SKILLS_LOADED};
+use crate::models::SkillProperties;
+use crate::parallel::{default_concurrency, map_bounded};
+use crate::skill::Skill;
+use crate::source::{FsSource, SkillSource};
+use crate::version::Version;
+
+/// Current snapshot format version. Bump whenever the snapshot layout changes.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// An ordered collection of loaded skills.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SkillSet {
+    skills: Vec<Skill>,
+    shadowed: Vec<ShadowedSkill>,
+}
+
+/// What to do when several loaded skills share a name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DuplicatePolicy {
+    /// Keep every skill (the default).
+    #[default]
+    KeepAll,
+
+    /// Keep only the skill with the highest `metadata.version`.
+    ///
+    /// Versions are compared leniently: numeric segments numerically
+    /// (`1.10.0` > `1.9.0`), a pre-release below its release, and a
+    /// missing or unparseable version below any other. On a tie the skill
+    /// whose directory path sorts first wins, whatever the load order.
+    KeepHighestVersion,
+}
+
+/// A skill left out because another skill with the same name won under
+/// [`DuplicatePolicy::KeepHighestVersion`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ShadowedSkill {
+    /// Skill name.
+    pub name: String,
+
+    /// Path to the shadowed skill directory.
+    pub path: PathBuf,
+
+    /// Version of the shadowed skill, if it has one.
+    pub version: Option<String>,
+
+    /// Path to the skill directory that was kept instead.
+    pub shadowed_by: PathBuf,
+}
+
+/// The `metadata.version` of a skill as a string, if set.
+fn version_string(skill: &Skill) -> Option<String> {
+    match skill.properties.metadata.as_ref()?.get("version")? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Apply `policy` to `skills`, keeping the survivors in load order.
+pub(crate) fn apply_duplicate_policy(
+    skills: Vec<Skill>,
+    policy: DuplicatePolicy,
+) -> (Vec<Skill>, Vec<ShadowedSkill>) {
+    if policy == DuplicatePolicy::KeepAll {
+        return (skills, Vec::new());
+    }
+
+    let versions: Vec<Option<String>> = skills.iter().map(version_string).collect();
+    let parsed: Vec<Option<Version>> = versions
+        .iter()
+        .map(|v| v.as_deref().and_then(Version::parse))
+        .collect();
+
+    // Index of the winning skill for each name.
+    let mut winners: HashMap<&str, usize> = HashMap::new();
+    for (i, skill) in skills.iter().enumerate() {
+        let best = winners.entry(skill.name()).or_insert(i);
+        let wins = match parsed[i].cmp(&parsed[*best]) {
+            std::cmp::Ordering::Equal => {
+                crate::ordering::compare_paths(&skill.dir, &skills[*best].dir).is_lt()
+            }
+            order => order.is_gt(),
+        };
+        if wins {
+            *best = i;
+        }
+    }
+
+    let winner_of: Vec<usize> = skills.iter().map(|s| winners[s.name()]).collect();
+    let dirs: Vec<PathBuf> = skills.iter().map(|s| s.dir.clone()).collect();
+    let mut kept = Vec::new();
+    let mut shadowed = Vec::new();
+    for (i, (skill, version)) in skills.into_iter().zip(versions).enumerate() {
+        if winner_of[i] == i {
+            kept.push(skill);
+        } else {
+            shadowed.push(ShadowedSkill {
+                name: skill.properties.name,
+                path: skill.dir,
+                version,
+                shadowed_by: dirs[winner_of[i]].clone(),
+            });
+        }
+    }
+    (kept, shadowed)
+}
+
+/// A skill that could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LoadFailure {
+    /// Path to the skill directory.
+    pub path: PathBuf,
+
+    /// Error message.
+    pub error: String,
+}
+
+/// Options for loading many skills.
+#[derive(Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct LoadOptions<'a> {
+    /// Maximum number of skills loaded at once. `None` uses
+    /// [`default_concurrency`].
+    pub concurrency: Option<usize>,
+
+    /// Called after each skill is loaded (or fails to load) with the number
+    /// of skills finished so far and the total. May be called from several
+    /// threads, in any order of completion.
+    pub progress: Option<&'a (dyn Fn(usize, usize) + Sync)>,
+
+    /// What to do with skills that share a name.
+    pub duplicates: DuplicatePolicy,
+
+    /// Where to report loaded skills, failures, and parse times (see
+    /// [`metrics`](crate::metrics)).
+    pub metrics: Option<&'a dyn Metrics>,
+
+    /// Where to read skills from. `None` reads the local file system.
+    pub source: Option<&'a dyn SkillSource>,
+}
+
+impl<'a> LoadOptions<'a> {
+    /// Set [`concurrency`](Self::concurrency).
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    /// Set [`progress`](Self::progress).
+    pub fn with_progress(mut self, progress: &'a (dyn Fn(usize, usize) + Sync)) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Set [`duplicates`](Self::duplicates).
+    pub fn with_duplicates(mut self, duplicates: DuplicatePolicy) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Set [`metrics`](Self::metrics).
+    pub fn with_metrics(mut self, metrics: &'a dyn Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Set [`source`](Self::source).
+    pub fn with_source(mut self, source: &'a dyn SkillSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+}
+
+impl fmt::Debug for LoadOptions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoadOptions")
+            .field("concurrency", &self.concurrency)
+            .field("progress", &self.progress.map(|_| ".."))
+            .field("duplicates", &self.duplicates)
+            .field("metrics", &self.metrics.map(|_| ".."))
+            .field("source", &self.source.map(|_| ".."))
+            .finish()
+    }
+}
+
+/// Load every directory in `dirs` in parallel, in input order.
+fn load_all(dirs: &[PathBuf], options: &LoadOptions) -> Vec<Result<Skill>> {
+    let total = dirs.len();
+    map_bounded(
+        dirs,
+        options.concurrency.unwrap_or_else(default_concurrency),
+        |dir| {
+            let started = Instant::now();
+            let result = Skill::load_from(options.source.unwrap_or(&FsSource), dir);
+            if let Some(metrics) = options.metrics {
+                metrics.observe(PARSE_SECONDS, started.elapsed().as_secs_f64());
+                metrics.incr(if result.is_ok() {
+                    SKILLS_LOADED
+                } else {
+                    LOAD_FAILURES
+                });
+            }
+            result
+        },
+        |done| {
+            if let Some(progress) = options.progress {
+                progress(done, total);
+            }
+        },
+    )
+}
+
+/// On-disk snapshot layout.
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    format_version: u32,
+    skills: Vec<SnapshotEntry>,
+}
+
+/// A single skill in a snapshot.
+#[derive(Serialize, Deserialize)]
+struct SnapshotEntry {
+    dir: PathBuf,
+    skill_md: PathBuf,
+    properties: SkillProperties,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    body: Option<String>,
+    digest: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    root: Option<PathBuf>,
+}
+
+impl SkillSet {
+    /// Create a skill set from already-loaded skills.
+    pub fn new(skills: Vec<Skill>) -> Self {
+        Self {
+            skills,
+            shadowed: Vec::new(),
+        }
+    }
+
+    /// Build a skill set from loaded skills, applying `policy` to skills
+    /// that share a name.
+    fn with_policy(skills: Vec<Skill>, policy: DuplicatePolicy) -> Self {
+        let (skills, shadowed) = apply_duplicate_policy(skills, policy);
+        Self { skills, shadowed }
+    }
+
+    /// Load skills from a list of skill directories, in order.
+    ///
+    /// Skills are loaded in parallel with the default [`LoadOptions`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first skill, in input order, that failed
+    /// to load, with that skill's path attached.
+    pub fn from_dirs(skill_dirs: &[&Path]) -> Result<Self> {
+        Self::from_dirs_with(skill_dirs, &LoadOptions::default())
+    }
+
+    /// Load skills from a list of skill directories with explicit options.
+    ///
+    /// Results keep the input order. A failing skill does not stop the
+    /// others from loading. Skills sharing a name are handled according to
+    /// `options.duplicates`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first skill, in input order, that failed
+    /// to load, with that skill's path attached.
+    pub fn from_dirs_with(skill_dirs: &[&Path], options: &LoadOptions) -> Result<Self> {
+        let dirs: Vec<PathBuf> = skill_dirs.iter().map(|d| d.to_path_buf()).collect();
+        let skills = dirs
+            .iter()
+            .zip(load_all(&dirs, options))
+            .map(|(dir, result)| result.map_err(|e| e.in_skill(dir)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::with_policy(skills, options.duplicates))
+    }
+
+    /// Load every skill found under `root`.
+    ///
+    /// Skills are discovered with [`find_skills`], and each records `root`
+    /// in [`Skill::root`]. Skills that fail to load are returned as failures
+    /// instead of aborting the scan.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `root` cannot be scanned.
+    pub fn discover(root: &Path) -> Result<(Self, Vec<LoadFailure>)> {
+        Self::discover_with(root, &LoadOptions::default())
+    }
+
+    /// Load every skill found under `root` with explicit options.
+    ///
+    /// See [`SkillSet::discover`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `root` cannot be scanned.
+    pub fn discover_with(root: &Path, options: &LoadOptions) -> Result<(Self, Vec<LoadFailure>)> {
+        let dirs = find_skills(root)?;
+        let mut skills = Vec::new();
+        let mut failures = Vec::new();
+        for (dir, result) in dirs.iter().zip(load_all(&dirs, options)) {
+            match result {
+                Ok(mut skill) => {
+                    skill.root = Some(root.to_path_buf());
+                    skills.push(skill);
+                }
+                Err(e) => failures.push(LoadFailure {
+                    path: dir.clone(),
+                    error: e.to_string(),
+                }),
+            }
+        }
+        Ok((Self::with_policy(skills, options.duplicates), failures))
+    }
+
+    /// All skills, in load order.
+    pub fn skills(&self) -> &[Skill] {
+        &self.skills
+    }
+
+    /// Iterate over the skills in load order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Skill> {
+        self.skills.iter()
+    }
+
+    /// Skills left out by the [`DuplicatePolicy`] the set was loaded with.
+    pub fn shadowed(&self) -> &[ShadowedSkill] {
+        &self.shadowed
+    }
+
+    /// Number of skills in the set.
+    pub fn len(&self) -> usize {
+        self.skills.len()
+    }
+
+    /// Check if the set contains no skills.
+    pub fn is_empty(&self) -> bool {
+        self.skills.is_empty()
+    }
+
+    /// Find the first skill with the given name.
+    pub fn get(&self, name: &str) -> Option<&Skill> {
+        self.skills.iter().find(|s| s.properties.name == name)
+    }
+
+    /// Save the parsed state of the set to a snapshot file.
+    ///
+    /// The snapshot is versioned JSON storing each skill's properties, source
+    /// paths, digest, and (if `include_bodies`) body.
+    #[cfg(feature = "fs-write")]
+    pub fn save_snapshot(&self, path: &Path, include_bodies: bool) -> Result<()> {
+        let snapshot = Snapshot {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            skills: self
+                .skills
+                .iter()
+                .map(|skill| SnapshotEntry {
+                    dir: skill.dir.clone(),
+                    skill_md: skill.skill_md.clone(),
+                    properties: skill.properties.clone(),
+                    body: include_bodies.then(|| skill.body.clone()),
+                    digest: skill.digest.clone(),
+                    root: skill.root.clone(),
+                })
+                .collect(),
+        };
+
+        let json = serde_json::to_string(&snapshot)
+            .map_err(|e| SkillError::parse(format!("Failed to serialize snapshot: {}", e)))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Load a skill set from a snapshot file.
+    ///
+    /// Skills saved without bodies are restored with an empty body, and
+    /// [`Skill::is_body_loaded`] returns `false` for them. With `verify`,
+    /// each skill's SKILL.md is re-read and its digest compared against the
+    /// snapshot; only skills whose digest changed are reloaded. A skill
+    /// whose SKILL.md cannot be read or reloaded is left out and returned
+    /// as a failure.
+    ///
+    /// # Errors
+    ///
+    /// * `ParseError` - If the file is not a snapshot, or was written with a
+    ///   different snapshot format version
+    pub fn load_snapshot(path: &Path, verify: bool) -> Result<(Self, Vec<LoadFailure>)> {
+        let data = std::fs::read_to_string(path)?;
+        let value: serde_json::Value = serde_json::from_str(&data).map_err(|e| {
+            SkillError::parse(format!("Invalid snapshot {}: {}", path.display(), e))
+        })?;
+
+        let version = value
+            .get("format_version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| {
+                SkillError::parse(format!(
+                    "Invalid snapshot {}: missing format_version",
+                    path.display()
+                ))
+            })?;
+        if version != u64::from(SNAPSHOT_FORMAT_VERSION) {
+            return Err(SkillError::parse(format!(
+                "Snapshot {} has format version {}, but this version of skills-ref reads version {}; regenerate the snapshot",
+                path.display(),
+                version,
+                SNAPSHOT_FORMAT_VERSION
+            )));
+        }
+
+        let snapshot: Snapshot = serde_json::from_value(value).map_err(|e| {
+            SkillError::parse(format!("Invalid snapshot {}: {}", path.display(), e))
+        })?;
+
+        let mut skills = Vec::with_capacity(snapshot.skills.len());
+        let mut failures = Vec::new();
+        for entry in snapshot.skills {
+            if verify {
+                let changed = std::fs::read(&entry.skill_md)
+                    .map_err(SkillError::from)
+                    .map(|content| sha256_hex(&content) != entry.digest);
+                let reloaded = match changed {
+                    Ok(false) => None,
+                    Ok(true) => Some(Skill::load(&entry.dir)),
+                    Err(e) => Some(Err(e)),
+                };
+                match reloaded {
+                    None => {}
+                    Some(Ok(mut skill)) => {
+                        skill.root = entry.root;
+                        skills.push(skill);
+                        continue;
+                    }
+                    Some(Err(e)) => {
+                        failures.push(LoadFailure {
+                            error: e.in_skill(&entry.dir).to_string(),
+                            path: entry.dir,
+                        });
+                        continue;
+                    }
+                }
+            }
+            let body_omitted = entry.body.is_none();
+            let body = entry.body.unwrap_or_default();
+            skills.push(Skill {
+                dir: entry.dir,
+                skill_md: entry.skill_md,
+                properties: entry.properties,
+                body,
+                digest: entry.digest,
+                root: entry.root,
+                body_omitted,
+            });
+        }
+
+        Ok((Self::new(skills), failures))
+    }
+}
+
+impl<'a> IntoIterator for &'a SkillSet {
+    type Item = &'a Skill;
+    type IntoIter = std::slice::Iter<'a, Skill>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.skills.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::RecordingMetrics;
+    use crate::source::tests::CountingSource;
+    use tempfile::TempDir;
+
+    fn create_skill(dir: &TempDir, name: &str, description: &str) -> PathBuf {
+        let skill_dir = dir.path().join(name);
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            format!(
+                "---\nname: {}\ndescription: {}\n---\n# {}\n",
+                name, description, name
+            ),
+        )
+        .unwrap();
+        skill_dir
+    }
+
+    #[test]
+    fn test_from_dirs_preserves_order() {
+        let dir = TempDir::new().unwrap();
+        let b = create_skill(&dir, "skill-b", "B");
+        let a = create_skill(&dir, "skill-a", "A");
+
+        let set = SkillSet::from_dirs(&[b.as_path(), a.as_path()]).unwrap();
+        let names: Vec<_> = set.iter().map(|s| s.name()).collect();
+        assert_eq!(names, vec!["skill-b", "skill-a"]);
+        assert_eq!(set.get("skill-a").unwrap().properties.description, "A");
+    }
+
+    #[test]
+    fn test_load_metrics() {
+        let dir = TempDir::new().unwrap();
+        create_skill(&dir, "skill-a", "A");
+        create_skill(&dir, "skill-b", "B");
+        std::fs::create_dir_all(dir.path().join("broken")).unwrap();
+        std::fs::write(dir.path().join("broken/SKILL.md"), "no frontmatter").unwrap();
+
+        let metrics = RecordingMetrics::new();
+        let options = LoadOptions {
+            metrics: Some(&metrics),
+            ..Default::default()
+        };
+        let (set, _) = SkillSet::discover_with(dir.path(), &options).unwrap();
+        assert_eq!(set.len(), 2);
+        assert_eq!(metrics.count(SKILLS_LOADED), 2);
+        assert_eq!(metrics.count(LOAD_FAILURES), 1);
+        assert_eq!(metrics.observations(PARSE_SECONDS).len(), 3);
+    }
+
+    #[test]
+    fn test_from_dirs_error_names_skill() {
+        let dir = TempDir::new().unwrap();
+        let good = create_skill(&dir, "skill-a", "A");
+        let unreadable = dir.path().join("unreadable");
+        std::fs::create_dir_all(&unreadable).unwrap();
+        std::fs::write(unreadable.join("SKILL.md"), b"---\nname: \xff\n---\n").unwrap();
+        let invalid = dir.path().join("invalid");
+        std::fs::create_dir_all(&invalid).unwrap();
+        std::fs::write(invalid.join("SKILL.md"), "---\nname: [unclosed\n---\n").unwrap();
+
+        for broken in [&unreadable, &invalid] {
+            let err = SkillSet::from_dirs(&[good.as_path(), broken.as_path()]).unwrap_err();
+            assert!(matches!(err, SkillError::InSkill { .. }));
+            let message = err.to_string();
+            assert!(
+                message.starts_with(&format!("{}: ", broken.display())),
+                "{}",
+                message
+            );
+        }
+    }
+
+    fn create_versioned(dir: &TempDir, rel: &str, version: Option<&str>) -> PathBuf {
+        let skill_dir = dir.path().join(rel).join("pdf");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        let metadata = version
+            .map(|v| format!("metadata:\n  version: \"{}\"\n", v))
+            .unwrap_or_default();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            format!("---\nname: pdf\ndescription: d\n{}---\n", metadata),
+        )
+        .unwrap();
+        skill_dir
+    }
+
+    #[test]
+    fn test_keep_highest_version() {
+        let dir = TempDir::new().unwrap();
+        let old = create_versioned(&dir, "a", Some("1.9.0"));
+        let new = create_versioned(&dir, "b", Some("1.10.0"));
+        let none = create_versioned(&dir, "c", None);
+        let other = create_skill(&dir, "other", "d");
+        let options = LoadOptions {
+            duplicates: DuplicatePolicy::KeepHighestVersion,
+            ..Default::default()
+        };
+
+        let set = SkillSet::from_dirs_with(
+            &[
+                none.as_path(),
+                old.as_path(),
+                other.as_path(),
+                new.as_path(),
+            ],
+            &options,
+        )
+        .unwrap();
+        let dirs: Vec<_> = set.iter().map(|s| s.dir.clone()).collect();
+        assert_eq!(dirs, vec![other, new.clone()]);
+        assert_eq!(
+            set.shadowed()
+                .iter()
+                .map(|s| (s.path.clone(), s.version.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![(none, None), (old, Some("1.9.0"))]
+        );
+        assert!(set.shadowed().iter().all(|s| s.shadowed_by == new));
+
+        // Without the policy, every skill is kept.
+        let set = SkillSet::from_dirs(&[new.as_path(), new.as_path()]).unwrap();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_keep_highest_version_ties_by_path() {
+        let dir = TempDir::new().unwrap();
+        let first = create_versioned(&dir, "a", Some("1.0"));
+        let second = create_versioned(&dir, "b", Some("1.0"));
+        let options = LoadOptions {
+            duplicates: DuplicatePolicy::KeepHighestVersion,
+            ..Default::default()
+        };
+
+        for order in [
+            [first.as_path(), second.as_path()],
+            [second.as_path(), first.as_path()],
+        ] {
+            let set = SkillSet::from_dirs_with(&order, &options).unwrap();
+            assert_eq!(set.skills()[0].dir, first);
+            assert_eq!(set.shadowed()[0].path, second);
+        }
+    }
+
+    #[test]
+    fn test_discover_collects_failures() {
+        let dir = TempDir::new().unwrap();
+        create_skill(&dir, "skill-a", "A");
+        std::fs::create_dir_all(dir.path().join("broken")).unwrap();
+        std::fs::write(dir.path().join("broken/SKILL.md"), "no frontmatter").unwrap();
+
+        let (set, failures) = SkillSet::discover(dir.path()).unwrap();
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.skills()[0].root.as_deref(), Some(dir.path()));
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].path, dir.path().join("broken"));
+
+        let loaded = SkillSet::from_dirs(&[dir.path().join("skill-a").as_path()]).unwrap();
+        assert_eq!(loaded.skills()[0].root, None);
+    }
+
+    #[test]
+    fn test_parallel_load_preserves_order() {
+        let dir = TempDir::new().unwrap();
+        let dirs: Vec<PathBuf> = (0..100)
+            .map(|i| create_skill(&dir, &format!("skill-{:03}", 99 - i), "d"))
+            .collect();
+        let refs: Vec<&Path> = dirs.iter().map(|d| d.as_path()).collect();
+
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let progress = |_done: usize, total: usize| {
+            assert_eq!(total, 100);
+            calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        };
+        let source = CountingSource::default();
+        let options = LoadOptions {
+            concurrency: Some(8),
+            progress: Some(&progress),
+            source: Some(&source),
+            ..Default::default()
+        };
+
+        let set = SkillSet::from_dirs_with(&refs, &options).unwrap();
+        let names: Vec<_> = set.iter().map(|s| s.name().to_string()).collect();
+        let expected: Vec<_> = (0..100).map(|i| format!("skill-{:03}", 99 - i)).collect();
+        assert_eq!(names, expected);
+        assert_eq!(calls.into_inner(), 100);
+        assert_eq!(source.counts(), (100, 100, 0, 0));
+    }
+
+    #[test]
+    fn test_parallel_load_failure_does_not_cancel_others() {
+        let dir = TempDir::new().unwrap();
+        for i in 0..50 {
+            create_skill(&dir, &format!("skill-{:02}", i), "d");
+        }
+        for broken in ["broken-a", "broken-b"] {
+            std::fs::create_dir_all(dir.path().join(broken)).unwrap();
+            std::fs::write(dir.path().join(broken).join("SKILL.md"), "no frontmatter").unwrap();
+        }
+
+        let options = LoadOptions {
+            concurrency: Some(4),
+            ..Default::default()
+        };
+        let (set, failures) = SkillSet::discover_with(dir.path(), &options).unwrap();
+        assert_eq!(set.len(), 50);
+        assert_eq!(failures.len(), 2);
+
+        let dirs = find_skills(dir.path()).unwrap();
+        let refs: Vec<&Path> = dirs.iter().map(|d| d.as_path()).collect();
+        let err = SkillSet::from_dirs_with(&refs, &options).unwrap_err();
+        let first_broken = dirs.iter().position(|d| d.ends_with("broken-a")).unwrap();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "{}: {}",
+                refs[first_broken].display(),
+                Skill::load(refs[first_broken]).unwrap_err()
+            )
+        );
+    }
+
+    #[cfg(feature = "fs-write")]
+    #[test]
+    fn test_snapshot_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let a = create_skill(&dir, "skill-a", "A");
+        let set = SkillSet::from_dirs(&[a.as_path()]).unwrap();
+
+        let snapshot = dir.path().join("snapshot.json");
+        set.save_snapshot(&snapshot, true).unwrap();
+        let (loaded, failures) = SkillSet::load_snapshot(&snapshot, false).unwrap();
+        assert_eq!(loaded, set);
+        assert!(failures.is_empty());
+        assert!(loaded.skills()[0].is_body_loaded());
+    }
+
+    #[cfg(feature = "fs-write")]
+    #[test]
+    fn test_snapshot_without_bodies() {
+        let dir = TempDir::new().unwrap();
+        let a = create_skill(&dir, "skill-a", "A");
+        let set = SkillSet::from_dirs(&[a.as_path()]).unwrap();
+
+        let snapshot = dir.path().join("snapshot.json");
+        set.save_snapshot(&snapshot, false).unwrap();
+        let (loaded, _) = SkillSet::load_snapshot(&snapshot, false).unwrap();
+        assert_eq!(loaded.skills()[0].properties, set.skills()[0].properties);
+        assert_eq!(loaded.skills()[0].body, "");
+        assert!(!loaded.skills()[0].is_body_loaded());
+    }
+
+    #[cfg(feature = "fs-write")]
+    #[test]
+    fn test_snapshot_verify_reloads_changed_skills() {
+        let dir = TempDir::new().unwrap();
+        let a = create_skill(&dir, "skill-a", "Original");
+        let b = create_skill(&dir, "skill-b", "Unchanged");
+        let set = SkillSet::from_dirs(&[a.as_path(), b.as_path()]).unwrap();
+
+        let snapshot = dir.path().join("snapshot.json");
+        set.save_snapshot(&snapshot, false).unwrap();
+        create_skill(&dir, "skill-a", "Edited");
+
+        let (stale, _) = SkillSet::load_snapshot(&snapshot, false).unwrap();
+        assert_eq!(stale.skills()[0].properties.description, "Original");
+
+        let (fresh, failures) = SkillSet::load_snapshot(&snapshot, true).unwrap();
+        assert!(failures.is_empty());
+        assert_eq!(fresh.skills()[0].properties.description, "Edited");
+        assert_eq!(fresh.skills()[0].body, "# skill-a");
+        assert!(fresh.skills()[0].is_body_loaded());
+        // Unchanged skills come from the snapshot, so their body stays empty.
+        assert_eq!(fresh.skills()[1].body, "");
+        assert!(!fresh.skills()[1].is_body_loaded());
+    }
+
+    #[cfg(feature = "fs-write")]
+    #[test]
+    fn test_snapshot_verify_reports_deleted_skills() {
+        let dir = TempDir::new().unwrap();
+        let a = create_skill(&dir, "skill-a", "Deleted");
+        let b = create_skill(&dir, "skill-b", "Kept");
+        let set = SkillSet::from_dirs(&[a.as_path(), b.as_path()]).unwrap();
+
+        let snapshot = dir.path().join("snapshot.json");
+        set.save_snapshot(&snapshot, true).unwrap();
+        std::fs::remove_dir_all(&a).unwrap();
+
+        let (loaded, failures) = SkillSet::load_snapshot(&snapshot, true).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.skills()[0].name(), "skill-b");
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].path, a);
+    }
+
+    #[test]
+    fn test_snapshot_version_mismatch() {
+        let dir = TempDir::new().unwrap();
+        let snapshot = dir.path().join("snapshot.json");
+        std::fs::write(&snapshot, r#"{"format_version": 0, "skills": []}"#).unwrap();
+
+        let err = SkillSet::load_snapshot(&snapshot, false).unwrap_err();
+        assert!(err.to_string().contains("format version 0"));
+        assert!(err.to_string().contains("regenerate"));
+    }
+
+    #[test]
+    fn test_snapshot_not_a_snapshot() {
+        let dir = TempDir::new().unwrap();
+        let snapshot = dir.path().join("snapshot.json");
+        std::fs::write(&snapshot, r#"{"skills": []}"#).unwrap();
+
+        let err = SkillSet::load_snapshot(&snapshot, false).unwrap_err();
+        assert!(err.to_string().contains("missing format_version"));
+    }
+}
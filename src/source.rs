@@ -0,0 +1,414 @@
+//! Where skills are read from.
+//!
+//! Loading a skill goes through a [`SkillSource`] rather than calling
+//! `std::fs` directly, and needs as few of its operations as possible: one
+//! listing of the skill directory, which tells whether it exists, which
+//! spelling of SKILL.md it holds, and what else is in it, then one read of
+//! SKILL.md. On network file systems each operation is a round trip, so
+//! this is most of the time spent loading a skill.
+//!
+//! [`FsSource`] reads the local file system and is what every function
+//! taking a path uses. Other sources can add caching or count operations,
+//! as the tests here do.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, SkillError};
+
+/// What kind of entry a path is, following symlinks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EntryKind {
+    /// A regular file.
+    File,
+
+    /// A directory.
+    Dir,
+
+    /// Anything else, including a broken symlink.
+    Other,
+}
+
+/// One entry of a directory listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceEntry {
+    /// File name. Names that are not valid UTF-8 are left out of listings.
+    pub name: String,
+
+    /// What the entry is, following symlinks.
+    pub kind: EntryKind,
+}
+
+/// Read access to the files skills are loaded from.
+pub trait SkillSource: Send + Sync {
+    /// List the entries of the directory at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`std::fs::read_dir`], in particular with
+    /// [`io::ErrorKind::NotFound`] if `path` does not exist.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<SourceEntry>>;
+
+    /// Read the whole file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`std::fs::read`].
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    /// What kind of entry `path` is.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`std::fs::metadata`].
+    fn kind(&self, path: &Path) -> io::Result<EntryKind>;
+
+    /// The canonical, absolute form of `path`.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`std::fs::canonicalize`].
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+}
+
+/// The local file system.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FsSource;
+
+fn kind_of(file_type: std::fs::FileType) -> EntryKind {
+    if file_type.is_file() {
+        EntryKind::File
+    } else if file_type.is_dir() {
+        EntryKind::Dir
+    } else {
+        EntryKind::Other
+    }
+}
+
+impl SkillSource for FsSource {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<SourceEntry>> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            // The listing already says what most entries are; only symlinks
+            // need another call to see what they point at.
+            let file_type = entry.file_type()?;
+            let kind = if file_type.is_symlink() {
+                std::fs::metadata(entry.path()).map_or(EntryKind::Other, |m| kind_of(m.file_type()))
+            } else {
+                kind_of(file_type)
+            };
+            entries.push(SourceEntry { name, kind });
+        }
+        Ok(entries)
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn kind(&self, path: &Path) -> io::Result<EntryKind> {
+        std::fs::metadata(path).map(|m| kind_of(m.file_type()))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        path.canonicalize()
+    }
+}
+
+/// Whether to resolve skill paths to their canonical form.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CanonicalizeMode {
+    /// Resolve symlinks and relative components, so the same skill always
+    /// has the same path (the default).
+    #[default]
+    Always,
+
+    /// Keep paths as given. Saves a lookup per path component, which adds
+    /// up on network file systems.
+    Never,
+}
+
+/// The preferred SKILL.md in a directory listing: `SKILL.md`, then
+/// `skill.md`, then any other casing, as for
+/// [`find_skill_md`](crate::find_skill_md).
+pub(crate) fn skill_md_name(entries: &[SourceEntry]) -> Option<&str> {
+    entries
+        .iter()
+        .filter(|e| e.kind == EntryKind::File && e.name.eq_ignore_ascii_case("skill.md"))
+        .map(|e| e.name.as_str())
+        .min_by_key(|name| {
+            let rank = match *name {
+                "SKILL.md" => 0,
+                "skill.md" => 1,
+                _ => 2,
+            };
+            (rank, *name)
+        })
+}
+
+/// Check if a path's file name is `SKILL.md` (case-insensitive).
+fn is_skill_md_name(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n.eq_ignore_ascii_case("skill.md"))
+        .unwrap_or(false)
+}
+
+/// Resolve `path`, a skill directory or its SKILL.md, to the skill
+/// directory and the SKILL.md file in it.
+///
+/// A directory takes one listing; a SKILL.md path takes one listing of its
+/// parent. Other operations only happen on the way to an error.
+///
+/// # Errors
+///
+/// Same as [`resolve_skill_dir`](crate::resolve_skill_dir).
+pub(crate) fn locate_skill(source: &dyn SkillSource, path: &Path) -> Result<(PathBuf, PathBuf)> {
+    let named_skill_md = is_skill_md_name(path);
+    if named_skill_md {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let file_name = path.file_name().and_then(|n| n.to_str());
+        if let Ok(entries) = source.read_dir(&parent)
+            && entries
+                .iter()
+                .any(|e| Some(e.name.as_str()) == file_name && e.kind == EntryKind::File)
+            && let Some(name) = skill_md_name(&entries)
+        {
+            let skill_md = parent.join(name);
+            return Ok((parent, skill_md));
+        }
+    }
+
+    match source.read_dir(path) {
+        Ok(entries) => match skill_md_name(&entries) {
+            Some(name) => Ok((path.to_path_buf(), path.join(name))),
+            None => Err(SkillError::not_found(
+                path,
+                format!("SKILL.md not found in {}", path.display()),
+            )),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let mut message = format!("Path does not exist: {}", path.display());
+            if let Some(similar) = find_similar_sibling(source, path) {
+                message.push_str(&format!("; did you mean '{}'?", similar.display()));
+            }
+            Err(SkillError::not_found(path, message))
+        }
+        Err(e) => match source.kind(path) {
+            Ok(EntryKind::Dir) | Err(_) => Err(e.into()),
+            Ok(_) if named_skill_md => Err(SkillError::not_found(
+                path,
+                format!("SKILL.md not found in {}", path.display()),
+            )),
+            Ok(_) => Err(SkillError::invalid_path(
+                path,
+                standalone_skill_message(source, path).unwrap_or_else(|| {
+                    format!(
+                        "Expected a skill directory or a SKILL.md file, got file: {}",
+                        path.display()
+                    )
+                }),
+            )),
+        },
+    }
+}
+
+/// Explain how to turn `path` into a skill directory, if it is a
+/// standalone skill file: a `.md` file other than SKILL.md that starts
+/// with frontmatter, such as `skills/pdf.md`.
+///
+/// The directory suggested is named after the skill's `name` field, or
+/// the file stem if it has none.
+pub(crate) fn standalone_skill_message(source: &dyn SkillSource, path: &Path) -> Option<String> {
+    let is_md = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("md"));
+    if !is_md || is_skill_md_name(path) {
+        return None;
+    }
+    let content = String::from_utf8(source.read(path).ok()?).ok()?;
+    let (metadata, _) = crate::parser::parse_frontmatter(&content).ok()?;
+    let name = metadata
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && !name.contains(['/', '\\']) && *name != "..")
+        .map(str::to_string)
+        .or_else(|| Some(path.file_stem()?.to_str()?.to_string()))?;
+    let dir = path.with_file_name(name);
+    Some(format!(
+        "{} is a standalone skill file; a skill must be a directory named after it, \
+         holding the file as SKILL.md. To convert it: mkdir {} && mv {} {}",
+        path.display(),
+        shell_word(&dir),
+        shell_word(path),
+        shell_word(&dir.join("SKILL.md"))
+    ))
+}
+
+/// `path` as one word for a POSIX shell, quoted only if it needs to be.
+fn shell_word(path: &Path) -> String {
+    let text = path.to_string_lossy();
+    if !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._-/+,:@%".contains(c))
+    {
+        text.into_owned()
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+/// Find a sibling directory whose name differs from `path`'s only in
+/// letter case or hyphens/underscores.
+pub(crate) fn find_similar_sibling(source: &dyn SkillSource, path: &Path) -> Option<PathBuf> {
+    fn loose_key(name: &str) -> String {
+        name.chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+
+    let wanted = path.file_name()?.to_str()?;
+    let key = loose_key(wanted);
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+
+    let mut candidates: Vec<PathBuf> = source
+        .read_dir(parent)
+        .ok()?
+        .into_iter()
+        .filter(|e| e.kind == EntryKind::Dir && e.name != wanted && loose_key(&e.name) == key)
+        .map(|e| parent.join(e.name))
+        .collect();
+    crate::ordering::sort_paths(&mut candidates);
+    candidates.into_iter().next()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    /// A file system source that counts each kind of operation.
+    #[derive(Debug, Default)]
+    pub(crate) struct CountingSource {
+        pub(crate) read_dirs: AtomicUsize,
+        pub(crate) reads: AtomicUsize,
+        pub(crate) kinds: AtomicUsize,
+        pub(crate) canonicalizes: AtomicUsize,
+    }
+
+    impl CountingSource {
+        /// Counts as (read_dir, read, kind, canonicalize).
+        pub(crate) fn counts(&self) -> (usize, usize, usize, usize) {
+            (
+                self.read_dirs.load(Ordering::Relaxed),
+                self.reads.load(Ordering::Relaxed),
+                self.kinds.load(Ordering::Relaxed),
+                self.canonicalizes.load(Ordering::Relaxed),
+            )
+        }
+    }
+
+    impl SkillSource for CountingSource {
+        fn read_dir(&self, path: &Path) -> io::Result<Vec<SourceEntry>> {
+            self.read_dirs.fetch_add(1, Ordering::Relaxed);
+            FsSource.read_dir(path)
+        }
+
+        fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+            FsSource.read(path)
+        }
+
+        fn kind(&self, path: &Path) -> io::Result<EntryKind> {
+            self.kinds.fetch_add(1, Ordering::Relaxed);
+            FsSource.kind(path)
+        }
+
+        fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+            self.canonicalizes.fetch_add(1, Ordering::Relaxed);
+            FsSource.canonicalize(path)
+        }
+    }
+
+    #[test]
+    fn test_locate_skill() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skill_dir.join("skill.md"), "").unwrap();
+        std::fs::write(skill_dir.join("SKILL.md"), "").unwrap();
+        let source = CountingSource::default();
+
+        let expected = (skill_dir.clone(), skill_dir.join("SKILL.md"));
+        assert_eq!(locate_skill(&source, &skill_dir).unwrap(), expected);
+        assert_eq!(source.counts(), (1, 0, 0, 0));
+        assert_eq!(
+            locate_skill(&source, &skill_dir.join("skill.md")).unwrap(),
+            expected
+        );
+        assert_eq!(source.counts(), (2, 0, 0, 0));
+
+        let err = locate_skill(&source, &dir.path().join("My_Skill")).unwrap_err();
+        assert!(err.to_string().contains("did you mean"), "{}", err);
+        let err = locate_skill(&source, &skill_dir.join("README")).unwrap_err();
+        assert!(err.to_string().contains("Path does not exist"), "{}", err);
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+        let err = locate_skill(&source, &dir.path().join("notes.txt")).unwrap_err();
+        assert!(
+            err.to_string().starts_with("Expected a skill directory"),
+            "{}",
+            err
+        );
+        let err = locate_skill(&source, dir.path()).unwrap_err();
+        assert!(err.to_string().starts_with("SKILL.md not found"), "{}", err);
+    }
+
+    #[test]
+    fn test_standalone_skill_file() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("pdf.md");
+        std::fs::write(&file, "---\nname: pdf-tools\ndescription: d\n---\n").unwrap();
+        let err = locate_skill(&FsSource, &file).unwrap_err();
+        assert!(matches!(err, SkillError::InvalidPath { .. }));
+        let target = dir.path().join("pdf-tools");
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "{} is a standalone skill file; a skill must be a directory named after it, \
+                 holding the file as SKILL.md. To convert it: mkdir {} && mv {} {}/SKILL.md",
+                file.display(),
+                target.display(),
+                file.display(),
+                target.display()
+            )
+        );
+
+        // Without a name, the file stem names the directory.
+        let file = dir.path().join("my notes.md");
+        std::fs::write(&file, "---\ndescription: d\n---\n").unwrap();
+        let message = standalone_skill_message(&FsSource, &file).unwrap();
+        assert!(message.ends_with("/my notes/SKILL.md'"), "{}", message);
+        assert!(message.contains("mkdir '"), "{}", message);
+
+        // Markdown without frontmatter is not taken for a skill.
+        std::fs::write(dir.path().join("README.md"), "# Readme\n").unwrap();
+        assert!(standalone_skill_message(&FsSource, &dir.path().join("README.md")).is_none());
+    }
+}
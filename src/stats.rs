@@ -0,0 +1,244 @@
+//! Aggregate statistics over a catalog of skills.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::cancel::CancellationToken;
+use crate::discovery::find_skills;
+use crate::error::Result;
+use crate::inspect::estimate_tokens;
+use crate::license::UNSPECIFIED_LICENSE;
+use crate::metrics::NoopMetrics;
+use crate::validator::ValidationConfig;
+use crate::workspace::{ScannedSkill, WorkspaceReport, scan, workspace_report};
+
+/// Lower bounds of the token histogram buckets after the first, which
+/// starts at 0.
+const TOKEN_BUCKETS: &[usize] = &[500, 1000, 2000, 5000];
+
+/// Minimum, median, and maximum of a set of lengths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LengthSummary {
+    pub min: usize,
+    /// Lower median for an even number of values.
+    pub median: usize,
+    pub max: usize,
+}
+
+impl LengthSummary {
+    fn from_values(mut values: Vec<usize>) -> Option<Self> {
+        values.sort_unstable();
+        Some(Self {
+            min: *values.first()?,
+            median: values[(values.len() - 1) / 2],
+            max: *values.last()?,
+        })
+    }
+}
+
+/// A bucket of the estimated token histogram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TokenBucket {
+    /// Smallest estimated token count in the bucket.
+    pub min: usize,
+
+    /// Largest estimated token count in the bucket, or `None` for the last
+    /// bucket.
+    pub max: Option<usize>,
+
+    /// Number of skills whose body falls in the bucket.
+    pub count: usize,
+}
+
+/// Catalog health numbers for dashboards.
+///
+/// Skills that fail to load count towards `skills` and `invalid` only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CatalogStats {
+    /// Number of skill directories found.
+    pub skills: usize,
+
+    /// Skills without validation errors.
+    pub valid: usize,
+
+    /// Skills with validation errors.
+    pub invalid: usize,
+
+    /// Description lengths in characters, or `None` if no skill loaded.
+    pub description_length: Option<LengthSummary>,
+
+    /// Total size of the files besides SKILL.md, across all skills.
+    pub resource_bytes: u64,
+
+    /// Number of skills per `license` value; skills without one are under
+    /// `unspecified`.
+    pub licenses: BTreeMap<String, usize>,
+
+    /// Number of skills declaring `allowed-tools`.
+    pub with_allowed_tools: usize,
+
+    /// Histogram of estimated body tokens (characters / 4).
+    pub token_histogram: Vec<TokenBucket>,
+}
+
+impl CatalogStats {
+    /// Compute statistics from a catalog scan.
+    pub(crate) fn from_scan(scanned: &[ScannedSkill]) -> Self {
+        let valid = scanned
+            .iter()
+            .filter(|s| !s.diagnostics.iter().any(|d| d.is_error()))
+            .count();
+        let loaded: Vec<_> = scanned.iter().filter_map(|s| s.skill.as_ref()).collect();
+
+        let mut licenses = BTreeMap::new();
+        for skill in &loaded {
+            let license = skill
+                .properties
+                .license
+                .as_deref()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .unwrap_or(UNSPECIFIED_LICENSE);
+            *licenses.entry(license.to_string()).or_default() += 1;
+        }
+
+        let mut token_histogram: Vec<TokenBucket> = std::iter::once(0)
+            .chain(TOKEN_BUCKETS.iter().copied())
+            .zip(
+                TOKEN_BUCKETS
+                    .iter()
+                    .map(|next| Some(next - 1))
+                    .chain([None]),
+            )
+            .map(|(min, max)| TokenBucket { min, max, count: 0 })
+            .collect();
+        for skill in &loaded {
+            let tokens = estimate_tokens(&skill.body);
+            let bucket = TOKEN_BUCKETS.iter().filter(|b| tokens >= **b).count();
+            token_histogram[bucket].count += 1;
+        }
+
+        Self {
+            skills: scanned.len(),
+            valid,
+            invalid: scanned.len() - valid,
+            description_length: LengthSummary::from_values(
+                loaded
+                    .iter()
+                    .map(|s| s.properties.description.chars().count())
+                    .collect(),
+            ),
+            resource_bytes: scanned.iter().filter_map(|s| s.resource_bytes).sum(),
+            licenses,
+            with_allowed_tools: loaded
+                .iter()
+                .filter(|s| s.properties.allowed_tools.is_some())
+                .count(),
+            token_histogram,
+        }
+    }
+}
+
+/// Compute catalog statistics for all skills under `root`.
+///
+/// # Errors
+///
+/// Returns an error if `root` cannot be scanned.
+pub fn stats(root: &Path) -> Result<CatalogStats> {
+    let skill_dirs = find_skills(root)?;
+    Ok(validate_with_stats(&skill_dirs, &ValidationConfig::default()).1)
+}
+
+/// Validate skill directories together (see
+/// [`validate_workspace`](crate::validate_workspace)) and compute catalog
+/// statistics from the same load pass.
+pub fn validate_with_stats(
+    skill_dirs: &[PathBuf],
+    config: &ValidationConfig,
+) -> (WorkspaceReport, CatalogStats) {
+    let scanned = scan(
+        skill_dirs,
+        config,
+        true,
+        &NoopMetrics,
+        &CancellationToken::new(),
+    );
+    (
+        workspace_report(&scanned, config),
+        CatalogStats::from_scan(&scanned),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_skill(root: &Path, name: &str, frontmatter: &str, body: &str) {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {}\n{}---\n{}", name, frontmatter, body),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_stats() {
+        let dir = TempDir::new().unwrap();
+        create_skill(dir.path(), "a", "description: d\nlicense: MIT\n", "short");
+        create_skill(
+            dir.path(),
+            "b",
+            "description: ddd\nlicense: MIT\nallowed-tools: Bash\n",
+            &"x".repeat(2400),
+        );
+        create_skill(dir.path(), "c", "description: dd\nextra: 1\n", "");
+        create_skill(dir.path(), "d", "", "no description");
+        std::fs::write(dir.path().join("a/ref.md"), "12345").unwrap();
+
+        let stats = stats(dir.path()).unwrap();
+        assert_eq!(stats.skills, 4);
+        assert_eq!(stats.valid, 2);
+        assert_eq!(stats.invalid, 2);
+        assert_eq!(
+            stats.description_length,
+            Some(LengthSummary {
+                min: 1,
+                median: 2,
+                max: 3
+            })
+        );
+        assert_eq!(stats.resource_bytes, 5);
+        assert_eq!(stats.licenses["MIT"], 2);
+        assert_eq!(stats.licenses[UNSPECIFIED_LICENSE], 1);
+        assert_eq!(stats.with_allowed_tools, 1);
+        let counts: Vec<_> = stats
+            .token_histogram
+            .iter()
+            .map(|b| (b.min, b.max, b.count))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                (0, Some(499), 2),
+                (500, Some(999), 1),
+                (1000, Some(1999), 0),
+                (2000, Some(4999), 0),
+                (5000, None, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stats_empty_catalog() {
+        let dir = TempDir::new().unwrap();
+        let stats = stats(dir.path()).unwrap();
+        assert_eq!(stats.skills, 0);
+        assert_eq!(stats.description_length, None);
+        assert!(stats.token_histogram.iter().all(|b| b.count == 0));
+    }
+}
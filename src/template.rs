@@ -0,0 +1,170 @@
+//! `{{NAME}}` placeholder substitution for skill bodies.
+//!
+//! Hosts substitute values such as `{{SKILL_DIR}}` or `{{WORKSPACE}}` when
+//! loading a skill. Substitution is plain text replacement: there are no
+//! conditionals, loops, or filters.
+//!
+//! * `{{NAME}}` is a placeholder when `NAME` starts with an ASCII letter or
+//!   underscore and continues with ASCII letters, digits, or underscores.
+//! * `\{{` and `{{{{` are escapes and render as a literal `{{`.
+//! * Any other `{{` (e.g. `{{ name }}`) is left as is.
+
+use std::collections::HashMap;
+
+use crate::error::{Result, SkillError};
+
+/// A piece of a parsed template.
+#[derive(Debug, PartialEq)]
+enum Piece<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split `body` into literal text and placeholders.
+fn parse(body: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut rest = body;
+
+    while let Some(open) = rest.find("{{") {
+        if rest[..open].ends_with('\\') {
+            pieces.push(Piece::Text(&rest[..open - 1]));
+            pieces.push(Piece::Text("{{"));
+            rest = &rest[open + 2..];
+            continue;
+        }
+        pieces.push(Piece::Text(&rest[..open]));
+        let after = &rest[open + 2..];
+        if let Some(escaped) = after.strip_prefix("{{") {
+            pieces.push(Piece::Text("{{"));
+            rest = escaped;
+        } else if let Some(close) = after.find("}}")
+            && is_placeholder_name(&after[..close])
+        {
+            pieces.push(Piece::Placeholder(&after[..close]));
+            rest = &after[close + 2..];
+        } else {
+            pieces.push(Piece::Text("{{"));
+            rest = after;
+        }
+    }
+    pieces.push(Piece::Text(rest));
+    pieces
+}
+
+fn is_placeholder_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Names of the placeholders used in `body`, in order of first use.
+pub fn placeholders(body: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for piece in parse(body) {
+        if let Piece::Placeholder(name) = piece
+            && !names.iter().any(|n| n == name)
+        {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Substitute `{{NAME}}` placeholders in `body` with values from `vars`.
+///
+/// # Errors
+///
+/// Returns a `ValidationError` listing every placeholder without a value.
+pub fn render(body: &str, vars: &HashMap<&str, &str>) -> Result<String> {
+    let pieces = parse(body);
+
+    let mut missing: Vec<&str> = Vec::new();
+    for piece in &pieces {
+        if let Piece::Placeholder(name) = piece
+            && !vars.contains_key(name)
+            && !missing.contains(name)
+        {
+            missing.push(name);
+        }
+    }
+    if !missing.is_empty() {
+        return Err(SkillError::validation_multiple(
+            format!(
+                "Missing values for body placeholders: {}",
+                missing.join(", ")
+            ),
+            missing
+                .iter()
+                .map(|name| format!("No value for placeholder {{{{{}}}}}", name))
+                .collect(),
+        ));
+    }
+
+    let mut out = String::with_capacity(body.len());
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => out.push_str(text),
+            Piece::Placeholder(name) => out.push_str(vars[name]),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_substitutes() {
+        let vars = HashMap::from([("SKILL_DIR", "/skills/pdf"), ("WORKSPACE", "/work")]);
+        assert_eq!(
+            render(
+                "Run {{SKILL_DIR}}/fill.py in {{WORKSPACE}}; {{SKILL_DIR}}",
+                &vars
+            )
+            .unwrap(),
+            "Run /skills/pdf/fill.py in /work; /skills/pdf"
+        );
+    }
+
+    #[test]
+    fn test_render_lists_all_missing() {
+        let vars = HashMap::from([("A", "1")]);
+        let err = render("{{A}} {{B}} {{C}} {{B}}", &vars).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Missing values for body placeholders: B, C"
+        );
+        assert_eq!(
+            err.errors().unwrap(),
+            &[
+                "No value for placeholder {{B}}",
+                "No value for placeholder {{C}}"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escapes_and_non_placeholders() {
+        let vars = HashMap::from([("X", "x")]);
+        assert_eq!(render(r"\{{X}}", &vars).unwrap(), "{{X}}");
+        assert_eq!(render("{{{{X}}", &vars).unwrap(), "{{X}}");
+        assert_eq!(
+            render("{{ X }} {{1A}} {{X", &vars).unwrap(),
+            "{{ X }} {{1A}} {{X"
+        );
+        assert_eq!(render("a\\b {{X}}", &vars).unwrap(), "a\\b x");
+        assert_eq!(render("}} {{X}}}}", &vars).unwrap(), "}} x}}");
+    }
+
+    #[test]
+    fn test_placeholders_in_order() {
+        assert_eq!(
+            placeholders("{{B}} \\{{A}} {{A}} {{B}} {{_c1}}"),
+            vec!["B", "A", "_c1"]
+        );
+        assert!(placeholders("no placeholders {{{{X}}").is_empty());
+    }
+}
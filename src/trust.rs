@@ -0,0 +1,98 @@
+//! How far a prompt's reader should trust a skill.
+//!
+//! Built-in skills and skills a user installed from anywhere can end up in
+//! the same prompt. A [`TrustLevel`], usually assigned per skill root (see
+//! [`PromptOptions::trust`](crate::PromptOptions::trust)), tells the model
+//! which is which.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SkillError};
+
+/// Trust level of a skill, from most to least trusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum TrustLevel {
+    /// Shipped with the client.
+    Builtin,
+
+    /// Reviewed by the client's publisher or the user's organization.
+    Verified,
+
+    /// Published by a third party and not reviewed.
+    Community,
+
+    /// Installed or written by the user on this machine.
+    Local,
+}
+
+impl TrustLevel {
+    /// All trust levels, most trusted first.
+    pub const ALL: &'static [TrustLevel] = &[
+        TrustLevel::Builtin,
+        TrustLevel::Verified,
+        TrustLevel::Community,
+        TrustLevel::Local,
+    ];
+
+    /// The level as written in prompts and on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrustLevel::Builtin => "builtin",
+            TrustLevel::Verified => "verified",
+            TrustLevel::Community => "community",
+            TrustLevel::Local => "local",
+        }
+    }
+}
+
+impl fmt::Display for TrustLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TrustLevel {
+    type Err = SkillError;
+
+    /// Parse a level written as by [`TrustLevel::as_str`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` listing the valid levels if `s` is not
+    /// one of them.
+    fn from_str(s: &str) -> Result<Self> {
+        TrustLevel::ALL
+            .iter()
+            .copied()
+            .find(|level| level.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = TrustLevel::ALL.iter().map(|l| l.as_str()).collect();
+                SkillError::validation(format!(
+                    "Unknown trust level '{}'; expected one of: {}",
+                    s,
+                    valid.join(", ")
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_round_trip() {
+        for level in TrustLevel::ALL {
+            assert_eq!(level.as_str().parse::<TrustLevel>().unwrap(), *level);
+        }
+        assert_eq!(
+            "Builtin".parse::<TrustLevel>().unwrap_err().to_string(),
+            "Unknown trust level 'Builtin'; expected one of: builtin, verified, community, local"
+        );
+    }
+}
@@ -1,11 +1,24 @@
 //! Skill validation logic.
 
 use std::collections::HashMap;
-use std::path::Path;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use serde::Serialize;
 use unicode_normalization::UnicodeNormalization;
 
-use crate::parser::{find_skill_md, parse_frontmatter};
+use crate::diagnostic::{Diagnostic, MessageRenderer, Param, Severity, shorten};
+use crate::error::SkillError;
+use crate::inspect::{ResourceFile, file_inventory};
+use crate::models::{SkillProperties, is_extension_field};
+use crate::ordering;
+use crate::parser::{
+    decode_skill_md, find_skill_md, is_canonical_skill_md_name, parse_frontmatter,
+    properties_from_metadata, read_frontmatter_only, read_skill_md_file,
+};
+use crate::source::{FsSource, standalone_skill_message};
+use crate::template::placeholders;
 
 /// Maximum length for skill names.
 pub const MAX_SKILL_NAME_LENGTH: usize = 64;
@@ -13,11 +26,24 @@ pub const MAX_SKILL_NAME_LENGTH: usize = 64;
 /// Maximum length for skill descriptions.
 pub const MAX_DESCRIPTION_LENGTH: usize = 1024;
 
+/// Characters below which a description gets a `description-min-length`
+/// warning unless [`ValidationConfig::min_description_chars`] says
+/// otherwise.
+pub const DEFAULT_MIN_DESCRIPTION_CHARS: usize = 20;
+
 /// Maximum length for compatibility field.
 pub const MAX_COMPATIBILITY_LENGTH: usize = 500;
 
+/// Characters of a frontmatter value quoted in a diagnostic message unless
+/// [`ValidationConfig::message_value_chars`] says otherwise.
+pub const DEFAULT_MESSAGE_VALUE_CHARS: usize = 120;
+
+/// Maximum number of keys and sequence indices on the path from `metadata`
+/// to any value, e.g. 3 for `metadata.a.b.c`.
+pub const MAX_METADATA_DEPTH: usize = 3;
+
 /// Allowed frontmatter fields per Agent Skills Spec.
-const ALLOWED_FIELDS: &[&str] = &[
+pub const ALLOWED_FIELDS: &[&str] = &[
     "name",
     "description",
     "license",
@@ -26,104 +52,1544 @@ const ALLOWED_FIELDS: &[&str] = &[
     "compatibility",
 ];
 
+/// Which directory names the `name-directory-match` rule compares the skill
+/// name against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NameMatch {
+    /// The directory containing SKILL.md (the default).
+    #[default]
+    ImmediateDir,
+
+    /// Any of the nearest `n` directories, starting with the one containing
+    /// SKILL.md. `AnyAncestorWithin(2)` accepts `pdf/1.2.0/SKILL.md` for a
+    /// skill named `pdf`.
+    AnyAncestorWithin(usize),
+
+    /// Skip the check.
+    Disabled,
+}
+
+impl NameMatch {
+    /// Names of the directories the skill name may match, nearest first.
+    fn candidates(self, skill_dir: &Path) -> Vec<&str> {
+        let depth = match self {
+            NameMatch::ImmediateDir => 1,
+            NameMatch::AnyAncestorWithin(n) => n,
+            NameMatch::Disabled => 0,
+        };
+        skill_dir
+            .ancestors()
+            .take(depth)
+            .filter_map(|dir| dir.file_name()?.to_str())
+            .collect()
+    }
+}
+
+/// Options enabling opt-in validation rules.
+///
+/// Start from [`ValidationConfig::default`] and set options with the
+/// `with_*` methods or by assigning fields.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ValidationConfig {
+    /// Check the format of conventional metadata values
+    /// (`maintainer-email` and `homepage`).
+    pub metadata_conventions: bool,
+
+    /// Accept top-level fields starting with `x-` as client extensions
+    /// instead of reporting them as unexpected.
+    pub allow_x_prefixed_fields: bool,
+
+    /// Report the `{{NAME}}` placeholders the body uses, so hosts can
+    /// check they provide a value for each.
+    pub list_placeholders: bool,
+
+    /// Directories the skill name must match.
+    pub name_match: NameMatch,
+
+    /// Maximum length of the skill name in UTF-8 bytes, after
+    /// normalization. `None` applies only the spec's character limit.
+    pub max_name_bytes: Option<usize>,
+
+    /// Check the files in the skill directory against these limits.
+    /// `None` skips the check.
+    pub size_limits: Option<SizeLimits>,
+
+    /// Rules defined outside this crate, run after the built-in rules on
+    /// every skill validated from a directory.
+    pub custom_rules: Vec<Arc<dyn ValidationRule>>,
+
+    /// Descriptions shorter than this many characters get a
+    /// `description-min-length` warning. `None` uses
+    /// [`DEFAULT_MIN_DESCRIPTION_CHARS`]; `Some(0)` turns the rule off.
+    pub min_description_chars: Option<usize>,
+
+    /// Maximum characters of a value quoted in a diagnostic message;
+    /// longer values are shortened, and the complete value is kept in
+    /// [`Diagnostic::value`]. `None` uses [`DEFAULT_MESSAGE_VALUE_CHARS`].
+    pub message_value_chars: Option<usize>,
+
+    /// Words the messages of diagnostics from directory and workspace
+    /// validation. `None` keeps the built-in English messages.
+    pub message_renderer: Option<Arc<dyn MessageRenderer>>,
+}
+
+impl ValidationConfig {
+    /// Set [`metadata_conventions`](Self::metadata_conventions).
+    pub fn with_metadata_conventions(mut self, metadata_conventions: bool) -> Self {
+        self.metadata_conventions = metadata_conventions;
+        self
+    }
+
+    /// Set [`allow_x_prefixed_fields`](Self::allow_x_prefixed_fields).
+    pub fn with_allow_x_prefixed_fields(mut self, allow_x_prefixed_fields: bool) -> Self {
+        self.allow_x_prefixed_fields = allow_x_prefixed_fields;
+        self
+    }
+
+    /// Set [`list_placeholders`](Self::list_placeholders).
+    pub fn with_list_placeholders(mut self, list_placeholders: bool) -> Self {
+        self.list_placeholders = list_placeholders;
+        self
+    }
+
+    /// Set [`name_match`](Self::name_match).
+    pub fn with_name_match(mut self, name_match: NameMatch) -> Self {
+        self.name_match = name_match;
+        self
+    }
+
+    /// Set [`max_name_bytes`](Self::max_name_bytes).
+    pub fn with_max_name_bytes(mut self, max_name_bytes: usize) -> Self {
+        self.max_name_bytes = Some(max_name_bytes);
+        self
+    }
+
+    /// Set [`size_limits`](Self::size_limits).
+    pub fn with_size_limits(mut self, size_limits: SizeLimits) -> Self {
+        self.size_limits = Some(size_limits);
+        self
+    }
+
+    /// Add a rule to [`custom_rules`](Self::custom_rules).
+    pub fn with_custom_rule(mut self, rule: Arc<dyn ValidationRule>) -> Self {
+        self.custom_rules.push(rule);
+        self
+    }
+
+    /// Set [`min_description_chars`](Self::min_description_chars).
+    pub fn with_min_description_chars(mut self, min_description_chars: usize) -> Self {
+        self.min_description_chars = Some(min_description_chars);
+        self
+    }
+
+    /// Set [`message_value_chars`](Self::message_value_chars).
+    pub fn with_message_value_chars(mut self, message_value_chars: usize) -> Self {
+        self.message_value_chars = Some(message_value_chars);
+        self
+    }
+
+    /// Set [`message_renderer`](Self::message_renderer).
+    pub fn with_message_renderer(mut self, message_renderer: Arc<dyn MessageRenderer>) -> Self {
+        self.message_renderer = Some(message_renderer);
+        self
+    }
+
+    /// Whether validation needs the SKILL.md body, not just the
+    /// frontmatter.
+    pub(crate) fn needs_body(&self) -> bool {
+        self.list_placeholders || !self.custom_rules.is_empty()
+    }
+
+    /// Characters of a value quoted in a message.
+    pub(crate) fn value_chars(&self) -> usize {
+        self.message_value_chars
+            .unwrap_or(DEFAULT_MESSAGE_VALUE_CHARS)
+    }
+
+    /// Reword `diagnostics` with the configured renderer, if any.
+    pub(crate) fn render_messages<'a>(
+        &self,
+        diagnostics: impl IntoIterator<Item = &'a mut Diagnostic>,
+    ) {
+        if let Some(renderer) = &self.message_renderer {
+            for diagnostic in diagnostics {
+                if let Some(message) = renderer.render(&diagnostic.rule, &diagnostic.params) {
+                    diagnostic.message = message;
+                }
+            }
+        }
+    }
+
+    /// The ID of the built-in or custom rule `id`, if there is one.
+    pub(crate) fn known_rule_id(&self, id: &str) -> Option<&'static str> {
+        rule_info(id).map(|info| info.id).or_else(|| {
+            self.custom_rules
+                .iter()
+                .map(|rule| rule.id())
+                .find(|custom| *custom == id)
+        })
+    }
+}
+
+/// A validation rule defined outside this crate, such as an
+/// organization's naming policy.
+///
+/// Custom rules see the skill after the built-in rules have run; their
+/// diagnostics are added to the same list. They are not listed by
+/// [`rules`] and cannot be explained with [`rule_info`].
+pub trait ValidationRule: Send + Sync {
+    /// Stable rule identifier; diagnostics from [`check`](Self::check)
+    /// should use it as their rule.
+    fn id(&self) -> &'static str;
+
+    /// Check one skill.
+    fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic>;
+}
+
+impl fmt::Debug for dyn ValidationRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ValidationRule({})", self.id())
+    }
+}
+
+/// Rules are equal if their IDs are.
+impl PartialEq for dyn ValidationRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for dyn ValidationRule {}
+
+/// What a [`ValidationRule`] can inspect.
+#[derive(Debug)]
+pub struct RuleContext<'a> {
+    /// Path to the skill directory.
+    pub skill_dir: &'a Path,
+
+    /// Raw frontmatter fields.
+    pub metadata: &'a HashMap<String, serde_yaml::Value>,
+
+    /// Typed properties, or `None` if the frontmatter lacks a valid name
+    /// or description.
+    pub properties: Option<&'a SkillProperties>,
+
+    /// Markdown body following the frontmatter.
+    pub body: &'a str,
+}
+
+/// Everything [`validate_input`] can check for one skill.
+///
+/// Only `metadata` is required. Each rule runs when the part it needs is
+/// present: the body rules need `body`, the name-directory match and
+/// portability rules need `skill_dir`, `skill-file-case` needs
+/// `skill_md_name`, and `skill-size` needs `resources`. Nothing is read
+/// from disk, so the skill may live in an archive, a database, or nowhere
+/// yet.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct ValidationInput<'a> {
+    /// Parsed frontmatter fields.
+    pub metadata: &'a HashMap<String, serde_yaml::Value>,
+
+    /// Markdown body following the frontmatter.
+    pub body: Option<&'a str>,
+
+    /// Path to the skill directory. It need not exist.
+    pub skill_dir: Option<&'a Path>,
+
+    /// File name of SKILL.md as found in the skill directory, such as
+    /// `skill.md`.
+    pub skill_md_name: Option<&'a str>,
+
+    /// Every file of the skill, SKILL.md included, with paths relative to
+    /// the skill directory.
+    pub resources: Option<&'a [ResourceFile]>,
+}
+
+impl<'a> ValidationInput<'a> {
+    /// Input with only frontmatter fields.
+    pub fn new(metadata: &'a HashMap<String, serde_yaml::Value>) -> Self {
+        Self {
+            metadata,
+            body: None,
+            skill_dir: None,
+            skill_md_name: None,
+            resources: None,
+        }
+    }
+
+    /// Set [`body`](Self::body).
+    pub fn with_body(mut self, body: &'a str) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    /// Set [`skill_dir`](Self::skill_dir).
+    pub fn with_skill_dir(mut self, skill_dir: &'a Path) -> Self {
+        self.skill_dir = Some(skill_dir);
+        self
+    }
+
+    /// Set [`skill_md_name`](Self::skill_md_name).
+    pub fn with_skill_md_name(mut self, skill_md_name: &'a str) -> Self {
+        self.skill_md_name = Some(skill_md_name);
+        self
+    }
+
+    /// Set [`resources`](Self::resources).
+    pub fn with_resources(mut self, resources: &'a [ResourceFile]) -> Self {
+        self.resources = Some(resources);
+        self
+    }
+}
+
+/// Result of [`validate_input`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ValidationReport {
+    /// Diagnostics of every rule that ran, in the documented order (see
+    /// [`validate_metadata`]).
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    /// Check if no diagnostic is an error.
+    pub fn is_valid(&self) -> bool {
+        !self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    /// Messages of the error diagnostics, as [`validate`] returns them.
+    pub fn error_messages(&self) -> Vec<String> {
+        messages(&self.diagnostics)
+    }
+}
+
+/// Bounds on the files of a skill directory, for hosts that copy whole
+/// skills into sandboxes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SizeLimits {
+    /// Maximum total size of all files, SKILL.md included, in bytes.
+    pub max_total_bytes: u64,
+
+    /// Maximum size of a single file, in bytes.
+    pub max_file_bytes: u64,
+
+    /// Maximum number of files, SKILL.md included.
+    pub max_files: usize,
+}
+
+impl Default for SizeLimits {
+    /// 50 MiB in total, [`DEFAULT_MAX_RESOURCE_BYTES`] per file, and 1000
+    /// files.
+    ///
+    /// [`DEFAULT_MAX_RESOURCE_BYTES`]: crate::invocation::DEFAULT_MAX_RESOURCE_BYTES
+    fn default() -> Self {
+        Self {
+            max_total_bytes: 50 * 1024 * 1024,
+            max_file_bytes: crate::invocation::DEFAULT_MAX_RESOURCE_BYTES,
+            max_files: 1000,
+        }
+    }
+}
+
+impl SizeLimits {
+    /// Set [`max_total_bytes`](Self::max_total_bytes).
+    pub fn with_max_total_bytes(mut self, max_total_bytes: u64) -> Self {
+        self.max_total_bytes = max_total_bytes;
+        self
+    }
+
+    /// Set [`max_file_bytes`](Self::max_file_bytes).
+    pub fn with_max_file_bytes(mut self, max_file_bytes: u64) -> Self {
+        self.max_file_bytes = max_file_bytes;
+        self
+    }
+
+    /// Set [`max_files`](Self::max_files).
+    pub fn with_max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+}
+
+/// Metadata describing a validation rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RuleInfo {
+    /// Stable rule identifier (e.g. `name-lowercase`).
+    pub id: &'static str,
+
+    /// One-line summary of what the rule checks.
+    pub summary: &'static str,
+
+    /// Severity of diagnostics produced by the rule.
+    pub default_severity: Severity,
+
+    /// Whether the rule's behavior can be changed by configuration.
+    pub configurable: bool,
+
+    /// Longer explanation of the rule and why it exists.
+    pub description: &'static str,
+
+    /// Example frontmatter value that violates the rule.
+    pub failing_example: &'static str,
+
+    /// Example frontmatter value that satisfies the rule.
+    pub passing_example: &'static str,
+}
+
+/// All built-in validation rules.
+///
+/// Every rule ID emitted by this module must have an entry here; the tests
+/// enforce this.
+const RULES: &[RuleInfo] = &[
+    RuleInfo {
+        id: "path-missing",
+        summary: "The skill path must exist",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Nothing can be checked at a path that does not exist. In a batch, \
+            this is usually a typo or a skill that was moved or deleted since the list of \
+            paths was made.",
+        failing_example: "skills-ref validate ./no-such-skill",
+        passing_example: "skills-ref validate ./my-skill",
+    },
+    RuleInfo {
+        id: "path-not-directory",
+        summary: "The skill path must be a directory",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "A skill is a directory holding SKILL.md and its resources, so the \
+            path must name that directory rather than a file in it. A standalone Markdown \
+            file with frontmatter, such as `skills/pdf.md`, is reported with the commands \
+            that move it into a directory of its own.",
+        failing_example: "skills-ref validate ./my-skill/SKILL.md",
+        passing_example: "skills-ref validate ./my-skill",
+    },
+    RuleInfo {
+        id: "skill-md-missing",
+        summary: "A skill directory must contain SKILL.md",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "SKILL.md (or skill.md) holds the frontmatter that identifies a \
+            skill; a directory without it is not a skill.",
+        failing_example: "my-skill/README.md",
+        passing_example: "my-skill/SKILL.md",
+    },
+    RuleInfo {
+        id: "skill-file",
+        summary: "SKILL.md must be readable and start with frontmatter",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "SKILL.md must be readable UTF-8 and begin with a YAML frontmatter \
+            block delimited by `---` lines; nothing else can be checked until that is true.",
+        failing_example: "# My Skill   # no frontmatter",
+        passing_example: "---\nname: my-skill\ndescription: Does things\n---\n# My Skill",
+    },
+    RuleInfo {
+        id: "skill-file-case",
+        summary: "SKILL.md should be spelled SKILL.md or skill.md",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "Skill files are found whatever their casing, but a file named, say, \
+            `Skill.MD` only works by accident on case-insensitive file systems such as \
+            macOS's default; other tools and case-sensitive checkouts look for `SKILL.md` \
+            or `skill.md` exactly.",
+        failing_example: "my-skill/Skill.MD",
+        passing_example: "my-skill/SKILL.md",
+    },
+    RuleInfo {
+        id: "unexpected-fields",
+        summary: "Frontmatter may only contain fields defined by the spec",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "The Agent Skills spec defines a fixed set of top-level frontmatter \
+            fields. Anything else is rejected so that typos (e.g. `descripton`) are caught \
+            instead of silently ignored. Client-specific values belong under `metadata`.",
+        failing_example: "author: Jane",
+        passing_example: "metadata:\n  author: Jane",
+    },
+    RuleInfo {
+        id: "required-field",
+        summary: "The name and description fields must be present",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Every skill needs a `name` to identify it and a `description` telling \
+            the model what the skill does and when to use it.",
+        failing_example: "name: pdf",
+        passing_example: "name: pdf\ndescription: Extract text from PDF files",
+    },
+    RuleInfo {
+        id: "field-type",
+        summary: "Text fields must hold strings, and name and description must not be empty",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "`name`, `description`, `license`, `compatibility`, and `allowed-tools` \
+            hold text (`allowed-tools` may also be a list). YAML reads unquoted values such as \
+            `1.0` or `true` as numbers and booleans; quote them to keep them text. Dates \
+            such as `2024-01-01` and words such as `no` stay strings. An empty optional field counts as \
+            absent, but `name` and `description` must not be empty.",
+        failing_example: "name: pdf\ndescription: Extract text from PDF files\ncompatibility: 3.11",
+        passing_example: "name: pdf\ndescription: Extract text from PDF files\ncompatibility: \"3.11\"",
+    },
+    RuleInfo {
+        id: "name-length",
+        summary: "Skill names must be at most 64 characters",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Names are used as identifiers in prompts and file systems, so they are \
+            limited to 64 characters after Unicode normalization.",
+        failing_example: "name: a-very-long-name-that-keeps-going-and-going-well-past-the-limit",
+        passing_example: "name: pdf-tools",
+    },
+    RuleInfo {
+        id: "name-bytes",
+        summary: "Skill names must fit the configured byte limit",
+        default_severity: Severity::Error,
+        configurable: true,
+        description: "Opt-in check for stores that limit names in bytes rather than \
+            characters. The limit applies to the UTF-8 encoding after Unicode \
+            normalization, where each CJK character takes three bytes.",
+        failing_example: "name: 文字列処理   # 15 bytes, with a 12-byte limit",
+        passing_example: "name: text-proc",
+    },
+    RuleInfo {
+        id: "name-lowercase",
+        summary: "Skill names must be lowercase",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Names are compared case-sensitively by clients and must match the \
+            directory name, so they are required to be lowercase to avoid ambiguity across \
+            case-insensitive file systems.",
+        failing_example: "name: PDF-Tools",
+        passing_example: "name: pdf-tools",
+    },
+    RuleInfo {
+        id: "name-hyphen-boundary",
+        summary: "Skill names cannot start or end with a hyphen",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Hyphens separate words in kebab-case names; a leading or trailing hyphen \
+            is almost always a mistake and is easily confused with command-line flags.",
+        failing_example: "name: -pdf-tools",
+        passing_example: "name: pdf-tools",
+    },
+    RuleInfo {
+        id: "name-consecutive-hyphens",
+        summary: "Skill names cannot contain consecutive hyphens",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Kebab-case names use exactly one hyphen between words.",
+        failing_example: "name: pdf--tools",
+        passing_example: "name: pdf-tools",
+    },
+    RuleInfo {
+        id: "name-characters",
+        summary: "Skill names may only contain letters, digits, and hyphens",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Names may use Unicode letters and digits plus hyphens. Underscores, \
+            spaces, dots, and punctuation are rejected.",
+        failing_example: "name: pdf_tools",
+        passing_example: "name: pdf-tools",
+    },
+    RuleInfo {
+        id: "name-normalization",
+        summary: "Notes skill names that change under Unicode normalization",
+        default_severity: Severity::Info,
+        configurable: false,
+        description: "Names are checked in NFKC form, so full-width letters and decomposed \
+            accents are accepted as their plain equivalents. A name that changes under \
+            normalization usually holds characters pasted from a document or another \
+            keyboard layout; writing the normalized form keeps the file and its directory \
+            name byte-identical.",
+        failing_example: "name: ｐｄｆ",
+        passing_example: "name: pdf",
+    },
+    RuleInfo {
+        id: "name-directory-match",
+        summary: "Skill names must match their directory name",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Clients locate skills by directory, so the `name` field must equal the \
+            name of the directory containing SKILL.md (after Unicode normalization).",
+        failing_example: "name: pdf-tools   # in directory pdf/",
+        passing_example: "name: pdf         # in directory pdf/",
+    },
+    RuleInfo {
+        id: "description-length",
+        summary: "Descriptions must be at most 1024 characters",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Descriptions are embedded in every agent prompt, so they are limited to \
+            1024 characters. Move detail into the SKILL.md body.",
+        failing_example: "description: <1025 or more characters>",
+        passing_example: "description: Extract text from PDF files",
+    },
+    RuleInfo {
+        id: "description-min-length",
+        summary: "Descriptions should be at least 20 characters",
+        default_severity: Severity::Warning,
+        configurable: true,
+        description: "Agents choose which skill to use from the descriptions alone. A few \
+            words like `utils` give the model nothing to match a request against; say what \
+            the skill does and when to use it. The minimum is configurable, and 0 turns the \
+            rule off.",
+        failing_example: "description: PDF utils",
+        passing_example: "description: Extract text and tables from PDF files",
+    },
+    RuleInfo {
+        id: "compatibility-length",
+        summary: "Compatibility notes must be at most 500 characters",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "The optional `compatibility` field is a short note about environment \
+            requirements and is limited to 500 characters.",
+        failing_example: "compatibility: <501 or more characters>",
+        passing_example: "compatibility: Requires Python 3.11+",
+    },
+    RuleInfo {
+        id: "metadata-email-format",
+        summary: "metadata.maintainer-email must look like an email address",
+        default_severity: Severity::Error,
+        configurable: true,
+        description: "Opt-in check for the conventional `maintainer-email` metadata key. \
+            The value must have the form `local@domain.tld`; only the format is checked.",
+        failing_example: "metadata:\n  maintainer-email: jane at example",
+        passing_example: "metadata:\n  maintainer-email: jane@example.com",
+    },
+    RuleInfo {
+        id: "metadata-url-format",
+        summary: "metadata.homepage must be an http(s) URL",
+        default_severity: Severity::Error,
+        configurable: true,
+        description: "Opt-in check for the conventional `homepage` metadata key. The value \
+            must be an absolute `http://` or `https://` URL with a host; the URL is not \
+            fetched.",
+        failing_example: "metadata:\n  homepage: example.com",
+        passing_example: "metadata:\n  homepage: https://example.com/my-skill",
+    },
+    RuleInfo {
+        id: "metadata-id-format",
+        summary: "metadata.id must be a UUID or a reverse-DNS identifier",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "The optional `id` metadata key gives a skill a machine identifier that \
+            survives renames. It must be a string holding either a UUID \
+            (`8-4-4-4-12` hex digits) or a reverse-DNS identifier of at least two \
+            dot-separated labels of letters, digits, and hyphens, starting with a letter. \
+            Skills without an id are fine.",
+        failing_example: "metadata:\n  id: my skill",
+        passing_example: "metadata:\n  id: com.example.pdf-tools",
+    },
+    RuleInfo {
+        id: "metadata-key-type",
+        summary: "Keys under metadata must be strings",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Metadata is exposed to clients as a JSON object, whose keys are \
+            strings. A key YAML reads as an integer, boolean, or null would have to be \
+            converted or dropped, so it must be quoted instead.",
+        failing_example: "metadata:\n  1: first",
+        passing_example: "metadata:\n  \"1\": first",
+    },
+    RuleInfo {
+        id: "metadata-depth",
+        summary: "Metadata values may nest at most 3 levels deep",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Nested mappings and sequences under metadata are kept as they are, \
+            but the path from `metadata` to any value may have at most three keys or \
+            indices. Deeper structures belong in a resource file.",
+        failing_example: "metadata:\n  a:\n    b:\n      c:\n        d: 1",
+        passing_example: "metadata:\n  a:\n    b:\n      c: 1",
+    },
+    RuleInfo {
+        id: "deprecated-without-message",
+        summary: "A deprecated skill should say what to use instead",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "A skill marked `metadata.deprecated: \"true\"` is left out of \
+            generated prompts by default but stays on disk for a transition period. \
+            `metadata.deprecated-message` tells its remaining users why it was retired and \
+            what replaces it.",
+        failing_example: "metadata:\n  deprecated: \"true\"",
+        passing_example: "metadata:\n  deprecated: \"true\"\n  deprecated-message: Use pdf-tools instead",
+    },
+    RuleInfo {
+        id: "dir-portability",
+        summary: "Skill directory names should be valid on every platform",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "A directory that one platform accepts can fail to check out on another. \
+            Windows reserves device names such as `con` and `lpt1` (even with an extension), \
+            does not allow `<>:\"\\|?*` or control characters, and strips trailing dots and \
+            spaces. The message names the platform the directory breaks on.",
+        failing_example: "skills/con/SKILL.md",
+        passing_example: "skills/console-tools/SKILL.md",
+    },
+    RuleInfo {
+        id: "workspace-path-length",
+        summary: "Paths in a skill should stay well under the Windows path limit",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "Checked by `validate_workspace` and `validate-all`. Windows limits \
+            paths to 260 characters by default, counted from the drive root, so a path that \
+            is already long relative to the repository fails once the repository is cloned \
+            into a nested directory.",
+        failing_example: "skills/<200 characters of nested directories>/SKILL.md",
+        passing_example: "skills/pdf-tools/scripts/fill.py",
+    },
+    RuleInfo {
+        id: "workspace-broken-reference",
+        summary: "Links to other skills must point at valid skills",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Checked by `validate_workspace` and `validate-all`. A body link that \
+            leaves the skill directory but stays under the root, or a `skill:name` link, \
+            must lead to an existing skill that is itself valid; otherwise the instructions \
+            send the model somewhere it cannot follow.",
+        failing_example: "See [forms](../pdf-forms/SKILL.md) with no pdf-forms skill",
+        passing_example: "See [forms](skill:pdf-forms) with a valid pdf-forms skill",
+    },
+    RuleInfo {
+        id: "workspace-duplicate-name",
+        summary: "No two skills under a root may share a name",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Checked by `validate_workspace` and `validate-all`. Clients address \
+            skills by name, so two skills whose names are equal after Unicode normalization \
+            make one of them unreachable.",
+        failing_example: "team-a/pdf/SKILL.md: name: pdf\nteam-b/pdf/SKILL.md: name: pdf",
+        passing_example: "team-a/pdf/SKILL.md: name: pdf\nteam-b/pdf-forms/SKILL.md: name: pdf-forms",
+    },
+    RuleInfo {
+        id: "workspace-duplicate-id",
+        summary: "No two skills under a root may share a metadata.id",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Checked by `validate_workspace` and `validate-all`. `metadata.id` \
+            identifies a skill across renames, so it must be unique; ids are compared \
+            ignoring ASCII case. Skills without an id are not compared.",
+        failing_example: "pdf/SKILL.md: id: com.example.pdf\npdf-forms/SKILL.md: id: com.example.pdf",
+        passing_example: "pdf/SKILL.md: id: com.example.pdf\npdf-forms/SKILL.md: id: com.example.pdf-forms",
+    },
+    RuleInfo {
+        id: "workspace-confusable-name",
+        summary: "Skill names under a root should not look alike",
+        default_severity: Severity::Warning,
+        configurable: false,
+        description: "Checked by `validate_workspace` and `validate-all`. Names that differ \
+            only by lookalike characters (Cyrillic `о` for Latin `o`, `rn` for `m`) are easy \
+            to confuse for both people and models.",
+        failing_example: "name: pdf-tools\nname: pdf-tооls   # Cyrillic о",
+        passing_example: "name: pdf-tools\nname: pdf-forms",
+    },
+    RuleInfo {
+        id: "workspace-case-collision",
+        summary: "Sibling skill directories may not differ only in case",
+        default_severity: Severity::Error,
+        configurable: false,
+        description: "Checked by `validate_workspace` and `validate-all`. Directories such as \
+            `PDF/` and `pdf/` cannot both exist on case-insensitive file systems, so one skill \
+            would be lost when the repository is checked out there.",
+        failing_example: "skills/PDF/SKILL.md\nskills/pdf/SKILL.md",
+        passing_example: "skills/pdf/SKILL.md\nskills/pdf-forms/SKILL.md",
+    },
+    RuleInfo {
+        id: "body-placeholders",
+        summary: "Lists the {{NAME}} placeholders the body expects the host to fill",
+        default_severity: Severity::Info,
+        configurable: true,
+        description: "Opt-in report of the `{{NAME}}` placeholders used in the SKILL.md \
+            body, so a host can check it provides a value for each before calling \
+            `Skill::render_body`. `\\{{` and `{{{{` are escapes for a literal `{{` and are \
+            not reported.",
+        failing_example: "Run {{SKILL_DIR}}/scripts/fill.py",
+        passing_example: "Run \\{{SKILL_DIR}} literally",
+    },
+    RuleInfo {
+        id: "skill-size",
+        summary: "The skill directory must stay within the configured size and file limits",
+        default_severity: Severity::Error,
+        configurable: true,
+        description: "Opt-in check for hosts that copy whole skill directories into \
+            sandboxes. The total size of all files, the size of each file, and the number of \
+            files are compared with `SizeLimits` (by default 50 MiB, 10 MiB, and 1000 files); \
+            each limit exceeded is reported with the actual numbers and the three largest \
+            files.",
+        failing_example: "my-skill/dataset.bin   # 80 MiB",
+        passing_example: "my-skill/scripts/fill.py   # 4 KiB",
+    },
+    RuleInfo {
+        id: "skill-symlink-outside",
+        summary: "Symlinks should not point outside the skill directory",
+        default_severity: Severity::Warning,
+        configurable: true,
+        description: "Reported with the size check. A symlink leaving the skill directory \
+            is counted as zero bytes, but a host copying the skill would either follow it \
+            and copy files the skill does not own, or ship a broken link. Hosts resolving \
+            paths with `resolve_within` refuse such links.",
+        failing_example: "my-skill/data -> ../../shared/data",
+        passing_example: "my-skill/data/   # a real directory",
+    },
+];
+
+/// List metadata for all built-in validation rules.
+pub fn rules() -> Vec<RuleInfo> {
+    RULES.to_vec()
+}
+
+/// Look up the metadata for a validation rule by ID.
+pub fn rule_info(id: &str) -> Option<RuleInfo> {
+    RULES.iter().find(|rule| rule.id == id).copied()
+}
+
+/// Convert error diagnostics to their legacy message strings.
+///
+/// Each string is the diagnostic's `Display`, so [`validate`] and the
+/// structured functions cannot drift apart in wording.
+fn messages(diagnostics: &[Diagnostic]) -> Vec<String> {
+    diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .map(Diagnostic::to_string)
+        .collect()
+}
+
+/// Sort diagnostics into their documented order.
+///
+/// Rules about the path, file, or frontmatter as a whole (`path-missing`,
+/// `path-not-directory`, `skill-md-missing`, `skill-file`,
+/// `skill-file-case`, `unexpected-fields`) come first, then rules about one field, in
+/// [`ALLOWED_FIELDS`] order, then rules about the directory, body, or
+/// files. Within each group diagnostics are ordered by rule ID; the sort is
+/// stable, so diagnostics of one rule keep the order they were found in.
+fn sort_diagnostics(diagnostics: &mut [Diagnostic]) {
+    diagnostics.sort_by(|a, b| {
+        diagnostic_rank(a)
+            .cmp(&diagnostic_rank(b))
+            .then_with(|| a.rule.cmp(&b.rule))
+    });
+}
+
+/// Sort group of a diagnostic; see [`sort_diagnostics`].
+fn diagnostic_rank(diagnostic: &Diagnostic) -> usize {
+    let rule = diagnostic.rule.as_str();
+    let field = match rule {
+        "path-missing" | "path-not-directory" | "skill-md-missing" | "skill-file"
+        | "skill-file-case" | "unexpected-fields" => return 0,
+        "required-field" | "field-type" => named_field(diagnostic),
+        "deprecated-without-message" => Some("metadata"),
+        _ => ALLOWED_FIELDS
+            .iter()
+            .copied()
+            .find(|field| rule.starts_with(&format!("{}-", field))),
+    };
+    match field.and_then(|field| ALLOWED_FIELDS.iter().position(|f| *f == field)) {
+        Some(index) => index + 1,
+        None => ALLOWED_FIELDS.len() + 1,
+    }
+}
+
+/// The field a `required-field` or `field-type` diagnostic is about: the
+/// first, if it is about several.
+fn named_field(diagnostic: &Diagnostic) -> Option<&'static str> {
+    let field = match diagnostic
+        .params
+        .get("field")
+        .or_else(|| diagnostic.params.get("fields"))?
+    {
+        Param::Text(field) => field.as_str(),
+        Param::List(fields) => fields.first()?.as_str(),
+        Param::Number(_) => return None,
+    };
+    // A list item such as `allowed-tools[1]` is on its field's lines.
+    let field = field.split('[').next().unwrap_or(field);
+    ALLOWED_FIELDS.iter().copied().find(|f| *f == field)
+}
+
 /// Check if a field is allowed.
-fn is_allowed_field(field: &str) -> bool {
-    ALLOWED_FIELDS.contains(&field)
+pub(crate) fn is_allowed_field(field: &str, config: &ValidationConfig) -> bool {
+    ALLOWED_FIELDS.contains(&field) || (config.allow_x_prefixed_fields && is_extension_field(field))
+}
+
+/// A frontmatter field as seen by the rules.
+#[derive(Clone, Copy)]
+enum FieldValue<'a> {
+    Missing,
+    /// Not a string; the YAML value, if the field came from frontmatter.
+    NotString(Option<&'a serde_yaml::Value>),
+    Str(&'a str),
+}
+
+/// Borrowed view of the fields the rules check.
+///
+/// Both frontmatter maps and [`SkillProperties`] are converted to this view
+/// so that [`validate_metadata`] and [`validate_properties`] run exactly the
+/// same rules.
+struct FieldsView<'a> {
+    name: FieldValue<'a>,
+    description: FieldValue<'a>,
+    /// Optional fields holding neither a string nor nothing, with their
+    /// values.
+    mistyped: Vec<(String, &'a serde_yaml::Value)>,
+    compatibility: Option<&'a str>,
+    maintainer_email: Option<&'a str>,
+    homepage: Option<&'a str>,
+    id: FieldValue<'a>,
+    deprecated: bool,
+    deprecation_message: Option<&'a str>,
+}
+
+impl<'a> FieldsView<'a> {
+    fn from_map(metadata: &'a HashMap<String, serde_yaml::Value>) -> Self {
+        let field = |key: &str| match metadata.get(key) {
+            None => FieldValue::Missing,
+            Some(value) => value
+                .as_str()
+                .map_or(FieldValue::NotString(Some(value)), FieldValue::Str),
+        };
+        // An optional field left empty is the same as no field; a list of
+        // strings is another way to write allowed-tools, so there each item
+        // is checked instead.
+        let mut mistyped = Vec::new();
+        for field in ["license", "compatibility", "allowed-tools"] {
+            match metadata.get(field) {
+                None | Some(serde_yaml::Value::String(_) | serde_yaml::Value::Null) => {}
+                Some(serde_yaml::Value::Sequence(items)) if field == "allowed-tools" => {
+                    mistyped.extend(
+                        items
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, item)| !item.is_string())
+                            .map(|(i, item)| (format!("{}[{}]", field, i), item)),
+                    );
+                }
+                Some(value) => mistyped.push((field.to_string(), value)),
+            }
+        }
+        let conventional = |key: &str| {
+            metadata
+                .get("metadata")
+                .and_then(|m| m.get(key))
+                .and_then(|v| v.as_str())
+        };
+
+        Self {
+            name: field("name"),
+            description: field("description"),
+            mistyped,
+            compatibility: metadata.get("compatibility").and_then(|v| v.as_str()),
+            maintainer_email: conventional("maintainer-email"),
+            homepage: conventional("homepage"),
+            id: match metadata.get("metadata").and_then(|m| m.get("id")) {
+                None => FieldValue::Missing,
+                Some(value) => value
+                    .as_str()
+                    .map_or(FieldValue::NotString(Some(value)), FieldValue::Str),
+            },
+            deprecated: metadata
+                .get("metadata")
+                .and_then(|m| m.get("deprecated"))
+                .is_some_and(|v| v.as_str() == Some("true") || v.as_bool() == Some(true)),
+            deprecation_message: conventional("deprecated-message"),
+        }
+    }
+
+    fn from_properties(props: &'a SkillProperties) -> Self {
+        Self {
+            name: FieldValue::Str(&props.name),
+            description: FieldValue::Str(&props.description),
+            mistyped: Vec::new(),
+            compatibility: props.compatibility.as_deref(),
+            maintainer_email: props.maintainer_email(),
+            homepage: props.homepage(),
+            id: match props.metadata.as_ref().and_then(|m| m.get("id")) {
+                None => FieldValue::Missing,
+                Some(value) => value
+                    .as_str()
+                    .map_or(FieldValue::NotString(None), FieldValue::Str),
+            },
+            deprecated: props.is_deprecated(),
+            deprecation_message: props.deprecation_message(),
+        }
+    }
+}
+
+/// Normalize a string field value before it is checked or stored.
+///
+/// Surrounding whitespace is not part of the value: length limits apply to
+/// the trimmed string, and [`read_properties`](crate::read_properties)
+/// stores the same trimmed string.
+pub(crate) fn normalize_field(value: &str) -> &str {
+    value.trim()
 }
 
 /// Validate skill name format and directory match.
 ///
 /// Skill names support i18n characters (Unicode letters) plus hyphens.
 /// Names must be lowercase and cannot start/end with hyphens.
-fn validate_name(name: &str, skill_dir: Option<&Path>) -> Vec<String> {
+/// The name must match one of `dir_names` after normalization; an empty
+/// list skips that check. Messages quote at most `value_chars` characters
+/// of the name.
+///
+/// The checks apply to the NFKC form, but diagnostics quote the name as
+/// written. When the two differ, each message says what the name becomes,
+/// and a `name-normalization` note is added.
+fn validate_name(
+    name: &str,
+    dir_names: &[&str],
+    max_bytes: Option<usize>,
+    value_chars: usize,
+) -> Vec<Diagnostic> {
     let mut errors = Vec::new();
 
-    if name.is_empty() || name.trim().is_empty() {
-        errors.push("Field 'name' must be a non-empty string".to_string());
+    let raw = normalize_field(name);
+    if raw.is_empty() {
+        errors.push(
+            Diagnostic::error("field-type", "Field 'name' must be a non-empty string")
+                .with_param("field", "name"),
+        );
         return errors;
     }
 
     // NFKC normalize the name
-    let name = name.trim().nfkc().collect::<String>();
+    let name = raw.nfkc().collect::<String>();
+    let shown = shorten(raw, value_chars);
 
     // Check length
     if name.chars().count() > MAX_SKILL_NAME_LENGTH {
-        errors.push(format!(
-            "Skill name '{}' exceeds {} character limit ({} chars)",
-            name,
-            MAX_SKILL_NAME_LENGTH,
-            name.chars().count()
-        ));
+        errors.push(
+            Diagnostic::error(
+                "name-length",
+                format!(
+                    "Skill name '{}' exceeds {} character limit ({} chars)",
+                    shown,
+                    MAX_SKILL_NAME_LENGTH,
+                    name.chars().count()
+                ),
+            )
+            .with_value(raw)
+            .with_limit(MAX_SKILL_NAME_LENGTH, name.chars().count())
+            .with_param("length", name.chars().count()),
+        );
+    }
+
+    if let Some(max_bytes) = max_bytes
+        && name.len() > max_bytes
+    {
+        errors.push(
+            Diagnostic::error(
+                "name-bytes",
+                format!(
+                    "Skill name '{}' exceeds {} byte limit ({} chars, {} bytes)",
+                    shown,
+                    max_bytes,
+                    name.chars().count(),
+                    name.len()
+                ),
+            )
+            .with_value(raw)
+            .with_limit(max_bytes, name.len())
+            .with_param("length", name.chars().count())
+            .with_param("bytes", name.len()),
+        );
     }
 
     // Check lowercase
     if name != name.to_lowercase() {
-        errors.push(format!("Skill name '{}' must be lowercase", name));
+        errors.push(
+            Diagnostic::error(
+                "name-lowercase",
+                format!("Skill name '{}' must be lowercase", shown),
+            )
+            .with_value(raw),
+        );
     }
 
     // Check leading/trailing hyphens
     if name.starts_with('-') || name.ends_with('-') {
-        errors.push("Skill name cannot start or end with a hyphen".to_string());
+        errors.push(
+            Diagnostic::error(
+                "name-hyphen-boundary",
+                "Skill name cannot start or end with a hyphen",
+            )
+            .with_value(raw),
+        );
     }
 
     // Check consecutive hyphens
     if name.contains("--") {
-        errors.push("Skill name cannot contain consecutive hyphens".to_string());
+        errors.push(
+            Diagnostic::error(
+                "name-consecutive-hyphens",
+                "Skill name cannot contain consecutive hyphens",
+            )
+            .with_value(raw),
+        );
     }
 
     // Check valid characters (alphanumeric or hyphen)
     if !name.chars().all(|c| c.is_alphanumeric() || c == '-') {
-        errors.push(format!(
+        let mut message = format!(
             "Skill name '{}' contains invalid characters. Only letters, digits, and hyphens are allowed.",
-            name
-        ));
+            shown
+        );
+        // A quoted `name: "1.0"` passes the type check and lands here.
+        if name.parse::<f64>().is_ok() {
+            message.push_str(" It reads as a version number, which belongs in metadata.version.");
+        }
+        errors.push(Diagnostic::error("name-characters", message).with_value(raw));
     }
 
     // Check directory name match
-    if let Some(dir) = skill_dir
-        && let Some(dir_name) = dir.file_name().and_then(|n| n.to_str())
-    {
-        let normalized_dir_name = dir_name.nfkc().collect::<String>();
-        if normalized_dir_name != name {
-            errors.push(format!(
-                "Directory name '{}' must match skill name '{}'",
-                dir_name, name
-            ));
+    let matches_dir = |dir_name: &&str| dir_name.nfkc().collect::<String>() == name;
+    match dir_names {
+        [] => {}
+        [dir_name] if !matches_dir(dir_name) => errors.push(
+            Diagnostic::error(
+                "name-directory-match",
+                format!(
+                    "Directory name '{}' must match skill name '{}'",
+                    shorten(dir_name, value_chars),
+                    shown
+                ),
+            )
+            .with_value(raw)
+            .with_param("directories", vec![dir_name.to_string()]),
+        ),
+        _ if !dir_names.iter().any(matches_dir) => errors.push(
+            Diagnostic::error(
+                "name-directory-match",
+                format!(
+                    "One of the directory names '{}' must match skill name '{}'",
+                    dir_names
+                        .iter()
+                        .map(|dir_name| shorten(dir_name, value_chars))
+                        .collect::<Vec<_>>()
+                        .join("', '"),
+                    shown
+                ),
+            )
+            .with_value(raw)
+            .with_param(
+                "directories",
+                dir_names.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            ),
+        ),
+        _ => {}
+    }
+
+    if name != raw {
+        let note = format!(
+            " (after Unicode normalization this becomes '{}')",
+            shorten(&name, value_chars)
+        );
+        for error in &mut errors {
+            error.message.push_str(&note);
+            error
+                .params
+                .insert("normalized".to_string(), Param::from(name.as_str()));
         }
+        errors.push(
+            Diagnostic::info(
+                "name-normalization",
+                format!(
+                    "Skill name '{}' changes under Unicode normalization to '{}'; it may contain characters pasted from elsewhere",
+                    shown,
+                    shorten(&name, value_chars)
+                ),
+            )
+            .with_value(raw)
+            .with_param("normalized", name.as_str()),
+        );
     }
 
     errors
 }
 
 /// Validate description format.
-fn validate_description(description: &str) -> Vec<String> {
+fn validate_description(description: &str, min_chars: usize) -> Vec<Diagnostic> {
     let mut errors = Vec::new();
 
-    if description.is_empty() || description.trim().is_empty() {
-        errors.push("Field 'description' must be a non-empty string".to_string());
+    let description = normalize_field(description);
+    if description.is_empty() {
+        errors.push(
+            Diagnostic::error(
+                "field-type",
+                "Field 'description' must be a non-empty string",
+            )
+            .with_param("field", "description"),
+        );
         return errors;
     }
 
-    if description.len() > MAX_DESCRIPTION_LENGTH {
-        errors.push(format!(
-            "Description exceeds {} character limit ({} chars)",
-            MAX_DESCRIPTION_LENGTH,
-            description.len()
-        ));
+    let chars = description.chars().count();
+    if chars > MAX_DESCRIPTION_LENGTH {
+        errors.push(
+            Diagnostic::error(
+                "description-length",
+                format!(
+                    "Description exceeds {} character limit ({} chars)",
+                    MAX_DESCRIPTION_LENGTH, chars
+                ),
+            )
+            .with_limit(MAX_DESCRIPTION_LENGTH, chars)
+            .with_param("length", chars),
+        );
+    }
+
+    if chars < min_chars {
+        errors.push(
+            Diagnostic::warning(
+                "description-min-length",
+                format!(
+                    "Description is {} characters, under the minimum of {}; agents pick \
+                     skills by description, so say what the skill does and when to use it",
+                    chars, min_chars
+                ),
+            )
+            .with_param("min", min_chars)
+            .with_param("actual", chars)
+            .with_param("length", chars),
+        );
     }
 
     errors
 }
 
 /// Validate compatibility format.
-fn validate_compatibility(compatibility: &str) -> Vec<String> {
+fn validate_compatibility(compatibility: &str) -> Vec<Diagnostic> {
+    let mut errors = Vec::new();
+
+    let chars = normalize_field(compatibility).chars().count();
+    if chars > MAX_COMPATIBILITY_LENGTH {
+        errors.push(
+            Diagnostic::error(
+                "compatibility-length",
+                format!(
+                    "Compatibility exceeds {} character limit ({} chars)",
+                    MAX_COMPATIBILITY_LENGTH, chars
+                ),
+            )
+            .with_limit(MAX_COMPATIBILITY_LENGTH, chars)
+            .with_param("length", chars),
+        );
+    }
+
+    errors
+}
+
+/// Check that `value` looks like an email address: `local@domain.tld`
+/// without whitespace.
+fn looks_like_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !value.chars().any(char::is_whitespace)
+        && !domain.contains('@')
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|label| !label.is_empty())
+}
+
+/// Check that `value` is an absolute http(s) URL with a non-empty host.
+fn looks_like_http_url(value: &str) -> bool {
+    let lower = value.to_ascii_lowercase();
+    let Some(rest) = lower
+        .strip_prefix("https://")
+        .or_else(|| lower.strip_prefix("http://"))
+    else {
+        return false;
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    !host.is_empty() && !value.chars().any(char::is_whitespace)
+}
+
+/// Check that `value` is a UUID or a reverse-DNS identifier such as
+/// `com.example.pdf-tools`.
+pub(crate) fn is_stable_id(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    let is_uuid = groups.iter().map(|g| g.len()).eq([8, 4, 4, 4, 12])
+        && groups
+            .iter()
+            .all(|g| g.chars().all(|c| c.is_ascii_hexdigit()));
+    if is_uuid {
+        return true;
+    }
+
+    let labels: Vec<&str> = value.split('.').collect();
+    labels.len() >= 2
+        && labels[0].starts_with(|c: char| c.is_ascii_alphabetic())
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Name of a YAML value's type, for error messages.
+fn yaml_type_name(value: &serde_yaml::Value) -> &'static str {
+    match value {
+        serde_yaml::Value::Null => "null",
+        serde_yaml::Value::Bool(_) => "boolean",
+        serde_yaml::Value::Number(n) if n.is_f64() => "float",
+        serde_yaml::Value::Number(_) => "integer",
+        serde_yaml::Value::String(_) => "string",
+        serde_yaml::Value::Sequence(_) => "sequence",
+        serde_yaml::Value::Mapping(_) => "mapping",
+        serde_yaml::Value::Tagged(_) => "tagged value",
+    }
+}
+
+/// The `field-type` error for a string field holding `value` instead.
+///
+/// An unquoted number or boolean is nearly always text YAML read as
+/// another type (`name: 1.0`, `license: true`), so the message names the
+/// type, shows the value as parsed, and says to quote it. Anything else
+/// gets the plain type error.
+pub(crate) fn field_type_error(field: &str, value: Option<&serde_yaml::Value>) -> Diagnostic {
+    let parsed = match value {
+        Some(serde_yaml::Value::Number(n)) => Some(n.to_string()),
+        Some(serde_yaml::Value::Bool(b)) => Some(b.to_string()),
+        _ => None,
+    };
+    let diagnostic = match (parsed, value) {
+        (Some(parsed), Some(value)) => Diagnostic::error(
+            "field-type",
+            format!(
+                "Field '{}' is a YAML {} ({}), not a string; put the value in quotes",
+                field,
+                yaml_type_name(value),
+                parsed
+            ),
+        )
+        .with_value(parsed),
+        _ if matches!(field, "name" | "description") => Diagnostic::error(
+            "field-type",
+            format!("Field '{}' must be a non-empty string", field),
+        ),
+        (_, value) => Diagnostic::error(
+            "field-type",
+            format!(
+                "Field '{}' must be a string, not {}",
+                field,
+                value.map_or("null", yaml_type_name)
+            ),
+        ),
+    };
+    let diagnostic = diagnostic.with_param("field", field);
+    match value {
+        Some(value) => diagnostic.with_param("type", yaml_type_name(value)),
+        None => diagnostic,
+    }
+}
+
+/// Check the structure of the `metadata` field: string keys at every level
+/// and at most [`MAX_METADATA_DEPTH`] levels of nesting.
+///
+/// Messages quote at most `value_chars` characters of a key or path.
+pub(crate) fn validate_metadata_structure(
+    metadata: &serde_yaml::Value,
+    value_chars: usize,
+) -> Vec<Diagnostic> {
+    fn walk(
+        value: &serde_yaml::Value,
+        path: &mut Vec<String>,
+        errors: &mut Vec<Diagnostic>,
+        value_chars: usize,
+    ) {
+        let children: Vec<(String, &serde_yaml::Value)> = match value {
+            serde_yaml::Value::Mapping(mapping) => mapping
+                .iter()
+                .filter_map(|(key, child)| match key.as_str() {
+                    Some(key) => Some((key.to_string(), child)),
+                    None => {
+                        let shown = serde_yaml::to_string(key).unwrap_or_default();
+                        let shown = shown.trim_end();
+                        let location = if path.is_empty() {
+                            "metadata".to_string()
+                        } else {
+                            format!("metadata.{}", path.join("."))
+                        };
+                        errors.push(
+                            Diagnostic::error(
+                                "metadata-key-type",
+                                format!(
+                                    "Key '{}' in {} must be a string, not {}",
+                                    shorten(shown, value_chars),
+                                    shorten(&location, value_chars),
+                                    yaml_type_name(key)
+                                ),
+                            )
+                            .with_value(shown)
+                            .with_param("location", location)
+                            .with_param("type", yaml_type_name(key)),
+                        );
+                        None
+                    }
+                })
+                .collect(),
+            serde_yaml::Value::Sequence(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, child)| (i.to_string(), child))
+                .collect(),
+            _ => return,
+        };
+
+        for (key, child) in children {
+            path.push(key);
+            if path.len() > MAX_METADATA_DEPTH {
+                let location = format!("metadata.{}", path.join("."));
+                errors.push(
+                    Diagnostic::error(
+                        "metadata-depth",
+                        format!(
+                            "{} nests deeper than {} levels",
+                            shorten(&location, value_chars),
+                            MAX_METADATA_DEPTH
+                        ),
+                    )
+                    .with_value(location)
+                    .with_limit(MAX_METADATA_DEPTH, path.len() + nesting(child)),
+                );
+            } else {
+                walk(child, path, errors, value_chars);
+            }
+            path.pop();
+        }
+    }
+
+    /// Levels of keys and indices below `value`.
+    fn nesting(value: &serde_yaml::Value) -> usize {
+        let children: Box<dyn Iterator<Item = &serde_yaml::Value>> = match value {
+            serde_yaml::Value::Mapping(map) => Box::new(map.values()),
+            serde_yaml::Value::Sequence(items) => Box::new(items.iter()),
+            _ => return 0,
+        };
+        children.map(|child| 1 + nesting(child)).max().unwrap_or(0)
+    }
+
+    let mut errors = Vec::new();
+    if matches!(metadata, serde_yaml::Value::Mapping(_)) {
+        walk(metadata, &mut Vec::new(), &mut errors, value_chars);
+    }
+    errors
+}
+
+/// Validate the format of conventional metadata values.
+fn validate_metadata_conventions(fields: &FieldsView, value_chars: usize) -> Vec<Diagnostic> {
+    let mut errors = Vec::new();
+
+    if let Some(email) = fields.maintainer_email
+        && !looks_like_email(email)
+    {
+        errors.push(
+            Diagnostic::error(
+                "metadata-email-format",
+                format!(
+                    "metadata.maintainer-email '{}' is not a valid email address",
+                    shorten(email, value_chars)
+                ),
+            )
+            .with_value(email)
+            .with_param("field", "metadata.maintainer-email"),
+        );
+    }
+
+    if let Some(url) = fields.homepage
+        && !looks_like_http_url(url)
+    {
+        errors.push(
+            Diagnostic::error(
+                "metadata-url-format",
+                format!(
+                    "metadata.homepage '{}' is not an http(s) URL",
+                    shorten(url, value_chars)
+                ),
+            )
+            .with_value(url)
+            .with_param("field", "metadata.homepage"),
+        );
+    }
+
+    errors
+}
+
+/// Device names Windows reserves in every directory, with or without an
+/// extension.
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Characters Windows does not allow in file names.
+const WINDOWS_RESERVED_CHARS: &[char] = &['<', '>', ':', '"', '\\', '|', '?', '*'];
+
+/// Check that a skill directory name can be checked out on every platform.
+///
+/// Messages quote at most `value_chars` characters of the name.
+fn validate_dir_portability(dir_name: &str, value_chars: usize) -> Vec<Diagnostic> {
     let mut errors = Vec::new();
+    let shown = shorten(dir_name, value_chars);
+    // Each warning names its problem in the `problem` param.
+    let warning = |problem: &str, message: String| {
+        Diagnostic::warning(
+            "dir-portability",
+            format!("Directory name '{}' {}", shown, message),
+        )
+        .with_value(dir_name)
+        .with_param("problem", problem)
+    };
+
+    let stem = dir_name.split('.').next().unwrap_or(dir_name).trim_end();
+    if WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+    {
+        errors.push(
+            warning(
+                "reserved-name",
+                format!("is reserved on Windows ({})", stem.to_uppercase()),
+            )
+            .with_param("reserved", stem.to_uppercase()),
+        );
+    }
+
+    let mut reserved: Vec<char> = dir_name
+        .chars()
+        .filter(|c| WINDOWS_RESERVED_CHARS.contains(c))
+        .collect();
+    reserved.dedup();
+    if !reserved.is_empty() {
+        let chars: Vec<String> = reserved.iter().map(|c| format!("'{}'", c)).collect();
+        errors.push(
+            warning(
+                "reserved-characters",
+                format!("contains {}, not allowed on Windows", chars.join(", ")),
+            )
+            .with_param(
+                "characters",
+                reserved.iter().map(char::to_string).collect::<Vec<_>>(),
+            ),
+        );
+    }
+    if dir_name.chars().any(char::is_control) {
+        errors.push(warning(
+            "control-characters",
+            "contains control characters, not allowed on Windows".to_string(),
+        ));
+    }
 
-    if compatibility.len() > MAX_COMPATIBILITY_LENGTH {
-        errors.push(format!(
-            "Compatibility exceeds {} character limit ({} chars)",
-            MAX_COMPATIBILITY_LENGTH,
-            compatibility.len()
+    if dir_name.ends_with('.') || dir_name.ends_with(' ') {
+        errors.push(warning(
+            "trailing-dot-or-space",
+            "ends with a dot or space, which Windows strips".to_string(),
         ));
     }
 
@@ -131,24 +1597,50 @@ fn validate_compatibility(compatibility: &str) -> Vec<String> {
 }
 
 /// Validate that only allowed fields are present.
-fn validate_metadata_fields(metadata: &HashMap<String, serde_yaml::Value>) -> Vec<String> {
+fn validate_metadata_fields(
+    metadata: &HashMap<String, serde_yaml::Value>,
+    config: &ValidationConfig,
+) -> Vec<Diagnostic> {
     let mut errors = Vec::new();
 
     let extra_fields: Vec<_> = metadata
         .keys()
-        .filter(|k| !is_allowed_field(k.as_str()))
+        .filter(|k| !is_allowed_field(k.as_str(), config))
         .collect();
 
     if !extra_fields.is_empty() {
         let mut sorted_extra: Vec<_> = extra_fields.iter().map(|s| s.as_str()).collect();
-        sorted_extra.sort();
+        ordering::sort_strings(&mut sorted_extra);
+        let shown: Vec<_> = sorted_extra
+            .iter()
+            .map(|field| shorten(field, config.value_chars()))
+            .collect();
         let mut sorted_allowed: Vec<_> = ALLOWED_FIELDS.to_vec();
-        sorted_allowed.sort();
-        errors.push(format!(
-            "Unexpected fields in frontmatter: {}. Only {:?} are allowed.",
-            sorted_extra.join(", "),
-            sorted_allowed
-        ));
+        ordering::sort_strings(&mut sorted_allowed);
+        errors.push(
+            Diagnostic::error(
+                "unexpected-fields",
+                format!(
+                    "Unexpected fields in frontmatter: {}. Only {:?} are allowed.",
+                    shown.join(", "),
+                    sorted_allowed
+                ),
+            )
+            .with_param(
+                "fields",
+                sorted_extra
+                    .iter()
+                    .map(|f| f.to_string())
+                    .collect::<Vec<_>>(),
+            )
+            .with_param(
+                "allowed",
+                sorted_allowed
+                    .iter()
+                    .map(|f| f.to_string())
+                    .collect::<Vec<_>>(),
+            ),
+        );
     }
 
     errors
@@ -166,39 +1658,237 @@ fn validate_metadata_fields(metadata: &HashMap<String, serde_yaml::Value>) -> Ve
 ///
 /// # Returns
 ///
-/// List of validation error messages. Empty list means valid.
+/// List of validation error messages. Empty list means valid. Messages
+/// come in a fixed order: unexpected fields first, then errors about each
+/// field in [`ALLOWED_FIELDS`] order, each field's errors ordered by rule
+/// ID.
+///
+/// Body and file rules do not run; use [`validate_input`] to include them.
 pub fn validate_metadata(
     metadata: &HashMap<String, serde_yaml::Value>,
     skill_dir: Option<&Path>,
 ) -> Vec<String> {
-    let mut errors = Vec::new();
-
-    // Check for unexpected fields
-    errors.extend(validate_metadata_fields(metadata));
+    let mut input = ValidationInput::new(metadata);
+    input.skill_dir = skill_dir;
+    validate_input(&input, &ValidationConfig::default()).error_messages()
+}
 
-    // Validate name
-    if !metadata.contains_key("name") {
-        errors.push("Missing required field in frontmatter: name".to_string());
-    } else if let Some(name) = metadata.get("name").and_then(|v| v.as_str()) {
-        errors.extend(validate_name(name, skill_dir));
-    } else {
-        errors.push("Field 'name' must be a non-empty string".to_string());
+/// Validate a skill from its parts: the rules of [`validate_detailed`],
+/// each run if `input` has what it needs (see [`ValidationInput`]).
+///
+/// Every validation function that starts from frontmatter goes through
+/// this one, so a skill given the same parts gets the same diagnostics
+/// whichever is called. The exception is [`validate_properties`], which
+/// starts from [`SkillProperties`] and runs the field rules directly.
+/// Custom rules in `config` run only when the input has a body.
+pub fn validate_input(input: &ValidationInput, config: &ValidationConfig) -> ValidationReport {
+    let mut diagnostics = metadata_diagnostics(input.metadata, input.skill_dir, config);
+    if let Some(name) = input.skill_md_name {
+        diagnostics.extend(skill_file_case(name));
     }
-
+    if let Some(body) = input.body
+        && config.list_placeholders
+    {
+        let names = placeholders(body);
+        if !names.is_empty() {
+            diagnostics.push(
+                Diagnostic::info(
+                    "body-placeholders",
+                    format!("Body uses placeholders: {}", names.join(", ")),
+                )
+                .with_param("placeholders", names),
+            );
+        }
+    }
+    if let (Some(limits), Some(resources)) = (config.size_limits.as_ref(), input.resources) {
+        diagnostics.extend(size_diagnostics(resources, limits));
+    }
+    if let Some(body) = input.body
+        && !config.custom_rules.is_empty()
+    {
+        let properties = properties_from_metadata(input.metadata).ok();
+        let ctx = RuleContext {
+            skill_dir: input.skill_dir.unwrap_or(Path::new("")),
+            metadata: input.metadata,
+            properties: properties.as_ref(),
+            body,
+        };
+        for rule in &config.custom_rules {
+            diagnostics.extend(rule.check(&ctx));
+        }
+    }
+    sort_diagnostics(&mut diagnostics);
+    config.render_messages(&mut diagnostics);
+    ValidationReport { diagnostics }
+}
+
+/// Validate skill properties built without a SKILL.md.
+///
+/// Applies the same rules as [`validate_metadata`], except those that only
+/// make sense for raw frontmatter (unexpected fields, missing fields, and
+/// non-string values), which [`SkillProperties`] rules out by construction.
+///
+/// # Arguments
+///
+/// * `props` - Skill properties to check
+/// * `expected_dir_name` - Name of the directory the skill will live in, if
+///   known (for the name-directory match check)
+///
+/// # Returns
+///
+/// Diagnostics for every rule violation, ordered as by
+/// [`validate_metadata`]. Empty means valid.
+pub fn validate_properties(
+    props: &SkillProperties,
+    expected_dir_name: Option<&str>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = fields_diagnostics(
+        &FieldsView::from_properties(props),
+        expected_dir_name.as_slice(),
+        &ValidationConfig::default(),
+    );
+    sort_diagnostics(&mut diagnostics);
+    diagnostics
+}
+
+/// Run all metadata rules, producing structured diagnostics.
+fn metadata_diagnostics(
+    metadata: &HashMap<String, serde_yaml::Value>,
+    skill_dir: Option<&Path>,
+    config: &ValidationConfig,
+) -> Vec<Diagnostic> {
+    let mut errors = Vec::new();
+
+    // Check for unexpected fields
+    errors.extend(validate_metadata_fields(metadata, config));
+
+    if let Some(value) = metadata.get("metadata") {
+        errors.extend(validate_metadata_structure(value, config.value_chars()));
+    }
+
+    if let Some(dir_name) = skill_dir
+        .and_then(|dir| dir.file_name())
+        .and_then(|name| name.to_str())
+    {
+        errors.extend(validate_dir_portability(dir_name, config.value_chars()));
+    }
+
+    let dir_names = skill_dir.map_or_else(Vec::new, |dir| config.name_match.candidates(dir));
+    errors.extend(fields_diagnostics(
+        &FieldsView::from_map(metadata),
+        &dir_names,
+        config,
+    ));
+
+    sort_diagnostics(&mut errors);
+    errors
+}
+
+/// Message for the required `fields` missing from frontmatter.
+pub(crate) fn missing_fields_message(fields: &[&str]) -> String {
+    match fields {
+        [field] => format!("Missing required field in frontmatter: {}", field),
+        _ => format!(
+            "Missing required fields in frontmatter: {}",
+            fields.join(", ")
+        ),
+    }
+}
+
+/// Run the field rules shared by frontmatter and properties validation.
+fn fields_diagnostics(
+    fields: &FieldsView,
+    dir_names: &[&str],
+    config: &ValidationConfig,
+) -> Vec<Diagnostic> {
+    let mut errors = Vec::new();
+
+    // Missing required fields are reported together.
+    let missing: Vec<&str> = [("name", fields.name), ("description", fields.description)]
+        .into_iter()
+        .filter(|(_, value)| matches!(value, FieldValue::Missing))
+        .map(|(field, _)| field)
+        .collect();
+    if !missing.is_empty() {
+        errors.push(
+            Diagnostic::error("required-field", missing_fields_message(&missing)).with_param(
+                "fields",
+                missing.iter().map(|f| f.to_string()).collect::<Vec<_>>(),
+            ),
+        );
+    }
+
+    // Validate name
+    match fields.name {
+        FieldValue::Missing => {}
+        FieldValue::NotString(value) => errors.push(field_type_error("name", value)),
+        FieldValue::Str(name) => errors.extend(validate_name(
+            name,
+            dir_names,
+            config.max_name_bytes,
+            config.value_chars(),
+        )),
+    }
+
     // Validate description
-    if !metadata.contains_key("description") {
-        errors.push("Missing required field in frontmatter: description".to_string());
-    } else if let Some(desc) = metadata.get("description").and_then(|v| v.as_str()) {
-        errors.extend(validate_description(desc));
-    } else {
-        errors.push("Field 'description' must be a non-empty string".to_string());
+    match fields.description {
+        FieldValue::Missing => {}
+        FieldValue::NotString(value) => errors.push(field_type_error("description", value)),
+        FieldValue::Str(desc) => errors.extend(validate_description(
+            desc,
+            config
+                .min_description_chars
+                .unwrap_or(DEFAULT_MIN_DESCRIPTION_CHARS),
+        )),
+    }
+
+    for (field, value) in &fields.mistyped {
+        errors.push(field_type_error(field, Some(value)));
     }
 
     // Validate compatibility if present
-    if let Some(compat) = metadata.get("compatibility").and_then(|v| v.as_str()) {
+    if let Some(compat) = fields.compatibility {
         errors.extend(validate_compatibility(compat));
     }
 
+    if config.metadata_conventions {
+        errors.extend(validate_metadata_conventions(fields, config.value_chars()));
+    }
+
+    match fields.id {
+        FieldValue::Missing => {}
+        FieldValue::NotString(_) => errors.push(
+            Diagnostic::error("metadata-id-format", "metadata.id must be a string")
+                .with_param("field", "metadata.id"),
+        ),
+        FieldValue::Str(id) if !is_stable_id(id) => errors.push(
+            Diagnostic::error(
+                "metadata-id-format",
+                format!(
+                    "metadata.id '{}' is not a UUID or reverse-DNS identifier",
+                    shorten(id, config.value_chars())
+                ),
+            )
+            .with_value(id)
+            .with_param("field", "metadata.id"),
+        ),
+        FieldValue::Str(_) => {}
+    }
+
+    if fields.deprecated
+        && fields
+            .deprecation_message
+            .is_none_or(|m| m.trim().is_empty())
+    {
+        errors.push(
+            Diagnostic::warning(
+                "deprecated-without-message",
+                "Skill is deprecated but has no metadata.deprecated-message",
+            )
+            .with_param("field", "metadata.deprecated-message"),
+        );
+    }
+
     errors
 }
 
@@ -210,41 +1900,387 @@ pub fn validate_metadata(
 ///
 /// # Returns
 ///
-/// List of validation error messages. Empty list means valid.
+/// List of validation error messages. Empty list means valid. Messages
+/// are ordered as by [`validate_metadata`], with errors about the
+/// directory, body, and files after all field errors.
+///
+/// Each message is the `Display` of an error diagnostic from
+/// [`validate_report`], and its wording is kept stable: code matching on
+/// these strings keeps working, and can move to the rule IDs of
+/// [`validate_report`] at its own pace.
 pub fn validate(skill_dir: &Path) -> Vec<String> {
+    validate_with_config(skill_dir, &ValidationConfig::default())
+}
+
+/// Validate a skill directory with opt-in rules enabled by `config`.
+///
+/// See [`validate`].
+pub fn validate_with_config(skill_dir: &Path, config: &ValidationConfig) -> Vec<String> {
+    validate_report(skill_dir, config).error_messages()
+}
+
+/// Validate a skill directory, producing a [`ValidationReport`].
+///
+/// The structured form of [`validate_with_config`]: the same checks, with
+/// warnings and notes kept and each problem identified by its rule.
+pub fn validate_report(skill_dir: &Path, config: &ValidationConfig) -> ValidationReport {
+    ValidationReport {
+        diagnostics: validate_detailed(skill_dir, config),
+    }
+}
+
+/// Validate a skill directory, producing structured diagnostics.
+///
+/// Same checks as [`validate_with_config`]. A path that is not a skill
+/// directory gets one diagnostic under `path-missing`, `path-not-directory`,
+/// or `skill-md-missing`, and a SKILL.md that cannot be read or parsed one
+/// under `skill-file`; nothing else is checked then.
+pub fn validate_detailed(skill_dir: &Path, config: &ValidationConfig) -> Vec<Diagnostic> {
+    // Only the frontmatter is needed unless a body rule is enabled.
+    match read_skill_md(skill_dir, config.needs_body()) {
+        Ok((_, content)) => validate_content(skill_dir, &content, config),
+        Err(mut diagnostic) => {
+            config.render_messages([&mut diagnostic]);
+            vec![diagnostic]
+        }
+    }
+}
+
+/// Locate and read the SKILL.md of `skill_dir` for validation.
+///
+/// With `full` unset, only the frontmatter is read (see
+/// [`read_frontmatter_only`]).
+///
+/// # Returns
+///
+/// Tuple of (SKILL.md path, content), or the diagnostic explaining why it
+/// could not be read: `path-missing`, `path-not-directory`, or
+/// `skill-md-missing` for a path that is not a skill directory, and
+/// `skill-file` for a SKILL.md that cannot be read.
+pub(crate) fn read_skill_md(
+    skill_dir: &Path,
+    full: bool,
+) -> std::result::Result<(PathBuf, String), Diagnostic> {
+    // Each failure also names its problem in the `problem` param, which
+    // predates the path rules.
+    let fail = |rule: &str, problem: &str, path: &Path, message: String| {
+        Err(Diagnostic::error(rule, message)
+            .with_param("problem", problem)
+            .with_param("path", path.to_string_lossy().into_owned()))
+    };
+
     // Check path exists
     if !skill_dir.exists() {
-        return vec![format!("Path does not exist: {}", skill_dir.display())];
+        return fail(
+            "path-missing",
+            "not-found",
+            skill_dir,
+            format!("Path does not exist: {}", skill_dir.display()),
+        );
     }
 
     // Check it's a directory
     if !skill_dir.is_dir() {
-        return vec![format!("Not a directory: {}", skill_dir.display())];
+        if let Some(message) = standalone_skill_message(&FsSource, skill_dir) {
+            return fail("path-not-directory", "standalone-file", skill_dir, message);
+        }
+        return fail(
+            "path-not-directory",
+            "not-a-directory",
+            skill_dir,
+            format!("Not a directory: {}", skill_dir.display()),
+        );
     }
 
     // Find SKILL.md
     let skill_md = match find_skill_md(skill_dir) {
         Some(path) => path,
-        None => return vec!["Missing required file: SKILL.md".to_string()],
+        None => {
+            return fail(
+                "skill-md-missing",
+                "missing-file",
+                skill_dir,
+                "Missing required file: SKILL.md".to_string(),
+            );
+        }
+    };
+
+    let content = if full {
+        read_skill_md_file(&skill_md)
+    } else {
+        read_frontmatter_only(&skill_md)
+    };
+    match content {
+        Ok(content) => Ok((skill_md, content)),
+        Err(e) => fail(
+            "skill-file",
+            "unreadable",
+            &skill_md,
+            format!("Failed to read {}: {}", skill_md.display(), e),
+        )
+        .map_err(|d: Diagnostic| d.with_param("error", e.to_string())),
+    }
+}
+
+/// Validate SKILL.md `content` for a skill that will be installed in
+/// `intended_dir`, before anything is written.
+///
+/// Runs the rules of [`validate_detailed`] on the content, with the name
+/// matched against `intended_dir` (and its ancestors, per
+/// `config.name_match`) and its directory name checked for portability.
+/// The rules that inspect files on disk, `skill-size` and
+/// `skill-file-case`, are skipped; `intended_dir` need not exist.
+pub fn validate_content_at(
+    content: &str,
+    intended_dir: &Path,
+    config: &ValidationConfig,
+) -> Vec<Diagnostic> {
+    content_diagnostics(intended_dir, content, config, false)
+}
+
+/// Validate the raw bytes of a SKILL.md that is not on disk, such as
+/// content piped to the CLI, naming it `label` in diagnostics.
+///
+/// Bytes that are not UTF-8 get the `skill-file` diagnostic an unreadable
+/// SKILL.md gets from [`validate_detailed`]. The name is matched against
+/// `intended_dir` as in [`validate_content_at`]; with `None` that rule is
+/// skipped. With [`size_limits`](ValidationConfig::size_limits) set, the
+/// content is checked as the only file of the skill.
+pub fn validate_bytes_at(
+    bytes: Vec<u8>,
+    label: &str,
+    intended_dir: Option<&Path>,
+    config: &ValidationConfig,
+) -> Vec<Diagnostic> {
+    let content = match decode_skill_md(bytes) {
+        Ok(content) => content,
+        Err(e) => {
+            let mut diagnostic =
+                Diagnostic::error("skill-file", format!("Failed to read {}: {}", label, e))
+                    .with_param("problem", "unreadable")
+                    .with_param("path", label)
+                    .with_param("error", e.to_string());
+            config.render_messages([&mut diagnostic]);
+            return vec![diagnostic];
+        }
     };
+    let (metadata, body) = match parse_frontmatter(&content) {
+        Ok(parsed) => parsed,
+        Err(e) => return invalid_frontmatter(label, &e, config),
+    };
+
+    let files = [ResourceFile {
+        path: "SKILL.md".to_string(),
+        size: content.len() as u64,
+    }];
+    let mut input = ValidationInput::new(&metadata).with_body(&body);
+    input.skill_dir = intended_dir;
+    if config.size_limits.is_some() {
+        input.resources = Some(&files);
+    }
+    validate_input(&input, config).diagnostics
+}
+
+/// The `skill-file` diagnostic for SKILL.md content at `path` whose
+/// frontmatter cannot be parsed.
+fn invalid_frontmatter(path: &str, e: &SkillError, config: &ValidationConfig) -> Vec<Diagnostic> {
+    let mut diagnostic = Diagnostic::error("skill-file", e.to_string())
+        .with_param("problem", "invalid-frontmatter")
+        .with_param("path", path)
+        .with_param("error", e.to_string());
+    config.render_messages([&mut diagnostic]);
+    vec![diagnostic]
+}
+
+/// Validate already-read SKILL.md content of the skill in `skill_dir`.
+pub(crate) fn validate_content(
+    skill_dir: &Path,
+    content: &str,
+    config: &ValidationConfig,
+) -> Vec<Diagnostic> {
+    content_diagnostics(skill_dir, content, config, true)
+}
 
-    // Read and parse content
-    let content = match std::fs::read_to_string(&skill_md) {
-        Ok(c) => c,
-        Err(e) => return vec![format!("Failed to read {}: {}", skill_md.display(), e)],
+/// Validate SKILL.md content, with the rules that read `skill_dir` from
+/// disk only if `on_disk` is set.
+fn content_diagnostics(
+    skill_dir: &Path,
+    content: &str,
+    config: &ValidationConfig,
+    on_disk: bool,
+) -> Vec<Diagnostic> {
+    let (metadata, body) = match parse_frontmatter(content) {
+        Ok(parsed) => parsed,
+        Err(e) => return invalid_frontmatter(&skill_dir.to_string_lossy(), &e, config),
     };
 
-    let metadata = match parse_frontmatter(&content) {
-        Ok((m, _)) => m,
-        Err(e) => return vec![e.to_string()],
+    let mut input = ValidationInput::new(&metadata)
+        .with_body(&body)
+        .with_skill_dir(skill_dir);
+    if !on_disk {
+        return validate_input(&input, config).diagnostics;
+    }
+
+    // What the rules need from disk: the name SKILL.md has and, only if
+    // sizes are checked, the whole file listing.
+    let skill_md = find_skill_md(skill_dir);
+    let skill_md_name = skill_md
+        .as_ref()
+        .and_then(|path| path.file_name())
+        .map(|name| name.to_string_lossy());
+    input.skill_md_name = skill_md_name.as_deref();
+    let mut disk_diagnostics = Vec::new();
+    let inventory = match config.size_limits {
+        Some(_) => match file_inventory(skill_dir) {
+            Ok(inventory) => Some(inventory),
+            Err(e) => {
+                disk_diagnostics.push(
+                    Diagnostic::error(
+                        "skill-size",
+                        format!("Cannot measure skill directory: {}", e),
+                    )
+                    .with_param("problem", "unmeasurable")
+                    .with_param("error", e.to_string()),
+                );
+                None
+            }
+        },
+        None => None,
+    };
+    if let Some(inventory) = &inventory {
+        input.resources = Some(&inventory.files);
+        for link in &inventory.outside_links {
+            disk_diagnostics.push(
+                Diagnostic::warning(
+                    "skill-symlink-outside",
+                    format!(
+                        "Symlink '{}' points outside the skill directory; it is counted as 0 bytes",
+                        link
+                    ),
+                )
+                .with_param("path", link.as_str()),
+            );
+        }
+    }
+
+    let mut diagnostics = validate_input(&input, config).diagnostics;
+    if !disk_diagnostics.is_empty() {
+        config.render_messages(&mut disk_diagnostics);
+        diagnostics.extend(disk_diagnostics);
+        sort_diagnostics(&mut diagnostics);
+    }
+    diagnostics
+}
+
+/// Warn if SKILL.md is named `name`, a casing other than `SKILL.md` or
+/// `skill.md`.
+fn skill_file_case(name: &str) -> Option<Diagnostic> {
+    (!is_canonical_skill_md_name(name)).then(|| {
+        Diagnostic::warning(
+            "skill-file-case",
+            format!("Skill file is named '{}'; rename it to SKILL.md", name),
+        )
+        .with_value(name)
+    })
+}
+
+/// Check the files of a skill against `limits`.
+fn size_diagnostics(files: &[ResourceFile], limits: &SizeLimits) -> Vec<Diagnostic> {
+    let mut largest: Vec<&ResourceFile> = files.iter().collect();
+    largest.sort_by(|a, b| {
+        b.size
+            .cmp(&a.size)
+            .then_with(|| ordering::compare(&a.path, &b.path))
+    });
+    let largest_paths: Vec<String> = largest.iter().take(3).map(|f| f.path.clone()).collect();
+    let largest: Vec<String> = largest
+        .iter()
+        .take(3)
+        .map(|f| format!("{} ({} bytes)", f.path, f.size))
+        .collect();
+    let largest = format!("largest files: {}", largest.join(", "));
+    // Each diagnostic names its problem in the `problem` param.
+    let size_error = |problem: &str, count: u64, limit: u64, actual: u64, message: String| {
+        Diagnostic::error("skill-size", message)
+            .with_param("problem", problem)
+            .with_param("count", count)
+            .with_limit(limit, actual)
+            .with_param("largest", largest_paths.clone())
     };
 
-    validate_metadata(&metadata, Some(skill_dir))
+    let mut diagnostics = Vec::new();
+    let total: u64 = files.iter().map(|f| f.size).sum();
+    if total > limits.max_total_bytes {
+        diagnostics.push(size_error(
+            "total-bytes",
+            total,
+            limits.max_total_bytes,
+            total,
+            format!(
+                "Skill directory holds {} bytes, over the {} byte limit; {}",
+                total, limits.max_total_bytes, largest
+            ),
+        ));
+    }
+    let oversized = files
+        .iter()
+        .filter(|f| f.size > limits.max_file_bytes)
+        .count();
+    if oversized > 0 {
+        diagnostics.push(size_error(
+            "file-bytes",
+            oversized as u64,
+            limits.max_file_bytes,
+            files.iter().map(|f| f.size).max().unwrap_or(0),
+            format!(
+                "{} file(s) over the {} byte limit per file; {}",
+                oversized, limits.max_file_bytes, largest
+            ),
+        ));
+    }
+    if files.len() > limits.max_files {
+        diagnostics.push(size_error(
+            "file-count",
+            files.len() as u64,
+            limits.max_files as u64,
+            files.len() as u64,
+            format!(
+                "Skill directory holds {} files, over the {} file limit; {}",
+                files.len(),
+                limits.max_files,
+                largest
+            ),
+        ));
+    }
+    diagnostics
+}
+
+/// Turn arbitrary text into a name that satisfies the name rules.
+///
+/// The text is NFKC-normalized and lowercased; every run of characters
+/// other than letters and digits becomes a single hyphen; leading and
+/// trailing hyphens are dropped; and the result is cut to
+/// [`MAX_SKILL_NAME_LENGTH`] characters. Returns an empty string if the
+/// text has no letters or digits.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.nfkc().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.chars().take(MAX_SKILL_NAME_LENGTH).collect();
+    slug.trim_end_matches('-').to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::diagnostic::EnglishRenderer;
+    use crate::parser::read_properties;
+    use std::collections::BTreeMap;
     use tempfile::TempDir;
 
     fn create_skill(dir: &TempDir, name: &str, content: &str) -> std::path::PathBuf {
@@ -271,12 +2307,33 @@ description: A test skill
         assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
     }
 
+    #[test]
+    fn test_body_with_field_like_first_line_and_rule() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "converter",
+            "---\nname: converter\ndescription: Converts files between formats\n---\ndescription: this skill converts files.\n\nRun the script.\n\n---\n\nMore notes.\n",
+        );
+        assert!(
+            validate(&skill_dir).is_empty(),
+            "{:?}",
+            validate(&skill_dir)
+        );
+        assert_eq!(read_properties(&skill_dir).unwrap().name, "converter");
+    }
+
     #[test]
     fn test_nonexistent_path() {
         let dir = TempDir::new().unwrap();
         let errors = validate(&dir.path().join("nonexistent"));
         assert_eq!(errors.len(), 1);
         assert!(errors[0].contains("does not exist"));
+        let report = validate_report(
+            &dir.path().join("nonexistent"),
+            &ValidationConfig::default(),
+        );
+        assert_eq!(report.diagnostics[0].rule, "path-missing");
     }
 
     #[test]
@@ -287,6 +2344,25 @@ description: A test skill
         let errors = validate(&file_path);
         assert_eq!(errors.len(), 1);
         assert!(errors[0].contains("Not a directory"));
+        let report = validate_report(&file_path, &ValidationConfig::default());
+        assert_eq!(report.diagnostics[0].rule, "path-not-directory");
+    }
+
+    #[test]
+    fn test_standalone_skill_file() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("pdf.md");
+        std::fs::write(&file_path, "---\nname: pdf\ndescription: d\n---\n").unwrap();
+        let report = validate_report(&file_path, &ValidationConfig::default());
+        assert_eq!(report.diagnostics.len(), 1);
+        let diagnostic = &report.diagnostics[0];
+        assert_eq!(diagnostic.rule, "path-not-directory");
+        assert_eq!(diagnostic.params["problem"], Param::from("standalone-file"));
+        assert!(
+            diagnostic.message.contains("is a standalone skill file"),
+            "{}",
+            diagnostic.message
+        );
     }
 
     #[test]
@@ -295,8 +2371,9 @@ description: A test skill
         let skill_dir = dir.path().join("my-skill");
         std::fs::create_dir_all(&skill_dir).unwrap();
         let errors = validate(&skill_dir);
-        assert_eq!(errors.len(), 1);
-        assert!(errors[0].contains("Missing required file: SKILL.md"));
+        assert_eq!(errors, ["Missing required file: SKILL.md"]);
+        let report = validate_report(&skill_dir, &ValidationConfig::default());
+        assert_eq!(report.diagnostics[0].rule, "skill-md-missing");
     }
 
     #[test]
@@ -358,81 +2435,1117 @@ Body
         assert!(
             errors
                 .iter()
-                .any(|e| e.contains("cannot start or end with a hyphen"))
-        );
+                .any(|e| e.contains("cannot start or end with a hyphen"))
+        );
+    }
+
+    #[test]
+    fn test_name_consecutive_hyphens() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "my--skill",
+            r#"---
+name: my--skill
+description: A test skill
+---
+Body
+"#,
+        );
+        let errors = validate(&skill_dir);
+        assert!(errors.iter().any(|e| e.contains("consecutive hyphens")));
+    }
+
+    #[test]
+    fn test_name_invalid_characters() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "my_skill",
+            r#"---
+name: my_skill
+description: A test skill
+---
+Body
+"#,
+        );
+        let errors = validate(&skill_dir);
+        assert!(errors.iter().any(|e| e.contains("invalid characters")));
+    }
+
+    #[test]
+    fn test_name_directory_mismatch() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "wrong-name",
+            r#"---
+name: correct-name
+description: A test skill
+---
+Body
+"#,
+        );
+        let errors = validate(&skill_dir);
+        assert!(errors.iter().any(|e| e.contains("must match skill name")));
+    }
+
+    #[test]
+    fn test_unexpected_fields() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "my-skill",
+            r#"---
+name: my-skill
+description: A test skill
+unknown_field: should not be here
+---
+Body
+"#,
+        );
+        let errors = validate(&skill_dir);
+        assert!(errors.iter().any(|e| e.contains("Unexpected fields")));
+    }
+
+    #[test]
+    fn test_long_values_are_shortened_in_messages() {
+        let dir = TempDir::new().unwrap();
+        let name = "N".repeat(1024 * 1024);
+        let skill_dir = create_skill(
+            &dir,
+            "pdf",
+            &format!(
+                "---\nname: {}\ndescription: '{}'\n---\n",
+                name,
+                "{\"k\":1}".repeat(128 * 1024)
+            ),
+        );
+
+        let diagnostics = validate_detailed(&skill_dir, &ValidationConfig::default());
+        assert!(diagnostics.len() >= 4);
+        for d in &diagnostics {
+            assert!(
+                d.message.len() < 400,
+                "{}: {} bytes",
+                d.rule,
+                d.message.len()
+            );
+        }
+        let lowercase = diagnostics
+            .iter()
+            .find(|d| d.rule == "name-lowercase")
+            .unwrap();
+        assert_eq!(
+            lowercase.message,
+            format!(
+                "Skill name '{}... (1048576 chars)' must be lowercase",
+                "N".repeat(DEFAULT_MESSAGE_VALUE_CHARS)
+            )
+        );
+        assert_eq!(lowercase.value.as_deref(), Some(name.as_str()));
+
+        let config = ValidationConfig {
+            message_value_chars: Some(4),
+            ..Default::default()
+        };
+        let diagnostics = validate_detailed(&skill_dir, &config);
+        assert!(
+            diagnostics
+                .iter()
+                .any(|d| d.message == "Skill name 'NNNN... (1048576 chars)' must be lowercase")
+        );
+    }
+
+    #[test]
+    fn test_validate_metadata_error_order() {
+        let content = format!(
+            "---\ncompatibility: {}\nname: Bad--Name\nowner: me\n---\n",
+            "x".repeat(501)
+        );
+        let (metadata, _) = parse_frontmatter(&content).unwrap();
+        let errors = validate_metadata(&metadata, None);
+        assert_eq!(
+            errors,
+            [
+                "Unexpected fields in frontmatter: owner. Only [\"allowed-tools\", \"compatibility\", \"description\", \"license\", \"metadata\", \"name\"] are allowed.",
+                "Skill name cannot contain consecutive hyphens",
+                "Skill name 'Bad--Name' must be lowercase",
+                "Missing required field in frontmatter: description",
+                "Compatibility exceeds 500 character limit (501 chars)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_required_fields_are_combined() {
+        let required = |frontmatter: &str| -> Vec<Diagnostic> {
+            let (metadata, _) = parse_frontmatter(&format!("---\n{}\n---\n", frontmatter)).unwrap();
+            metadata_diagnostics(&metadata, None, &ValidationConfig::default())
+                .into_iter()
+                .filter(|d| d.rule == "required-field")
+                .collect()
+        };
+
+        assert!(required("name: x\ndescription: d").is_empty());
+
+        let one = required("name: x");
+        assert_eq!(one.len(), 1);
+        assert_eq!(
+            one[0].message,
+            "Missing required field in frontmatter: description"
+        );
+        assert_eq!(
+            one[0].params["fields"],
+            Param::List(vec!["description".to_string()])
+        );
+
+        let both = required("license: MIT");
+        assert_eq!(both.len(), 1);
+        assert_eq!(
+            both[0].message,
+            "Missing required fields in frontmatter: name, description"
+        );
+        assert_eq!(
+            both[0].params["fields"],
+            Param::List(vec!["name".to_string(), "description".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_valid_with_all_fields() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "my-skill",
+            r#"---
+name: my-skill
+description: A test skill
+license: MIT
+metadata:
+  author: Test
+---
+Body
+"#,
+        );
+        let errors = validate(&skill_dir);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn test_allowed_tools_accepted() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "my-skill",
+            r#"---
+name: my-skill
+description: A test skill
+allowed-tools: Bash(jq:*) Bash(git:*)
+---
+Body
+"#,
+        );
+        let errors = validate(&skill_dir);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn test_i18n_chinese_name() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "技能",
+            r#"---
+name: 技能
+description: A skill with Chinese name
+---
+Body
+"#,
+        );
+        let errors = validate(&skill_dir);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn test_i18n_russian_name_with_hyphens() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "мой-навык",
+            r#"---
+name: мой-навык
+description: A skill with Russian name
+---
+Body
+"#,
+        );
+        let errors = validate(&skill_dir);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn test_i18n_russian_lowercase_valid() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "навык",
+            r#"---
+name: навык
+description: A skill with Russian lowercase name
+---
+Body
+"#,
+        );
+        let errors = validate(&skill_dir);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn test_i18n_russian_uppercase_rejected() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "НАВЫК",
+            r#"---
+name: НАВЫК
+description: A skill with Russian uppercase name
+---
+Body
+"#,
+        );
+        let errors = validate(&skill_dir);
+        assert!(errors.iter().any(|e| e.contains("lowercase")));
+    }
+
+    #[test]
+    fn test_description_too_long() {
+        let dir = TempDir::new().unwrap();
+        let long_desc = "x".repeat(1100);
+        let skill_dir = create_skill(
+            &dir,
+            "my-skill",
+            &format!(
+                r#"---
+name: my-skill
+description: {}
+---
+Body
+"#,
+                long_desc
+            ),
+        );
+        let errors = validate(&skill_dir);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("exceeds") && e.contains("1024"))
+        );
+    }
+
+    #[test]
+    fn test_valid_compatibility() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "my-skill",
+            r#"---
+name: my-skill
+description: A test skill
+compatibility: Requires Python 3.11+
+---
+Body
+"#,
+        );
+        let errors = validate(&skill_dir);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn test_length_limits_apply_to_trimmed_values() {
+        let name = "n".repeat(MAX_SKILL_NAME_LENGTH);
+        let cases = [
+            ("name", name.clone(), MAX_SKILL_NAME_LENGTH),
+            ("description", "d".repeat(1020), MAX_DESCRIPTION_LENGTH),
+            (
+                "compatibility",
+                "c".repeat(MAX_COMPATIBILITY_LENGTH),
+                MAX_COMPATIBILITY_LENGTH,
+            ),
+        ];
+        for (field, value, limit) in cases {
+            let dir = TempDir::new().unwrap();
+            let padded = format!("{}{}", " ".repeat(10), value);
+            assert!(padded.len() > limit);
+            let mut fields = vec![
+                ("name", name.clone()),
+                ("description", "A test skill".to_string()),
+            ];
+            fields.retain(|(key, _)| *key != field);
+            fields.push((field, padded));
+            let frontmatter: String = fields
+                .iter()
+                .map(|(key, value)| format!("{}: \"{}\"\n", key, value))
+                .collect();
+            let skill_dir = create_skill(&dir, &name, &format!("---\n{}---\nBody\n", frontmatter));
+
+            let errors = validate(&skill_dir);
+            assert!(errors.is_empty(), "{}: {:?}", field, errors);
+
+            let props = crate::read_properties(&skill_dir).unwrap();
+            let stored = match field {
+                "name" => props.name,
+                "description" => props.description,
+                _ => props.compatibility.unwrap(),
+            };
+            assert_eq!(stored, value, "{}", field);
+        }
+    }
+
+    #[test]
+    fn test_length_rules_carry_limit_and_actual() {
+        let config = ValidationConfig::default().with_max_name_bytes(80);
+        // Rule, limit, and the SKILL.md content whose value has length n.
+        type Case = (&'static str, usize, fn(usize) -> String);
+        let cases: [Case; 7] = [
+            ("name-length", MAX_SKILL_NAME_LENGTH, |n| {
+                format!(
+                    "---\nname: {}\ndescription: A test skill\n---\n",
+                    "a".repeat(n)
+                )
+            }),
+            ("name-bytes", 80, |n| {
+                let name = "é".repeat(n / 2) + &"a".repeat(n % 2);
+                format!("---\nname: {}\ndescription: A test skill\n---\n", name)
+            }),
+            ("description-length", MAX_DESCRIPTION_LENGTH, |n| {
+                format!("---\nname: my-skill\ndescription: {}\n---\n", "d".repeat(n))
+            }),
+            ("compatibility-length", MAX_COMPATIBILITY_LENGTH, |n| {
+                format!(
+                    "---\nname: my-skill\ndescription: A test skill\ncompatibility: {}\n---\n",
+                    "c".repeat(n)
+                )
+            }),
+            // Two bytes per character: the limits count characters
+            ("description-length", MAX_DESCRIPTION_LENGTH, |n| {
+                format!("---\nname: my-skill\ndescription: {}\n---\n", "é".repeat(n))
+            }),
+            ("compatibility-length", MAX_COMPATIBILITY_LENGTH, |n| {
+                format!(
+                    "---\nname: my-skill\ndescription: A test skill\ncompatibility: {}\n---\n",
+                    "é".repeat(n)
+                )
+            }),
+            ("metadata-depth", MAX_METADATA_DEPTH, |n| {
+                let mut value = "x".to_string();
+                for level in (0..n).rev() {
+                    value = format!("{{k{}: {}}}", level, value);
+                }
+                format!(
+                    "---\nname: my-skill\ndescription: A test skill\nmetadata: {}\n---\n",
+                    value
+                )
+            }),
+        ];
+
+        for (rule, limit, content) in cases {
+            for actual in [limit, limit + 1, limit * 4] {
+                let content = content(actual);
+                let (metadata, _) = parse_frontmatter(&content).unwrap();
+                let name = metadata["name"]
+                    .as_str()
+                    .unwrap()
+                    .nfkc()
+                    .collect::<String>();
+                let dir = Path::new("/nonexistent").join(name);
+                let found: Vec<Diagnostic> = validate_content_at(&content, &dir, &config)
+                    .into_iter()
+                    .filter(|d| d.rule == rule)
+                    .collect();
+                if actual == limit {
+                    assert!(found.is_empty(), "{} at {}: {:?}", rule, actual, found);
+                    continue;
+                }
+                assert_eq!(found.len(), 1, "{} at {}: {:?}", rule, actual, found);
+                assert_eq!(
+                    found[0].number_param("limit"),
+                    Some(limit as u64),
+                    "{}",
+                    rule
+                );
+                assert_eq!(
+                    found[0].number_param("actual"),
+                    Some(actual as u64),
+                    "{}",
+                    rule
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_compatibility_too_long() {
+        let dir = TempDir::new().unwrap();
+        let long_compat = "x".repeat(550);
+        let skill_dir = create_skill(
+            &dir,
+            "my-skill",
+            &format!(
+                r#"---
+name: my-skill
+description: A test skill
+compatibility: {}
+---
+Body
+"#,
+                long_compat
+            ),
+        );
+        let errors = validate(&skill_dir);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("exceeds") && e.contains("500"))
+        );
+    }
+
+    /// Run the metadata rules over frontmatter parsed from `yaml`.
+    fn diagnostics_for(dir_name: &str, yaml: &str) -> Vec<Diagnostic> {
+        let metadata: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(yaml).unwrap();
+        let config = ValidationConfig {
+            metadata_conventions: true,
+            max_name_bytes: Some(64),
+            ..Default::default()
+        };
+        metadata_diagnostics(&metadata, Some(Path::new(dir_name)), &config)
+    }
+
+    fn placeholder_fixture() -> Vec<Diagnostic> {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "x",
+            "---\nname: x\ndescription: Fill and merge PDF forms\n---\n{{SKILL_DIR}} {{WORKSPACE}} \\{{ESCAPED}}\n",
+        );
+        let config = ValidationConfig {
+            list_placeholders: true,
+            ..Default::default()
+        };
+        validate_detailed(&skill_dir, &config)
+    }
+
+    #[test]
+    fn test_name_match_modes() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(&dir, "pdf/1.2.0", "---\nname: pdf\ndescription: d\n---\n");
+        let with = |name_match| ValidationConfig {
+            name_match,
+            ..Default::default()
+        };
+
+        let errors = validate_with_config(&skill_dir, &with(NameMatch::ImmediateDir));
+        assert_eq!(
+            errors,
+            ["Directory name '1.2.0' must match skill name 'pdf'"]
+        );
+        assert!(
+            validate_with_config(&skill_dir, &with(NameMatch::AnyAncestorWithin(2))).is_empty()
+        );
+        assert!(validate_with_config(&skill_dir, &with(NameMatch::Disabled)).is_empty());
+
+        let nested = create_skill(
+            &dir,
+            "pdf/v2/release",
+            "---\nname: pdf\ndescription: d\n---\n",
+        );
+        let errors = validate_with_config(&nested, &with(NameMatch::AnyAncestorWithin(2)));
+        assert_eq!(
+            errors,
+            ["One of the directory names 'release', 'v2' must match skill name 'pdf'"]
+        );
+    }
+
+    #[test]
+    fn test_max_name_bytes() {
+        let dir = TempDir::new().unwrap();
+        let name = "文".repeat(30);
+        let skill_dir = create_skill(
+            &dir,
+            &name,
+            &format!("---\nname: {}\ndescription: d\n---\n", name),
+        );
+        assert!(validate(&skill_dir).is_empty());
+
+        let config = ValidationConfig {
+            max_name_bytes: Some(64),
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_with_config(&skill_dir, &config),
+            [format!(
+                "Skill name '{}' exceeds 64 byte limit (30 chars, 90 bytes)",
+                name
+            )]
+        );
+
+        // The limit applies after NFKC normalization: 'ｐｄｆ' becomes 'pdf'.
+        let skill_dir = create_skill(&dir, "pdf", "---\nname: ｐｄｆ\ndescription: d\n---\n");
+        let config = ValidationConfig {
+            max_name_bytes: Some(3),
+            ..Default::default()
+        };
+        assert!(validate_with_config(&skill_dir, &config).is_empty());
+    }
+
+    #[test]
+    fn test_list_placeholders_is_info_only() {
+        let diagnostics = placeholder_fixture();
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::info(
+                    "body-placeholders",
+                    "Body uses placeholders: SKILL_DIR, WORKSPACE"
+                )
+                .with_param(
+                    "placeholders",
+                    vec!["SKILL_DIR".to_string(), "WORKSPACE".to_string()]
+                )
+            ]
+        );
+        assert!(messages(&diagnostics).is_empty());
+    }
+
+    fn workspace_fixture() -> Vec<Diagnostic> {
+        let dir = TempDir::new().unwrap();
+        for (dir_name, name) in [("PDF", "pdf"), ("pdf", "pdf"), ("pdf-tооls", "pdf-tооls")] {
+            let content = format!(
+                "---\nname: {}\ndescription: d\nmetadata:\n  id: com.example.pdf\n---\n",
+                name
+            );
+            create_skill(&dir, dir_name, &content);
+        }
+        let skill_dir = create_skill(
+            &dir,
+            "pdf-tools",
+            "---\nname: pdf-tools\ndescription: d\n---\n",
+        );
+        std::fs::write(skill_dir.join("r".repeat(210)), "").unwrap();
+        create_skill(
+            &dir,
+            "csv",
+            "---\nname: csv\ndescription: d\n---\nSee [pdf](skill:pdf-forms).\n",
+        );
+
+        crate::workspace::validate_workspace(dir.path(), &ValidationConfig::default())
+            .unwrap()
+            .findings
+            .into_iter()
+            .map(|f| f.diagnostic)
+            .collect()
+    }
+
+    /// Frontmatter fixtures that together trigger every rule.
+    fn rule_fixtures() -> Vec<Vec<Diagnostic>> {
+        vec![
+            diagnostics_for("x", "description: d\nextra: 1"),
+            diagnostics_for("x", "name: 5\ndescription: \"\""),
+            diagnostics_for(
+                &"A".repeat(70),
+                &format!("name: {}\ndescription: d", "A".repeat(70)),
+            ),
+            diagnostics_for("-a--b_", "name: -a--b_\ndescription: d"),
+            diagnostics_for("other", "name: my-skill\ndescription: d"),
+            diagnostics_for("con", "name: con\ndescription: d"),
+            diagnostics_for(
+                "x",
+                &format!(
+                    "name: x\ndescription: {}\ncompatibility: {}",
+                    "d".repeat(1100),
+                    "c".repeat(600)
+                ),
+            ),
+            diagnostics_for(
+                "x",
+                "name: x\ndescription: d\nmetadata:\n  maintainer-email: jane\n  homepage: example.com",
+            ),
+            diagnostics_for(
+                "x",
+                "name: x\ndescription: d\nmetadata:\n  deprecated: \"true\"",
+            ),
+            diagnostics_for("x", "name: x\ndescription: d\nmetadata:\n  id: my skill"),
+            diagnostics_for("ｐｄｆ", "name: ｐｄｆ\ndescription: d"),
+            diagnostics_for(
+                "x",
+                "name: x\ndescription: d\nmetadata:\n  1: a\n  a:\n    b:\n      c:\n        d: 1",
+            ),
+            validate_detailed(
+                Path::new("/nonexistent/skill"),
+                &ValidationConfig::default(),
+            ),
+            path_fixture(),
+            placeholder_fixture(),
+            skill_file_case_fixture(),
+            size_fixture(),
+            workspace_fixture(),
+        ]
+    }
+
+    /// A file, a directory without SKILL.md, and a SKILL.md without
+    /// frontmatter.
+    fn path_fixture() -> Vec<Diagnostic> {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "x").unwrap();
+        let skill_dir = create_skill(&dir, "x", "# No frontmatter\n");
+        [file.as_path(), dir.path(), &skill_dir]
+            .into_iter()
+            .flat_map(|path| validate_detailed(path, &ValidationConfig::default()))
+            .collect()
+    }
+
+    fn skill_file_case_fixture() -> Vec<Diagnostic> {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = dir.path().join("x");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("Skill.MD"),
+            "---\nname: x\ndescription: Fill and merge PDF forms\n---\n",
+        )
+        .unwrap();
+        validate_detailed(&skill_dir, &ValidationConfig::default())
+    }
+
+    #[test]
+    fn test_validate_content_at() {
+        let content = "---\nname: pdf-tools\ndescription: Fill and merge PDF forms\n---\n# Body\n";
+        let config = ValidationConfig {
+            size_limits: Some(SizeLimits {
+                max_total_bytes: 0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(
+            validate_content_at(content, Path::new("/nonexistent/pdf-tools"), &config).is_empty()
+        );
+
+        let diagnostics = validate_content_at(content, Path::new("/nonexistent/pdf"), &config);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::error(
+                    "name-directory-match",
+                    "Directory name 'pdf' must match skill name 'pdf-tools'"
+                )
+                .with_value("pdf-tools")
+                .with_param("directories", vec!["pdf".to_string()])
+            ]
+        );
+
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(&dir, "pdf-tools", content);
+        assert_eq!(
+            validate_content_at(content, &skill_dir, &ValidationConfig::default()),
+            validate_detailed(&skill_dir, &ValidationConfig::default())
+        );
+    }
+
+    #[test]
+    fn test_validate_bytes_at() {
+        let content = "---\nname: pdf-tools\ndescription: Fill and merge PDF forms\n---\n# Body\n";
+        let config = ValidationConfig::default();
+        let bytes = || content.as_bytes().to_vec();
+        assert!(validate_bytes_at(bytes(), "<stdin>", None, &config).is_empty());
+        assert_eq!(
+            validate_bytes_at(bytes(), "<stdin>", Some(Path::new("pdf")), &config),
+            validate_content_at(content, Path::new("pdf"), &config)
+        );
+
+        let config = ValidationConfig {
+            size_limits: Some(SizeLimits {
+                max_file_bytes: 10,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let diagnostics = validate_bytes_at(bytes(), "<stdin>", None, &config);
+        let rules: Vec<&str> = diagnostics.iter().map(|d| d.rule.as_str()).collect();
+        assert_eq!(rules, ["skill-size"]);
+
+        let diagnostics = validate_bytes_at(vec![0xFF, 0xFE, b'-', 0], "<stdin>", None, &config);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::error(
+                    "skill-file",
+                    "Failed to read <stdin>: SKILL.md appears to be UTF-16 encoded; files must be UTF-8"
+                )
+                .with_param("problem", "unreadable")
+                .with_param("path", "<stdin>")
+                .with_param(
+                    "error",
+                    "SKILL.md appears to be UTF-16 encoded; files must be UTF-8"
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skill_file_case_warns() {
+        assert_eq!(
+            skill_file_case_fixture(),
+            vec![
+                Diagnostic::warning(
+                    "skill-file-case",
+                    "Skill file is named 'Skill.MD'; rename it to SKILL.md"
+                )
+                .with_value("Skill.MD")
+            ]
+        );
+
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(
+            &dir,
+            "x",
+            "---\nname: x\ndescription: Fill and merge PDF forms\n---\n",
+        );
+        assert!(validate_detailed(&skill_dir, &ValidationConfig::default()).is_empty());
+    }
+
+    fn size_fixture() -> Vec<Diagnostic> {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(&dir, "x", "---\nname: x\ndescription: d\n---\n");
+        std::fs::create_dir_all(skill_dir.join("data")).unwrap();
+        std::fs::write(skill_dir.join("data/big.bin"), vec![0u8; 300]).unwrap();
+        std::fs::write(skill_dir.join("data/mid.bin"), vec![0u8; 200]).unwrap();
+        std::fs::write(skill_dir.join("small.txt"), "tiny").unwrap();
+        std::fs::write(dir.path().join("outside.bin"), vec![0u8; 1000]).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.path().join("outside.bin"), skill_dir.join("link.bin"))
+            .unwrap();
+
+        let config = ValidationConfig {
+            size_limits: Some(SizeLimits {
+                max_total_bytes: 500,
+                max_file_bytes: 250,
+                max_files: 3,
+            }),
+            min_description_chars: Some(0),
+            ..Default::default()
+        };
+        validate_detailed(&skill_dir, &config)
+    }
+
+    /// Example organization policy: every skill documents its usage.
+    struct RequireUsageSection;
+
+    impl ValidationRule for RequireUsageSection {
+        fn id(&self) -> &'static str {
+            "org-usage-section"
+        }
+
+        fn check(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+            let has_usage = crate::markdown::headings(ctx.body)
+                .iter()
+                .any(|h| h.text.eq_ignore_ascii_case("usage"));
+            if has_usage {
+                return Vec::new();
+            }
+            let name = ctx.properties.map_or("skill", |p| p.name.as_str());
+            vec![Diagnostic::error(
+                self.id(),
+                format!("Skill '{}' has no Usage section", name),
+            )]
+        }
     }
 
     #[test]
-    fn test_name_consecutive_hyphens() {
+    fn test_custom_rules_run_after_built_in_rules() {
         let dir = TempDir::new().unwrap();
+        let config = ValidationConfig {
+            custom_rules: vec![Arc::new(RequireUsageSection)],
+            ..Default::default()
+        };
+
         let skill_dir = create_skill(
             &dir,
-            "my--skill",
-            r#"---
-name: my--skill
-description: A test skill
----
-Body
-"#,
+            "pdf",
+            "---\nname: pdf\ndescription: Fill and merge PDF forms\n---\n# PDF\n## Usage\nRun it.\n",
         );
-        let errors = validate(&skill_dir);
-        assert!(errors.iter().any(|e| e.contains("consecutive hyphens")));
+        assert!(validate_detailed(&skill_dir, &config).is_empty());
+
+        let skill_dir = create_skill(
+            &dir,
+            "other",
+            "---\nname: Other\ndescription: Fill and merge PDF forms\n---\n# Other\n",
+        );
+        let rules: Vec<String> = validate_detailed(&skill_dir, &config)
+            .into_iter()
+            .map(|d| d.rule)
+            .collect();
+        assert_eq!(
+            rules,
+            [
+                "name-directory-match",
+                "name-lowercase",
+                "org-usage-section"
+            ]
+        );
+
+        let metrics = crate::metrics::RecordingMetrics::default();
+        crate::workspace::validate_skill_dirs_with(&[skill_dir], &config, &metrics);
+        assert_eq!(metrics.count("validation_failures{org-usage-section}"), 1);
+        assert_eq!(config, config.clone());
     }
 
     #[test]
-    fn test_name_invalid_characters() {
+    fn test_size_limits() {
+        let largest = "largest files: data/big.bin (300 bytes), data/mid.bin (200 bytes), \
+            SKILL.md (31 bytes)";
+        let mut expected = vec![
+            format!(
+                "Skill directory holds 535 bytes, over the 500 byte limit; {}",
+                largest
+            ),
+            format!("1 file(s) over the 250 byte limit per file; {}", largest),
+            format!(
+                "Skill directory holds 4 files, over the 3 file limit; {}",
+                largest
+            ),
+        ];
+        if cfg!(unix) {
+            expected.push(
+                "Symlink 'link.bin' points outside the skill directory; it is counted as 0 bytes"
+                    .to_string(),
+            );
+        }
+        let messages: Vec<String> = size_fixture().into_iter().map(|d| d.message).collect();
+        assert_eq!(messages, expected);
+
         let dir = TempDir::new().unwrap();
         let skill_dir = create_skill(
             &dir,
-            "my_skill",
-            r#"---
-name: my_skill
-description: A test skill
----
-Body
-"#,
+            "x",
+            "---\nname: x\ndescription: Fill and merge PDF forms\n---\n",
         );
-        let errors = validate(&skill_dir);
-        assert!(errors.iter().any(|e| e.contains("invalid characters")));
+        let config = ValidationConfig {
+            size_limits: Some(SizeLimits::default()),
+            ..Default::default()
+        };
+        assert!(validate_detailed(&skill_dir, &config).is_empty());
     }
 
     #[test]
-    fn test_name_directory_mismatch() {
+    fn test_validate_input_matches_validate_metadata() {
+        let cases = [
+            "name: my-skill\ndescription: Fill and merge PDF forms",
+            "name: Bad--Name\nowner: me",
+            "name: other\ndescription: \"  \"",
+        ];
+        for yaml in cases {
+            let metadata: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(yaml).unwrap();
+            let dir = Path::new("my-skill");
+            let report = validate_input(
+                &ValidationInput::new(&metadata).with_skill_dir(dir),
+                &ValidationConfig::default(),
+            );
+            assert_eq!(
+                report.error_messages(),
+                validate_metadata(&metadata, Some(dir)),
+                "{}",
+                yaml
+            );
+            assert_eq!(report.is_valid(), report.error_messages().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_validate_input_matches_validate_detailed() {
         let dir = TempDir::new().unwrap();
-        let skill_dir = create_skill(
-            &dir,
-            "wrong-name",
-            r#"---
-name: correct-name
-description: A test skill
----
-Body
-"#,
+        let skill_dir = dir.path().join("pdf-tools");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        let content = "---\nname: pdf-tools\ndescription: Fill and merge PDF forms\n---\n\
+            Use {{input}} here.\n";
+        std::fs::write(skill_dir.join("Skill.MD"), content).unwrap();
+        std::fs::write(skill_dir.join("data.bin"), vec![0u8; 300]).unwrap();
+        let config = ValidationConfig {
+            list_placeholders: true,
+            size_limits: Some(SizeLimits {
+                max_file_bytes: 250,
+                ..SizeLimits::default()
+            }),
+            ..Default::default()
+        };
+
+        let (metadata, body) = parse_frontmatter(content).unwrap();
+        let resources = [
+            ResourceFile {
+                path: "Skill.MD".to_string(),
+                size: content.len() as u64,
+            },
+            ResourceFile {
+                path: "data.bin".to_string(),
+                size: 300,
+            },
+        ];
+        let input = ValidationInput::new(&metadata)
+            .with_body(&body)
+            .with_skill_dir(&skill_dir)
+            .with_skill_md_name("Skill.MD")
+            .with_resources(&resources);
+        let report = validate_input(&input, &config);
+        let rules: Vec<&str> = report.diagnostics.iter().map(|d| d.rule.as_str()).collect();
+        assert_eq!(
+            rules,
+            ["skill-file-case", "body-placeholders", "skill-size"]
         );
-        let errors = validate(&skill_dir);
-        assert!(errors.iter().any(|e| e.contains("must match skill name")));
+        assert!(!report.is_valid());
+        assert_eq!(report.diagnostics, validate_detailed(&skill_dir, &config));
+
+        // Without a body or listing, only the frontmatter is checked.
+        let report = validate_input(&ValidationInput::new(&metadata), &config);
+        assert_eq!(report, ValidationReport::default());
     }
 
     #[test]
-    fn test_unexpected_fields() {
+    fn test_slugify() {
+        assert_eq!(slugify("My_Skill"), "my-skill");
+        assert_eq!(slugify("--PDF  Tools!!"), "pdf-tools");
+        assert_eq!(slugify("a--b"), "a-b");
+        assert_eq!(slugify("Café Ｍenu"), "café-menu");
+        assert_eq!(slugify("___"), "");
+        assert_eq!(slugify(&format!("{}-b", "a".repeat(63))), "a".repeat(63));
+        for input in ["My_Skill", "--PDF  Tools!!", "Café Ｍenu"] {
+            let slug = slugify(input);
+            assert!(
+                validate_name(&slug, &[slug.as_str()], None, DEFAULT_MESSAGE_VALUE_CHARS)
+                    .is_empty(),
+                "{} -> {}",
+                input,
+                slug
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_emitted_rule_has_metadata() {
+        for diagnostics in rule_fixtures() {
+            for diagnostic in diagnostics {
+                let info = rule_info(&diagnostic.rule)
+                    .unwrap_or_else(|| panic!("Rule '{}' has no metadata", diagnostic.rule));
+                assert_eq!(info.default_severity, diagnostic.severity);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_rule_is_exercised() {
+        let emitted: Vec<String> = rule_fixtures()
+            .into_iter()
+            .flatten()
+            .map(|d| d.rule)
+            .collect();
+        for rule in rules() {
+            // The fixture needs a symlink.
+            if cfg!(not(unix)) && rule.id == "skill-symlink-outside" {
+                continue;
+            }
+            assert!(
+                emitted.iter().any(|id| id == rule.id),
+                "Rule '{}' is never emitted by the fixtures",
+                rule.id
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_rule_provides_params() {
+        for diagnostic in rule_fixtures().into_iter().flatten() {
+            assert!(
+                !diagnostic.params.is_empty(),
+                "Rule '{}' has no params: {}",
+                diagnostic.rule,
+                diagnostic.message
+            );
+            if let Some(value) = &diagnostic.value {
+                assert_eq!(
+                    diagnostic.params.get("value"),
+                    Some(&Param::Text(value.clone()))
+                );
+            }
+        }
+    }
+
+    /// Example translation covering two rules.
+    struct German;
+
+    impl MessageRenderer for German {
+        fn locale(&self) -> &str {
+            "de"
+        }
+
+        fn render(&self, rule: &str, params: &BTreeMap<String, Param>) -> Option<String> {
+            match rule {
+                "name-lowercase" => Some(format!(
+                    "Der Skill-Name '{}' muss kleingeschrieben sein",
+                    params["value"]
+                )),
+                "description-length" => Some(format!(
+                    "Die Beschreibung ist länger als {} Zeichen ({})",
+                    params["limit"], params["length"]
+                )),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_message_renderer() {
         let dir = TempDir::new().unwrap();
         let skill_dir = create_skill(
             &dir,
-            "my-skill",
-            r#"---
-name: my-skill
-description: A test skill
-unknown_field: should not be here
----
-Body
-"#,
+            "PDF",
+            &format!("---\nname: PDF\ndescription: {}\n---\n", "d".repeat(1030)),
         );
-        let errors = validate(&skill_dir);
-        assert!(errors.iter().any(|e| e.contains("Unexpected fields")));
+        let config = ValidationConfig {
+            message_renderer: Some(Arc::new(German)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            validate_with_config(&skill_dir, &config),
+            [
+                "Der Skill-Name 'PDF' muss kleingeschrieben sein",
+                "Die Beschreibung ist länger als 1024 Zeichen (1030)",
+            ]
+        );
+        let english = validate_detailed(&skill_dir, &ValidationConfig::default());
+        assert_eq!(
+            english[0].render(&German),
+            "Der Skill-Name 'PDF' muss kleingeschrieben sein"
+        );
+        assert_eq!(english[0].render(&EnglishRenderer), english[0].message);
     }
 
     #[test]
-    fn test_valid_with_all_fields() {
+    fn test_rule_ids_unique() {
+        let all = rules();
+        for (i, rule) in all.iter().enumerate() {
+            assert!(all[i + 1..].iter().all(|other| other.id != rule.id));
+        }
+    }
+
+    #[test]
+    fn test_rule_info_lookup() {
+        let info = rule_info("name-lowercase").unwrap();
+        assert_eq!(info.default_severity, Severity::Error);
+        assert!(rule_info("no-such-rule").is_none());
+    }
+
+    #[test]
+    fn test_metadata_conventions_opt_in() {
         let dir = TempDir::new().unwrap();
         let skill_dir = create_skill(
             &dir,
@@ -440,170 +3553,300 @@ Body
             r#"---
 name: my-skill
 description: A test skill
-license: MIT
 metadata:
-  author: Test
+  maintainer-email: not-an-email
+  homepage: ftp://example.com
 ---
 Body
 "#,
         );
-        let errors = validate(&skill_dir);
-        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        assert!(validate(&skill_dir).is_empty());
+
+        let config = ValidationConfig {
+            metadata_conventions: true,
+            ..Default::default()
+        };
+        let errors = validate_with_config(&skill_dir, &config);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("maintainer-email"));
+        assert!(errors[1].contains("homepage"));
     }
 
     #[test]
-    fn test_allowed_tools_accepted() {
+    fn test_deprecated_without_message_warns() {
         let dir = TempDir::new().unwrap();
         let skill_dir = create_skill(
             &dir,
             "my-skill",
-            r#"---
-name: my-skill
-description: A test skill
-allowed-tools: Bash(jq:*) Bash(git:*)
----
-Body
-"#,
+            "---\nname: my-skill\ndescription: A skill used in tests\nmetadata:\n  deprecated: \"true\"\n---\nBody\n",
         );
-        let errors = validate(&skill_dir);
-        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
-    }
 
-    #[test]
-    fn test_i18n_chinese_name() {
-        let dir = TempDir::new().unwrap();
+        assert!(validate(&skill_dir).is_empty());
+        let diagnostics = validate_detailed(&skill_dir, &ValidationConfig::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, "deprecated-without-message");
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+
         let skill_dir = create_skill(
             &dir,
-            "技能",
-            r#"---
-name: 技能
-description: A skill with Chinese name
----
-Body
-"#,
+            "other-skill",
+            "---\nname: other-skill\ndescription: A skill used in tests\nmetadata:\n  deprecated: \"true\"\n  deprecated-message: Use my-skill\n---\nBody\n",
         );
-        let errors = validate(&skill_dir);
-        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+        assert!(validate_detailed(&skill_dir, &ValidationConfig::default()).is_empty());
     }
 
     #[test]
-    fn test_i18n_russian_name_with_hyphens() {
-        let dir = TempDir::new().unwrap();
-        let skill_dir = create_skill(
-            &dir,
-            "мой-навык",
-            r#"---
-name: мой-навык
-description: A skill with Russian name
----
-Body
-"#,
+    fn test_stable_id_format() {
+        for id in [
+            "3f2b8c1e-9d4a-4f6b-8e2a-1c5d7e9f0a3b",
+            "3F2B8C1E-9D4A-4F6B-8E2A-1C5D7E9F0A3B",
+            "com.example.pdf-tools",
+            "io.github.jane.skill2",
+        ] {
+            assert!(is_stable_id(id), "{}", id);
+        }
+        for id in [
+            "",
+            "pdf-tools",
+            "3f2b8c1e-9d4a-4f6b-8e2a-1c5d7e9f0a3",
+            "3f2b8c1e9d4a4f6b8e2a1c5d7e9f0a3b",
+            "1password.example",
+            "com..example",
+            "com.-example",
+            "com.example_tools",
+            "com.example ",
+        ] {
+            assert!(!is_stable_id(id), "{}", id);
+        }
+
+        let mut props = SkillProperties::new("my-skill", "A skill used in tests");
+        assert!(validate_properties(&props, None).is_empty());
+        props.metadata = Some(HashMap::from([("id".to_string(), 42.into())]));
+        assert_eq!(
+            validate_properties(&props, None)[0].message,
+            "metadata.id must be a string"
         );
-        let errors = validate(&skill_dir);
-        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+        props.metadata = Some(HashMap::from([("id".to_string(), "pdf".into())]));
+        let diagnostics = validate_properties(&props, None);
+        assert_eq!(diagnostics[0].rule, "metadata-id-format");
+        assert_eq!(diagnostics[0].value.as_deref(), Some("pdf"));
     }
 
     #[test]
-    fn test_i18n_russian_lowercase_valid() {
-        let dir = TempDir::new().unwrap();
-        let skill_dir = create_skill(
-            &dir,
-            "навык",
-            r#"---
-name: навык
-description: A skill with Russian lowercase name
----
-Body
-"#,
-        );
-        let errors = validate(&skill_dir);
-        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    fn test_dir_portability() {
+        assert!(validate_dir_portability("pdf-tools", DEFAULT_MESSAGE_VALUE_CHARS).is_empty());
+        assert!(validate_dir_portability("console", DEFAULT_MESSAGE_VALUE_CHARS).is_empty());
+
+        for (name, problem) in [
+            ("con", "is reserved on Windows (CON)"),
+            ("Lpt1.skill", "is reserved on Windows (LPT1)"),
+            ("what?", "contains '?', not allowed on Windows"),
+            ("pdf.", "ends with a dot or space, which Windows strips"),
+            ("pdf ", "ends with a dot or space, which Windows strips"),
+        ] {
+            let diagnostics = validate_dir_portability(name, DEFAULT_MESSAGE_VALUE_CHARS);
+            assert_eq!(diagnostics.len(), 1, "{}", name);
+            assert_eq!(diagnostics[0].rule, "dir-portability");
+            assert_eq!(
+                diagnostics[0].message,
+                format!("Directory name '{}' {}", name, problem)
+            );
+        }
     }
 
     #[test]
-    fn test_i18n_russian_uppercase_rejected() {
-        let dir = TempDir::new().unwrap();
-        let skill_dir = create_skill(
-            &dir,
-            "НАВЫК",
-            r#"---
-name: НАВЫК
-description: A skill with Russian uppercase name
----
-Body
-"#,
-        );
-        let errors = validate(&skill_dir);
-        assert!(errors.iter().any(|e| e.contains("lowercase")));
+    fn test_metadata_convention_formats() {
+        assert!(looks_like_email("jane@example.com"));
+        assert!(looks_like_email("j.doe+skills@mail.example.org"));
+        assert!(!looks_like_email("jane"));
+        assert!(!looks_like_email("jane@localhost"));
+        assert!(!looks_like_email("@example.com"));
+        assert!(!looks_like_email("jane@@example.com"));
+        assert!(!looks_like_email("jane doe@example.com"));
+
+        assert!(looks_like_http_url("https://example.com"));
+        assert!(looks_like_http_url("HTTP://example.com/path?q=1"));
+        assert!(!looks_like_http_url("example.com"));
+        assert!(!looks_like_http_url("https://"));
+        assert!(!looks_like_http_url("https://exa mple.com"));
+        assert!(!looks_like_http_url("ftp://example.com"));
     }
 
     #[test]
-    fn test_description_too_long() {
-        let dir = TempDir::new().unwrap();
-        let long_desc = "x".repeat(1100);
-        let skill_dir = create_skill(
-            &dir,
-            "my-skill",
+    fn test_validate_properties_matches_validate_metadata() {
+        let cases = [
+            "name: my-skill\ndescription: A test skill",
+            "name: My--Skill-\ndescription: A test skill",
+            "name: other\ndescription: \"  \"",
             &format!(
-                r#"---
-name: my-skill
-description: {}
----
-Body
-"#,
-                long_desc
+                "name: my-skill\ndescription: {}\ncompatibility: {}",
+                "d".repeat(1100),
+                "c".repeat(600)
             ),
-        );
-        let errors = validate(&skill_dir);
-        assert!(
-            errors
+        ];
+        for yaml in cases {
+            let metadata: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(yaml).unwrap();
+            let props = SkillProperties {
+                name: metadata["name"].as_str().unwrap().to_string(),
+                description: metadata["description"].as_str().unwrap().to_string(),
+                compatibility: metadata
+                    .get("compatibility")
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+                ..SkillProperties::new("", "")
+            };
+
+            assert_eq!(
+                validate_properties(&props, Some("my-skill")),
+                metadata_diagnostics(
+                    &metadata,
+                    Some(Path::new("my-skill")),
+                    &ValidationConfig::default()
+                ),
+                "{}",
+                yaml
+            );
+        }
+    }
+
+    #[test]
+    fn test_unquoted_scalars_in_text_fields() {
+        let base = [
+            ("name", "my-skill"),
+            ("description", "Fill and merge PDF forms"),
+        ];
+        let check = |field: &str, value: &str| -> Vec<String> {
+            let mut yaml: Vec<String> = base
                 .iter()
-                .any(|e| e.contains("exceeds") && e.contains("1024"))
+                .filter(|(f, _)| *f != field)
+                .map(|(f, v)| format!("{}: {}", f, v))
+                .collect();
+            yaml.push(format!("{}: {}", field, value));
+            let metadata: HashMap<String, serde_yaml::Value> =
+                serde_yaml::from_str(&yaml.join("\n")).unwrap();
+            validate_metadata(&metadata, None)
+        };
+
+        for field in [
+            "name",
+            "description",
+            "license",
+            "compatibility",
+            "allowed-tools",
+        ] {
+            assert_eq!(
+                check(field, "1.10"),
+                [format!(
+                    "Field '{}' is a YAML float (1.1), not a string; put the value in quotes",
+                    field
+                )]
+            );
+            assert_eq!(
+                check(field, "42"),
+                [format!(
+                    "Field '{}' is a YAML integer (42), not a string; put the value in quotes",
+                    field
+                )]
+            );
+            assert_eq!(
+                check(field, "true"),
+                [format!(
+                    "Field '{}' is a YAML boolean (true), not a string; put the value in quotes",
+                    field
+                )]
+            );
+            // YAML 1.2 has no timestamp type: dates stay strings.
+            let date = check(field, "2024-01-01");
+            assert!(date.iter().all(|e| !e.contains("Field")), "{:?}", date);
+        }
+
+        for field in ["name", "description"] {
+            assert_eq!(
+                check(field, "~"),
+                [format!("Field '{}' must be a non-empty string", field)]
+            );
+        }
+        for field in ["license", "compatibility", "allowed-tools"] {
+            assert!(check(field, "~").is_empty());
+        }
+        assert_eq!(
+            check("license", "[MIT]"),
+            ["Field 'license' must be a string, not sequence"]
+        );
+        assert!(check("allowed-tools", "[Read]").is_empty());
+        assert_eq!(
+            check("allowed-tools", "[Read, 42, {a: b}]"),
+            [
+                "Field 'allowed-tools[1]' is a YAML integer (42), not a string; put the value in quotes",
+                "Field 'allowed-tools[2]' must be a string, not mapping",
+            ]
+        );
+
+        let (metadata, _) = parse_frontmatter("---\nname: 1.0\ndescription: d\n---\n").unwrap();
+        let diagnostic = field_type_error("name", metadata.get("name"));
+        assert_eq!(diagnostic.value.as_deref(), Some("1.0"));
+        assert_eq!(
+            diagnostic.params.get("type"),
+            Some(&Param::Text("float".to_string()))
         );
     }
 
     #[test]
-    fn test_valid_compatibility() {
-        let dir = TempDir::new().unwrap();
-        let skill_dir = create_skill(
-            &dir,
-            "my-skill",
-            r#"---
-name: my-skill
-description: A test skill
-compatibility: Requires Python 3.11+
----
-Body
-"#,
+    fn test_quoted_number_name_points_at_version() {
+        let errors = check_name_only("\"1.0\"");
+        assert_eq!(
+            errors,
+            [
+                "Skill name '1.0' contains invalid characters. Only letters, digits, and hyphens \
+              are allowed. It reads as a version number, which belongs in metadata.version."
+            ]
         );
-        let errors = validate(&skill_dir);
-        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+        assert!(
+            !check_name_only("pdf_tools")[0].contains("version"),
+            "only numbers get the hint"
+        );
+    }
+
+    fn check_name_only(name: &str) -> Vec<String> {
+        let metadata: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(&format!(
+            "name: {}\ndescription: Fill and merge PDF forms",
+            name
+        ))
+        .unwrap();
+        validate_metadata(&metadata, None)
     }
 
     #[test]
-    fn test_compatibility_too_long() {
+    fn test_x_prefixed_fields_opt_in() {
         let dir = TempDir::new().unwrap();
-        let long_compat = "x".repeat(550);
         let skill_dir = create_skill(
             &dir,
             "my-skill",
-            &format!(
-                r#"---
-name: my-skill
-description: A test skill
-compatibility: {}
----
-Body
-"#,
-                long_compat
-            ),
+            "---\nname: my-skill\ndescription: d\nx-acme-team: search\nx-: bare\n---\n",
         );
+
         let errors = validate(&skill_dir);
-        assert!(
-            errors
-                .iter()
-                .any(|e| e.contains("exceeds") && e.contains("500"))
-        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("x-, x-acme-team"));
+
+        let config = ValidationConfig {
+            allow_x_prefixed_fields: true,
+            ..Default::default()
+        };
+        let errors = validate_with_config(&skill_dir, &config);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("Unexpected fields in frontmatter: x-."));
+    }
+
+    #[test]
+    fn test_validate_properties_without_dir() {
+        let props = SkillProperties::new("any-name", "A skill used in tests");
+        assert!(validate_properties(&props, None).is_empty());
+
+        let diagnostics = validate_properties(&props, Some("other"));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, "name-directory-match");
     }
 
     #[test]
@@ -631,5 +3874,77 @@ Body
 
         let errors = validate(&skill_dir);
         assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+        let diagnostics = validate_detailed(&skill_dir, &ValidationConfig::default());
+        assert_eq!(
+            diagnostics[..1],
+            [
+                Diagnostic::info(
+                    "name-normalization",
+                    "Skill name 'cafe\u{0301}' changes under Unicode normalization to 'café'; it may contain characters pasted from elsewhere"
+                )
+                .with_value(decomposed_name)
+                .with_param("normalized", composed_name)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_name_errors_quote_the_raw_value() {
+        let diagnostics_for = |dir_name, yaml| {
+            let mut diagnostics = diagnostics_for(dir_name, yaml);
+            diagnostics.retain(|d| d.rule.starts_with("name-"));
+            diagnostics
+        };
+
+        // Full-width letters are accepted as their ASCII equivalents...
+        let diagnostics = diagnostics_for("latin", "name: ｌａｔｉｎ\ndescription: d");
+        let rules: Vec<&str> = diagnostics.iter().map(|d| d.rule.as_str()).collect();
+        assert_eq!(rules, ["name-normalization"]);
+        assert_eq!(diagnostics[0].severity, Severity::Info);
+
+        // ...but errors quote the name as written, and say what it becomes.
+        let diagnostics = diagnostics_for("latin", "name: Ｌａｔｉｎ\ndescription: d");
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::error(
+                    "name-directory-match",
+                    "Directory name 'latin' must match skill name 'Ｌａｔｉｎ' (after Unicode normalization this becomes 'Latin')"
+                )
+                .with_value("Ｌａｔｉｎ")
+                .with_param("directories", vec!["latin".to_string()])
+                .with_param("normalized", "Latin"),
+                Diagnostic::error(
+                    "name-lowercase",
+                    "Skill name 'Ｌａｔｉｎ' must be lowercase (after Unicode normalization this becomes 'Latin')"
+                )
+                .with_value("Ｌａｔｉｎ")
+                .with_param("normalized", "Latin"),
+                Diagnostic::info(
+                    "name-normalization",
+                    "Skill name 'Ｌａｔｉｎ' changes under Unicode normalization to 'Latin'; it may contain characters pasted from elsewhere"
+                )
+                .with_value("Ｌａｔｉｎ")
+                .with_param("normalized", "Latin"),
+            ]
+        );
+
+        // A decomposed accent on an invalid name
+        let diagnostics = diagnostics_for("x", "name: cafe\u{0301}_bar\ndescription: d");
+        let characters = diagnostics
+            .iter()
+            .find(|d| d.rule == "name-characters")
+            .unwrap();
+        assert_eq!(characters.value.as_deref(), Some("cafe\u{0301}_bar"));
+        assert!(
+            characters
+                .message
+                .ends_with("(after Unicode normalization this becomes 'café_bar')"),
+            "{}",
+            characters.message
+        );
+
+        // Names that are already normalized get no note.
+        assert!(diagnostics_for("latin", "name: latin\ndescription: d").is_empty());
     }
 }
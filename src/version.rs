@@ -0,0 +1,77 @@
+//! Lenient version parsing and ordering, for skill versions that only
+//! roughly follow semver.
+
+use std::cmp::Ordering;
+
+/// A leniently parsed version: numeric components and an optional
+/// pre-release suffix.
+#[derive(Debug)]
+pub(crate) struct Version<'a> {
+    numbers: Vec<u64>,
+    pre: Option<&'a str>,
+}
+
+impl<'a> Version<'a> {
+    /// Parse `1.2.0`, `v2`, or `2.0.0-rc.1`; `None` if the numeric part
+    /// is not dot-separated integers.
+    pub(crate) fn parse(name: &'a str) -> Option<Self> {
+        let name = name.strip_prefix('v').unwrap_or(name);
+        let (core, pre) = match name.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (name, None),
+        };
+        let numbers = core
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        Some(Self { numbers, pre })
+    }
+}
+
+impl Ord for Version<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.numbers.len().max(other.numbers.len());
+        let number = |v: &Self, i: usize| v.numbers.get(i).copied().unwrap_or(0);
+        (0..len)
+            .map(|i| number(self, i).cmp(&number(other, i)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+            // A release sorts after its pre-releases.
+            .then_with(|| match (self.pre, other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+impl PartialEq for Version<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Version<'_> {}
+
+impl PartialOrd for Version<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_order() {
+        let v = |s| Version::parse(s).unwrap();
+        assert!(v("1.10.0") > v("1.9.0"));
+        assert!(v("1.10.0") > v("1.2.0"));
+        assert!(v("1.2") == v("v1.2.0"));
+        assert!(v("2.0.0") > v("2.0.0-rc.1"));
+        assert!(Version::parse("latest").is_none());
+        assert!(Version::parse("1.x").is_none());
+    }
+}
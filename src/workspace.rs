@@ -0,0 +1,879 @@
+//! Validation of every skill under a root, including cross-skill rules.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde::Serialize;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::cancel::CancellationToken;
+use crate::confusables::skeleton;
+use crate::diagnostic::{Diagnostic, shorten};
+use crate::discovery::find_skills;
+use crate::error::Result;
+use crate::inspect::collect_resources;
+use crate::markdown;
+use crate::metrics::{Metrics, NoopMetrics, PARSE_SECONDS, SKILLS_VALIDATED, VALIDATION_FAILURES};
+use crate::ordering;
+use crate::parallel::{default_concurrency, map_bounded};
+use crate::parser::{find_skill_md, parse_frontmatter, read_skill_md_file, split_frontmatter};
+use crate::paths::resolve_within;
+use crate::skill::Skill;
+use crate::validator::{ValidationConfig, read_skill_md, validate_content};
+
+/// Validation result for one skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkillValidation {
+    /// Path to the skill directory.
+    pub path: PathBuf,
+
+    /// Diagnostics for the skill on its own.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl SkillValidation {
+    /// Check if the skill has no errors.
+    pub fn is_valid(&self) -> bool {
+        !self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+}
+
+/// A problem involving several skills.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorkspaceFinding {
+    /// The diagnostic.
+    #[serde(flatten)]
+    pub diagnostic: Diagnostic,
+
+    /// Directories of the skills involved, in discovery order.
+    pub paths: Vec<PathBuf>,
+}
+
+/// Result of validating a set of skills together.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WorkspaceReport {
+    /// Per-skill results, in discovery order.
+    pub skills: Vec<SkillValidation>,
+
+    /// Problems between skills.
+    pub findings: Vec<WorkspaceFinding>,
+
+    /// Whether validation was cancelled (see
+    /// [`validate_many_cancellable`]). A cancelled report lists only the
+    /// skills validated in full before cancellation, and no findings.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cancelled: bool,
+}
+
+impl WorkspaceReport {
+    /// Number of skills with errors of their own.
+    pub fn failed_count(&self) -> usize {
+        self.skills.iter().filter(|s| !s.is_valid()).count()
+    }
+
+    /// Check if no skill and no cross-skill finding has errors.
+    pub fn is_valid(&self) -> bool {
+        self.failed_count() == 0 && !self.findings.iter().any(|f| f.diagnostic.is_error())
+    }
+}
+
+/// Validate every skill under `root` and check them against each other.
+///
+/// Besides each skill's own diagnostics, the report lists:
+///
+/// * `workspace-duplicate-name` (error) - skills whose names are equal after
+///   NFKC normalization;
+/// * `workspace-duplicate-id` (error) - skills whose `metadata.id` values
+///   are equal ignoring ASCII case;
+/// * `workspace-confusable-name` (warning) - different names that share a
+///   confusable skeleton (see [`skeleton`]);
+/// * `workspace-case-collision` (error) - sibling directories whose names
+///   differ only in case, which cannot coexist on case-insensitive file
+///   systems;
+/// * `workspace-path-length` (warning) - skills with a file whose path
+///   relative to `root` is longer than [`MAX_PORTABLE_PATH_LENGTH`] (see
+///   [`path_length_findings`]);
+/// * `workspace-broken-reference` (error) - body links to another skill
+///   that is missing or invalid (see [`reference_findings`]).
+///
+/// # Errors
+///
+/// Returns an error if `root` cannot be scanned.
+pub fn validate_workspace(root: &Path, config: &ValidationConfig) -> Result<WorkspaceReport> {
+    let skill_dirs = find_skills(root)?;
+    let mut report = validate_skill_dirs(&skill_dirs, config);
+    let mut root_findings = path_length_findings(root, &skill_dirs);
+    root_findings.extend(reference_findings(root, &report));
+    config.render_messages(root_findings.iter_mut().map(|f| &mut f.diagnostic));
+    report.findings.extend(root_findings);
+    Ok(report)
+}
+
+/// Longest path, relative to the scan root, that a skill file may have
+/// without a `workspace-path-length` warning.
+///
+/// Windows limits paths to 260 characters by default; the rest is left for
+/// the directory the repository is cloned into.
+pub const MAX_PORTABLE_PATH_LENGTH: usize = 200;
+
+/// Warn about skills with a file whose path relative to `root` is longer
+/// than [`MAX_PORTABLE_PATH_LENGTH`] characters, naming the longest one.
+pub fn path_length_findings(root: &Path, skill_dirs: &[PathBuf]) -> Vec<WorkspaceFinding> {
+    skill_dirs
+        .iter()
+        .filter_map(|dir| {
+            let mut files = Vec::new();
+            collect_resources(dir, Path::new(""), None, &mut files, &mut Vec::new()).ok()?;
+            let rel_dir = dir.strip_prefix(root).unwrap_or(dir);
+            let longest = files
+                .iter()
+                .map(|file| rel_dir.join(&file.path).to_string_lossy().into_owned())
+                .max_by_key(|path| path.chars().count())?;
+            let length = longest.chars().count();
+            (length > MAX_PORTABLE_PATH_LENGTH).then(|| WorkspaceFinding {
+                diagnostic: Diagnostic::warning(
+                    "workspace-path-length",
+                    format!(
+                        "Path '{}' is {} characters long; clones of this repository in all but \
+                         the shortest directories break on Windows (260-character path limit)",
+                        longest, length
+                    ),
+                )
+                .with_param("path", longest.as_str())
+                .with_param("length", length)
+                .with_limit(MAX_PORTABLE_PATH_LENGTH, length),
+                paths: vec![dir.clone()],
+            })
+        })
+        .collect()
+}
+
+/// Report body links from one skill of `report` to another that is
+/// missing or invalid.
+///
+/// Two kinds of links are checked:
+///
+/// * relative links that leave the skill directory but stay under `root`,
+///   such as `[pdf](../pdf/SKILL.md)`: the target must exist, and the skill
+///   containing it must be valid;
+/// * `skill:` links such as `[pdf](skill:pdf)`: a skill of that name must
+///   be in the report and be valid.
+///
+/// Each finding names the linking skill first and, for an invalid target,
+/// the target skill second. Skills whose SKILL.md cannot be read or parsed
+/// are skipped; their own diagnostics already say why.
+pub fn reference_findings(root: &Path, report: &WorkspaceReport) -> Vec<WorkspaceFinding> {
+    let mut sources = Vec::new();
+    let mut by_name: HashMap<String, &SkillValidation> = HashMap::new();
+    for skill in &report.skills {
+        let Some(content) = find_skill_md(&skill.path).and_then(|p| read_skill_md_file(&p).ok())
+        else {
+            continue;
+        };
+        if let (Some(name), _) = frontmatter_identity(&content) {
+            by_name.entry(name).or_insert(skill);
+        }
+        let Ok((_, body)) = split_frontmatter(&content) else {
+            continue;
+        };
+        let first_line = content[..content.len() - body.len()].matches('\n').count() + 1;
+        sources.push((skill, markdown::links(body), first_line));
+    }
+
+    let mut findings = Vec::new();
+    for (skill, links, first_line) in sources {
+        let Some(dir) = workspace_path(root, &skill.path) else {
+            continue;
+        };
+        for link in links {
+            let line = first_line + link.line - 1;
+            let target_skill = if let Some(name) = link.target.strip_prefix("skill:") {
+                let name = name.split(['#', '?']).next().unwrap_or_default();
+                match by_name.get(&name.trim().nfkc().collect::<String>()) {
+                    Some(target) => Some(*target),
+                    None => {
+                        findings.push(missing_reference(skill, line, &link.target));
+                        continue;
+                    }
+                }
+            } else {
+                let Some(path) = workspace_link_path(root, &dir, &link.target) else {
+                    continue;
+                };
+                if !path.exists() {
+                    findings.push(missing_reference(skill, line, &link.target));
+                    continue;
+                }
+                report
+                    .skills
+                    .iter()
+                    .filter(|s| workspace_path(root, &s.path).is_some_and(|d| path.starts_with(d)))
+                    .max_by_key(|s| s.path.components().count())
+            };
+            if let Some(target) = target_skill.filter(|t| !t.is_valid()) {
+                findings.push(WorkspaceFinding {
+                    diagnostic: Diagnostic::error(
+                        "workspace-broken-reference",
+                        format!(
+                            "SKILL.md line {} links to '{}' in skill {}, which is invalid",
+                            line,
+                            link.target,
+                            target.path.display()
+                        ),
+                    )
+                    .with_param("problem", "invalid-target")
+                    .with_param("line", line)
+                    .with_param("target", link.target.as_str())
+                    .with_param("skill", target.path.to_string_lossy().into_owned()),
+                    paths: vec![skill.path.clone(), target.path.clone()],
+                });
+            }
+        }
+    }
+    findings
+}
+
+fn missing_reference(skill: &SkillValidation, line: usize, target: &str) -> WorkspaceFinding {
+    WorkspaceFinding {
+        diagnostic: Diagnostic::error(
+            "workspace-broken-reference",
+            format!(
+                "SKILL.md line {} links to '{}', which does not exist",
+                line, target
+            ),
+        )
+        .with_param("problem", "missing-target")
+        .with_param("line", line)
+        .with_param("target", target),
+        paths: vec![skill.path.clone()],
+    }
+}
+
+/// `path`, which lies under `root`, resolved with [`resolve_within`] so
+/// that symlinks leading out of `root` are refused.
+fn workspace_path(root: &Path, path: &Path) -> Option<PathBuf> {
+    let rel = path.strip_prefix(root).ok()?.to_str()?;
+    resolve_within(root, rel).ok()
+}
+
+/// The path a relative link `target` in the skill at `dir` points to, if
+/// it leaves the skill directory but stays under `root`. `dir` must come
+/// from [`workspace_path`].
+fn workspace_link_path(root: &Path, dir: &Path, target: &str) -> Option<PathBuf> {
+    let has_scheme = target.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    let path = target.split(['#', '?']).next().unwrap_or_default();
+    if has_scheme || path.is_empty() || Path::new(path).has_root() {
+        return None;
+    }
+    let resolved = workspace_path(root, &dir.join(path))?;
+    (!resolved.starts_with(dir)).then_some(resolved)
+}
+
+/// Validate the given skill directories together.
+///
+/// See [`validate_workspace`] for the cross-skill rules.
+pub fn validate_skill_dirs(skill_dirs: &[PathBuf], config: &ValidationConfig) -> WorkspaceReport {
+    validate_skill_dirs_with(skill_dirs, config, &NoopMetrics)
+}
+
+/// Validate the given skill directories together, reporting to `metrics`.
+///
+/// Each skill counts toward [`SKILLS_VALIDATED`](crate::metrics::SKILLS_VALIDATED)
+/// and its parse time toward [`PARSE_SECONDS`](crate::metrics::PARSE_SECONDS);
+/// each error diagnostic counts toward
+/// [`VALIDATION_FAILURES`](crate::metrics::VALIDATION_FAILURES), labeled
+/// with its rule ID.
+pub fn validate_skill_dirs_with(
+    skill_dirs: &[PathBuf],
+    config: &ValidationConfig,
+    metrics: &dyn Metrics,
+) -> WorkspaceReport {
+    let never = CancellationToken::new();
+    workspace_report(&scan(skill_dirs, config, false, metrics, &never), config)
+}
+
+/// Validate the given skill directories together, stopping early once
+/// `cancel` is cancelled.
+///
+/// The token is checked before each skill and between reading, validating,
+/// and measuring one. When it is cancelled, skills not yet finished are
+/// dropped, the cross-skill checks are skipped, and the report is marked
+/// [`cancelled`](WorkspaceReport::cancelled): every skill it lists has all
+/// its diagnostics, in input order, but skills may be missing. Otherwise
+/// the report is that of [`validate_skill_dirs`].
+pub fn validate_many_cancellable(
+    skill_dirs: &[PathBuf],
+    config: &ValidationConfig,
+    cancel: &CancellationToken,
+) -> WorkspaceReport {
+    let scanned = scan(skill_dirs, config, false, &NoopMetrics, cancel);
+    if cancel.is_cancelled() {
+        return WorkspaceReport {
+            skills: scanned.iter().map(ScannedSkill::validation).collect(),
+            findings: Vec::new(),
+            cancelled: true,
+        };
+    }
+    workspace_report(&scanned, config)
+}
+
+/// One skill as seen by a single pass over a catalog.
+pub(crate) struct ScannedSkill {
+    /// Path to the skill directory.
+    pub path: PathBuf,
+
+    /// Diagnostics for the skill on its own.
+    pub diagnostics: Vec<Diagnostic>,
+
+    /// Normalized frontmatter `name`, even if other fields are invalid.
+    pub name: Option<String>,
+
+    /// Frontmatter `metadata.id`, even if other fields are invalid.
+    pub id: Option<String>,
+
+    /// The loaded skill, if the scan read whole files and it loads.
+    pub skill: Option<Skill>,
+
+    /// Total size of the files besides SKILL.md, if the scan read whole
+    /// files.
+    pub resource_bytes: Option<u64>,
+}
+
+impl ScannedSkill {
+    /// The skill's own validation result.
+    fn validation(&self) -> SkillValidation {
+        SkillValidation {
+            path: self.path.clone(),
+            diagnostics: self.diagnostics.clone(),
+        }
+    }
+}
+
+/// Read, validate, and (with `whole_files`) load every skill directory
+/// once, in parallel, keeping input order.
+///
+/// Without `whole_files`, only the frontmatter is read unless a body rule
+/// is enabled, and `skill` and `resource_bytes` are `None`. Once `cancel`
+/// is cancelled, skills not finished yet are left out.
+pub(crate) fn scan(
+    skill_dirs: &[PathBuf],
+    config: &ValidationConfig,
+    whole_files: bool,
+    metrics: &dyn Metrics,
+    cancel: &CancellationToken,
+) -> Vec<ScannedSkill> {
+    let full = whole_files || config.needs_body();
+    let scanned = map_bounded(
+        skill_dirs,
+        default_concurrency(),
+        |dir| {
+            if cancel.is_cancelled() {
+                return None;
+            }
+            let mut scanned = ScannedSkill {
+                path: dir.clone(),
+                diagnostics: Vec::new(),
+                name: None,
+                id: None,
+                skill: None,
+                resource_bytes: None,
+            };
+            let mut skill_md = None;
+            let started = Instant::now();
+            match read_skill_md(dir, full) {
+                Ok(_) if cancel.is_cancelled() => return None,
+                Ok((path, content)) => {
+                    scanned.diagnostics = validate_content(dir, &content, config);
+                    metrics.observe(PARSE_SECONDS, started.elapsed().as_secs_f64());
+                    (scanned.name, scanned.id) = frontmatter_identity(&content);
+                    if whole_files {
+                        scanned.skill =
+                            Skill::from_content(dir.clone(), path.clone(), &content).ok();
+                    }
+                    skill_md = Some(path);
+                }
+                Err(mut diagnostic) => {
+                    config.render_messages([&mut diagnostic]);
+                    scanned.diagnostics.push(diagnostic);
+                }
+            }
+            if cancel.is_cancelled() {
+                return None;
+            }
+            if whole_files {
+                scanned.resource_bytes = resource_bytes(dir, skill_md.as_deref()).ok();
+            }
+
+            metrics.incr(SKILLS_VALIDATED);
+            for diagnostic in scanned.diagnostics.iter().filter(|d| d.is_error()) {
+                let rule = config.known_rule_id(&diagnostic.rule).unwrap_or("unknown");
+                metrics.incr_labeled(VALIDATION_FAILURES, rule);
+            }
+            Some(scanned)
+        },
+        |_| {},
+    );
+    scanned.into_iter().flatten().collect()
+}
+
+/// Build the workspace report from a scan, with finding messages worded
+/// by the renderer of `config`.
+pub(crate) fn workspace_report(
+    scanned: &[ScannedSkill],
+    config: &ValidationConfig,
+) -> WorkspaceReport {
+    let skills = scanned.iter().map(ScannedSkill::validation).collect();
+
+    let names: Vec<(PathBuf, String)> = scanned
+        .iter()
+        .filter_map(|s| Some((s.path.clone(), s.name.clone()?)))
+        .collect();
+    let ids: Vec<(PathBuf, String)> = scanned
+        .iter()
+        .filter_map(|s| Some((s.path.clone(), s.id.clone()?)))
+        .collect();
+    let dirs: Vec<PathBuf> = scanned.iter().map(|s| s.path.clone()).collect();
+
+    let mut findings = Vec::new();
+    findings.extend(duplicate_names(&names, config.value_chars()));
+    findings.extend(duplicate_ids(&ids, config.value_chars()));
+    findings.extend(confusable_names(&names));
+    findings.extend(case_collisions(&dirs));
+    config.render_messages(findings.iter_mut().map(|f| &mut f.diagnostic));
+
+    WorkspaceReport {
+        skills,
+        findings,
+        cancelled: false,
+    }
+}
+
+/// Total size of the resource files of a skill.
+fn resource_bytes(dir: &Path, skill_md: Option<&Path>) -> Result<u64> {
+    let mut resources = Vec::new();
+    if dir.is_dir() {
+        collect_resources(
+            dir,
+            Path::new(""),
+            skill_md,
+            &mut resources,
+            &mut Vec::new(),
+        )?;
+    }
+    Ok(resources.iter().map(|r| r.size).sum())
+}
+
+/// The normalized `name` and the `metadata.id` from SKILL.md content, if
+/// it has them.
+///
+/// Skills with otherwise invalid frontmatter still take part in the name
+/// and id checks.
+fn frontmatter_identity(content: &str) -> (Option<String>, Option<String>) {
+    let Ok((metadata, _)) = parse_frontmatter(content) else {
+        return (None, None);
+    };
+    let name = metadata
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(|name| name.trim().nfkc().collect());
+    let id = metadata
+        .get("metadata")
+        .and_then(|m| m.get("id"))
+        .and_then(|v| v.as_str())
+        .map(|id| id.trim().to_string());
+    (name, id)
+}
+
+/// Group items by key, keeping groups with more than one member.
+fn groups<T: Clone>(items: impl IntoIterator<Item = (String, T)>) -> Vec<(String, Vec<T>)> {
+    let mut grouped: BTreeMap<String, Vec<T>> = BTreeMap::new();
+    for (key, item) in items {
+        grouped.entry(key).or_default().push(item);
+    }
+    grouped.into_iter().filter(|(_, v)| v.len() > 1).collect()
+}
+
+fn duplicate_names(names: &[(PathBuf, String)], value_chars: usize) -> Vec<WorkspaceFinding> {
+    groups(names.iter().map(|(dir, name)| (name.clone(), dir.clone())))
+        .into_iter()
+        .map(|(name, paths)| WorkspaceFinding {
+            diagnostic: Diagnostic::error(
+                "workspace-duplicate-name",
+                format!(
+                    "{} skills are named '{}'",
+                    paths.len(),
+                    shorten(&name, value_chars)
+                ),
+            )
+            .with_value(name.as_str())
+            .with_param("count", paths.len()),
+            paths,
+        })
+        .collect()
+}
+
+fn duplicate_ids(ids: &[(PathBuf, String)], value_chars: usize) -> Vec<WorkspaceFinding> {
+    groups(
+        ids.iter()
+            .map(|(dir, id)| (id.to_ascii_lowercase(), dir.clone())),
+    )
+    .into_iter()
+    .map(|(id, paths)| WorkspaceFinding {
+        diagnostic: Diagnostic::error(
+            "workspace-duplicate-id",
+            format!(
+                "{} skills have metadata.id '{}'",
+                paths.len(),
+                shorten(&id, value_chars)
+            ),
+        )
+        .with_value(id.as_str())
+        .with_param("count", paths.len()),
+        paths,
+    })
+    .collect()
+}
+
+fn confusable_names(names: &[(PathBuf, String)]) -> Vec<WorkspaceFinding> {
+    groups(
+        names
+            .iter()
+            .map(|(dir, name)| (skeleton(name), (dir.clone(), name.clone()))),
+    )
+    .into_iter()
+    .filter_map(|(_, members)| {
+        let mut distinct: Vec<String> = members.iter().map(|(_, n)| n.clone()).collect();
+        ordering::sort_strings(&mut distinct);
+        distinct.dedup();
+        // Exact duplicates are reported as workspace-duplicate-name.
+        (distinct.len() > 1).then(|| WorkspaceFinding {
+            diagnostic: Diagnostic::warning(
+                "workspace-confusable-name",
+                format!("Skill names look alike: {}", distinct.join(", ")),
+            )
+            .with_param("names", distinct),
+            paths: members.into_iter().map(|(dir, _)| dir).collect(),
+        })
+    })
+    .collect()
+}
+
+fn case_collisions(skill_dirs: &[PathBuf]) -> Vec<WorkspaceFinding> {
+    let keyed = skill_dirs.iter().filter_map(|dir| {
+        let name = dir.file_name()?.to_string_lossy().to_lowercase();
+        let parent = dir.parent().unwrap_or(Path::new(""));
+        Some((
+            parent.join(name).to_string_lossy().into_owned(),
+            dir.clone(),
+        ))
+    });
+    groups(keyed)
+        .into_iter()
+        .map(|(_, paths)| {
+            let shown: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+            WorkspaceFinding {
+                diagnostic: Diagnostic::error(
+                    "workspace-case-collision",
+                    format!(
+                        "Directories differ only in case, so only one survives a checkout on \
+                         case-insensitive file systems (Windows, macOS): {}",
+                        shown.join(", ")
+                    ),
+                )
+                .with_param("paths", shown),
+                paths,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_skill(root: &Path, dir: &str, name: &str) -> PathBuf {
+        let skill_dir = root.join(dir);
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            format!("---\nname: {}\ndescription: d\n---\n", name),
+        )
+        .unwrap();
+        skill_dir
+    }
+
+    fn rules(report: &WorkspaceReport) -> Vec<&str> {
+        report
+            .findings
+            .iter()
+            .map(|f| f.diagnostic.rule.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_duplicate_normalized_names() {
+        let dir = TempDir::new().unwrap();
+        let a = create_skill(dir.path(), "team-a/pdf", "pdf");
+        let b = create_skill(dir.path(), "team-b/pdf", "ｐｄｆ");
+        create_skill(dir.path(), "other", "other");
+
+        let report = validate_workspace(dir.path(), &ValidationConfig::default()).unwrap();
+        assert_eq!(rules(&report), vec!["workspace-duplicate-name"]);
+        assert_eq!(report.findings[0].paths, vec![a, b]);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_duplicate_ids() {
+        let dir = TempDir::new().unwrap();
+        for (name, id) in [
+            ("pdf", Some("com.example.pdf")),
+            ("pdf-forms", Some("COM.example.PDF")),
+            ("csv", Some("com.example.csv")),
+            ("docx", None),
+            ("xlsx", None),
+        ] {
+            let skill_dir = dir.path().join(name);
+            std::fs::create_dir_all(&skill_dir).unwrap();
+            let metadata = id
+                .map(|id| format!("metadata:\n  id: {}\n", id))
+                .unwrap_or_default();
+            std::fs::write(
+                skill_dir.join("SKILL.md"),
+                format!("---\nname: {}\ndescription: d\n{}---\n", name, metadata),
+            )
+            .unwrap();
+        }
+
+        let report = validate_workspace(dir.path(), &ValidationConfig::default()).unwrap();
+        assert_eq!(rules(&report), vec!["workspace-duplicate-id"]);
+        assert_eq!(
+            report.findings[0].diagnostic.message,
+            "2 skills have metadata.id 'com.example.pdf'"
+        );
+        assert_eq!(
+            report.findings[0].paths,
+            vec![dir.path().join("pdf"), dir.path().join("pdf-forms")]
+        );
+
+        let config = ValidationConfig::default().with_message_value_chars(7);
+        let report = validate_workspace(dir.path(), &config).unwrap();
+        assert_eq!(
+            report.findings[0].diagnostic.message,
+            "2 skills have metadata.id 'com.exa... (15 chars)'"
+        );
+        assert_eq!(
+            report.findings[0].diagnostic.value.as_deref(),
+            Some("com.example.pdf")
+        );
+    }
+
+    #[test]
+    fn test_confusable_names_warn() {
+        let dir = TempDir::new().unwrap();
+        create_skill(dir.path(), "pdf-tools", "pdf-tools");
+        create_skill(dir.path(), "pdf-tооls", "pdf-tооls");
+
+        let report = validate_workspace(dir.path(), &ValidationConfig::default()).unwrap();
+        assert_eq!(rules(&report), vec!["workspace-confusable-name"]);
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn test_validation_metrics() {
+        let dir = TempDir::new().unwrap();
+        let good = create_skill(dir.path(), "pdf", "pdf");
+        let bad = create_skill(dir.path(), "other", "Bad_Name");
+
+        let metrics = crate::metrics::RecordingMetrics::new();
+        let report = validate_skill_dirs_with(&[good, bad], &ValidationConfig::default(), &metrics);
+        assert_eq!(report.failed_count(), 1);
+        assert_eq!(metrics.count(SKILLS_VALIDATED), 2);
+        assert_eq!(metrics.count("validation_failures{name-lowercase}"), 1);
+        assert_eq!(
+            metrics.count("validation_failures{name-directory-match}"),
+            1
+        );
+        assert_eq!(metrics.observations(PARSE_SECONDS).len(), 2);
+    }
+
+    #[test]
+    fn test_path_length() {
+        let dir = TempDir::new().unwrap();
+        let skill_dir = create_skill(dir.path(), "skills/pdf", "pdf");
+        std::fs::create_dir_all(skill_dir.join("references")).unwrap();
+        let file = format!("{}.md", "r".repeat(MAX_PORTABLE_PATH_LENGTH));
+        std::fs::write(skill_dir.join("references").join(&file), "").unwrap();
+        create_skill(dir.path(), "skills/short", "short");
+
+        let report = validate_workspace(dir.path(), &ValidationConfig::default()).unwrap();
+        assert_eq!(rules(&report), vec!["workspace-path-length"]);
+        assert_eq!(report.findings[0].paths, vec![skill_dir]);
+        assert!(
+            report.findings[0]
+                .diagnostic
+                .message
+                .contains(&format!("'skills/pdf/references/{}'", file))
+        );
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn test_broken_references() {
+        let dir = TempDir::new().unwrap();
+        create_skill(dir.path(), "pdf", "pdf");
+        let broken = create_skill(dir.path(), "broken", "Broken");
+        let csv = dir.path().join("csv");
+        std::fs::create_dir_all(&csv).unwrap();
+        std::fs::write(
+            csv.join("SKILL.md"),
+            "---\nname: csv\ndescription: d\n---\n\
+             Fill forms with [pdf](../pdf/SKILL.md) or [pdf](skill:pdf#usage).\n\
+             See [gone](../gone/SKILL.md), [nope](skill:nope), [b](../broken/).\n\
+             Local [ref](references/a.md), [web](https://example.com), [up](../../x).\n\
+             `[code](../gone/SKILL.md)`\n",
+        )
+        .unwrap();
+
+        let report = validate_workspace(dir.path(), &ValidationConfig::default()).unwrap();
+        let findings: Vec<_> = report
+            .findings
+            .iter()
+            .filter(|f| f.diagnostic.rule == "workspace-broken-reference")
+            .collect();
+        let messages: Vec<&str> = findings
+            .iter()
+            .map(|f| f.diagnostic.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "SKILL.md line 6 links to '../gone/SKILL.md', which does not exist",
+                "SKILL.md line 6 links to 'skill:nope', which does not exist",
+                &format!(
+                    "SKILL.md line 6 links to '../broken/' in skill {}, which is invalid",
+                    broken.display()
+                ),
+            ]
+        );
+        assert_eq!(findings[0].paths, vec![csv.clone()]);
+        assert_eq!(findings[2].paths, vec![csv, broken]);
+        assert_eq!(
+            findings[0].diagnostic.params["line"],
+            crate::diagnostic::Param::Number(6)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reference_through_symlink_outside_root() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("skills");
+        let csv = create_skill(&root, "csv", "csv");
+        create_skill(dir.path(), "outside", "outside");
+        std::os::unix::fs::symlink(dir.path().join("outside"), root.join("linked")).unwrap();
+
+        let dir_path = workspace_path(&root, &csv).unwrap();
+        assert_eq!(
+            workspace_link_path(&root, &dir_path, "../pdf/SKILL.md"),
+            Some(root.join("pdf/SKILL.md"))
+        );
+        assert_eq!(
+            workspace_link_path(&root, &dir_path, "../linked/SKILL.md"),
+            None
+        );
+        assert_eq!(
+            workspace_link_path(&root, &dir_path, "../../outside/SKILL.md"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_case_collisions() {
+        let dirs = vec![PathBuf::from("root/PDF"), PathBuf::from("root/pdf")];
+        let findings = case_collisions(&dirs);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].paths, dirs);
+        assert!(case_collisions(&[PathBuf::from("a/pdf"), PathBuf::from("b/PDF")]).is_empty());
+    }
+
+    /// Cancels its token once it has checked `after` skills.
+    struct CancelAfter {
+        after: usize,
+        checked: std::sync::atomic::AtomicUsize,
+        cancel: CancellationToken,
+    }
+
+    impl crate::validator::ValidationRule for CancelAfter {
+        fn id(&self) -> &'static str {
+            "cancel-after"
+        }
+
+        fn check(&self, _ctx: &crate::validator::RuleContext) -> Vec<Diagnostic> {
+            let checked = self
+                .checked
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+                + 1;
+            if checked == self.after {
+                self.cancel.cancel();
+            }
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn test_validate_many_cancellable() {
+        let dir = TempDir::new().unwrap();
+        // Every skill's name differs from its directory, and all share it.
+        let dirs: Vec<PathBuf> = (0..300)
+            .map(|i| create_skill(dir.path(), &format!("skill-{:03}", i), "pdf"))
+            .collect();
+        let full = validate_skill_dirs(&dirs, &ValidationConfig::default());
+        assert!(!full.cancelled);
+        assert!(!full.findings.is_empty());
+
+        let cancel = CancellationToken::new();
+        let config =
+            ValidationConfig::default().with_custom_rule(std::sync::Arc::new(CancelAfter {
+                after: 40,
+                checked: Default::default(),
+                cancel: cancel.clone(),
+            }));
+        let worker = {
+            let dirs = dirs.clone();
+            let cancel = cancel.clone();
+            std::thread::spawn(move || validate_many_cancellable(&dirs, &config, &cancel))
+        };
+        let partial = worker.join().unwrap();
+
+        assert!(partial.cancelled);
+        assert!(partial.findings.is_empty());
+        assert!(!partial.skills.is_empty());
+        assert!(
+            partial.skills.len() < dirs.len(),
+            "{}",
+            partial.skills.len()
+        );
+        // Each listed skill is complete, and they keep input order.
+        let mut rest = full.skills.iter();
+        for skill in &partial.skills {
+            assert!(rest.any(|s| s == skill), "{}", skill.path.display());
+        }
+
+        let serialized = serde_json::to_value(&partial).unwrap();
+        assert_eq!(serialized["cancelled"], true);
+        assert!(
+            serde_json::to_value(&full)
+                .unwrap()
+                .get("cancelled")
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,623 @@
+//! Serialization of skills back to SKILL.md files.
+
+#[cfg(feature = "fs-write")]
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, SkillError};
+use crate::models::{AllowedToolsRepr, SkillProperties};
+use crate::parser::FrontmatterSyntax;
+#[cfg(feature = "fs-write")]
+use crate::parser::{find_skill_md, read_skill_md_file, split_frontmatter};
+use crate::yaml_emit::{self, Node};
+
+/// Frontmatter fields of `props`, ready for [`yaml_emit::emit`].
+fn frontmatter_fields(props: &SkillProperties) -> Vec<(String, Node)> {
+    let mut fields = vec![
+        ("name".to_string(), Node::from(props.name.as_str())),
+        (
+            "description".to_string(),
+            Node::from(props.description.as_str()),
+        ),
+    ];
+
+    let optional = [
+        ("license", &props.license),
+        ("compatibility", &props.compatibility),
+    ];
+    for (key, value) in optional {
+        if let Some(value) = value {
+            fields.push((key.to_string(), Node::from(value.as_str())));
+        }
+    }
+    match &props.allowed_tools {
+        Some(AllowedToolsRepr::Raw(raw)) => {
+            fields.push(("allowed-tools".to_string(), Node::from(raw.as_str())));
+        }
+        Some(AllowedToolsRepr::List(entries)) => {
+            let items = entries.iter().map(|e| Node::from(e.as_str())).collect();
+            fields.push(("allowed-tools".to_string(), Node::Seq(items)));
+        }
+        None => {}
+    }
+
+    if let Some(metadata) = props.metadata.as_ref().filter(|m| !m.is_empty()) {
+        let entries = metadata
+            .iter()
+            .map(|(key, value)| (key.clone(), Node::from(value)))
+            .collect();
+        fields.push(("metadata".to_string(), Node::Map(entries)));
+    }
+
+    if let Some(extensions) = props.extensions.as_ref() {
+        for (key, value) in extensions {
+            fields.push((key.clone(), Node::from(value)));
+        }
+    }
+
+    fields
+}
+
+/// Render a complete SKILL.md from properties and a markdown body.
+///
+/// Fields are written in spec order with the shared frontmatter emitter:
+/// values are quoted whenever a plain scalar would be read back as
+/// something else, so `description: "yes"` stays a string, and multi-line
+/// values use block style.
+/// [`parse_frontmatter`](crate::parse_frontmatter) returns the same
+/// properties and the trimmed body.
+pub fn to_skill_md(props: &SkillProperties, body: &str) -> String {
+    let body = body.trim();
+    let mut out = format!("---\n{}---\n", yaml_emit::emit(&frontmatter_fields(props)));
+    if !body.is_empty() {
+        out.push_str(body);
+        out.push('\n');
+    }
+    out
+}
+
+/// Render a complete SKILL.md with frontmatter in `syntax`.
+///
+/// YAML output is that of [`to_skill_md`]. TOML output has the same fields
+/// in the same order, with `metadata` as a table, between `+++` lines.
+///
+/// # Errors
+///
+/// * `ValidationError` - If TOML is requested without the `toml` feature,
+///   or a value (such as a null) has no TOML form
+pub fn to_skill_md_with(
+    props: &SkillProperties,
+    body: &str,
+    syntax: FrontmatterSyntax,
+) -> Result<String> {
+    let frontmatter = match syntax {
+        FrontmatterSyntax::Yaml => return Ok(to_skill_md(props, body)),
+        FrontmatterSyntax::Toml => toml_frontmatter(props)?,
+    };
+
+    let body = body.trim();
+    let mut out = format!("+++\n{}+++\n", frontmatter);
+    if !body.is_empty() {
+        out.push_str(body);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Frontmatter fields of `props` as a TOML document.
+#[cfg(feature = "toml")]
+fn toml_frontmatter(props: &SkillProperties) -> Result<String> {
+    fn to_toml(key: &str, value: &serde_json::Value) -> Result<toml::Value> {
+        toml::Value::try_from(value)
+            .map_err(|e| SkillError::validation(format!("Cannot write '{}' as TOML: {}", key, e)))
+    }
+
+    let mut table = toml::Table::new();
+    table.insert("name".to_string(), props.name.clone().into());
+    table.insert("description".to_string(), props.description.clone().into());
+
+    let optional = [
+        ("license", &props.license),
+        ("compatibility", &props.compatibility),
+    ];
+    for (key, value) in optional {
+        if let Some(value) = value {
+            table.insert(key.to_string(), value.clone().into());
+        }
+    }
+    match &props.allowed_tools {
+        Some(AllowedToolsRepr::Raw(raw)) => {
+            table.insert("allowed-tools".to_string(), raw.clone().into());
+        }
+        Some(AllowedToolsRepr::List(entries)) => {
+            table.insert("allowed-tools".to_string(), entries.clone().into());
+        }
+        None => {}
+    }
+
+    if let Some(metadata) = props.metadata.as_ref().filter(|m| !m.is_empty()) {
+        let mut keys: Vec<&String> = metadata.keys().collect();
+        keys.sort();
+        let mut entries = toml::Table::new();
+        for key in keys {
+            let value = to_toml(&format!("metadata.{}", key), &metadata[key])?;
+            entries.insert(key.clone(), value);
+        }
+        table.insert("metadata".to_string(), entries.into());
+    }
+
+    if let Some(extensions) = props.extensions.as_ref() {
+        let mut keys: Vec<&String> = extensions.keys().collect();
+        keys.sort();
+        for key in keys {
+            table.insert(key.clone(), to_toml(key, &extensions[key])?);
+        }
+    }
+
+    toml::to_string(&table).map_err(|e| SkillError::validation(format!("Cannot write TOML: {}", e)))
+}
+
+#[cfg(not(feature = "toml"))]
+fn toml_frontmatter(_props: &SkillProperties) -> Result<String> {
+    Err(SkillError::validation(
+        "Writing TOML frontmatter needs the `toml` feature",
+    ))
+}
+
+/// What [`write_skill`] does when the skill directory already has a
+/// SKILL.md.
+#[cfg(feature = "fs-write")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OverwriteMode {
+    /// Fail, leaving the existing skill alone.
+    #[default]
+    Refuse,
+
+    /// Replace the existing SKILL.md.
+    Overwrite,
+
+    /// Replace only the frontmatter of the existing SKILL.md and keep its
+    /// body as it is, ignoring the given body. Without an existing
+    /// SKILL.md, the skill is written with the given body.
+    UpdateFrontmatterOnly,
+}
+
+/// Write a skill to `skill_dir/SKILL.md`, creating the directory if needed.
+///
+/// The properties are validated first, with the directory name as the
+/// expected skill name. Writing into an existing empty directory is fine;
+/// an existing SKILL.md (found whatever its casing, and rewritten under its
+/// own name) is handled according to `mode`.
+///
+/// # Returns
+///
+/// Path to the written SKILL.md.
+///
+/// # Errors
+///
+/// * `ValidationError` - If the properties break a validation rule
+/// * `InvalidPath` - If `skill_dir` exists but is not a directory
+/// * `IoError` - If the directory or file cannot be written, or, with
+///   [`OverwriteMode::Refuse`], of kind `AlreadyExists` if the directory
+///   already has a SKILL.md
+/// * `ParseError` - With [`OverwriteMode::UpdateFrontmatterOnly`], if the
+///   existing SKILL.md has no valid frontmatter delimiters
+#[cfg(feature = "fs-write")]
+pub fn write_skill(
+    skill_dir: &Path,
+    props: &SkillProperties,
+    body: &str,
+    mode: OverwriteMode,
+) -> Result<PathBuf> {
+    write_skill_with(skill_dir, props, body, FrontmatterSyntax::Yaml, mode)
+}
+
+/// Write a skill like [`write_skill`], with frontmatter in `syntax`.
+///
+/// # Errors
+///
+/// As [`write_skill`] and [`to_skill_md_with`].
+#[cfg(feature = "fs-write")]
+pub fn write_skill_with(
+    skill_dir: &Path,
+    props: &SkillProperties,
+    body: &str,
+    syntax: FrontmatterSyntax,
+    mode: OverwriteMode,
+) -> Result<PathBuf> {
+    let dir_name = skill_dir.file_name().and_then(|n| n.to_str());
+    props.validate_for_write(dir_name)?;
+
+    if skill_dir.exists() && !skill_dir.is_dir() {
+        return Err(SkillError::invalid_path(
+            skill_dir,
+            format!(
+                "Target exists and is not a directory: {}",
+                skill_dir.display()
+            ),
+        ));
+    }
+    let existing = find_skill_md(skill_dir);
+
+    let content = match (&existing, mode) {
+        (Some(skill_md), OverwriteMode::Refuse) => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("Skill already exists: {}", skill_md.display()),
+            )
+            .into());
+        }
+        (Some(skill_md), OverwriteMode::UpdateFrontmatterOnly) => {
+            let current = read_skill_md_file(skill_md)?;
+            let (_, current_body) = split_frontmatter(&current)?;
+            format!("{}{}", to_skill_md_with(props, "", syntax)?, current_body)
+        }
+        _ => to_skill_md_with(props, body, syntax)?,
+    };
+
+    std::fs::create_dir_all(skill_dir)?;
+    let skill_md = existing.unwrap_or_else(|| skill_dir.join("SKILL.md"));
+    std::fs::write(&skill_md, content)?;
+    Ok(skill_md)
+}
+
+/// Write `contents` to `path` atomically: write a sibling temporary file,
+/// give it the permissions of any existing file, and rename it over
+/// `path`.
+///
+/// # Errors
+///
+/// Any IO error from writing or renaming; the temporary file is removed.
+#[cfg(feature = "fs-write")]
+pub fn atomic_write(path: &Path, contents: &str) -> std::io::Result<()> {
+    let file_name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "path has no file name")
+    })?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    std::fs::write(&tmp_path, contents)?;
+    if let Ok(metadata) = std::fs::metadata(path) {
+        std::fs::set_permissions(&tmp_path, metadata.permissions())?;
+    }
+    std::fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp_path);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_frontmatter, properties_from_metadata};
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+
+    fn round_trip(props: &SkillProperties, body: &str) -> (SkillProperties, String) {
+        round_trip_content(&to_skill_md(props, body))
+    }
+
+    fn round_trip_content(content: &str) -> (SkillProperties, String) {
+        let (metadata, body) = parse_frontmatter(content)
+            .unwrap_or_else(|e| panic!("{}\n--- content ---\n{}", e, content));
+        let props = properties_from_metadata(&metadata)
+            .unwrap_or_else(|e| panic!("{}\n--- content ---\n{}", e, content));
+        (props, body)
+    }
+
+    #[test]
+    fn test_to_skill_md_layout() {
+        let mut props = SkillProperties::new("my-skill", "A test skill");
+        props.license = Some("MIT".to_string());
+        let mut metadata = HashMap::new();
+        metadata.insert("version".to_string(), "1.0".into());
+        metadata.insert("author".to_string(), "Jane".into());
+        props.metadata = Some(metadata);
+
+        assert_eq!(
+            to_skill_md(&props, "\n# Body\n\n"),
+            "---\nname: my-skill\ndescription: A test skill\nlicense: MIT\nmetadata:\n  author: Jane\n  version: \"1.0\"\n---\n# Body\n"
+        );
+    }
+
+    #[cfg(feature = "fs-write")]
+    #[test]
+    fn test_write_skill() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        let props = SkillProperties::new("my-skill", "yes");
+
+        let path = write_skill(&skill_dir, &props, "# Body", OverwriteMode::Refuse).unwrap();
+        assert_eq!(path, skill_dir.join("SKILL.md"));
+        assert_eq!(crate::read_properties(&skill_dir).unwrap(), props);
+        assert!(crate::validate(&skill_dir).is_empty());
+    }
+
+    #[cfg(feature = "fs-write")]
+    #[test]
+    fn test_write_skill_into_empty_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir(&skill_dir).unwrap();
+        let props = SkillProperties::new("my-skill", "d");
+
+        write_skill(&skill_dir, &props, "# Body", OverwriteMode::Refuse).unwrap();
+        assert_eq!(crate::read_properties(&skill_dir).unwrap(), props);
+    }
+
+    #[cfg(feature = "fs-write")]
+    #[test]
+    fn test_write_skill_overwrite_modes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        let skill_md = skill_dir.join("SKILL.md");
+        std::fs::create_dir(&skill_dir).unwrap();
+        let original =
+            "---\nname: my-skill\ndescription: Old\n---\n\n# Hand-written body\n\n  keep spacing\n";
+        std::fs::write(&skill_md, original).unwrap();
+        let props = SkillProperties::new("my-skill", "New");
+
+        let err = write_skill(&skill_dir, &props, "# New", OverwriteMode::Refuse).unwrap_err();
+        assert!(
+            matches!(&err, SkillError::Io(e) if e.kind() == std::io::ErrorKind::AlreadyExists),
+            "{}",
+            err
+        );
+        assert_eq!(std::fs::read_to_string(&skill_md).unwrap(), original);
+
+        write_skill(
+            &skill_dir,
+            &props,
+            "# Ignored",
+            OverwriteMode::UpdateFrontmatterOnly,
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&skill_md).unwrap(),
+            "---\nname: my-skill\ndescription: New\n---\n\n# Hand-written body\n\n  keep spacing\n"
+        );
+
+        write_skill(&skill_dir, &props, "# New", OverwriteMode::Overwrite).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&skill_md).unwrap(),
+            "---\nname: my-skill\ndescription: New\n---\n# New\n"
+        );
+
+        // Without an existing SKILL.md, the given body is written.
+        let fresh = dir.path().join("fresh");
+        let props = SkillProperties::new("fresh", "d");
+        write_skill(
+            &fresh,
+            &props,
+            "# Body",
+            OverwriteMode::UpdateFrontmatterOnly,
+        )
+        .unwrap();
+        assert!(
+            std::fs::read_to_string(fresh.join("SKILL.md"))
+                .unwrap()
+                .ends_with("---\n# Body\n")
+        );
+    }
+
+    #[cfg(feature = "fs-write")]
+    #[test]
+    fn test_write_skill_target_is_a_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let target = dir.path().join("my-skill");
+        std::fs::write(&target, "not a directory").unwrap();
+        let props = SkillProperties::new("my-skill", "d");
+
+        for mode in [
+            OverwriteMode::Refuse,
+            OverwriteMode::Overwrite,
+            OverwriteMode::UpdateFrontmatterOnly,
+        ] {
+            let err = write_skill(&target, &props, "", mode).unwrap_err();
+            assert!(matches!(err, SkillError::InvalidPath { .. }), "{}", err);
+            assert!(
+                err.to_string()
+                    .starts_with("Target exists and is not a directory: ")
+            );
+        }
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "not a directory");
+    }
+
+    #[cfg(all(feature = "fs-write", unix))]
+    #[test]
+    fn test_write_skill_read_only_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir(&skill_dir).unwrap();
+        std::fs::set_permissions(&skill_dir, std::fs::Permissions::from_mode(0o555)).unwrap();
+        // Permissions do not bind privileged users.
+        let writable = std::fs::write(skill_dir.join("probe"), "").is_ok();
+
+        let props = SkillProperties::new("my-skill", "d");
+        let result = write_skill(&skill_dir, &props, "", OverwriteMode::Overwrite);
+        std::fs::set_permissions(&skill_dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        if !writable {
+            assert!(matches!(result, Err(SkillError::Io(_))), "{:?}", result);
+            assert!(!skill_dir.join("SKILL.md").exists());
+        }
+    }
+
+    #[cfg(feature = "fs-write")]
+    #[test]
+    fn test_write_skill_rejects_invalid() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let props = SkillProperties::new("other-name", "d");
+
+        let err = write_skill(
+            &dir.path().join("my-skill"),
+            &props,
+            "",
+            OverwriteMode::Refuse,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("must match skill name"));
+        assert!(!dir.path().join("my-skill").exists());
+    }
+
+    /// Strings mixing YAML-significant fragments with arbitrary printable text.
+    fn tricky_text() -> impl Strategy<Value = String> {
+        let fragment = prop_oneof![
+            Just("yes".to_string()),
+            Just("null".to_string()),
+            Just("1.0".to_string()),
+            Just("~".to_string()),
+            Just("*star".to_string()),
+            Just("&amp".to_string()),
+            Just(": ".to_string()),
+            Just(" #".to_string()),
+            Just("\n".to_string()),
+            Just("\t".to_string()),
+            Just("'".to_string()),
+            Just("\"".to_string()),
+            Just("\\".to_string()),
+            Just("- ".to_string()),
+            Just("---".to_string()),
+            Just("{}[],|>!%@`?".to_string()),
+            Just("ünïcødé".to_string()),
+            "[ -~]{0,12}",
+        ];
+        prop::collection::vec(fragment, 1..6).prop_map(|parts| parts.concat())
+    }
+
+    fn tricky_value() -> impl Strategy<Value = String> {
+        prop_oneof![
+            Just("yes".to_string()),
+            Just("null".to_string()),
+            Just("1.0".to_string()),
+            Just("*ref".to_string()),
+            Just("&anchor".to_string()),
+            Just("".to_string()),
+            tricky_text(),
+        ]
+    }
+
+    fn extension_value() -> impl Strategy<Value = serde_json::Value> {
+        let leaf = prop_oneof![
+            tricky_value().prop_map(serde_json::Value::from),
+            any::<bool>().prop_map(serde_json::Value::from),
+            any::<i64>().prop_map(serde_json::Value::from),
+            Just(serde_json::Value::Null),
+        ];
+        leaf.prop_recursive(2, 8, 3, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..3).prop_map(serde_json::Value::from),
+                prop::collection::hash_map("[a-z]{1,6}", inner, 0..3)
+                    .prop_map(|m| serde_json::Value::Object(m.into_iter().collect())),
+            ]
+        })
+    }
+
+    prop_compose! {
+        fn valid_properties()(
+            name in "[a-z0-9]{1,8}(-[a-z0-9]{1,8}){0,3}",
+            description in tricky_text()
+                .prop_map(|s| s.trim().to_string())
+                .prop_filter("non-empty", |s| !s.is_empty()),
+            license in proptest::option::of(tricky_value()),
+            compatibility in proptest::option::of(
+                tricky_value().prop_map(|s| s.trim().to_string()),
+            ),
+            allowed_tools in proptest::option::of(prop_oneof![
+                tricky_value().prop_map(AllowedToolsRepr::Raw),
+                prop::collection::vec(tricky_value(), 0..3).prop_map(AllowedToolsRepr::List),
+            ]),
+            metadata in proptest::option::of(
+                prop::collection::hash_map(
+                    prop_oneof![Just("yes".to_string()), Just("1".to_string()), "[a-z][a-z0-9-]{0,8}"],
+                    tricky_value().prop_map(serde_json::Value::from),
+                    1..4,
+                ),
+            ),
+            extensions in proptest::option::of(
+                prop::collection::hash_map("x-[a-z]{1,8}", extension_value(), 1..3),
+            ),
+        ) -> SkillProperties {
+            SkillProperties {
+                name,
+                description,
+                license,
+                compatibility,
+                allowed_tools,
+                metadata,
+                extensions,
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_round_trip(props in valid_properties(), body in tricky_text()) {
+            let (parsed, parsed_body) = round_trip(&props, &body);
+            prop_assert_eq!(parsed, props);
+            prop_assert_eq!(parsed_body, body.trim());
+        }
+    }
+
+    #[test]
+    fn test_allowed_tools_form_round_trips() {
+        for content in [
+            "---\nname: my-skill\ndescription: d\nallowed-tools: Bash(git:*) Read\n---\n",
+            "---\nname: my-skill\ndescription: d\nallowed-tools:\n  - Bash(git log:*)\n  - Read\n---\n",
+        ] {
+            let (props, body) = round_trip_content(content);
+            assert_eq!(to_skill_md(&props, &body), content);
+        }
+    }
+
+    #[test]
+    fn test_to_skill_md_with_yaml_matches_to_skill_md() {
+        let props = SkillProperties::new("my-skill", "A test skill");
+        assert_eq!(
+            to_skill_md_with(&props, "# Body", FrontmatterSyntax::Yaml).unwrap(),
+            to_skill_md(&props, "# Body")
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_to_skill_md_with_toml_round_trip() {
+        let props = SkillProperties::builder("my-skill", "A test skill")
+            .license("MIT")
+            .metadata("version", "1.0")
+            .metadata("review", serde_json::json!({"owners": ["a"]}))
+            .build()
+            .unwrap();
+
+        let content = to_skill_md_with(&props, "# Body", FrontmatterSyntax::Toml).unwrap();
+        assert_eq!(
+            content,
+            "+++\nname = \"my-skill\"\ndescription = \"A test skill\"\nlicense = \"MIT\"\n\n[metadata]\nversion = \"1.0\"\n\n[metadata.review]\nowners = [\"a\"]\n+++\n# Body\n"
+        );
+        assert_eq!(round_trip_content(&content), (props, "# Body".to_string()));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_to_skill_md_with_toml_rejects_null() {
+        let props = SkillProperties::builder("my-skill", "A test skill")
+            .metadata("owner", serde_json::Value::Null)
+            .build()
+            .unwrap();
+        let err = to_skill_md_with(&props, "", FrontmatterSyntax::Toml).unwrap_err();
+        assert!(err.to_string().contains("metadata.owner"), "{}", err);
+    }
+
+    #[cfg(feature = "fs-write")]
+    #[test]
+    fn test_atomic_write_leaves_no_temp_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("SKILL.md");
+        atomic_write(&file, "content").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "content");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,369 @@
+//! Canonical YAML emission for SKILL.md frontmatter.
+//!
+//! Every writer of frontmatter goes through [`emit`] so quoting and layout
+//! rules live in one place:
+//!
+//! * top-level fields come in spec order, then any other fields sorted by
+//!   key; keys of nested mappings are sorted;
+//! * strings are plain unless they would read back as something else, in
+//!   which case they are double-quoted;
+//! * multi-line strings use literal block style (`|`);
+//! * nested mappings and sequences are indented by two spaces.
+
+/// Order of the spec-defined top-level fields.
+const FIELD_ORDER: &[&str] = &[
+    "name",
+    "description",
+    "license",
+    "compatibility",
+    "allowed-tools",
+    "metadata",
+];
+
+/// Indentation added per nesting level.
+pub(crate) const INDENT: &str = "  ";
+
+/// A YAML value to emit.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Node {
+    /// A string, quoted only when needed.
+    Str(String),
+    /// A scalar written verbatim, such as a number, boolean, or `null`.
+    Raw(String),
+    /// A block sequence.
+    Seq(Vec<Node>),
+    /// A block mapping; keys are emitted in sorted order.
+    Map(Vec<(String, Node)>),
+}
+
+impl From<&str> for Node {
+    fn from(s: &str) -> Self {
+        Self::Str(s.to_string())
+    }
+}
+
+impl From<&serde_json::Value> for Node {
+    fn from(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::String(s) => Self::Str(s.clone()),
+            serde_json::Value::Array(items) => Self::Seq(items.iter().map(Self::from).collect()),
+            serde_json::Value::Object(map) => Self::Map(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), Self::from(value)))
+                    .collect(),
+            ),
+            other => Self::Raw(other.to_string()),
+        }
+    }
+}
+
+/// Emit `fields` as canonical frontmatter YAML, without `---` delimiters.
+///
+/// Every line, including the last, ends with a newline.
+pub(crate) fn emit(fields: &[(String, Node)]) -> String {
+    let mut ordered: Vec<&(String, Node)> = fields.iter().collect();
+    ordered.sort_by_key(|(key, _)| {
+        let rank = FIELD_ORDER
+            .iter()
+            .position(|f| f == key)
+            .unwrap_or(FIELD_ORDER.len());
+        (rank, key.as_str())
+    });
+
+    let mut out = String::new();
+    for (key, value) in ordered {
+        emit_entry(&mut out, "", key, value);
+    }
+    out
+}
+
+/// Emit a single `key: value` entry whose key line starts with `indent`.
+#[cfg(feature = "fs-write")]
+pub(crate) fn emit_field(indent: &str, key: &str, value: &Node) -> String {
+    let mut out = String::new();
+    emit_entry(&mut out, indent, key, value);
+    out
+}
+
+fn emit_entry(out: &mut String, indent: &str, key: &str, value: &Node) {
+    out.push_str(indent);
+    out.push_str(&scalar(key));
+    out.push(':');
+    emit_value(out, indent, value);
+}
+
+/// Emit `value` after a `key:` or `-` marker already written to `out`.
+fn emit_value(out: &mut String, indent: &str, value: &Node) {
+    let nested = format!("{}{}", indent, INDENT);
+    match value {
+        Node::Str(s) if block_safe(s) => {
+            let trailing = s.len() - s.trim_end_matches('\n').len();
+            let chomp = match trailing {
+                0 => "-",
+                1 => "",
+                _ => "+",
+            };
+            out.push_str(&format!(" |{}\n", chomp));
+            for line in s[..s.len() - trailing].split('\n') {
+                if !line.is_empty() {
+                    out.push_str(&nested);
+                    out.push_str(line);
+                }
+                out.push('\n');
+            }
+            for _ in 1..trailing {
+                out.push('\n');
+            }
+        }
+        Node::Str(s) => out.push_str(&format!(" {}\n", scalar(s))),
+        Node::Raw(raw) => out.push_str(&format!(" {}\n", raw)),
+        Node::Seq(items) if items.is_empty() => out.push_str(" []\n"),
+        Node::Map(entries) if entries.is_empty() => out.push_str(" {}\n"),
+        Node::Seq(items) => {
+            out.push('\n');
+            for item in items {
+                out.push_str(&nested);
+                out.push('-');
+                emit_value(out, &nested, item);
+            }
+        }
+        Node::Map(entries) => {
+            out.push('\n');
+            let mut entries: Vec<&(String, Node)> = entries.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            for (key, value) in entries {
+                emit_entry(out, &nested, key, value);
+            }
+        }
+    }
+}
+
+/// Check if a multi-line string can be written as a literal block scalar
+/// and read back unchanged.
+fn block_safe(s: &str) -> bool {
+    let content = s.trim_end_matches('\n');
+    s.contains('\n')
+        && !content.is_empty()
+        && !content.starts_with([' ', '\t', '\n'])
+        && !s.chars().any(|c| c.is_control() && c != '\n' && c != '\t')
+        && !content.split('\n').any(|line| line.ends_with([' ', '\t']))
+}
+
+/// Check if a string can be written as a plain (unquoted) YAML scalar and
+/// read back as the same string.
+fn is_plain_safe(s: &str) -> bool {
+    const INDICATORS: &[char] = &[
+        '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@',
+        '`',
+    ];
+    // Words YAML 1.1 parsers (including the Python reference) read as
+    // booleans even though YAML 1.2 does not.
+    const YAML_11_BOOLEANS: &[&str] = &["y", "n", "yes", "no", "on", "off"];
+
+    if s.is_empty()
+        || s.trim() != s
+        || s.starts_with(INDICATORS)
+        || s.ends_with(':')
+        || s.contains(": ")
+        || s.contains('#')
+        || s.chars().any(char::is_control)
+        || YAML_11_BOOLEANS.iter().any(|b| s.eq_ignore_ascii_case(b))
+        || looks_like_yaml_11_number(s)
+    {
+        return false;
+    }
+
+    // Anything YAML would resolve to a non-string (numbers, `null`, `~`,
+    // `true`, ...) must be quoted.
+    matches!(
+        serde_yaml::from_str::<serde_yaml::Value>(s),
+        Ok(serde_yaml::Value::String(ref parsed)) if parsed == s
+    )
+}
+
+/// Check for numbers and timestamps that YAML 1.1 parsers resolve but YAML
+/// 1.2 does not, such as `1_000`, `1:30`, or `2024-01-01`.
+fn looks_like_yaml_11_number(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_digit() || c == '+' || c == '.')
+        && s.chars()
+            .all(|c| c.is_ascii_hexdigit() || "xXoO_.:+-TtZz ".contains(c))
+}
+
+/// Format a string as a single-line YAML scalar, quoting only when needed.
+///
+/// Quoted scalars use YAML double-quoted style with JSON escapes, which
+/// YAML accepts as-is.
+pub(crate) fn scalar(s: &str) -> String {
+    if is_plain_safe(s) {
+        s.to_string()
+    } else {
+        serde_json::to_string(s).expect("strings always serialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(key: &str, value: Node) -> (String, Node) {
+        (key.to_string(), value)
+    }
+
+    /// Emit `value` under a key and parse it back.
+    fn round_trip(value: &str) -> serde_yaml::Value {
+        let yaml = emit(&[field("k", Node::from(value))]);
+        let parsed: serde_yaml::Mapping = serde_yaml::from_str(&yaml)
+            .unwrap_or_else(|e| panic!("{:?} emitted unparsable YAML {:?}: {}", value, yaml, e));
+        parsed["k"].clone()
+    }
+
+    #[test]
+    fn test_scalar_matrix() {
+        let plain = [
+            "pdf-tools",
+            "Extract text from PDF files",
+            "a:b",
+            "100%",
+            "under_score",
+            "a, b",
+            "a-b",
+            "x?",
+            "Ünïcödé",
+            "日本語",
+        ];
+        let quoted = [
+            "",
+            " ",
+            " padded",
+            "padded ",
+            "\tx",
+            "yes",
+            "No",
+            "ON",
+            "off",
+            "y",
+            "n",
+            "null",
+            "Null",
+            "~",
+            "true",
+            "False",
+            "1",
+            "-1",
+            "1.0",
+            "1e3",
+            ".inf",
+            "-.inf",
+            ".nan",
+            "0x1F",
+            "0o7",
+            "0b1",
+            "1_000",
+            "2024-01-01",
+            "*ref",
+            "&anchor",
+            "!tag",
+            "- item",
+            "? q",
+            "key: value",
+            "trailing:",
+            "C#",
+            "a #comment",
+            "#x",
+            "[a]",
+            "{a}",
+            "|",
+            ">",
+            "'single'",
+            "\"double\"",
+            "%x",
+            "@x",
+            "`x`",
+            "---",
+            "...",
+            "a\rb",
+            "bell\u{7}",
+        ];
+
+        for s in plain {
+            assert_eq!(scalar(s), s, "{:?} should be plain", s);
+            assert_eq!(round_trip(s), serde_yaml::Value::from(s));
+        }
+        for s in quoted {
+            assert!(scalar(s).starts_with('"'), "{:?} should be quoted", s);
+            assert_eq!(round_trip(s), serde_yaml::Value::from(s), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn test_multi_line_matrix() {
+        let block = [
+            "line one\nline two",
+            "line one\nline two\n",
+            "line one\n\nline two\n\n",
+            "a: b\n# not a comment\n- not a list",
+            "---\nnot a document marker",
+            "tab\tinside\nok",
+        ];
+        let quoted = [
+            "\n",
+            " leading space\nx",
+            "\nleading newline",
+            "trailing space \nx",
+            "crlf\r\nline",
+        ];
+
+        for s in block {
+            let yaml = emit(&[field("k", Node::from(s))]);
+            assert!(yaml.starts_with("k: |"), "{:?} -> {:?}", s, yaml);
+            assert_eq!(round_trip(s), serde_yaml::Value::from(s), "{:?}", s);
+        }
+        for s in quoted {
+            let yaml = emit(&[field("k", Node::from(s))]);
+            assert!(yaml.starts_with("k: \""), "{:?} -> {:?}", s, yaml);
+            assert_eq!(round_trip(s), serde_yaml::Value::from(s), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn test_emit_layout() {
+        let fields = vec![
+            field(
+                "x-tags",
+                Node::Seq(vec![Node::from("a"), Node::Raw("1".to_string())]),
+            ),
+            field(
+                "metadata",
+                Node::Map(vec![
+                    field("version", Node::from("1.0")),
+                    field("author", Node::from("Jane")),
+                ]),
+            ),
+            field("description", Node::from("First line.\nSecond line.")),
+            field("name", Node::from("my-skill")),
+            field("x-empty", Node::Map(Vec::new())),
+        ];
+
+        assert_eq!(
+            emit(&fields),
+            "name: my-skill\n\
+             description: |-\n  First line.\n  Second line.\n\
+             metadata:\n  author: Jane\n  version: \"1.0\"\n\
+             x-empty: {}\n\
+             x-tags:\n  - a\n  - 1\n"
+        );
+    }
+
+    #[test]
+    fn test_nested_json() {
+        let value = serde_json::json!({"b": [{"k": "v"}, []], "a": null});
+        let yaml = emit(&[field("x-data", Node::from(&value))]);
+        assert_eq!(
+            yaml,
+            "x-data:\n  a: null\n  b:\n    -\n      k: v\n    - []\n"
+        );
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+        let back: serde_json::Value = serde_yaml::from_value(parsed["x-data"].clone()).unwrap();
+        assert_eq!(back, value);
+    }
+}
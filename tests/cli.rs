@@ -0,0 +1,1341 @@
+//! End-to-end tests of the `skills-ref` binary: argument parsing, path
+//! resolution, exit codes, and which output goes to stdout or stderr.
+//!
+//! Exit codes: 0 on success, 1 when a command fails or finds problems, and
+//! 2 for usage errors reported by the argument parser.
+
+mod common;
+
+use common::{DESCRIPTION, Fixture, skill_md, skills_ref, stdout_json};
+use predicates::prelude::*;
+use predicates::str::contains;
+use skills_ref::MAX_SKILL_MD_BYTES;
+
+// Path resolution and usage errors
+
+#[test]
+fn test_skill_md_path_resolves_to_directory() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+
+    for arg in ["pdf-tools", "pdf-tools/SKILL.md", "pdf-tools/"] {
+        fx.cmd()
+            .args(["validate", arg])
+            .assert()
+            .success()
+            .stdout(contains("Valid skill: pdf-tools"))
+            .stderr("");
+    }
+}
+
+#[test]
+fn test_lowercase_skill_md_is_found() {
+    let fx = Fixture::new();
+    fx.write("pdf-tools/skill.md", &skill_md("pdf-tools", DESCRIPTION));
+
+    fx.cmd()
+        .args(["validate", "pdf-tools/skill.md"])
+        .assert()
+        .success()
+        .stdout("Valid skill: pdf-tools\n");
+}
+
+#[test]
+fn test_usage_errors_exit_2() {
+    skills_ref().assert().code(2).stdout("");
+    skills_ref()
+        .arg("no-such-command")
+        .assert()
+        .code(2)
+        .stderr(contains("unrecognized subcommand"));
+    skills_ref()
+        .args(["validate-all", "--stdin", "skills"])
+        .assert()
+        .code(2)
+        .stdout("");
+    skills_ref()
+        .args(["inspect", "x", "--outline"])
+        .assert()
+        .code(2)
+        .stderr(contains("--json"));
+}
+
+#[test]
+fn test_help_and_version() {
+    skills_ref()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(contains("validate-all"))
+        .stderr("");
+    skills_ref()
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(contains(env!("CARGO_PKG_VERSION")));
+}
+
+// validate, explain, inspect, lint, manifest-one
+
+#[test]
+fn test_validate() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+    fx.invalid_skill("bad");
+
+    fx.cmd()
+        .args(["validate", "pdf-tools"])
+        .assert()
+        .success()
+        .stdout("Valid skill: pdf-tools\n")
+        .stderr("");
+    fx.cmd()
+        .args(["validate", "bad"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(contains("Validation failed for bad:\n  - "));
+    fx.cmd()
+        .args(["validate", "missing"])
+        .assert()
+        .code(1)
+        .stderr(contains("Path does not exist"));
+    fx.cmd()
+        .args(["validate", "bad", "--porcelain"])
+        .assert()
+        .code(1)
+        .stdout(contains("invalid\tbad\t"))
+        .stderr("");
+}
+
+#[test]
+fn test_standalone_skill_file() {
+    let fx = Fixture::new();
+    fx.write("skills/pdf.md", &skill_md("pdf", DESCRIPTION));
+    let hint = "skills/pdf.md is a standalone skill file; a skill must be a directory named \
+                after it, holding the file as SKILL.md. To convert it: mkdir skills/pdf && \
+                mv skills/pdf.md skills/pdf/SKILL.md";
+
+    for command in ["validate", "read-properties", "to-prompt"] {
+        let output = fx.cmd().args([command, "skills/pdf.md"]).output().unwrap();
+        assert_eq!(output.status.code(), Some(1), "{}", command);
+        let printed =
+            String::from_utf8_lossy(&output.stdout) + String::from_utf8_lossy(&output.stderr);
+        assert!(printed.contains(hint), "{}: {}", command, printed);
+    }
+
+    // Following the hint gives a valid skill.
+    std::fs::create_dir(fx.join("skills/pdf")).unwrap();
+    std::fs::rename(fx.join("skills/pdf.md"), fx.join("skills/pdf/SKILL.md")).unwrap();
+    fx.cmd().args(["validate", "skills/pdf"]).assert().success();
+}
+
+#[test]
+fn test_validate_notes_go_to_stdout() {
+    let fx = Fixture::new();
+    fx.skill(
+        "pdf-tools",
+        "---\nname: pdf-tools\ndescription: Fill and merge PDF forms\n---\nOpen {{FILE}}.\n",
+    );
+
+    fx.cmd()
+        .args(["validate", "pdf-tools", "--list-placeholders"])
+        .assert()
+        .success()
+        .stdout(contains("info: Body uses placeholders: FILE"))
+        .stderr("");
+}
+
+#[test]
+fn test_explain() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+    fx.invalid_skill("bad");
+
+    fx.cmd()
+        .args(["explain", "pdf-tools"])
+        .assert()
+        .success()
+        .stdout("Valid skill: pdf-tools\n");
+    fx.cmd()
+        .args(["explain", "bad"])
+        .assert()
+        .code(1)
+        .stdout(contains("in bad:"))
+        .stderr("");
+}
+
+#[test]
+fn test_inspect() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+
+    let output = fx
+        .cmd()
+        .args(["inspect", "pdf-tools/SKILL.md", "--json", "--outline"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let report = stdout_json(&output);
+    assert_eq!(report["properties"]["name"], "pdf-tools");
+    assert_eq!(report["outline"][0]["text"], "Usage");
+
+    let output = fx
+        .cmd()
+        .args(["inspect", "pdf-tools", "--json"])
+        .output()
+        .unwrap();
+    assert!(stdout_json(&output).get("outline").is_none());
+
+    fx.cmd()
+        .args(["inspect", "pdf-tools"])
+        .assert()
+        .success()
+        .stdout(contains("pdf-tools"));
+    // A skill that cannot be read is still a report, not a failure.
+    fx.cmd()
+        .args(["inspect", "missing"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "error [path-missing] Path does not exist: missing",
+        ));
+}
+
+#[test]
+fn test_lint() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+    fx.skill(
+        "terse",
+        "---\nname: terse\ndescription: Terse\n---\nSee the docs.\n",
+    );
+
+    fx.cmd().args(["lint", "pdf-tools"]).assert().success();
+    let output = fx
+        .cmd()
+        .args(["lint", "pdf-tools", "--json"])
+        .output()
+        .unwrap();
+    assert_eq!(stdout_json(&output)["findings"], serde_json::json!([]));
+
+    fx.cmd()
+        .args(["lint", "terse"])
+        .assert()
+        .code(1)
+        .stdout(contains("terse"));
+    let output = fx.cmd().args(["lint", "terse", "--json"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        !stdout_json(&output)["findings"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+/// A tree with one fixable, one clean, and one unfixable skill.
+fn fix_fixture() -> Fixture {
+    let fx = Fixture::new();
+    fx.skill(
+        "skills/a",
+        "---\nname: a\ndescription: |\n  Fill PDF forms.\n  Use for PDFs.\n---\nUsage\n=====\nRun it.\n",
+    );
+    fx.skill(
+        "skills/b",
+        "---\nname: b\ndescription: Fill PDF forms\n---\n# B\n",
+    );
+    fx.skill(
+        "skills/c",
+        "---\nname: c\ndescription: Fill PDF forms\n---\n# One\n# Two\n",
+    );
+    fx
+}
+
+#[test]
+fn test_lint_fix_json_golden() {
+    let fx = fix_fixture();
+    let output = fx
+        .cmd()
+        .args(["lint", "--fix", "--json", "skills"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout_json(&output),
+        serde_json::json!({
+            "files": [
+                {
+                    "path": "skills/a/SKILL.md",
+                    "status": "changed",
+                    "fixed": [
+                        {
+                            "rule": "description-newline",
+                            "severity": "warning",
+                            "message": "Description spans 2 lines; it is shown in the prompt as one entry",
+                            "line": 3
+                        },
+                        {
+                            "rule": "setext-heading",
+                            "severity": "warning",
+                            "message": "Underlined heading 'Usage' is not recognized; write it as '# Usage'",
+                            "line": 7
+                        }
+                    ],
+                    "remaining": [],
+                    "bytes_changed": 51
+                },
+                {
+                    "path": "skills/b/SKILL.md",
+                    "status": "clean",
+                    "fixed": [],
+                    "remaining": [],
+                    "bytes_changed": 0
+                },
+                {
+                    "path": "skills/c/SKILL.md",
+                    "status": "unfixable",
+                    "fixed": [],
+                    "remaining": [
+                        {
+                            "rule": "multiple-h1",
+                            "severity": "warning",
+                            "message": "Second H1 heading 'Two'",
+                            "line": 6
+                        }
+                    ],
+                    "bytes_changed": 0
+                }
+            ],
+            "summary": {"changed": 1, "clean": 1, "unfixable": 1, "write_failed": 0}
+        })
+    );
+    assert_eq!(
+        fx.read("skills/a/SKILL.md"),
+        "---\nname: a\ndescription: Fill PDF forms. Use for PDFs.\n---\n# Usage\nRun it.\n"
+    );
+}
+
+#[test]
+fn test_lint_fix_text_and_dry_run() {
+    let fx = fix_fixture();
+    fx.cmd()
+        .args(["--dry-run", "lint", "--fix", "skills"])
+        .assert()
+        .code(1)
+        .stdout(
+            contains("+# Usage")
+                .and(contains("changed: skills/a/SKILL.md (2 fixed, 51 bytes)"))
+                .and(contains("unfixable: skills/c/SKILL.md (0 fixed)"))
+                .and(contains("  skills/c/SKILL.md:6: warning [multiple-h1]"))
+                .and(contains("1 changed, 1 clean, 1 unfixable, 0 write failed")),
+        );
+    assert!(fx.read("skills/a/SKILL.md").contains("Usage\n====="));
+
+    std::fs::remove_dir_all(fx.join("skills/c")).unwrap();
+    fx.cmd()
+        .args(["lint", "--fix", "skills"])
+        .assert()
+        .success();
+    fx.cmd()
+        .args(["lint", "--fix", "skills"])
+        .assert()
+        .success()
+        .stdout(contains("0 changed, 2 clean"));
+}
+
+#[test]
+fn test_fmt_tree_json_golden() {
+    let fx = Fixture::new();
+    fx.skill(
+        "skills/a",
+        "---\nname: a\ndescription: d\n---\nFill the form\nand save it.\n",
+    );
+    fx.valid_skill("skills/b");
+    let output = fx
+        .cmd()
+        .args(["fmt", "--unwrap", "--json", "skills"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout_json(&output),
+        serde_json::json!({
+            "files": [
+                {
+                    "path": "skills/a/SKILL.md",
+                    "status": "changed",
+                    "fixed": [],
+                    "remaining": [],
+                    "bytes_changed": 1
+                },
+                {
+                    "path": "skills/b/SKILL.md",
+                    "status": "clean",
+                    "fixed": [],
+                    "remaining": [],
+                    "bytes_changed": 0
+                }
+            ],
+            "summary": {"changed": 1, "clean": 1, "unfixable": 0, "write_failed": 0}
+        })
+    );
+    assert!(
+        fx.read("skills/a/SKILL.md")
+            .ends_with("Fill the form and save it.\n")
+    );
+}
+
+#[test]
+fn test_fix_tree_accepts_skill_md_path() {
+    let fx = Fixture::new();
+    fx.skill(
+        "pdf",
+        "---\nname: pdf\ndescription: d\n---\nFill the form\nand save it.\n",
+    );
+    let output = fx
+        .cmd()
+        .args(["fmt", "--unwrap", "--json", "pdf/SKILL.md"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+    assert_eq!(stdout_json(&output)["summary"]["changed"], 1);
+
+    fx.skill(
+        "lint",
+        "---\nname: lint\ndescription: Fix headings in a fixture skill\n---\nTitle\n=====\n",
+    );
+    let output = fx
+        .cmd()
+        .args(["lint", "--fix", "lint/SKILL.md"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    assert!(fx.read("lint/SKILL.md").ends_with("# Title\n"));
+}
+
+#[test]
+fn test_manifest_one() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+    fx.write("pdf-tools/scripts/run.sh", "echo run\n");
+
+    let output = fx
+        .cmd()
+        .args(["manifest-one", "pdf-tools", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let manifest = stdout_json(&output);
+    assert_eq!(manifest["name"], "pdf-tools");
+    assert!(manifest.to_string().contains("scripts/run.sh"));
+
+    fx.cmd()
+        .args(["manifest-one", "pdf-tools"])
+        .assert()
+        .success()
+        .stdout(contains("scripts/run.sh"));
+    fx.cmd()
+        .args(["manifest-one", "missing"])
+        .assert()
+        .code(1)
+        .stderr(contains("Error: "));
+}
+
+// validate-all and read-properties, with paths from arguments or stdin
+
+#[test]
+fn test_validate_all() {
+    let fx = Fixture::new();
+    fx.valid_skill("skills/pdf-tools");
+    fx.valid_skill("skills/csv-tools");
+
+    fx.cmd()
+        .args(["validate-all", "skills"])
+        .assert()
+        .success()
+        .stdout(contains("Valid skill: skills/csv-tools").and(contains("skills/pdf-tools")))
+        .stderr("");
+
+    fx.invalid_skill("skills/bad");
+    fx.cmd()
+        .args(["validate-all", "skills"])
+        .assert()
+        .code(1)
+        .stdout(contains("Valid skill: skills/pdf-tools"))
+        .stderr(
+            contains("Validation failed for skills/bad:")
+                .and(contains("1 of 3 skills failed validation")),
+        );
+    fx.cmd()
+        .args(["validate-all", "skills", "--porcelain"])
+        .assert()
+        .code(1)
+        .stdout(contains("invalid\tskills/bad\t"))
+        .stderr("");
+    fx.cmd()
+        .args(["validate-all", "missing"])
+        .assert()
+        .code(1)
+        .stderr(contains("Error: "));
+}
+
+#[test]
+fn test_validate_all_stdin() {
+    let fx = Fixture::new();
+    fx.valid_skill("skills/pdf-tools");
+    fx.invalid_skill("skills/bad");
+
+    fx.cmd()
+        .args(["validate-all", "--stdin"])
+        .write_stdin("skills/pdf-tools/SKILL.md\n\n")
+        .assert()
+        .success()
+        .stdout("Valid skill: skills/pdf-tools\n");
+    fx.cmd()
+        .args(["validate-all", "--paths-from-stdin"])
+        .write_stdin("skills/pdf-tools\nskills/bad\n")
+        .assert()
+        .code(1)
+        .stderr(contains("1 of 2 skills failed validation"));
+
+    // Paths that are not skills are counted and reported like any other
+    // failure
+    fx.write("notes.txt", "not a skill");
+    fx.cmd()
+        .args(["validate-all", "--paths-from-stdin"])
+        .write_stdin("skills/pdf-tools\nmissing\nnotes.txt\nskills/bad\n")
+        .assert()
+        .code(1)
+        .stderr(
+            contains("Validation failed for missing:\n  - Path does not exist: missing\n")
+                .and(contains(
+                    "Validation failed for notes.txt:\n  - Not a directory: notes.txt\n",
+                ))
+                .and(contains("3 of 4 skills failed validation")),
+        );
+    fx.cmd()
+        .args(["validate-all", "--paths-from-stdin", "--porcelain"])
+        .write_stdin("missing\nnotes.txt\nskills/bad\n")
+        .assert()
+        .code(1)
+        .stdout(
+            contains("\tmissing\tpath-missing\t")
+                .and(contains("\tnotes.txt\tpath-not-directory\t"))
+                .and(contains("\tskills/bad\t")),
+        );
+}
+
+#[test]
+fn test_validate_all_stats() {
+    let fx = Fixture::new();
+    fx.valid_skill("skills/pdf-tools");
+
+    fx.cmd()
+        .args(["validate-all", "skills", "--stats"])
+        .assert()
+        .success()
+        .stdout(contains("Valid skill: skills/pdf-tools"));
+}
+
+#[test]
+fn test_read_properties() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+
+    let output = fx
+        .cmd()
+        .args(["read-properties", "pdf-tools/SKILL.md"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    let props = stdout_json(&output);
+    assert_eq!(props["name"], "pdf-tools");
+    assert_eq!(props["description"], DESCRIPTION);
+
+    fx.cmd()
+        .args(["read-properties", "missing"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(contains("Error: "));
+    fx.cmd()
+        .args(["read-properties", "missing", "--ignore-errors"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_skill_md_from_stdin() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+    let content = std::fs::read_to_string(fx.join("pdf-tools/SKILL.md")).unwrap();
+
+    fx.cmd()
+        .args(["validate", "--stdin", "--name", "pdf-tools"])
+        .write_stdin(content.clone())
+        .assert()
+        .success()
+        .stdout(contains("Valid skill: <stdin>"));
+    fx.cmd()
+        .args(["validate", "--stdin", "--name", "pdf"])
+        .write_stdin(content.clone())
+        .assert()
+        .code(1)
+        .stderr(contains(
+            "Directory name 'pdf' must match skill name 'pdf-tools'",
+        ));
+    fx.cmd()
+        .args(["validate", "--stdin", "--porcelain"])
+        .write_stdin(vec![0xFF, 0xFE, b'-', 0])
+        .assert()
+        .code(1)
+        .stdout(contains("SKILL.md appears to be UTF-16 encoded"));
+
+    let output = fx
+        .cmd()
+        .args(["read-properties", "--stdin"])
+        .write_stdin(content)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let from_file = fx
+        .cmd()
+        .args(["read-properties", "pdf-tools"])
+        .output()
+        .unwrap();
+    assert_eq!(output.stdout, from_file.stdout);
+    fx.cmd()
+        .args(["read-properties", "--stdin"])
+        .write_stdin(b"---\nname: caf\xE9\n---\n".to_vec())
+        .assert()
+        .code(1)
+        .stderr(contains("SKILL.md is not valid UTF-8"));
+
+    // Content on stdin is capped like a skill file, with the same error
+    let mut oversized = b"---\nname: big\ndescription: d\n---\n".to_vec();
+    oversized.resize(MAX_SKILL_MD_BYTES as usize + 1, b'x');
+    fx.write("big/SKILL.md", std::str::from_utf8(&oversized).unwrap());
+    for args in [
+        &["read-properties", "--stdin"][..],
+        &["read-properties", "big"],
+        &["validate", "--stdin"],
+        &["validate", "big"],
+    ] {
+        let output = fx
+            .cmd()
+            .args(args)
+            .write_stdin(oversized.clone())
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(1), "{:?}", args);
+        let all = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(
+            all.contains("SKILL.md is larger than 10485760 bytes"),
+            "{:?}: {}",
+            args,
+            all
+        );
+    }
+}
+
+#[test]
+fn test_cached_results() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+    let entries = |dir: &str| std::fs::read_dir(fx.join(dir)).unwrap().count();
+    let read = |args: &[&str]| {
+        let output = fx.cmd().args(args).output().unwrap();
+        assert!(output.status.success());
+        stdout_json(&output)
+    };
+
+    let uncached = read(&["read-properties", "pdf-tools"]);
+    let args = ["--cache-dir", "cache", "read-properties", "pdf-tools"];
+    assert_eq!(read(&args), uncached);
+    assert_eq!(entries("cache"), 1);
+    assert_eq!(read(&args), uncached);
+    assert_eq!(entries("cache"), 1);
+
+    // A corrupted entry is ignored and rewritten
+    let entry = std::fs::read_dir(fx.join("cache"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    std::fs::write(&entry, "{").unwrap();
+    assert_eq!(read(&args), uncached);
+    assert_ne!(std::fs::read(&entry).unwrap(), b"{");
+
+    fx.cmd()
+        .args(["--cache-dir", "cache", "validate", "pdf-tools"])
+        .assert()
+        .success()
+        .stdout(contains("Valid skill: pdf-tools"));
+    assert_eq!(entries("cache"), 2);
+
+    // --cache uses the user's cache directory
+    fx.cmd()
+        .env("XDG_CACHE_HOME", fx.join("xdg"))
+        .args(["--cache", "validate", "pdf-tools"])
+        .assert()
+        .success();
+    assert_eq!(entries("xdg/skills-ref"), 1);
+}
+
+#[test]
+fn test_read_properties_many_and_stdin() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+
+    let output = fx
+        .cmd()
+        .args(["read-properties", "pdf-tools", "missing"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let entries = stdout_json(&output);
+    assert_eq!(entries[0]["properties"]["name"], "pdf-tools");
+    assert!(entries[1]["error"].is_string());
+
+    let output = fx
+        .cmd()
+        .args(["read-properties", "--paths-from-stdin", "--ignore-errors"])
+        .write_stdin("pdf-tools\nmissing\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(stdout_json(&output).as_array().unwrap().len(), 2);
+}
+
+// to-prompt and preview
+
+#[test]
+fn test_to_prompt() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+
+    fx.cmd()
+        .args(["to-prompt", "pdf-tools"])
+        .assert()
+        .success()
+        .stdout(contains("<available_skills>").and(contains("<name>\npdf-tools\n</name>")))
+        .stderr("");
+    fx.cmd()
+        .args(["to-prompt", "missing"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(contains("Error: "));
+    fx.cmd()
+        .args([
+            "to-prompt",
+            "pdf-tools",
+            "--paginate",
+            "100000",
+            "--page",
+            "2",
+        ])
+        .assert()
+        .code(1)
+        .stderr(contains("Page 2 out of range"));
+}
+
+#[test]
+fn test_to_prompt_report_and_empty_policy() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+    fx.skill(
+        "old-tools",
+        "---\nname: old-tools\ndescription: Fill and merge PDF forms\nmetadata:\n  deprecated: \"true\"\n---\n",
+    );
+
+    fx.cmd()
+        .args([
+            "to-prompt",
+            "pdf-tools",
+            "old-tools",
+            "--report",
+            "report.json",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("Excluded old-tools"));
+    let report: serde_json::Value = serde_json::from_str(&fx.read("report.json")).unwrap();
+    assert_eq!(report["included"][0]["name"], "pdf-tools");
+    assert_eq!(report["excluded"][0]["name"], "old-tools");
+    assert!(report["included"][0].get("location_verified").is_none());
+
+    fx.cmd()
+        .args([
+            "to-prompt",
+            "pdf-tools",
+            "--verify-locations",
+            "--report",
+            "report.json",
+        ])
+        .assert()
+        .success();
+    let report: serde_json::Value = serde_json::from_str(&fx.read("report.json")).unwrap();
+    assert_eq!(report["included"][0]["location_verified"], true);
+
+    fx.cmd()
+        .args(["to-prompt", "pdf-tools", "--fail-on-unreadable"])
+        .assert()
+        .code(2);
+
+    fx.cmd()
+        .args(["to-prompt", "old-tools"])
+        .assert()
+        .code(1)
+        .stdout("");
+    fx.cmd()
+        .args(["to-prompt", "old-tools", "--allow-empty"])
+        .assert()
+        .success()
+        .stdout("<available_skills>\n</available_skills>\n");
+}
+
+#[test]
+fn test_to_prompt_promptset() {
+    let fx = Fixture::new();
+    fx.valid_skill("skills/pdf-tools");
+    fx.valid_skill("skills/csv-tools");
+    fx.write(
+        "prompts/promptset.yaml",
+        "sets:\n  default:\n    roots: [../skills]\n  pdf:\n    skills: [../skills/pdf-tools]\n    include_ids: true\n",
+    );
+
+    fx.cmd()
+        .args(["to-prompt", "--promptset", "prompts/promptset.yaml"])
+        .assert()
+        .success()
+        .stdout(contains("csv-tools").and(contains("pdf-tools")));
+    fx.cmd()
+        .args([
+            "to-prompt",
+            "--promptset",
+            "prompts/promptset.yaml",
+            "--set",
+            "pdf",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("pdf-tools").and(contains("csv-tools").not()));
+    fx.cmd()
+        .args([
+            "to-prompt",
+            "--promptset",
+            "prompts/promptset.yaml",
+            "--set",
+            "nope",
+        ])
+        .assert()
+        .code(1)
+        .stderr(contains("sets: default, pdf"));
+    fx.cmd()
+        .args([
+            "to-prompt",
+            "--promptset",
+            "prompts/promptset.yaml",
+            "skills/pdf-tools",
+        ])
+        .assert()
+        .code(2);
+
+    fx.write("bad.yaml", "sets:\n  default:\n    skills: [missing]\n");
+    fx.cmd()
+        .args(["to-prompt", "--promptset", "bad.yaml"])
+        .assert()
+        .code(1)
+        .stderr(contains("bad.yaml:3: skill 'missing' not found"));
+}
+
+/// Run `args` in `fx` and return its stdout and the bytes of `files`.
+fn artifacts(fx: &Fixture, args: &[&str], files: &[&str]) -> Vec<Vec<u8>> {
+    let output = fx.cmd().args(args).output().unwrap();
+    assert!(output.status.success(), "{:?}: {:?}", args, output);
+    let mut artifacts = vec![output.stdout];
+    artifacts.extend(files.iter().map(|f| std::fs::read(fx.join(f)).unwrap()));
+    artifacts
+}
+
+#[test]
+fn test_reproducible_output() {
+    let runs = [Fixture::new(), Fixture::new()];
+    for fx in &runs {
+        fx.valid_skill("skills/pdf-tools");
+        fx.valid_skill("skills/csv-tools");
+        fx.write("skills/pdf-tools/scripts/fill.py", "print('fill')\n");
+    }
+    let commands: &[(&[&str], &[&str])] = &[
+        (&["manifest-one", "skills/pdf-tools", "--json"], &[]),
+        (
+            &[
+                "to-prompt",
+                "skills/pdf-tools",
+                "skills/csv-tools",
+                "--report",
+                "report.json",
+            ],
+            &["report.json"],
+        ),
+        (
+            &[
+                "export",
+                "skills",
+                "--format",
+                "markdown-bundle",
+                "--out",
+                "SKILLS.md",
+            ],
+            &["SKILLS.md"],
+        ),
+        (&["validate-all", "skills", "--stats"], &[]),
+    ];
+
+    for (args, files) in commands {
+        let [a, b] = &runs;
+        // manifest-one names files by absolute path, so the temp
+        // directories show unless output is reproducible.
+        let flagged: Vec<&str> = ["--reproducible"]
+            .iter()
+            .chain(args.iter())
+            .copied()
+            .collect();
+        assert_eq!(
+            artifacts(a, &flagged, files),
+            artifacts(b, &flagged, files),
+            "{:?}",
+            args
+        );
+    }
+
+    let [a, b] = &runs;
+    let args = ["manifest-one", "skills/pdf-tools", "--json"];
+    assert_ne!(artifacts(a, &args, &[]), artifacts(b, &args, &[]));
+    let with_epoch = |fx: &Fixture| {
+        let output = fx
+            .cmd()
+            .env("SOURCE_DATE_EPOCH", "0")
+            .args(args)
+            .output()
+            .unwrap();
+        output.stdout
+    };
+    assert_eq!(with_epoch(a), with_epoch(b));
+    assert!(
+        String::from_utf8(with_epoch(a))
+            .unwrap()
+            .contains("\"./skills/pdf-tools")
+    );
+
+    // Other commands are left alone unless --reproducible is given.
+    a.write("skills/bad/SKILL.md", "---\nname: bad\n---\n");
+    a.cmd()
+        .env("SOURCE_DATE_EPOCH", "0")
+        .arg("validate")
+        .arg(a.join("skills/bad"))
+        .assert()
+        .code(1)
+        .stderr(contains(a.join("skills/bad").display().to_string()));
+}
+
+#[test]
+fn test_redact_paths() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+
+    fx.cmd()
+        .arg("--redact-paths")
+        .arg(fx.path())
+        .arg("to-prompt")
+        .arg(fx.join("pdf-tools"))
+        .assert()
+        .success()
+        .stdout(contains(
+            "<location>\n&lt;ROOT&gt;/pdf-tools/SKILL.md\n</location>",
+        ));
+}
+
+#[test]
+fn test_preview() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+
+    fx.cmd()
+        .args(["preview", "pdf-tools", "--raw"])
+        .assert()
+        .success()
+        .stdout(contains("<available_skills>"));
+    fx.cmd()
+        .args(["preview", "pdf-tools", "--budget", "8000", "--width", "60"])
+        .assert()
+        .success()
+        .stdout(contains("pdf-tools").and(contains("\x1b[").not()));
+    fx.cmd()
+        .args(["preview", "missing"])
+        .assert()
+        .code(1)
+        .stderr(contains("Error: "));
+}
+
+// Commands that write: init, rename, set, set-field, fmt, fork, and
+// --dry-run
+
+#[test]
+fn test_init() {
+    let fx = Fixture::new();
+
+    fx.cmd()
+        .args(["init", "pdf-tools", "--description", DESCRIPTION])
+        .assert()
+        .success()
+        .stdout("Created skill pdf-tools: pdf-tools\n");
+    fx.cmd().args(["validate", "pdf-tools"]).assert().success();
+    fx.cmd()
+        .args(["init", "pdf-tools"])
+        .assert()
+        .code(1)
+        .stderr(contains("Error: "));
+
+    fx.cmd()
+        .args(["init", "csv-tools", "--template", "script"])
+        .assert()
+        .success();
+    assert!(fx.join("csv-tools/scripts/run.sh").is_file());
+    fx.cmd()
+        .args(["init", "x", "--template", "nope"])
+        .assert()
+        .code(1)
+        .stderr(contains("Unknown template 'nope'"));
+    fx.cmd()
+        .args(["init", "--list-templates"])
+        .assert()
+        .success()
+        .stdout(contains("minimal"));
+}
+
+#[test]
+fn test_dry_run_writes_nothing() {
+    let fx = Fixture::new();
+
+    fx.cmd()
+        .args(["--dry-run", "init", "pdf-tools"])
+        .assert()
+        .success()
+        .stdout(contains("pdf-tools/SKILL.md"));
+    assert!(!fx.join("pdf-tools").exists());
+}
+
+#[test]
+fn test_rename() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+
+    fx.cmd()
+        .args(["rename", "pdf-tools", "pdf-forms"])
+        .assert()
+        .success()
+        .stdout(contains("Renamed skill to pdf-forms"));
+    assert!(fx.read("pdf-forms/SKILL.md").contains("name: pdf-forms"));
+    fx.cmd()
+        .args(["rename", "pdf-forms", "Bad Name"])
+        .assert()
+        .code(1)
+        .stderr(contains("Error: "));
+}
+
+#[test]
+fn test_set() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+
+    fx.cmd()
+        .args(["set", "pdf-tools", "metadata.version", "1.3.0"])
+        .assert()
+        .success();
+    assert!(fx.read("pdf-tools/SKILL.md").contains("version: \"1.3.0\""));
+    fx.cmd()
+        .args(["set", "pdf-tools", "metadata.version", "--unset"])
+        .assert()
+        .success();
+    assert!(!fx.read("pdf-tools/SKILL.md").contains("version"));
+    fx.cmd()
+        .args(["set", "missing", "license", "MIT"])
+        .assert()
+        .code(1)
+        .stderr(contains("Error: "));
+}
+
+#[test]
+fn test_set_field() {
+    let fx = Fixture::new();
+    fx.valid_skill("skills/pdf-tools");
+    fx.valid_skill("skills/csv-tools");
+
+    fx.cmd()
+        .args([
+            "set-field",
+            "skills",
+            "--key",
+            "metadata.owner",
+            "--value",
+            "platform-team",
+        ])
+        .assert()
+        .success();
+    assert!(
+        fx.read("skills/csv-tools/SKILL.md")
+            .contains("owner: platform-team")
+    );
+    fx.cmd()
+        .args([
+            "set-field",
+            "skills",
+            "--key",
+            "name",
+            "--value",
+            "Bad Name",
+        ])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_fmt() {
+    let fx = Fixture::new();
+    fx.skill(
+        "pdf-tools",
+        "---\nname: pdf-tools\ndescription: Fill and merge PDF forms\n---\nOne\nparagraph.\n",
+    );
+
+    fx.cmd()
+        .args(["fmt", "pdf-tools", "--unwrap"])
+        .assert()
+        .success();
+    assert!(fx.read("pdf-tools/SKILL.md").ends_with("One paragraph.\n"));
+    fx.cmd().args(["fmt", "pdf-tools"]).assert().code(2);
+}
+
+#[test]
+fn test_fork() {
+    let fx = Fixture::new();
+    fx.valid_skill("pdf-tools");
+
+    fx.cmd()
+        .args(["fork", "pdf-tools", "pdf-forms"])
+        .assert()
+        .success()
+        .stdout(contains("pdf-forms"));
+    fx.cmd().args(["validate", "pdf-forms"]).assert().success();
+    fx.cmd()
+        .args(["fork", "pdf-tools", "pdf-forms"])
+        .assert()
+        .code(1)
+        .stderr(contains("Error: "));
+}
+
+// Catalog commands
+
+#[test]
+fn test_list() {
+    let fx = Fixture::new();
+    fx.valid_skill("skills/pdf-tools");
+
+    fx.cmd()
+        .args(["list", "skills"])
+        .assert()
+        .success()
+        .stdout(contains("pdf-tools"));
+    fx.cmd()
+        .args(["list", "skills", "--porcelain"])
+        .assert()
+        .success()
+        .stdout(contains("pdf-tools\t"));
+    fx.cmd()
+        .args(["list", "skills", "--duplicates"])
+        .assert()
+        .success()
+        .stdout("");
+    fx.cmd()
+        .args(["list", "missing"])
+        .assert()
+        .code(1)
+        .stderr(contains("Error: "));
+}
+
+#[test]
+fn test_stats() {
+    let fx = Fixture::new();
+    fx.valid_skill("skills/pdf-tools");
+
+    fx.cmd().args(["stats", "skills"]).assert().success();
+    let output = fx
+        .cmd()
+        .args(["stats", "skills", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(stdout_json(&output).is_object());
+    fx.cmd().args(["stats", "missing"]).assert().code(1);
+}
+
+#[test]
+fn test_licenses() {
+    let fx = Fixture::new();
+    fx.valid_skill("skills/pdf-tools");
+
+    fx.cmd()
+        .args(["licenses", "skills"])
+        .assert()
+        .success()
+        .stdout(contains("pdf-tools"));
+    let output = fx
+        .cmd()
+        .args(["licenses", "skills", "--json"])
+        .output()
+        .unwrap();
+    assert!(stdout_json(&output).is_object());
+    fx.cmd()
+        .args(["licenses", "skills", "--deny", "unspecified"])
+        .assert()
+        .code(1)
+        .stderr(contains("Denied licenses found:"));
+}
+
+#[test]
+fn test_export() {
+    let fx = Fixture::new();
+    fx.valid_skill("skills/pdf-tools");
+
+    fx.cmd()
+        .args(["export", "skills", "--format", "markdown-bundle"])
+        .assert()
+        .success()
+        .stdout(contains("pdf-tools"));
+    fx.cmd()
+        .args([
+            "export",
+            "skills",
+            "--format",
+            "markdown-bundle",
+            "--out",
+            "SKILLS.md",
+        ])
+        .assert()
+        .success()
+        .stdout("");
+    assert!(fx.read("SKILLS.md").contains("pdf-tools"));
+    fx.cmd()
+        .args([
+            "--dry-run",
+            "export",
+            "skills",
+            "--format",
+            "markdown-bundle",
+            "--out",
+            "DRY.md",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("DRY.md").and(contains("pdf-tools")));
+    assert!(!fx.join("DRY.md").exists());
+    fx.cmd()
+        .args(["export", "skills", "--format", "html"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_changed_feeds_validate_all() {
+    let fx = Fixture::new();
+    fx.valid_skill("old/pdf-tools");
+    fx.valid_skill("new/pdf-tools");
+    fx.valid_skill("new/csv-tools");
+
+    let output = fx
+        .cmd()
+        .args(["changed", "old", "new", "--paths-only"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let paths = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(paths.trim_end(), "new/csv-tools");
+
+    fx.cmd()
+        .args(["validate-all", "--paths-from-stdin"])
+        .write_stdin(paths)
+        .assert()
+        .success()
+        .stdout("Valid skill: new/csv-tools\n");
+    fx.cmd()
+        .args(["changed", "old", "new"])
+        .assert()
+        .success()
+        .stdout("added     new/csv-tools\n");
+}
+
+#[test]
+fn test_doctor() {
+    let fx = Fixture::new();
+    fx.valid_skill("skills/pdf-tools");
+
+    fx.cmd().args(["doctor", "skills"]).assert().success();
+    let output = fx
+        .cmd()
+        .args(["doctor", "skills", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(stdout_json(&output).is_object());
+    fx.cmd().args(["doctor", "missing"]).assert().code(1);
+}
+
+// Commands that need no skills
+
+#[test]
+fn test_self_test() {
+    skills_ref()
+        .arg("self-test")
+        .assert()
+        .success()
+        .stdout(contains("checks passed"));
+    let output = skills_ref().args(["self-test", "--json"]).output().unwrap();
+    assert!(stdout_json(&output)["checks"].is_array());
+}
+
+#[test]
+fn test_info() {
+    let output = skills_ref().arg("info").output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(stdout_json(&output)["version"], env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn test_rules() {
+    skills_ref()
+        .arg("rules")
+        .assert()
+        .success()
+        .stdout(contains("name-lowercase"));
+    let output = skills_ref().args(["rules", "--json"]).output().unwrap();
+    assert!(stdout_json(&output).as_array().unwrap().len() > 1);
+    skills_ref()
+        .args(["rules", "--explain", "name-lowercase"])
+        .assert()
+        .success()
+        .stdout(contains("Failing example:"));
+    skills_ref()
+        .args(["rules", "--explain", "nope"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr("Error: Unknown rule: nope\n");
+}
@@ -0,0 +1,106 @@
+//! Fixture helpers shared by the CLI tests.
+//!
+//! A [`Fixture`] is a scratch directory of skills. Commands built with
+//! [`Fixture::cmd`] run the compiled `skills-ref` binary inside it, so
+//! tests pass paths relative to the fixture root and output does not
+//! depend on where the temp directory is.
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use assert_cmd::Command;
+use tempfile::TempDir;
+
+/// Description given to skills made by [`Fixture::valid_skill`]. Long
+/// enough that no rule warns about it.
+pub const DESCRIPTION: &str = "Fill and merge PDF forms";
+
+/// A scratch directory holding skills.
+pub struct Fixture {
+    dir: TempDir,
+}
+
+impl Fixture {
+    /// An empty fixture.
+    pub fn new() -> Self {
+        Self {
+            dir: TempDir::new().unwrap(),
+        }
+    }
+
+    /// Root of the fixture.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// `rel` under the fixture root.
+    pub fn join(&self, rel: &str) -> PathBuf {
+        self.dir.path().join(rel)
+    }
+
+    /// Write `contents` to `rel`, creating parent directories.
+    pub fn write(&self, rel: &str, contents: &str) -> PathBuf {
+        let path = self.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// Read `rel` under the fixture root.
+    pub fn read(&self, rel: &str) -> String {
+        std::fs::read_to_string(self.join(rel)).unwrap()
+    }
+
+    /// Write `content` as the SKILL.md of the skill directory `rel`.
+    pub fn skill(&self, rel: &str, content: &str) -> PathBuf {
+        self.write(&format!("{}/SKILL.md", rel), content);
+        self.join(rel)
+    }
+
+    /// A valid skill at `rel`, named after its last path component.
+    pub fn valid_skill(&self, rel: &str) -> PathBuf {
+        let name = rel.rsplit('/').next().unwrap();
+        self.skill(rel, &skill_md(name, DESCRIPTION))
+    }
+
+    /// A skill at `rel` whose name does not follow the naming rules.
+    pub fn invalid_skill(&self, rel: &str) -> PathBuf {
+        self.skill(
+            rel,
+            "---\nname: Bad--Name\ndescription: Fill and merge PDF forms\n---\n# Usage\n",
+        )
+    }
+
+    /// The binary, run from the fixture root.
+    pub fn cmd(&self) -> Command {
+        let mut cmd = skills_ref();
+        cmd.current_dir(self.path());
+        cmd
+    }
+}
+
+/// The compiled `skills-ref` binary, with no color, terminal, or
+/// reproducible-build settings inherited from the environment.
+pub fn skills_ref() -> Command {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("skills-ref");
+    cmd.env("NO_COLOR", "1")
+        .env_remove("COLUMNS")
+        .env_remove("SOURCE_DATE_EPOCH");
+    cmd
+}
+
+/// SKILL.md content with the given name and description and a short body.
+pub fn skill_md(name: &str, description: &str) -> String {
+    format!(
+        "---\nname: {}\ndescription: {}\n---\n# Usage\n\nRun the script on a file.\n",
+        name, description
+    )
+}
+
+/// Parse the stdout of a finished command as JSON.
+pub fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("stdout is not JSON ({}):\n{}", e, stdout))
+}
@@ -0,0 +1,189 @@
+//! The exact strings `validate` returns.
+//!
+//! Downstream code matches on these messages, so their wording is part of
+//! the API until callers have moved to the rule IDs of `validate_report`.
+//! Each case pins every message of one skill verbatim; a change here
+//! breaks callers and belongs in the release notes.
+
+use std::path::Path;
+
+use skills_ref::{ValidationConfig, validate, validate_report};
+use tempfile::TempDir;
+
+/// A skill directory name, its SKILL.md, and the messages `validate`
+/// returns for it, in order.
+const CASES: &[(&str, &str, &[&str])] = &[
+    (
+        "my-skill",
+        "---\nname: my-skill\ndescription: A test skill\n---\n# My Skill\n",
+        &[],
+    ),
+    (
+        "MySkill",
+        "---\nname: MySkill\ndescription: A test skill\n---\nBody\n",
+        &["Skill name 'MySkill' must be lowercase"],
+    ),
+    (
+        "-my-skill",
+        "---\nname: -my-skill\ndescription: A test skill\n---\nBody\n",
+        &["Skill name cannot start or end with a hyphen"],
+    ),
+    (
+        "my--skill",
+        "---\nname: my--skill\ndescription: A test skill\n---\nBody\n",
+        &["Skill name cannot contain consecutive hyphens"],
+    ),
+    (
+        "my_skill",
+        "---\nname: my_skill\ndescription: A test skill\n---\nBody\n",
+        &[
+            "Skill name 'my_skill' contains invalid characters. Only letters, digits, and hyphens are allowed.",
+        ],
+    ),
+    (
+        "wrong-name",
+        "---\nname: correct-name\ndescription: A test skill\n---\nBody\n",
+        &["Directory name 'wrong-name' must match skill name 'correct-name'"],
+    ),
+    (
+        "my-skill",
+        "---\nname: my-skill\ndescription: A test skill\nunknown_field: x\n---\nBody\n",
+        &[
+            "Unexpected fields in frontmatter: unknown_field. Only [\"allowed-tools\", \"compatibility\", \"description\", \"license\", \"metadata\", \"name\"] are allowed.",
+        ],
+    ),
+    (
+        "my-skill",
+        "---\nname: my-skill\n---\nBody\n",
+        &["Missing required field in frontmatter: description"],
+    ),
+    (
+        "my-skill",
+        "---\nlicense: MIT\n---\nBody\n",
+        &["Missing required fields in frontmatter: name, description"],
+    ),
+    (
+        "a-skill-name-which-is-exactly-one-character-longer-than-the-limit",
+        "---\nname: a-skill-name-which-is-exactly-one-character-longer-than-the-limit\ndescription: A test skill\n---\nBody\n",
+        &[
+            "Skill name 'a-skill-name-which-is-exactly-one-character-longer-than-the-limit' exceeds 64 character limit (65 chars)",
+        ],
+    ),
+    (
+        "my-skill",
+        "---\nname: my-skill\ndescription: DESCRIPTION\n---\nBody\n",
+        &["Description exceeds 1024 character limit (1025 chars)"],
+    ),
+    (
+        "my-skill",
+        "---\nname: my-skill\ndescription: \"  \"\n---\nBody\n",
+        &["Field 'description' must be a non-empty string"],
+    ),
+    (
+        "my-skill",
+        "---\nname: my-skill\ndescription: [a, b]\n---\nBody\n",
+        &["Field 'description' must be a non-empty string"],
+    ),
+    (
+        "bad--name",
+        "---\nname: Bad--Name\nowner: me\ncompatibility: COMPAT\n---\n",
+        &[
+            "Unexpected fields in frontmatter: owner. Only [\"allowed-tools\", \"compatibility\", \"description\", \"license\", \"metadata\", \"name\"] are allowed.",
+            "Skill name cannot contain consecutive hyphens",
+            "Directory name 'bad--name' must match skill name 'Bad--Name'",
+            "Skill name 'Bad--Name' must be lowercase",
+            "Missing required field in frontmatter: description",
+            "Compatibility exceeds 500 character limit (501 chars)",
+        ],
+    ),
+    (
+        "my-skill",
+        "---\nname: 1.0\ndescription: A test skill\nlicense: true\n---\n",
+        &[
+            "Field 'name' is a YAML float (1.0), not a string; put the value in quotes",
+            "Field 'license' is a YAML boolean (true), not a string; put the value in quotes",
+        ],
+    ),
+    (
+        "my-skill",
+        "---\nname: my-skill\ndescription: A test skill\nmetadata:\n  1: x\n---\n",
+        &["Key '1' in metadata must be a string, not integer"],
+    ),
+    (
+        "my-skill",
+        "---\nname: my-skill\ndescription: A test skill\nmetadata:\n  a:\n    b:\n      c:\n        d: x\n---\n",
+        &["metadata.a.b.c.d nests deeper than 3 levels"],
+    ),
+    (
+        "my-skill",
+        "---\nname: my-skill\ndescription: A test skill\n---\n",
+        &[],
+    ),
+    (
+        "my-skill",
+        "# No frontmatter\n",
+        &["SKILL.md must start with YAML frontmatter (---)"],
+    ),
+    (
+        "my-skill",
+        "---\nname: my-skill\n",
+        &["SKILL.md frontmatter not properly closed with ---"],
+    ),
+];
+
+/// Write each case and run `f` on its skill directory.
+fn for_each_case(f: impl Fn(&Path, &[&str])) {
+    for (dir_name, content, expected) in CASES {
+        let root = TempDir::new().unwrap();
+        let skill_dir = root.path().join(dir_name);
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        let content = content
+            .replace("COMPAT", &"x".repeat(501))
+            .replace("DESCRIPTION", &"x".repeat(1025));
+        std::fs::write(skill_dir.join("SKILL.md"), content).unwrap();
+        f(&skill_dir, expected);
+    }
+}
+
+#[test]
+fn test_validate_messages_are_unchanged() {
+    for_each_case(|skill_dir, expected| {
+        assert_eq!(validate(skill_dir), expected, "{}", skill_dir.display());
+    });
+}
+
+#[test]
+fn test_validate_is_display_of_report_errors() {
+    for_each_case(|skill_dir, _| {
+        let report = validate_report(skill_dir, &ValidationConfig::default());
+        let displayed: Vec<String> = report
+            .diagnostics
+            .iter()
+            .filter(|d| d.is_error())
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(validate(skill_dir), displayed);
+        assert_eq!(report.is_valid(), displayed.is_empty());
+    });
+}
+
+#[test]
+fn test_path_messages_are_unchanged() {
+    let root = TempDir::new().unwrap();
+    let missing = root.path().join("missing");
+    assert_eq!(
+        validate(&missing),
+        [format!("Path does not exist: {}", missing.display())]
+    );
+
+    let file = root.path().join("file.txt");
+    std::fs::write(&file, "").unwrap();
+    assert_eq!(
+        validate(&file),
+        [format!("Not a directory: {}", file.display())]
+    );
+
+    let empty = root.path().join("empty");
+    std::fs::create_dir(&empty).unwrap();
+    assert_eq!(validate(&empty), ["Missing required file: SKILL.md"]);
+}